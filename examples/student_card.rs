@@ -0,0 +1,31 @@
+use librus_rs::Client;
+
+#[tokio::main]
+async fn main() -> Result<(), librus_rs::Error> {
+    let mut client = Client::from_env().await?;
+    let card = client.student_card().await?;
+
+    println!("{} ({})", card.name, card.class.as_deref().unwrap_or("?"));
+
+    if let Some(averages) = &card.averages {
+        println!("\nAverages:");
+        for avg in averages {
+            let name = avg.subject_name.as_deref().unwrap_or("?");
+            println!("  {:<30} {:.2} ({} grades)", name, avg.average, avg.count);
+        }
+    }
+    if let Some(absences) = card.unexcused_absences {
+        println!("\nUnexcused absences: {}", absences);
+    }
+    if let Some(unread) = card.unread_messages {
+        println!("Unread messages: {}", unread);
+    }
+    if let Some(homework) = &card.next_homework {
+        println!("Next homework ({}): {}", homework.date, homework.content);
+    }
+
+    for error in &card.errors {
+        eprintln!("Could not load {:?}: {}", error.section, error.message);
+    }
+    Ok(())
+}
@@ -7,10 +7,17 @@ async fn main() -> Result<(), librus_rs::Error> {
 
     println!("Authentication successful!");
 
-    // Test Me endpoint
-    println!("\n--- Me ---");
-    let me = client.me().await?;
-    println!("User: {} {}", me.me.user.first_name, me.me.user.last_name);
+    // Test the student card (Me, class, grades, attendance, homework, messages)
+    println!("\n--- Student Card ---");
+    let card = client.student_card().await?;
+    println!(
+        "Student: {} ({})",
+        card.name,
+        card.class.as_deref().unwrap_or("?")
+    );
+    for error in &card.errors {
+        println!("  {:?} unavailable: {}", error.section, error.message);
+    }
 
     // Test Grades
     println!("\n--- Grades ---");
@@ -0,0 +1,172 @@
+//! Anonymization helpers for sharing API responses in bug reports.
+//!
+//! Raw Librus responses contain personal data of students, parents and teachers.
+//! [`scrub_json()`] replaces the values of known personal fields with stable
+//! pseudonyms while keeping IDs, URLs and the overall JSON shape intact, so a
+//! scrubbed body still reproduces the same parse failure.
+//!
+//! # Example
+//!
+//! ```rust
+//! use librus_rs::anonymize::scrub_json;
+//!
+//! let body = r#"{"User":{"Id":7,"FirstName":"Jan","LastName":"Kowalski"}}"#;
+//! let scrubbed = scrub_json(body);
+//! assert!(!scrubbed.contains("Jan"));
+//! assert!(scrubbed.contains(r#""Id":7"#));
+//! // Pseudonyms are deterministic, so the same input scrubs the same way.
+//! assert_eq!(scrubbed, scrub_json(body));
+//! ```
+
+use serde_json::Value;
+
+/// Keys whose values are considered personal data, compared case-insensitively.
+const PII_KEYS: &[&str] = &[
+    "firstname",
+    "lastname",
+    "email",
+    "login",
+    "topic",
+    "content",
+    "message",
+    "text",
+    "subject",
+    "sendername",
+    "senderfirstname",
+    "senderlastname",
+    "receivername",
+    "receiverfirstname",
+    "receiverlastname",
+    "name",
+    "phone",
+    "pesel",
+];
+
+/// Whether values under `key` are personal data.
+///
+/// Only string values are replaced: `Subject` is a notice title on
+/// `SchoolNotices` but an `{Id, Url}` reference on grades, and the latter must
+/// keep its ID.
+fn is_pii_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    PII_KEYS.contains(&key.as_str())
+}
+
+/// Replaces personal data in a JSON body with deterministic pseudonyms.
+///
+/// String values of known personal keys (`FirstName`, `LastName`, `Email`,
+/// `Login`, `topic`, `content`, `senderName`, ...) are replaced at any nesting
+/// depth with `"<Key>-<hash>"`. Numbers, booleans, nulls, IDs and the structure
+/// of the document are left untouched.
+///
+/// Bodies that are not valid JSON (e.g. HTML error pages) are fully redacted,
+/// since their content cannot be scrubbed reliably.
+pub fn scrub_json(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            scrub_value(&mut value);
+            value.to_string()
+        }
+        Err(_) => format!("<{} bytes of non-JSON content redacted>", body.len()),
+    }
+}
+
+fn scrub_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    Value::String(s) if is_pii_key(key) => *s = pseudonym(key, s),
+                    Value::Array(items) if is_pii_key(key) => {
+                        for item in items.iter_mut() {
+                            if let Value::String(s) = item {
+                                *s = pseudonym(key, s);
+                            } else {
+                                scrub_value(item);
+                            }
+                        }
+                    }
+                    _ => scrub_value(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(scrub_value),
+        _ => {}
+    }
+}
+
+fn pseudonym(key: &str, value: &str) -> String {
+    if value.is_empty() {
+        return String::new();
+    }
+    format!("{}-{:08x}", key, fnv1a(value.as_bytes()) as u32)
+}
+
+/// FNV-1a hash, used instead of `DefaultHasher` because its output is stable
+/// across Rust versions and platforms.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    fnv1a_update(FNV1A_OFFSET, bytes)
+}
+
+/// Initial state of [`fnv1a_update()`].
+pub(crate) const FNV1A_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Continues an FNV-1a hash over `bytes`, for data hashed in pieces.
+pub(crate) fn fnv1a_update(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ResponseGrades;
+
+    #[test]
+    fn test_scrubs_pii_at_any_depth() {
+        let body = r#"{
+            "Me": {"Account": {"Id": 1, "Login": "jkowalski", "Email": "jan@example.com"}},
+            "data": [{"messageId": "42", "senderName": "Anna Nowak",
+                      "nested": [{"deeper": {"lastName": "Nowak"}}]}]
+        }"#;
+        let scrubbed = scrub_json(body);
+        for secret in ["jkowalski", "jan@example.com", "Anna", "Nowak"] {
+            assert!(!scrubbed.contains(secret), "{secret} leaked: {scrubbed}");
+        }
+
+        let value: Value = serde_json::from_str(&scrubbed).unwrap();
+        assert_eq!(value["Me"]["Account"]["Id"], 1);
+        assert_eq!(value["data"][0]["messageId"], "42");
+        assert_eq!(
+            value["data"][0]["nested"][0]["deeper"]["lastName"],
+            Value::String(pseudonym("lastName", "Nowak"))
+        );
+    }
+
+    #[test]
+    fn test_scrubbed_body_fails_the_same_way() {
+        let body = include_str!("../tests/fixtures/grades.json").replacen(
+            r#""Semester": 1"#,
+            r#""Semester": "first""#,
+            1,
+        );
+        let original = serde_json::from_str::<ResponseGrades>(&body).unwrap_err();
+        let scrubbed = serde_json::from_str::<ResponseGrades>(&scrub_json(&body)).unwrap_err();
+
+        assert_eq!(original.classify(), scrubbed.classify());
+        let message = |e: &serde_json::Error| {
+            let s = e.to_string();
+            s[..s.find(" at line").unwrap_or(s.len())].to_string()
+        };
+        assert_eq!(message(&original), message(&scrubbed));
+    }
+
+    #[test]
+    fn test_non_json_is_redacted() {
+        assert_eq!(
+            scrub_json("<html>Jan Kowalski</html>"),
+            "<25 bytes of non-JSON content redacted>"
+        );
+    }
+}
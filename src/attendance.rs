@@ -0,0 +1,353 @@
+//! Attendance statistics computed from attendance records and types.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::indexes::AttendanceTypeIndex;
+use crate::structs::lessons::{Attendance, AttendanceType, ResponseAttendances};
+use crate::types::ApiDate;
+use crate::{dates, Error, Result};
+
+/// Attendance counts for a student.
+///
+/// Returned by [`Client::attendance_summary()`](crate::Client::attendance_summary)
+/// or computed with [`AttendanceSummary::from_records()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AttendanceSummary {
+    /// Total number of attendance records.
+    pub total: usize,
+    /// Lessons the student attended.
+    pub present: usize,
+    /// Lessons the student was late for.
+    pub late: usize,
+    /// Excused absences.
+    pub excused: usize,
+    /// Unexcused absences, not counting absences during school trips.
+    pub unexcused: usize,
+    /// Lessons the student was released from.
+    pub released: usize,
+    /// Absences recorded while the student was on a school trip.
+    pub on_trip: usize,
+    /// Records whose type could not be classified.
+    pub other: usize,
+}
+
+impl AttendanceSummary {
+    /// Computes a summary from attendance records and the attendance types
+    /// they reference.
+    ///
+    /// Types are classified by their standard identifier (`ob`, `sp`, `u`,
+    /// `nb`, `zw`). Absences marked with a trip are counted as
+    /// [`on_trip`](Self::on_trip) rather than unexcused.
+    pub fn from_records(records: &[Attendance], types: &[AttendanceType]) -> Self {
+        let types: HashMap<i64, &AttendanceType> =
+            types.iter().map(|t| (i64::from(t.id), t)).collect();
+        Self::tally(records, |id| types.get(&id).copied())
+    }
+
+    /// Like [`AttendanceSummary::from_records()`], with the types in an
+    /// index that is already at hand.
+    pub fn from_index(records: &[Attendance], types: &AttendanceTypeIndex) -> Self {
+        Self::tally(records, |id| types.get(id))
+    }
+
+    fn tally<'a>(
+        records: &[Attendance],
+        type_of: impl Fn(i64) -> Option<&'a AttendanceType>,
+    ) -> Self {
+        let mut summary = Self {
+            total: records.len(),
+            ..Self::default()
+        };
+
+        for record in records {
+            let Some(kind) = record.attendance_type.id().and_then(&type_of) else {
+                summary.other += 1;
+                continue;
+            };
+            match kind.identifier.to_lowercase().as_str() {
+                "ob" => summary.present += 1,
+                "sp" => summary.late += 1,
+                "u" => summary.excused += 1,
+                "zw" => summary.released += 1,
+                "nb" if record.trip.is_some() => summary.on_trip += 1,
+                "nb" => summary.unexcused += 1,
+                _ if kind.is_presence_kind => summary.present += 1,
+                _ => summary.other += 1,
+            }
+        }
+
+        summary
+    }
+}
+
+/// A school's rules for justifying absences, see
+/// [`ResponseAttendances::needing_justification()`].
+///
+/// The rules differ between schools, so there is no default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JustificationPolicy {
+    /// Days after an absence within which it must be justified, e.g. `7`.
+    pub days: u32,
+    /// IDs of the attendance types that need a justification, usually the
+    /// unexcused absence (`nb`) type.
+    pub justifiable_type_ids: Vec<i64>,
+}
+
+/// Absences from one day that still need a justification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingJustification {
+    /// Day of the absences.
+    pub date: ApiDate,
+    /// Last day to justify them.
+    pub deadline: ApiDate,
+    /// Days from today to the deadline; `0` on the last day, negative once
+    /// it has passed.
+    pub days_left: i64,
+    /// Lesson numbers of the absences, in order.
+    pub lessons: Vec<i32>,
+}
+
+impl PendingJustification {
+    /// Whether the deadline has passed.
+    pub fn is_overdue(&self) -> bool {
+        self.days_left < 0
+    }
+}
+
+impl ResponseAttendances {
+    /// Lists unjustified absences by day, oldest first, whose deadline under
+    /// `policy` is at most `within_days` away as seen on `today`
+    /// (`YYYY-MM-DD`).
+    ///
+    /// Records of the types in
+    /// [`justifiable_type_ids`](JustificationPolicy::justifiable_type_ids)
+    /// count, except absences during school trips. Once a teacher accepts a
+    /// justification the record changes type and drops out. Days whose
+    /// deadline has passed are always included, see
+    /// [`PendingJustification::is_overdue()`]; pass `policy.days` as
+    /// `within_days` to list every pending day. Records after `today` are
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDate`] if `today` is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, JustificationPolicy};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let policy = JustificationPolicy {
+    ///     days: 7,
+    ///     justifiable_type_ids: vec![1],
+    /// };
+    /// let attendances = client.attendances().await?;
+    /// // Absences with two days or less left to justify them.
+    /// for day in attendances.needing_justification(&policy, "2025-10-06", 2)? {
+    ///     println!("{}: justify by {}", day.date, day.deadline);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn needing_justification(
+        &self,
+        policy: &JustificationPolicy,
+        today: &str,
+        within_days: u32,
+    ) -> Result<Vec<PendingJustification>> {
+        let today = dates::parse_day(today).ok_or_else(|| Error::InvalidDate(today.to_string()))?;
+        let mut days: BTreeMap<i64, Vec<i32>> = BTreeMap::new();
+        for record in &self.attendances {
+            let justifiable = record
+                .attendance_type
+                .id()
+                .is_some_and(|id| policy.justifiable_type_ids.contains(&id));
+            if !justifiable || record.trip.is_some() {
+                continue;
+            }
+            match dates::parse_day(record.date.as_str()) {
+                Some(day) if day <= today => days.entry(day).or_default().push(record.lesson_no),
+                _ => {}
+            }
+        }
+
+        Ok(days
+            .into_iter()
+            .map(|(day, lessons)| (day + i64::from(policy.days), day, lessons))
+            .filter(|(deadline, _, _)| deadline - today <= i64::from(within_days))
+            .map(|(deadline, day, mut lessons)| {
+                lessons.sort_unstable();
+                PendingJustification {
+                    date: ApiDate::from_raw(dates::format_day(day)),
+                    deadline: ApiDate::from_raw(dates::format_day(deadline)),
+                    days_left: deadline - today,
+                    lessons,
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attendance_type(id: i32, identifier: &str, is_presence_kind: bool) -> AttendanceType {
+        serde_json::from_value(serde_json::json!({
+            "Id": id,
+            "Name": identifier,
+            "Short": identifier,
+            "Standard": true,
+            "IsPresenceKind": is_presence_kind,
+            "Order": id,
+            "Identifier": identifier,
+        }))
+        .unwrap()
+    }
+
+    fn record(id: i32, type_id: i32, trip: Option<i32>) -> Attendance {
+        dated_record(id, type_id, trip, "2025-05-21", 1)
+    }
+
+    fn dated_record(
+        id: i32,
+        type_id: i32,
+        trip: Option<i32>,
+        date: &str,
+        lesson_no: i32,
+    ) -> Attendance {
+        let mut value = serde_json::json!({
+            "Id": id,
+            "Lesson": {"Id": 1, "Url": "https://api.librus.pl/2.0/Lessons/1"},
+            "Student": {"Id": 2, "Url": "https://api.librus.pl/2.0/Users/2"},
+            "Date": date,
+            "AddDate": format!("{date} 10:00:00"),
+            "LessonNo": lesson_no,
+            "Semester": 2,
+            "Type": {"Id": type_id, "Url": "https://api.librus.pl/2.0/Attendances/Types/1"},
+            "AddedBy": {"Id": 3, "Url": "https://api.librus.pl/2.0/Users/3"},
+        });
+        if let Some(trip) = trip {
+            value["Trip"] =
+                serde_json::json!({"Id": trip, "Url": "https://api.librus.pl/2.0/Trips/7"});
+        }
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_trip_absences_are_not_unexcused() {
+        let types = [
+            attendance_type(1, "nb", false),
+            attendance_type(2, "ob", true),
+            attendance_type(3, "u", false),
+            attendance_type(4, "sp", true),
+        ];
+        let records = [
+            record(1, 1, None),
+            record(2, 1, Some(7)),
+            record(3, 1, Some(7)),
+            record(4, 2, None),
+            record(5, 3, None),
+            record(6, 4, None),
+            record(7, 99, None),
+        ];
+
+        let summary = AttendanceSummary::from_records(&records, &types);
+        assert_eq!(
+            summary,
+            AttendanceSummary {
+                total: 7,
+                present: 1,
+                late: 1,
+                excused: 1,
+                unexcused: 1,
+                released: 0,
+                on_trip: 2,
+                other: 1,
+            }
+        );
+        let index = AttendanceTypeIndex::from(Vec::from(types));
+        assert_eq!(AttendanceSummary::from_index(&records, &index), summary);
+    }
+
+    fn policy() -> JustificationPolicy {
+        JustificationPolicy {
+            days: 7,
+            justifiable_type_ids: vec![1],
+        }
+    }
+
+    fn response(records: Vec<Attendance>) -> ResponseAttendances {
+        ResponseAttendances {
+            attendances: records,
+            resources: Default::default(),
+            url: "https://api.librus.pl/2.0/Attendances".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_needing_justification_groups_by_day() {
+        let attendances = response(vec![
+            dated_record(1, 1, None, "2025-10-01", 3),
+            dated_record(2, 1, None, "2025-09-29", 5),
+            dated_record(3, 1, None, "2025-10-01", 1),
+            // Excused, on a trip, and in the future.
+            dated_record(4, 3, None, "2025-10-01", 2),
+            dated_record(5, 1, Some(7), "2025-10-02", 1),
+            dated_record(6, 1, None, "2025-10-09", 1),
+        ]);
+        let pending = attendances
+            .needing_justification(&policy(), "2025-10-06", 7)
+            .unwrap();
+        assert_eq!(
+            pending,
+            [
+                PendingJustification {
+                    date: ApiDate::from_raw("2025-09-29"),
+                    deadline: ApiDate::from_raw("2025-10-06"),
+                    days_left: 0,
+                    lessons: vec![5],
+                },
+                PendingJustification {
+                    date: ApiDate::from_raw("2025-10-01"),
+                    deadline: ApiDate::from_raw("2025-10-08"),
+                    days_left: 2,
+                    lessons: vec![1, 3],
+                },
+            ]
+        );
+
+        // Only the day due today is within a one-day threshold.
+        let soon = attendances
+            .needing_justification(&policy(), "2025-10-06", 1)
+            .unwrap();
+        assert_eq!(soon, pending[..1]);
+    }
+
+    #[test]
+    fn test_justification_deadline_boundary() {
+        let attendances = response(vec![dated_record(1, 1, None, "2025-10-28", 1)]);
+        let on = |today| attendances.needing_justification(&policy(), today, 7);
+        // The deadline crosses the month end.
+        let day = |today| on(today).unwrap()[0].clone();
+        assert_eq!(day("2025-10-28").deadline.as_str(), "2025-11-04");
+        assert_eq!(day("2025-10-28").days_left, 7);
+        assert!(!day("2025-11-04").is_overdue());
+        assert_eq!(day("2025-11-05").days_left, -1);
+        assert!(day("2025-11-05").is_overdue());
+        assert!(on("2025-10-27").unwrap().is_empty());
+        assert!(matches!(on("jutro"), Err(Error::InvalidDate(_))));
+
+        // Overdue days are reported whatever the threshold.
+        let overdue = attendances
+            .needing_justification(&policy(), "2025-11-20", 0)
+            .unwrap();
+        assert!(overdue[0].is_overdue());
+        assert!(attendances
+            .needing_justification(&policy(), "2025-11-01", 0)
+            .unwrap()
+            .is_empty());
+    }
+}
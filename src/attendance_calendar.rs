@@ -0,0 +1,225 @@
+//! Attendance records laid out by day and lesson number.
+
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+use crate::structs::lessons::{Attendance, FilledAttendance};
+use crate::structs::units::LessonRange;
+use crate::types::ApiDate;
+
+/// One lesson of a day in an [`AttendanceDayView`].
+#[derive(Debug)]
+pub enum DaySlot {
+    /// The student's attendance record for the lesson.
+    Recorded(Box<Attendance>),
+    /// The teacher filled in attendance, but there is no record for the
+    /// student.
+    NoRecord,
+    /// No lesson with attendance took place.
+    NoLesson,
+}
+
+/// Attendance by day, with one slot per lesson number, for rendering an
+/// attendance calendar.
+///
+/// Returned by [`Client::attendance_calendar()`](crate::Client::attendance_calendar)
+/// or built with [`AttendanceDayView::build()`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use librus_rs::{Client, DaySlot};
+///
+/// # async fn example() -> Result<(), librus_rs::Error> {
+/// let client = Client::from_env().await?;
+/// let view = client.attendance_calendar("2025-10-06", "2025-10-10").await?;
+/// for (date, slots) in &view.days {
+///     let row: String = slots
+///         .iter()
+///         .map(|slot| match slot {
+///             DaySlot::Recorded(_) => 'x',
+///             DaySlot::NoRecord => '?',
+///             DaySlot::NoLesson => '.',
+///         })
+///         .collect();
+///     println!("{date} {row}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct AttendanceDayView {
+    /// Slots by day, for each day with a record or a filled-in lesson. The
+    /// slot of lesson `n` is at index `n - first_lesson`.
+    pub days: BTreeMap<ApiDate, Vec<DaySlot>>,
+    /// Lesson number of the first slot of each day.
+    pub first_lesson: i32,
+    /// Records whose lesson number is outside the range of the view.
+    pub outside_range: Vec<Attendance>,
+}
+
+impl AttendanceDayView {
+    /// Lays out `attendances` for the lesson numbers in `lessons`, e.g.
+    /// `1..=8`, using `filled` to tell lessons without a record from slots
+    /// without a lesson.
+    ///
+    /// Of several records for the same lesson the one added last is kept.
+    pub fn build(
+        attendances: Vec<Attendance>,
+        filled: &[FilledAttendance],
+        lessons: RangeInclusive<i32>,
+    ) -> Self {
+        let first_lesson = *lessons.start();
+        let offset = |lesson_no: i32| i64::from(lesson_no) - i64::from(first_lesson);
+        let slot_count = usize::try_from(offset(*lessons.end()) + 1).unwrap_or(0);
+        let empty_day = || (0..slot_count).map(|_| DaySlot::NoLesson).collect();
+        let index = |lesson_no: i32| {
+            lessons
+                .contains(&lesson_no)
+                .then(|| offset(lesson_no) as usize)
+        };
+
+        let mut view = Self {
+            first_lesson,
+            ..Self::default()
+        };
+        for lesson in filled {
+            let Some(index) = index(lesson.lesson_no) else {
+                continue;
+            };
+            view.days
+                .entry(lesson.date.clone())
+                .or_insert_with(empty_day)[index] = DaySlot::NoRecord;
+        }
+        for record in attendances {
+            let Some(index) = index(record.lesson_no) else {
+                view.outside_range.push(record);
+                continue;
+            };
+            let slot = &mut view
+                .days
+                .entry(record.date.clone())
+                .or_insert_with(empty_day)[index];
+            let newer = match slot {
+                DaySlot::Recorded(kept) => record.add_date >= kept.add_date,
+                _ => true,
+            };
+            if newer {
+                *slot = DaySlot::Recorded(Box::new(record));
+            }
+        }
+        view
+    }
+}
+
+/// The lesson numbers the school set hours for in `lessons_range`, from 1,
+/// or 0 if lesson 0 has hours, to the last one with hours. `None` if no
+/// lesson has hours.
+pub(crate) fn school_lessons(lessons_range: &[LessonRange]) -> Option<RangeInclusive<i32>> {
+    let mut numbered = (0..).zip(lessons_range).filter(|(_, range)| range.hours().is_some());
+    let first = numbered.next()?.0;
+    let last = numbered.last().map_or(first, |(no, _)| no);
+    Some(first.min(1)..=last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::lessons::AttendanceId;
+
+    fn record(id: i32, date: &str, lesson_no: i32, added: &str) -> Attendance {
+        serde_json::from_value(serde_json::json!({
+            "Id": id,
+            "Lesson": {"Id": 1, "Url": "https://api.librus.pl/2.0/Lessons/1"},
+            "Student": {"Id": 2, "Url": "https://api.librus.pl/2.0/Users/2"},
+            "Date": date,
+            "AddDate": format!("{date} {added}"),
+            "LessonNo": lesson_no,
+            "Semester": 1,
+            "Type": {"Id": 1, "Url": "https://api.librus.pl/2.0/Attendances/Types/1"},
+            "AddedBy": {"Id": 3, "Url": "https://api.librus.pl/2.0/Users/3"},
+        }))
+        .unwrap()
+    }
+
+    fn filled() -> Vec<FilledAttendance> {
+        serde_json::from_str::<crate::ResponseFilledAttendances>(include_str!(
+            "../tests/fixtures/attendances_filled_by_teacher.json"
+        ))
+        .unwrap()
+        .attendances
+    }
+
+    fn kinds(view: &AttendanceDayView, date: &str) -> String {
+        view.days[&ApiDate::from_raw(date)]
+            .iter()
+            .map(|slot| match slot {
+                DaySlot::Recorded(record) => match record.id {
+                    AttendanceId::Integer(id) => char::from_digit(id as u32, 10).unwrap(),
+                    AttendanceId::String(_) => 'x',
+                },
+                DaySlot::NoRecord => '?',
+                DaySlot::NoLesson => '.',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_day_with_gap() {
+        // Lessons 1 to 4, 6 and 12 were filled in on Monday; the student
+        // has records for 1, 2 and 4, and two for 6.
+        let records = vec![
+            record(1, "2025-10-06", 1, "08:50:00"),
+            record(2, "2025-10-06", 2, "09:45:00"),
+            record(4, "2025-10-06", 4, "11:40:00"),
+            record(5, "2025-10-06", 6, "13:30:00"),
+            record(6, "2025-10-06", 6, "14:00:00"),
+            record(7, "2025-10-07", 1, "08:50:00"),
+        ];
+        let view = AttendanceDayView::build(records, &filled(), 1..=8);
+        assert_eq!(view.first_lesson, 1);
+        assert_eq!(view.days.len(), 2);
+        assert_eq!(kinds(&view, "2025-10-06"), "12?4.6..");
+        // Tuesday's lesson was not in the filled-in data.
+        assert_eq!(kinds(&view, "2025-10-07"), "7.......");
+        assert!(view.outside_range.is_empty());
+    }
+
+    #[test]
+    fn test_lesson_outside_range() {
+        let records = vec![
+            record(1, "2025-10-06", 0, "07:50:00"),
+            record(2, "2025-10-06", 9, "16:00:00"),
+            record(3, "2025-10-06", 1, "08:50:00"),
+        ];
+        let view = AttendanceDayView::build(records, &filled(), 1..=8);
+        assert_eq!(kinds(&view, "2025-10-06"), "3???.?..");
+        assert_eq!(view.outside_range.len(), 2);
+
+        // Starting at lesson 0 keeps the early lesson; an empty range keeps
+        // nothing.
+        let records = vec![record(1, "2025-10-06", 0, "07:50:00")];
+        let view = AttendanceDayView::build(records, &[], 0..=2);
+        assert_eq!(kinds(&view, "2025-10-06"), "1..");
+        let records = vec![record(1, "2025-10-06", 3, "07:50:00")];
+        #[allow(clippy::reversed_empty_ranges)]
+        let view = AttendanceDayView::build(records, &filled(), 1..=0);
+        assert!(view.days.values().all(Vec::is_empty));
+        assert_eq!(view.outside_range.len(), 1);
+    }
+
+    #[test]
+    fn test_school_lessons() {
+        let range = |from: &str| LessonRange {
+            from: Some(from.to_string()),
+            to: Some("08:45".to_string()),
+        };
+        let unused = LessonRange::default();
+        let hours = [unused.clone(), range("08:00"), range("08:55"), unused.clone()];
+        assert_eq!(school_lessons(&hours), Some(1..=2));
+        let early = [range("07:10"), range("08:00")];
+        assert_eq!(school_lessons(&early), Some(0..=1));
+        assert_eq!(school_lessons(&[unused]), None);
+        assert_eq!(school_lessons(&[]), None);
+    }
+}
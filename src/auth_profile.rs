@@ -0,0 +1,94 @@
+//! Login flow settings for schools on other Librus instances.
+
+/// URLs, OAuth client and form fields of the login flow.
+///
+/// The default, [`AuthProfile::family_portal()`], is the flow of the Librus
+/// family portal used by most schools. Schools that sign in elsewhere can be
+/// served by another profile, set with
+/// [`ClientBuilder::auth_profile()`](crate::ClientBuilder::auth_profile), or
+/// by trying several in order with
+/// [`ClientBuilder::auth_profiles()`](crate::ClientBuilder::auth_profiles).
+///
+/// # Example
+///
+/// ```rust
+/// use librus_rs::{AuthProfile, ClientBuilder};
+///
+/// let profile = AuthProfile {
+///     name: "custom-client".to_string(),
+///     client_id: "52".to_string(),
+///     ..AuthProfile::family_portal()
+/// };
+/// let builder = ClientBuilder::new().auth_profile(profile);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthProfile {
+    /// Short name of the profile, e.g. `"family-portal"`.
+    pub name: String,
+    /// Base URL of the OAuth server, e.g. `https://api.librus.pl`.
+    pub auth_host: String,
+    /// OAuth client ID sent with the login form, e.g. `"46"`.
+    pub client_id: String,
+    /// Page that starts the login flow and receives the OAuth code.
+    pub portal_url: String,
+    /// Name of the login form's username field.
+    pub login_field: String,
+    /// Name of the login form's password field.
+    pub password_field: String,
+}
+
+impl AuthProfile {
+    /// The Librus family portal, used by most schools.
+    pub fn family_portal() -> Self {
+        Self {
+            name: "family-portal".to_string(),
+            auth_host: "https://api.librus.pl".to_string(),
+            client_id: "46".to_string(),
+            portal_url: "https://synergia.librus.pl/loguj/portalRodzina".to_string(),
+            login_field: "login".to_string(),
+            password_field: "pass".to_string(),
+        }
+    }
+
+    /// The older Synergia login page (`synergia.librus.pl/loguj`), still
+    /// used by some schools.
+    pub fn synergia_login() -> Self {
+        Self {
+            name: "synergia-login".to_string(),
+            portal_url: "https://synergia.librus.pl/loguj".to_string(),
+            ..Self::family_portal()
+        }
+    }
+
+    /// The profiles shipped with the crate, most common first: the family
+    /// portal and the Synergia login page.
+    ///
+    /// No other client ID or login page is known to work, so there is only
+    /// one alternate. Schools on a flow not listed here can build their own
+    /// profile, as in the [`AuthProfile`] example.
+    pub fn known() -> Vec<Self> {
+        vec![Self::family_portal(), Self::synergia_login()]
+    }
+
+    /// URL the login form is posted to.
+    pub(crate) fn auth_url(&self) -> String {
+        format!(
+            "{}/OAuth/Authorization?client_id={}",
+            self.auth_host, self.client_id
+        )
+    }
+
+    /// URL a school unit is chosen at, unless the unit page names another.
+    pub(crate) fn unit_select_url(&self) -> String {
+        format!(
+            "{}/OAuth/Authorization/Unit?client_id={}",
+            self.auth_host, self.client_id
+        )
+    }
+}
+
+impl Default for AuthProfile {
+    fn default() -> Self {
+        Self::family_portal()
+    }
+}
@@ -0,0 +1,281 @@
+//! Recording the steps of the login flow for bug reports.
+//!
+//! Librus changes its login flow from time to time. With
+//! [`ClientBuilder::trace_auth()`](crate::ClientBuilder::trace_auth) each
+//! step is recorded in an [`AuthTrace`], with the password and cookie values
+//! removed, so it can be pasted into an issue.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use reqwest::header::SET_COOKIE;
+use reqwest::Url;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::error::AuthStep;
+use crate::redirects::Followed;
+
+/// Longest body excerpt kept per step, in characters.
+const EXCERPT_CHARS: usize = 200;
+
+/// Replaces secrets in body excerpts.
+const REDACTED: &str = "[redacted]";
+
+/// The steps of one login, see
+/// [`Client::last_auth_trace()`](crate::Client::last_auth_trace).
+#[derive(Debug, Clone, Default)]
+pub struct AuthTrace {
+    steps: Vec<AuthTraceStep>,
+}
+
+impl AuthTrace {
+    /// The recorded steps, in order. A successful login has four.
+    pub fn steps(&self) -> &[AuthTraceStep] {
+        &self.steps
+    }
+}
+
+/// One request of the login flow.
+#[derive(Debug, Clone)]
+pub struct AuthTraceStep {
+    /// The step of the flow.
+    pub step: AuthStep,
+    /// URL requested, without the query string.
+    pub url: String,
+    /// HTTP status of the response.
+    pub status: u16,
+    /// Where the step led: the URL the redirects ended at, or the `goTo`
+    /// target of the login form, without the query string.
+    pub redirect: Option<String>,
+    /// Each redirect followed, as the URL it pointed to without the query
+    /// string.
+    pub hops: Vec<String>,
+    /// Start of the response body, with the password and cookie values
+    /// replaced by `[redacted]`.
+    pub body_excerpt: String,
+    /// Time since the previous step ended, or since the login started.
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for AuthTrace {
+    /// One line per step, e.g. `init 200 https://.../portalRodzina (120 ms)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            write!(
+                f,
+                "{} {} {} ({} ms)",
+                step.step,
+                step.status,
+                step.url,
+                step.elapsed.as_millis()
+            )?;
+            if let Some(redirect) = &step.redirect {
+                write!(f, " -> {redirect}")?;
+            }
+            writeln!(f)?;
+            for hop in &step.hops {
+                writeln!(f, "    via {hop}")?;
+            }
+            if !step.body_excerpt.is_empty() {
+                writeln!(f, "    {}", step.body_excerpt)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Status, URL and cookies of a response, taken before its body is read.
+pub(crate) struct StepResponse {
+    status: u16,
+    url: Url,
+    hops: Vec<String>,
+    cookies: Vec<String>,
+}
+
+/// Collects an [`AuthTrace`] during a login, or nothing when tracing is off.
+pub(crate) struct AuthRecorder {
+    trace: Option<AuthTrace>,
+    password: String,
+    last: Instant,
+}
+
+impl AuthRecorder {
+    pub(crate) fn new(enabled: bool, password: &str) -> Self {
+        Self {
+            trace: enabled.then(AuthTrace::default),
+            password: password.to_string(),
+            last: Instant::now(),
+        }
+    }
+
+    /// The parts of a response the trace needs, if tracing.
+    pub(crate) fn capture(&self, followed: &Followed) -> Option<StepResponse> {
+        self.trace.as_ref()?;
+        let response = &followed.response;
+        let cookies = response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|header| header.to_str().ok())
+            .filter_map(|cookie| cookie.split(';').next()?.split_once('='))
+            .map(|(_, value)| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect();
+        Some(StepResponse {
+            status: response.status().as_u16(),
+            url: response.url().clone(),
+            hops: followed.hops.iter().map(without_query).collect(),
+            cookies,
+        })
+    }
+
+    /// Records a step whose response body is not otherwise needed, reading
+    /// the body only if tracing.
+    pub(crate) async fn record_response(
+        &mut self,
+        step: AuthStep,
+        requested: &str,
+        followed: Followed,
+    ) {
+        let Some(captured) = self.capture(&followed) else {
+            return;
+        };
+        let body = followed.response.text().await.unwrap_or_default();
+        self.record_page(step, requested, captured, &body);
+    }
+
+    /// Records a step whose body was read, taking the URL the redirects
+    /// ended at as its redirect target.
+    pub(crate) fn record_page(
+        &mut self,
+        step: AuthStep,
+        requested: &str,
+        response: StepResponse,
+        body: &str,
+    ) {
+        let redirect = (without_query(&response.url) != without_query_str(requested))
+            .then(|| without_query(&response.url));
+        self.record(step, requested, response, body, redirect);
+    }
+
+    /// Records a step from a captured response and its body.
+    pub(crate) fn record(
+        &mut self,
+        step: AuthStep,
+        requested: &str,
+        response: StepResponse,
+        body: &str,
+        redirect: Option<String>,
+    ) {
+        let elapsed = self.last.elapsed();
+        self.last = Instant::now();
+        let body_excerpt = self.redact(body, &response.cookies);
+        if let Some(trace) = &mut self.trace {
+            trace.steps.push(AuthTraceStep {
+                step,
+                url: without_query_str(requested),
+                status: response.status,
+                redirect,
+                hops: response.hops,
+                body_excerpt,
+                elapsed,
+            });
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> Option<AuthTrace> {
+        self.trace.take()
+    }
+
+    /// Shortens `body` to an excerpt without the password or `cookies`.
+    fn redact(&self, body: &str, cookies: &[String]) -> String {
+        let mut secrets = Zeroizing::new(cookies.to_vec());
+        if !self.password.is_empty() {
+            secrets.push(self.password.clone());
+            secrets.push(form_encode(&self.password));
+        }
+        // Longest first, so a secret containing another is replaced whole.
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        let mut body = body.to_string();
+        for secret in secrets.iter() {
+            body = body.replace(secret.as_str(), REDACTED);
+        }
+        let mut excerpt: String = body
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .take(EXCERPT_CHARS)
+            .collect();
+        if body.chars().count() > EXCERPT_CHARS {
+            excerpt.push('…');
+        }
+        excerpt
+    }
+}
+
+impl Drop for AuthRecorder {
+    fn drop(&mut self) {
+        self.password.zeroize();
+    }
+}
+
+fn without_query(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_query(None);
+    url.set_fragment(None);
+    url.to_string()
+}
+
+fn without_query_str(url: &str) -> String {
+    Url::parse(url).map_or_else(
+        |_| url.split(['?', '#']).next().unwrap_or(url).to_string(),
+        |url| without_query(&url),
+    )
+}
+
+/// `text` as `application/x-www-form-urlencoded` encodes it.
+fn form_encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b' ' => out.push('+'),
+            b if b.is_ascii_alphanumeric() || b"*-._".contains(&b) => out.push(b as char),
+            b => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let recorder = AuthRecorder::new(true, "s3cr3t &pass");
+        let cookies = ["abc123".to_string()];
+        let body = "echo s3cr3t &pass s3cr3t+%26pass DZIENNIKSID=abc123\nend";
+        assert_eq!(
+            recorder.redact(body, &cookies),
+            "echo [redacted] [redacted] DZIENNIKSID=[redacted] end"
+        );
+    }
+
+    #[test]
+    fn test_excerpt_is_shortened() {
+        let recorder = AuthRecorder::new(true, "hunter22");
+        let body = format!("{}hunter22", "ż".repeat(EXCERPT_CHARS));
+        let excerpt = recorder.redact(&body, &[]);
+        assert_eq!(excerpt.chars().count(), EXCERPT_CHARS + 1);
+        assert!(excerpt.ends_with("ż…"));
+    }
+
+    #[test]
+    fn test_url_without_query() {
+        assert_eq!(
+            without_query_str(
+                "https://api.librus.pl/OAuth/Authorization/Grant?client_id=46&code=x"
+            ),
+            "https://api.librus.pl/OAuth/Authorization/Grant"
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Behaviour point totals from notes.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::structs::notes::{Note, NoteCategory};
+use crate::structs::semester::Semester;
+
+/// Behaviour points of one semester, see [`BehaviourPointsSummary`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SemesterPoints {
+    /// Sum of the points of positive notes.
+    pub positive: i32,
+    /// Sum of the points of negative notes, zero or less.
+    pub negative: i32,
+}
+
+impl SemesterPoints {
+    /// Positive and negative points together.
+    pub fn total(&self) -> i32 {
+        self.positive + self.negative
+    }
+}
+
+/// Behaviour points per semester, for schools using a point system.
+///
+/// A note's points come from the note itself or else from its category.
+/// Their sign follows whether the note is positive, since schools send
+/// points both with and without a minus. Notes without points count for
+/// nothing, so the summary of a school without points is empty.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use librus_rs::{BehaviourPointsSummary, Client};
+///
+/// # async fn example() -> Result<(), librus_rs::Error> {
+/// let client = Client::from_env().await?;
+/// let notes = client.notes().await?.notes;
+/// let categories = client.note_categories().await?.categories;
+/// let summary = BehaviourPointsSummary::compute(&notes, &categories);
+/// for (semester, points) in &summary.semesters {
+///     println!("semester {semester}: {} points", points.total());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BehaviourPointsSummary {
+    /// Points by semester, for semesters with at least one scored note.
+    pub semesters: BTreeMap<Semester, SemesterPoints>,
+}
+
+impl BehaviourPointsSummary {
+    /// Totals the points of `notes`, looking up categories in `categories`.
+    pub fn compute(notes: &[Note], categories: &[NoteCategory]) -> Self {
+        let category_points: HashMap<i64, i32> = categories
+            .iter()
+            .filter_map(|category| Some((category.id, category.points?)))
+            .collect();
+        let mut semesters: BTreeMap<Semester, SemesterPoints> = BTreeMap::new();
+        for note in notes {
+            let points = note.points.or_else(|| {
+                let category = note.category.as_ref()?.id()?;
+                category_points.get(&category).copied()
+            });
+            let Some(points) = points else { continue };
+            let entry = semesters.entry(note.semester()).or_default();
+            if note.positive {
+                entry.positive += points.abs();
+            } else {
+                entry.negative -= points.abs();
+            }
+        }
+        Self { semesters }
+    }
+
+    /// Points of the whole school year.
+    pub fn year(&self) -> SemesterPoints {
+        self.semesters
+            .values()
+            .fold(SemesterPoints::default(), |sum, points| SemesterPoints {
+                positive: sum.positive + points.positive,
+                negative: sum.negative + points.negative,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::notes::{ResponseNoteCategories, ResponseNotes};
+
+    fn notes(fixture: &str) -> Vec<Note> {
+        serde_json::from_str::<ResponseNotes>(fixture)
+            .unwrap()
+            .notes
+    }
+
+    fn categories() -> Vec<NoteCategory> {
+        serde_json::from_str::<ResponseNoteCategories>(include_str!(
+            "../tests/fixtures/note_categories.json"
+        ))
+        .unwrap()
+        .categories
+    }
+
+    #[test]
+    fn test_points_per_semester() {
+        let notes = notes(include_str!("../tests/fixtures/notes.json"));
+        let summary = BehaviourPointsSummary::compute(&notes, &categories());
+        // +10 from the note, -5 from the category of the late arrival, and
+        // -3 from a note whose points are sent without a minus.
+        assert_eq!(
+            summary.semesters[&Semester::First],
+            SemesterPoints {
+                positive: 10,
+                negative: -8
+            }
+        );
+        // Points of null fall back to the category's 5.
+        assert_eq!(summary.semesters[&Semester::Second].total(), 5);
+        let year = summary.year();
+        assert_eq!((year.positive, year.negative, year.total()), (15, -8, 7));
+    }
+
+    #[test]
+    fn test_school_without_points() {
+        let notes = notes(include_str!("../tests/fixtures/notes_no_points.json"));
+        let summary = BehaviourPointsSummary::compute(&notes, &[]);
+        assert!(summary.semesters.is_empty());
+        assert_eq!(summary.year().total(), 0);
+    }
+}
@@ -0,0 +1,333 @@
+//! Borrowing variants of the largest list responses.
+//!
+//! The types in the crate root own all their strings, which costs an
+//! allocation per field when parsing multi-megabyte grade or attendance
+//! lists. The types here borrow from the response body instead, copying a
+//! string only when it contains JSON escapes. Fetch the body with
+//! [`Client::grades_raw()`](crate::Client::grades_raw) or
+//! [`Client::attendances_raw()`](crate::Client::attendances_raw), parse it
+//! with [`RawResponse::parse()`], and convert single entries to the owned
+//! types with `From` where needed.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use librus_rs::borrowed::ResponseGrades;
+//! use librus_rs::Client;
+//!
+//! # async fn example() -> Result<(), librus_rs::Error> {
+//! let client = Client::from_env().await?;
+//! let raw = client.grades_raw().await?;
+//! let grades: ResponseGrades<'_> = raw.parse()?;
+//! let fives = grades.grades.iter().filter(|g| g.grade == "5").count();
+//! println!("{fives} fives");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::borrow::Cow;
+
+use serde::Deserialize;
+
+use crate::structs::lessons::AttendanceId;
+use crate::structs::resources::Reference;
+use crate::structs::semester::Semester;
+use crate::types::{ApiDate, ApiDateTime};
+use crate::{Error, ErrorBody, Result};
+
+/// A response body as returned by the API.
+///
+/// Keeps the body alive so that borrowed types can be parsed from it.
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    body: String,
+    keep_full_error_bodies: bool,
+}
+
+impl RawResponse {
+    pub(crate) fn new(body: String, keep_full_error_bodies: bool) -> Self {
+        Self {
+            body,
+            keep_full_error_bodies,
+        }
+    }
+
+    /// The response body.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Returns the response body.
+    pub fn into_body(self) -> String {
+        self.body
+    }
+
+    /// Parses the body, borrowing strings from it where possible.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Parse`] if the body does not match `T`. Like the
+    /// other endpoints, it keeps the start of the body unless the client was
+    /// built with
+    /// [`ClientBuilder::keep_full_error_bodies()`](crate::ClientBuilder::keep_full_error_bodies).
+    pub fn parse<'a, T: Deserialize<'a>>(&'a self) -> Result<T> {
+        serde_json::from_str(&self.body).map_err(|e| Error::Parse {
+            source: e,
+            body: ErrorBody::new(self.body.as_str().into(), self.keep_full_error_bodies),
+        })
+    }
+}
+
+/// A reference to another resource, borrowing its URL.
+///
+/// See [`Reference`] for the meaning of the fields.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Redirect<'a> {
+    /// Resource ID, if sent by the API.
+    #[serde(default)]
+    pub id: Option<i64>,
+    /// API URL to fetch the resource.
+    #[serde(borrow)]
+    pub url: Cow<'a, str>,
+}
+
+impl From<Redirect<'_>> for Reference {
+    fn from(redirect: Redirect<'_>) -> Self {
+        Self {
+            id: redirect.id,
+            url: redirect.url.into_owned().into(),
+        }
+    }
+}
+
+/// A student's grade, borrowing from the response body.
+///
+/// See [`crate::Grade`] for the meaning of the fields.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Grade<'a> {
+    /// Unique grade identifier.
+    pub id: i64,
+    /// Reference to the lesson this grade is from.
+    #[serde(borrow)]
+    pub lesson: Redirect<'a>,
+    /// Reference to the subject.
+    #[serde(borrow)]
+    pub subject: Redirect<'a>,
+    /// Reference to the student who received this grade.
+    #[serde(borrow)]
+    pub student: Redirect<'a>,
+    /// Reference to the grade category.
+    #[serde(borrow)]
+    pub category: Redirect<'a>,
+    /// Reference to the teacher who added this grade.
+    #[serde(borrow)]
+    pub added_by: Redirect<'a>,
+    /// The grade value (e.g., "5", "4+", "A").
+    #[serde(borrow)]
+    pub grade: Cow<'a, str>,
+    /// Date when the grade was given (`YYYY-MM-DD`).
+    #[serde(borrow)]
+    pub date: Cow<'a, str>,
+    /// Date when the grade was added to the system (`YYYY-MM-DD HH:MM:SS`).
+    #[serde(borrow)]
+    pub add_date: Cow<'a, str>,
+    /// Semester the grade belongs to.
+    pub semester: Semester,
+    /// Whether this grade counts toward the average.
+    pub is_constituent: bool,
+    /// Whether this is a semester grade.
+    pub is_semester: bool,
+    /// Whether this is a proposed semester grade.
+    pub is_semester_proposition: bool,
+    /// Whether this is a final grade.
+    pub is_final: bool,
+    /// Whether this is a proposed final grade.
+    pub is_final_proposition: bool,
+    /// Comments on this grade. Owned, as they are few and may embed text.
+    pub comments: Option<Vec<crate::CommentRef>>,
+    /// Reference to an improvement grade.
+    #[serde(borrow)]
+    pub improvement: Option<Redirect<'a>>,
+    /// Reference to a resit grade.
+    #[serde(borrow)]
+    pub resit: Option<Redirect<'a>>,
+}
+
+impl From<Grade<'_>> for crate::Grade {
+    #[allow(deprecated)]
+    fn from(grade: Grade<'_>) -> Self {
+        Self {
+            id: grade.id,
+            lesson: grade.lesson.into(),
+            subject: grade.subject.into(),
+            student: grade.student.into(),
+            category: grade.category.into(),
+            added_by: grade.added_by.into(),
+            grade: grade.grade.into_owned(),
+            date: ApiDate::from_raw(grade.date),
+            add_date: ApiDateTime::from_raw(grade.add_date),
+            semester: i64::from(grade.semester.number()),
+            is_constituent: grade.is_constituent,
+            is_semester: grade.is_semester,
+            is_semester_proposition: grade.is_semester_proposition,
+            is_final: grade.is_final,
+            is_final_proposition: grade.is_final_proposition,
+            comments: grade.comments,
+            improvement: grade.improvement.map(Into::into),
+            resit: grade.resit.map(Into::into),
+            #[cfg(feature = "raw-extras")]
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+/// Response containing all grades, borrowing from the response body.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseGrades<'a> {
+    /// List of grades.
+    #[serde(borrow)]
+    pub grades: Vec<Grade<'a>>,
+    /// API URL for this response.
+    #[serde(borrow)]
+    pub url: Cow<'a, str>,
+}
+
+/// A student's attendance record, borrowing from the response body.
+///
+/// See [`crate::Attendance`] for the meaning of the fields.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Attendance<'a> {
+    /// Unique attendance record identifier.
+    pub id: AttendanceId,
+    /// Reference to the lesson.
+    #[serde(borrow)]
+    pub lesson: Redirect<'a>,
+    /// Reference to the student.
+    #[serde(borrow)]
+    pub student: Redirect<'a>,
+    /// Date of the lesson (`YYYY-MM-DD`).
+    #[serde(borrow)]
+    pub date: Cow<'a, str>,
+    /// Date when the record was added (`YYYY-MM-DD HH:MM:SS`).
+    #[serde(borrow)]
+    pub add_date: Cow<'a, str>,
+    /// Lesson number in the day.
+    pub lesson_no: i32,
+    /// Semester the record belongs to.
+    pub semester: Semester,
+    /// Reference to the attendance type.
+    #[serde(rename = "Type", borrow)]
+    pub attendance_type: Redirect<'a>,
+    /// Reference to the teacher who recorded attendance.
+    #[serde(borrow)]
+    pub added_by: Redirect<'a>,
+    /// Reference to a school trip, if applicable.
+    #[serde(borrow)]
+    pub trip: Option<Redirect<'a>>,
+}
+
+impl From<Attendance<'_>> for crate::Attendance {
+    #[allow(deprecated)]
+    fn from(attendance: Attendance<'_>) -> Self {
+        Self {
+            id: attendance.id,
+            lesson: attendance.lesson.into(),
+            student: attendance.student.into(),
+            date: ApiDate::from_raw(attendance.date),
+            add_date: ApiDateTime::from_raw(attendance.add_date),
+            lesson_no: attendance.lesson_no,
+            semester: i32::from(attendance.semester.number()),
+            attendance_type: attendance.attendance_type.into(),
+            added_by: attendance.added_by.into(),
+            trip: attendance.trip.map(Into::into),
+            #[cfg(feature = "raw-extras")]
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+/// Response containing all attendances, borrowing from the response body.
+///
+/// Unlike [`crate::ResponseAttendances`], only the flat list form is accepted.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseAttendances<'a> {
+    /// List of attendance records.
+    #[serde(borrow)]
+    pub attendances: Vec<Attendance<'a>>,
+    /// API URL for this response.
+    #[serde(borrow)]
+    pub url: Cow<'a, str>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grades_parity_with_owned() {
+        let raw = RawResponse::new(
+            include_str!("../tests/fixtures/grades.json").to_string(),
+            false,
+        );
+        let owned: crate::ResponseGrades = raw.parse().unwrap();
+        let borrowed: ResponseGrades<'_> = raw.parse().unwrap();
+        assert!(matches!(borrowed.grades[0].grade, Cow::Borrowed("5")));
+
+        let converted: Vec<crate::Grade> = borrowed.grades.into_iter().map(Into::into).collect();
+        assert_eq!(
+            serde_json::to_value(&converted).unwrap(),
+            serde_json::to_value(&owned.grades).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_attendances_parity_with_owned() {
+        let raw = RawResponse::new(
+            include_str!("../tests/fixtures/attendances.json").to_string(),
+            false,
+        );
+        let owned: crate::ResponseAttendances = raw.parse().unwrap();
+        let borrowed: ResponseAttendances<'_> = raw.parse().unwrap();
+        // Escaped strings cannot be borrowed and are copied.
+        assert!(matches!(
+            borrowed.attendances[0].lesson.url,
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(borrowed.attendances[1].lesson.url, Cow::Owned(_)));
+
+        let converted: Vec<crate::Attendance> =
+            borrowed.attendances.into_iter().map(Into::into).collect();
+        assert_eq!(
+            serde_json::to_value(&converted).unwrap(),
+            serde_json::to_value(&owned.attendances).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_error_keeps_body() {
+        let raw = RawResponse::new("{}".to_string(), false);
+        match raw.parse::<ResponseGrades<'_>>() {
+            Err(Error::Parse { body, .. }) => assert_eq!(body.as_str(), "{}"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // Long bodies are cut like for the other endpoints, unless the
+        // client keeps full error bodies.
+        let long = format!(r#"{{"Grades": "{}"}}"#, "x".repeat(5000));
+        for keep_full in [false, true] {
+            let raw = RawResponse::new(long.clone(), keep_full);
+            match raw.parse::<ResponseGrades<'_>>() {
+                Err(Error::Parse { body, .. }) => {
+                    assert_eq!(body.len(), long.len());
+                    assert_eq!(body.is_truncated(), !keep_full);
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+    }
+}
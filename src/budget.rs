@@ -0,0 +1,289 @@
+//! A request budget shared by several clients.
+
+use std::collections::VecDeque;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use reqwest::cookie::CookieStore;
+use reqwest::header::{COOKIE, SET_COOKIE};
+use reqwest::{Client as HttpClient, Request, RequestBuilder, Response};
+
+use crate::cookies::FilteredJar;
+use crate::{Error, Result};
+
+/// Weight of an attachment download unless set with
+/// [`RequestBudget::attachment_weight()`].
+const DEFAULT_ATTACHMENT_WEIGHT: u32 = 5;
+
+/// What a client does when a [`RequestBudget`] is used up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BudgetPolicy {
+    /// Wait until enough of the window has passed.
+    #[default]
+    Block,
+    /// Fail the request with [`Error::BudgetExhausted`].
+    Error,
+    /// Send the request anyway and count it in
+    /// [`RequestBudget::over_budget()`].
+    Warn,
+}
+
+/// A limit on the requests sent in a rolling window, shared by all clients
+/// built with it, e.g. to stay under Librus' fair-use thresholds across many
+/// accounts.
+///
+/// Every request counts, including each step of the login flow and each
+/// redirect followed. Attachment downloads count as
+/// [`attachment_weight()`](Self::attachment_weight) requests. Clones share
+/// the requests spent.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use librus_rs::{BudgetPolicy, ClientBuilder, RequestBudget};
+///
+/// # async fn example() -> Result<(), librus_rs::Error> {
+/// let budget = RequestBudget::per_hour(500).policy(BudgetPolicy::Block);
+/// let first = ClientBuilder::new()
+///     .username("rodzic1")
+///     .password("haslo1")
+///     .request_budget(budget.clone())
+///     .build()
+///     .await?;
+/// let second = ClientBuilder::new()
+///     .username("rodzic2")
+///     .password("haslo2")
+///     .request_budget(budget.clone())
+///     .build()
+///     .await?;
+/// println!("{} requests left this hour", budget.remaining());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RequestBudget {
+    limit: u32,
+    window: Duration,
+    policy: BudgetPolicy,
+    attachment_weight: u32,
+    usage: Arc<Mutex<Usage>>,
+}
+
+#[derive(Debug, Default)]
+struct Usage {
+    /// Requests in the window, oldest first, with their weight.
+    spent: VecDeque<(Instant, u32)>,
+    over_budget: u64,
+}
+
+impl Usage {
+    fn prune(&mut self, now: Instant, window: Duration) {
+        while let Some(&(at, _)) = self.spent.front() {
+            if now.duration_since(at) < window {
+                break;
+            }
+            self.spent.pop_front();
+        }
+    }
+
+    fn total(&self) -> u32 {
+        self.spent.iter().map(|&(_, weight)| weight).sum()
+    }
+}
+
+/// How much a request counts against a [`RequestBudget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Cost {
+    Request,
+    Attachment,
+}
+
+impl RequestBudget {
+    /// A budget of `limit` requests in any `window`.
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            policy: BudgetPolicy::default(),
+            attachment_weight: DEFAULT_ATTACHMENT_WEIGHT,
+            usage: Arc::default(),
+        }
+    }
+
+    /// A budget of `limit` requests in any hour.
+    pub fn per_hour(limit: u32) -> Self {
+        Self::new(limit, Duration::from_secs(3600))
+    }
+
+    /// What to do when the budget is used up. Defaults to
+    /// [`BudgetPolicy::Block`].
+    pub fn policy(mut self, policy: BudgetPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// How many requests an attachment download counts as. Defaults to 5.
+    pub fn attachment_weight(mut self, weight: u32) -> Self {
+        self.attachment_weight = weight;
+        self
+    }
+
+    /// Requests spent in the current window.
+    pub fn spent(&self) -> u32 {
+        let mut usage = self.lock();
+        usage.prune(Instant::now(), self.window);
+        usage.total()
+    }
+
+    /// Requests left in the current window.
+    pub fn remaining(&self) -> u32 {
+        self.limit.saturating_sub(self.spent())
+    }
+
+    /// Requests sent over the budget under [`BudgetPolicy::Warn`].
+    pub fn over_budget(&self) -> u64 {
+        self.lock().over_budget
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Usage> {
+        self.usage.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Takes a request of `cost` from the budget, waiting or failing
+    /// according to the policy when it is used up.
+    ///
+    /// A request weighing more than the whole budget is let through once
+    /// nothing else was spent in the window.
+    pub(crate) async fn spend(&self, cost: Cost) -> Result<()> {
+        let weight = match cost {
+            Cost::Request => 1,
+            Cost::Attachment => self.attachment_weight,
+        };
+        loop {
+            let wait = {
+                let mut usage = self.lock();
+                let now = Instant::now();
+                usage.prune(now, self.window);
+                let total = usage.total();
+                let fits = total.saturating_add(weight) <= self.limit || total == 0;
+                if fits || self.policy == BudgetPolicy::Warn {
+                    usage.over_budget += u64::from(!fits);
+                    usage.spent.push_back((now, weight));
+                    return Ok(());
+                }
+                // Wait until enough requests fall out of the window.
+                let mut freed = 0;
+                let mut until = now;
+                for &(at, spent) in &usage.spent {
+                    freed += spent;
+                    until = at + self.window;
+                    if total - freed + weight <= self.limit {
+                        break;
+                    }
+                }
+                until.saturating_duration_since(now)
+            };
+            match self.policy {
+                BudgetPolicy::Error => return Err(Error::BudgetExhausted { retry_after: wait }),
+                _ => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+}
+
+/// The HTTP client of a [`Client`](crate::Client), spending from its
+/// request budget, if any, before each request.
+///
+/// Cookies are kept here rather than in the reqwest client, so clients of a
+/// [`ClientPool`](crate::ClientPool) can share one connection pool while
+/// each keeps its own session.
+#[derive(Debug, Clone)]
+pub(crate) struct Http {
+    client: HttpClient,
+    jar: Arc<FilteredJar>,
+    budget: Option<RequestBudget>,
+}
+
+impl Http {
+    pub(crate) fn new(client: HttpClient, jar: FilteredJar, budget: Option<RequestBudget>) -> Self {
+        Self {
+            client,
+            jar: Arc::new(jar),
+            budget,
+        }
+    }
+
+    /// Sends `request` after spending `cost` from the budget, with the
+    /// cookies of the jar, and stores the cookies the response sets.
+    pub(crate) async fn execute(&self, mut request: Request, cost: Cost) -> Result<Response> {
+        if let Some(budget) = &self.budget {
+            budget.spend(cost).await?;
+        }
+        if !request.headers().contains_key(COOKIE) {
+            if let Some(cookies) = self.jar.cookies(request.url()) {
+                request.headers_mut().insert(COOKIE, cookies);
+            }
+        }
+        let response = self.client.execute(request).await.map_err(Error::from)?;
+        let mut set_cookies = response.headers().get_all(SET_COOKIE).iter();
+        self.jar.set_cookies(&mut set_cookies, response.url());
+        Ok(response)
+    }
+
+    /// Builds and sends a request of `cost`.
+    pub(crate) async fn send(&self, request: RequestBuilder, cost: Cost) -> Result<Response> {
+        self.execute(request.build().map_err(Error::from)?, cost)
+            .await
+    }
+}
+
+impl Deref for Http {
+    type Target = HttpClient;
+
+    fn deref(&self) -> &HttpClient {
+        &self.client
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spend_and_policies() {
+        let budget = RequestBudget::new(3, Duration::from_secs(3600)).policy(BudgetPolicy::Error);
+        budget.spend(Cost::Request).await.unwrap();
+        assert_eq!((budget.spent(), budget.remaining()), (1, 2));
+        // An attachment heavier than what is left does not fit.
+        let attachment = budget.clone().attachment_weight(3);
+        match attachment.spend(Cost::Attachment).await {
+            Err(Error::BudgetExhausted { retry_after }) => {
+                assert!(retry_after > Duration::from_secs(3590))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        budget.spend(Cost::Request).await.unwrap();
+        budget.spend(Cost::Request).await.unwrap();
+        assert!(budget.spend(Cost::Request).await.is_err());
+        assert_eq!(budget.remaining(), 0);
+
+        let warn = budget.clone().policy(BudgetPolicy::Warn);
+        warn.spend(Cost::Request).await.unwrap();
+        assert_eq!((budget.spent(), budget.over_budget()), (4, 1));
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_the_window() {
+        let budget = RequestBudget::new(2, Duration::from_millis(200)).attachment_weight(5);
+        let start = Instant::now();
+        budget.spend(Cost::Request).await.unwrap();
+        budget.spend(Cost::Request).await.unwrap();
+        budget.spend(Cost::Request).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        // Heavier than the whole budget: sent once the window is empty.
+        budget.spend(Cost::Attachment).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(400));
+        assert_eq!(budget.spent(), 5);
+    }
+}
@@ -0,0 +1,135 @@
+//! Logging in many accounts at once.
+//!
+//! Tools that manage several parent accounts would otherwise build each
+//! [`Client`] by hand and log in one after another. [`ClientPool`] builds them
+//! from one [`ClientBuilder`] configuration, logs in a bounded number at a
+//! time and spaces out the login requests so the batch does not trip the
+//! server's rate limit.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use librus_rs::{ClientBuilder, ClientPool};
+//!
+//! # async fn example() {
+//! let pool = ClientPool::new(ClientBuilder::new().normalize_responses());
+//! let accounts = vec![
+//!     ("1234567u".to_string(), "password".to_string()),
+//!     ("7654321u".to_string(), "password".to_string()),
+//! ];
+//! for (login, result) in accounts.iter().zip(pool.authenticate_many(accounts.clone(), 4).await) {
+//!     match result {
+//!         Ok(_client) => println!("{}: logged in", login.0),
+//!         Err(e) => println!("{}: {}", login.0, e),
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+use crate::{Client, ClientBuilder, Endpoints, Result};
+
+/// Default minimum time between the starts of two logins.
+const DEFAULT_LOGIN_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Builds and logs in many [`Client`]s with shared settings.
+///
+/// The clients of one batch share a single connection pool. Every client
+/// keeps its own cookies, since cookies carry the session.
+pub struct ClientPool {
+    builder: ClientBuilder,
+    endpoints: Endpoints,
+    login_interval: Duration,
+    last_login: Arc<Mutex<Option<Instant>>>,
+}
+
+impl ClientPool {
+    /// Creates a pool that configures every client like `builder`.
+    ///
+    /// Credentials set on `builder` are ignored.
+    pub fn new(builder: ClientBuilder) -> Self {
+        Self {
+            builder,
+            endpoints: Endpoints::default(),
+            login_interval: DEFAULT_LOGIN_INTERVAL,
+            last_login: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Sets the minimum time between the starts of two logins.
+    ///
+    /// The limit holds across all batches of this pool. Defaults to 500 ms.
+    pub fn login_interval(mut self, interval: Duration) -> Self {
+        self.login_interval = interval;
+        self
+    }
+
+    /// Logs in every account, at most `concurrency` at a time.
+    ///
+    /// Results are returned in the order of `credentials`. A failed login
+    /// only fails its own entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `credentials` - `(username, password)` pairs
+    /// * `concurrency` - Maximum number of logins in progress; `0` is treated as `1`
+    pub async fn authenticate_many(
+        &self,
+        credentials: Vec<(String, String)>,
+        concurrency: usize,
+    ) -> Vec<Result<Client>> {
+        // If the pool cannot be built, each client tries again on its own
+        // and reports the error.
+        let pool = self.builder.connection_pool().ok();
+        let permits = Arc::new(Semaphore::new(concurrency.max(1)));
+        let mut tasks = JoinSet::new();
+        let count = credentials.len();
+        for (index, (username, password)) in credentials.into_iter().enumerate() {
+            let builder = self.builder.clone().username(username).password(password);
+            let http = pool.clone().map(|pool| builder.http_on(pool));
+            let endpoints = self.endpoints.clone();
+            let permits = Arc::clone(&permits);
+            let last_login = Arc::clone(&self.last_login);
+            let interval = self.login_interval;
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                wait_turn(&last_login, interval).await;
+                let client = match http {
+                    Some(http) => builder.build_on(http, endpoints).await,
+                    None => builder.build_with(endpoints).await,
+                };
+                (index, client)
+            });
+        }
+
+        let mut results: Vec<Option<Result<Client>>> = (0..count).map(|_| None).collect();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((index, result)) => results[index] = Some(result),
+                Err(e) => std::panic::resume_unwind(e.into_panic()),
+            }
+        }
+        results.into_iter().flatten().collect()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+}
+
+/// Waits until `interval` has passed since the previous login started.
+async fn wait_turn(last_login: &Mutex<Option<Instant>>, interval: Duration) {
+    let mut last = last_login.lock().await;
+    if let Some(previous) = *last {
+        tokio::time::sleep_until(previous + interval).await;
+    }
+    *last = Some(Instant::now());
+}
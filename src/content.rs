@@ -0,0 +1,591 @@
+//! Structured data from HTML message and notice bodies.
+//!
+//! Schools paste tables, e.g. daily substitution plans, into message bodies.
+//! [`extract_tables()`] turns them into rows of plain-text cells instead of
+//! the run-on text produced by flattening the whole body.
+//!
+//! # Example
+//!
+//! ```rust
+//! use librus_rs::content::extract_tables;
+//!
+//! let html = "<table><tr><th>Lekcja</th><th>Zastępstwo</th></tr>\
+//!             <tr><td>3</td><td><b>mgr</b> Nowak</td></tr></table>";
+//! let tables = extract_tables(html);
+//! assert_eq!(tables[0].headers, ["Lekcja", "Zastępstwo"]);
+//! assert_eq!(tables[0].rows, [["3", "mgr Nowak"]]);
+//! ```
+
+/// Upper bound for `colspan`, so a malformed value cannot blow up a row.
+const MAX_COLSPAN: usize = 64;
+
+/// A table found in an HTML body.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Table {
+    /// Header cells, empty if the table has no header row.
+    pub headers: Vec<String>,
+    /// Data rows. Cells spanning several columns are repeated in each.
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Extracts the tables of an HTML body, in document order.
+///
+/// The header is taken from `<thead>`, or else from a first row made only of
+/// `<th>` cells. Formatting inside cells is flattened to text and a cell with
+/// `colspan="n"` is repeated `n` times. Tables nested in a cell become part
+/// of that cell's text. Malformed markup is tolerated: unclosed cells and rows
+/// end at the next cell, row or the end of the table.
+pub fn extract_tables(html: &str) -> Vec<Table> {
+    let mut tables = Vec::new();
+    let mut current: Option<TableBuilder> = None;
+    // Depth of tables nested inside a cell of the current table.
+    let mut nested = 0usize;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            if let Some(table) = current.as_mut() {
+                table.text(rest);
+            }
+            break;
+        };
+        if let Some(table) = current.as_mut() {
+            table.text(&rest[..start]);
+        }
+        let Some(len) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + len];
+        rest = &rest[start + len + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if name == "table" {
+            match (closing, current.as_mut()) {
+                (false, None) => current = Some(TableBuilder::default()),
+                (false, Some(_)) => nested += 1,
+                (true, Some(_)) if nested > 0 => nested -= 1,
+                (true, Some(_)) => tables.extend(current.take().map(TableBuilder::finish)),
+                (true, None) => {}
+            }
+            continue;
+        }
+        let Some(table) = current.as_mut() else {
+            continue;
+        };
+        if nested > 0 {
+            // Keep cells of nested tables apart in the outer cell's text.
+            if matches!(name.as_str(), "td" | "th" | "tr" | "br") {
+                table.text(" ");
+            }
+            continue;
+        }
+        match (name.as_str(), closing) {
+            ("thead", false) => table.in_head = true,
+            ("thead", true) => table.in_head = false,
+            ("tbody" | "tfoot", false) => table.in_head = false,
+            ("tr", false) => table.start_row(),
+            ("tr", true) => table.end_row(),
+            ("td" | "th", false) => {
+                let colspan = attribute(tag, "colspan")
+                    .and_then(|span| span.trim().parse().ok())
+                    .unwrap_or(1);
+                table.start_cell(name == "th", colspan);
+            }
+            ("td" | "th", true) => table.end_cell(),
+            ("br" | "p" | "div" | "li", _) => table.text(" "),
+            _ => {}
+        }
+    }
+    // An unclosed table still counts.
+    tables.extend(current.map(TableBuilder::finish));
+    tables
+}
+
+#[derive(Default)]
+struct TableBuilder {
+    in_head: bool,
+    head_rows: Vec<Vec<String>>,
+    /// Rows with, for each, whether every cell was a `<th>`.
+    rows: Vec<(Vec<String>, bool)>,
+    row: Option<Row>,
+    cell: Option<Cell>,
+}
+
+#[derive(Default)]
+struct Row {
+    cells: Vec<String>,
+    all_header: bool,
+    in_head: bool,
+}
+
+struct Cell {
+    text: String,
+    header: bool,
+    colspan: usize,
+}
+
+impl TableBuilder {
+    fn text(&mut self, text: &str) {
+        if let Some(cell) = self.cell.as_mut() {
+            cell.text.push_str(text);
+        }
+    }
+
+    fn start_row(&mut self) {
+        self.end_row();
+        self.row = Some(Row {
+            all_header: true,
+            in_head: self.in_head,
+            ..Row::default()
+        });
+    }
+
+    fn end_row(&mut self) {
+        self.end_cell();
+        let Some(row) = self.row.take() else {
+            return;
+        };
+        if row.cells.is_empty() {
+            return;
+        }
+        if row.in_head {
+            self.head_rows.push(row.cells);
+        } else {
+            self.rows.push((row.cells, row.all_header));
+        }
+    }
+
+    fn start_cell(&mut self, header: bool, colspan: usize) {
+        self.end_cell();
+        if self.row.is_none() {
+            // A cell outside `<tr>` starts an implicit row.
+            self.start_row();
+        }
+        self.cell = Some(Cell {
+            text: String::new(),
+            header,
+            colspan: colspan.clamp(1, MAX_COLSPAN),
+        });
+    }
+
+    fn end_cell(&mut self) {
+        let (Some(cell), Some(row)) = (self.cell.take(), self.row.as_mut()) else {
+            return;
+        };
+        let text = decode_entities(&cell.text)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        row.all_header &= cell.header;
+        row.cells.extend(std::iter::repeat_n(text, cell.colspan));
+    }
+
+    fn finish(mut self) -> Table {
+        self.end_row();
+        let mut rows = self.rows.into_iter().peekable();
+        let headers = match self.head_rows.into_iter().next() {
+            Some(headers) => headers,
+            None => rows
+                .next_if(|(_, all_header)| *all_header)
+                .map(|(cells, _)| cells)
+                .unwrap_or_default(),
+        };
+        Table {
+            headers,
+            rows: rows.map(|(cells, _)| cells).collect(),
+        }
+    }
+}
+
+/// Decodes the few entities found in Librus pages.
+pub(crate) fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Value of an attribute in an opening tag, quoted or not.
+pub(crate) fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    loop {
+        let at = rest.find(name)?;
+        let before = rest[..at].chars().next_back();
+        let after = rest[at + name.len()..].trim_start();
+        rest = &rest[at + name.len()..];
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let Some(value) = after.strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        return match value.chars().next()? {
+            quote @ ('"' | '\'') => {
+                let value = &value[1..];
+                value.find(quote).map(|end| &value[..end])
+            }
+            _ => value.split(|c: char| c.is_whitespace() || c == '/').next(),
+        };
+    }
+}
+
+/// How [`render()`] writes formatting.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Style {
+    /// Plain text; link targets follow their text in parentheses.
+    Text,
+    /// Markdown as rendered by chat apps such as Discord and Matrix.
+    Markdown,
+}
+
+/// Renders a body that is plain text or HTML, keeping line breaks,
+/// paragraphs, list items and links.
+///
+/// A `<` only starts a tag when followed by a letter, `/` or `!`, so text
+/// such as `x < 5` is kept. Runs of spaces are collapsed and at most one
+/// blank line is kept between paragraphs.
+pub(crate) fn render(body: &str, style: Style) -> String {
+    let markdown = style == Style::Markdown;
+    let mut out = String::with_capacity(body.len());
+    // Start of the current link's text in `out`, and its target.
+    let mut link: Option<(usize, String)> = None;
+    let mut rest = body;
+
+    while !rest.is_empty() {
+        let (text, tag) = match next_tag(rest) {
+            Some((start, end)) => {
+                let parts = (&rest[..start], Some(&rest[start + 1..end]));
+                rest = &rest[end + 1..];
+                parts
+            }
+            None => (std::mem::take(&mut rest), None),
+        };
+        push_text(&mut out, &decode_entities(text), markdown);
+        let Some(tag) = tag else {
+            break;
+        };
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match (name.as_str(), closing) {
+            ("br", _) => out.push('\n'),
+            ("p" | "ul" | "ol" | "table" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6", _) => {
+                out.push_str("\n\n")
+            }
+            ("div" | "tr", _) => out.push('\n'),
+            ("li", false) => out.push_str("\n- "),
+            ("td" | "th", false) => out.push(' '),
+            ("b" | "strong", _) if markdown => out.push_str("**"),
+            ("i" | "em", _) if markdown => out.push('*'),
+            ("a", false) => {
+                link = href(tag).map(|href| (out.len(), href));
+            }
+            ("a", true) => {
+                if let Some((start, href)) = link.take() {
+                    let text = out[start..].trim().to_string();
+                    out.truncate(start);
+                    out.push_str(&match style {
+                        _ if text.is_empty() || text == href => href,
+                        Style::Markdown => format!("[{text}]({href})"),
+                        Style::Text if text.contains(&href) => text,
+                        Style::Text => format!("{text} ({href})"),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    normalize_whitespace(&out)
+}
+
+/// Byte offsets of the `<` and `>` of the next tag in `text`.
+fn next_tag(text: &str) -> Option<(usize, usize)> {
+    let mut from = 0;
+    loop {
+        let start = from + text[from..].find('<')?;
+        let starts_tag = text[start + 1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '/' || c == '!');
+        if starts_tag {
+            let end = start + text[start..].find('>')?;
+            return Some((start, end));
+        }
+        from = start + 1;
+    }
+}
+
+/// Appends text, escaping Markdown syntax outside of URLs.
+fn push_text(out: &mut String, text: &str, markdown: bool) {
+    if !markdown {
+        out.push_str(text);
+        return;
+    }
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (before, url) = match find_url(rest) {
+            Some((start, end)) => {
+                let parts = (&rest[..start], &rest[start..end]);
+                rest = &rest[end..];
+                parts
+            }
+            None => (std::mem::take(&mut rest), ""),
+        };
+        for c in before.chars() {
+            if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '~') {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+        out.push_str(url);
+    }
+}
+
+/// Collapses runs of spaces, trims lines and keeps at most one blank line in
+/// a row.
+fn normalize_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank = false;
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            blank = !out.is_empty();
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if blank { "\n\n" } else { "\n" });
+        }
+        out.push_str(&line);
+        blank = false;
+    }
+    out
+}
+
+/// Links in a body: `href`s of `<a>` tags and bare `http(s)://` URLs in the
+/// text, in order of appearance and without duplicates.
+pub(crate) fn extract_links(body: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    let mut add = |url: String| {
+        if !links.contains(&url) {
+            links.push(url);
+        }
+    };
+    let mut rest = body;
+    while !rest.is_empty() {
+        let (text, tag) = match next_tag(rest) {
+            Some((start, end)) => {
+                let parts = (&rest[..start], Some(&rest[start + 1..end]));
+                rest = &rest[end + 1..];
+                parts
+            }
+            None => (std::mem::take(&mut rest), None),
+        };
+        let text = decode_entities(text);
+        let mut from = 0;
+        while let Some((start, end)) = find_url(&text[from..]) {
+            add(text[from + start..from + end].to_string());
+            from += end;
+        }
+
+        let Some(tag) = tag else {
+            break;
+        };
+        let is_link = tag
+            .split(|c: char| c.is_whitespace() || c == '>')
+            .next()
+            .is_some_and(|name| name.eq_ignore_ascii_case("a"));
+        if let Some(href) = href(tag).filter(|href| is_link && is_web_url(href)) {
+            add(href);
+        }
+    }
+    links
+}
+
+/// Decoded `href` of a tag, in either case.
+fn href(tag: &str) -> Option<String> {
+    attribute(tag, "href")
+        .or_else(|| attribute(tag, "HREF"))
+        .map(|href| decode_entities(href.trim()))
+}
+
+fn is_web_url(text: &str) -> bool {
+    let lower = text.get(..8).unwrap_or(text).to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Byte range of the first bare `http(s)://` URL in `text`.
+///
+/// A URL ends at whitespace, `<`, `>`, quotes and Polish quotation marks.
+/// Trailing sentence punctuation, dashes and closing brackets without an
+/// opening one in the URL are not part of it.
+fn find_url(text: &str) -> Option<(usize, usize)> {
+    let mut from = 0;
+    let start = loop {
+        let at = from + text[from..].find(['h', 'H'])?;
+        let preceded_by_word = text[..at]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric);
+        if !preceded_by_word && is_web_url(&text[at..]) {
+            break at;
+        }
+        from = at + 1;
+    };
+    let scheme = text[start..].find("//")? + 2;
+    let len = text[start..]
+        .find(|c: char| {
+            c.is_whitespace() || matches!(c, '<' | '>' | '"' | '„' | '”' | '“' | '«' | '»')
+        })
+        .unwrap_or(text.len() - start);
+    let mut url = &text[start..start + len];
+    while let Some(last) = url.chars().next_back() {
+        let unbalanced = |open: char| url.matches(open).count() < url.matches(last).count();
+        let trim = match last {
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '’' | '…' | '–' | '—' => true,
+            ')' => unbalanced('('),
+            ']' => unbalanced('['),
+            '}' => unbalanced('{'),
+            _ => false,
+        };
+        if !trim {
+            break;
+        }
+        url = &url[..url.len() - last.len_utf8()];
+    }
+    (url.len() > scheme).then_some((start, start + url.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_table_with_thead() {
+        let tables = extract_tables(include_str!("../tests/fixtures/table_thead.html"));
+        assert_eq!(tables.len(), 1);
+        assert_eq!(
+            tables[0].headers,
+            strings(&[
+                "Lekcja",
+                "Klasa",
+                "Nauczyciel nieobecny",
+                "Zastępstwo",
+                "Sala"
+            ])
+        );
+        assert_eq!(
+            tables[0].rows,
+            [
+                strings(&["1", "4a", "Anna Kowalska", "Jan Nowak", "12"]),
+                strings(&["3", "5b", "Anna Kowalska", "Lekcja odwołana", ""]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_table_with_colspan_and_th_row() {
+        let tables = extract_tables(include_str!("../tests/fixtures/table_colspan.html"));
+        assert_eq!(tables.len(), 1);
+        assert_eq!(
+            tables[0].headers,
+            strings(&["Godz.", "Klasa", "Przedmiot", "Uwagi"])
+        );
+        assert_eq!(
+            tables[0].rows,
+            [
+                strings(&[
+                    "8:00",
+                    "6c",
+                    "Matematyka (za p. Wiśniewską)",
+                    "sala 21 → 14"
+                ]),
+                strings(&[
+                    "8:55",
+                    "Wycieczka klas szóstych",
+                    "Wycieczka klas szóstych",
+                    "Wycieczka klas szóstych"
+                ]),
+                strings(&["9:50", "6a", "Język polski", "Zajęcia & konsultacje"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_table_without_header_row() {
+        let html = include_str!("../tests/fixtures/table_no_header.html");
+        let tables = extract_tables(html);
+        assert_eq!(tables.len(), 2);
+        assert!(tables[0].headers.is_empty());
+        assert_eq!(
+            tables[0].rows,
+            [
+                strings(&["Poniedziałek", "lekcje do 12:35"]),
+                strings(&["Wtorek", "1–2 lekcja: apel"]),
+            ]
+        );
+        // A table nested in a cell is part of the outer cell's text.
+        assert_eq!(tables[1].rows, [strings(&["Uwaga", "A B"])]);
+    }
+
+    #[test]
+    fn test_bare_url_boundaries() {
+        let links = |text: &str| extract_links(text);
+        assert_eq!(links("(zob. https://a.pl/x)."), ["https://a.pl/x"]);
+        assert_eq!(
+            links("„https://a.pl/x”, «https://b.pl»"),
+            ["https://a.pl/x", "https://b.pl"]
+        );
+        assert_eq!(links("https://a.pl/x… i dalej"), ["https://a.pl/x"]);
+        assert_eq!(links("HTTPS://A.PL/ą?x=1;"), ["HTTPS://A.PL/ą?x=1"]);
+        assert_eq!(
+            links("https://a.pl/wiki/X_(y)"),
+            ["https://a.pl/wiki/X_(y)"]
+        );
+        assert!(links("xhttps://a.pl http:// https:").is_empty());
+        assert_eq!(
+            links(r#"<a href='http://a.pl/'>http://a.pl/</a> <a name="x">"#),
+            ["http://a.pl/"]
+        );
+        assert!(links(r#"<a href="mailto:a@b.pl">a@b.pl</a>"#).is_empty());
+    }
+
+    #[test]
+    fn test_render_links() {
+        let html = r#"<a href="https://a.pl">https://a.pl</a>, <a href="https://b.pl"></a>"#;
+        assert_eq!(render(html, Style::Text), "https://a.pl, https://b.pl");
+        assert_eq!(render(html, Style::Markdown), "https://a.pl, https://b.pl");
+        assert_eq!(
+            render(r#"<A HREF="https://a.pl">see_this</A>"#, Style::Markdown),
+            "[see\\_this](https://a.pl)"
+        );
+    }
+
+    #[test]
+    fn test_no_tables() {
+        assert!(extract_tables("<p>Dzień dobry</p>").is_empty());
+        assert!(extract_tables("").is_empty());
+        assert_eq!(
+            extract_tables("<table><tr><td>x<td>y")[0].rows,
+            [strings(&["x", "y"])]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Filtering which cookies the client keeps.
+
+use reqwest::cookie::{CookieStore, Jar};
+use reqwest::header::HeaderValue;
+use reqwest::Url;
+
+/// Which cookies set by Librus the client stores and sends back, see
+/// [`ClientBuilder::cookie_filter()`](crate::ClientBuilder::cookie_filter).
+///
+/// Librus sets analytics and advertising cookies during login, which would
+/// otherwise be replayed on every API call.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum CookieFilter {
+    /// Keep every cookie, like a browser would.
+    #[default]
+    All,
+    /// Keep only the cookies the login flow and both APIs need:
+    ///
+    /// - `DZIENNIKSID` and `SDZIENNIKSID`, the Synergia session,
+    /// - `oauth_state` and `oauth_token`, set during the OAuth login,
+    /// - `PHPSESSID`, the login form and Messages session,
+    /// - `AWSALB*`, the load balancer routing a session to one server.
+    EssentialOnly,
+    /// Keep cookies whose name the function accepts.
+    Custom(fn(&str) -> bool),
+}
+
+/// Names of the cookies kept by [`CookieFilter::EssentialOnly`].
+const ESSENTIAL_COOKIES: &[&str] = &[
+    "DZIENNIKSID",
+    "SDZIENNIKSID",
+    "oauth_state",
+    "oauth_token",
+    "PHPSESSID",
+];
+
+/// Prefix of the load balancer stickiness cookies.
+const LOAD_BALANCER_PREFIX: &str = "AWSALB";
+
+impl CookieFilter {
+    /// Whether a cookie named `name` is stored.
+    fn keeps(self, name: &str) -> bool {
+        match self {
+            CookieFilter::All => true,
+            CookieFilter::EssentialOnly => {
+                ESSENTIAL_COOKIES.contains(&name) || name.starts_with(LOAD_BALANCER_PREFIX)
+            }
+            CookieFilter::Custom(keep) => keep(name),
+        }
+    }
+}
+
+/// A cookie jar that drops the cookies rejected by a [`CookieFilter`] when
+/// they are set, so they are never sent back.
+#[derive(Debug)]
+pub(crate) struct FilteredJar {
+    jar: Jar,
+    filter: CookieFilter,
+}
+
+impl FilteredJar {
+    pub(crate) fn new(filter: CookieFilter) -> Self {
+        Self {
+            jar: Jar::default(),
+            filter,
+        }
+    }
+}
+
+impl CookieStore for FilteredJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let filter = self.filter;
+        let mut kept = cookie_headers.filter(|header| {
+            header
+                .to_str()
+                .ok()
+                .and_then(|cookie| cookie.split_once('='))
+                .is_some_and(|(name, _)| filter.keeps(name.trim()))
+        });
+        self.jar.set_cookies(&mut kept, url);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        self.jar.cookies(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters() {
+        assert!(CookieFilter::All.keeps("_ga"));
+        assert!(CookieFilter::EssentialOnly.keeps("DZIENNIKSID"));
+        assert!(CookieFilter::EssentialOnly.keeps("AWSALBCORS"));
+        assert!(!CookieFilter::EssentialOnly.keeps("_ga"));
+        assert!(!CookieFilter::EssentialOnly.keeps("dziennikSID"));
+        let custom = CookieFilter::Custom(|name| !name.starts_with('_'));
+        assert!(custom.keeps("consent"));
+        assert!(!custom.keeps("_fbp"));
+    }
+
+    #[test]
+    fn test_jar_drops_rejected_cookies() {
+        let url: Url = "https://synergia.librus.pl/".parse().unwrap();
+        let jar = FilteredJar::new(CookieFilter::EssentialOnly);
+        let headers = [
+            HeaderValue::from_static("DZIENNIKSID=abc; Path=/"),
+            HeaderValue::from_static("_ga=GA1.2.3; Path=/"),
+            HeaderValue::from_static("malformed"),
+        ];
+        jar.set_cookies(&mut headers.iter(), &url);
+        assert_eq!(jar.cookies(&url).unwrap(), "DZIENNIKSID=abc");
+    }
+}
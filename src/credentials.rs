@@ -0,0 +1,144 @@
+//! Checking credentials before they are sent to Librus.
+//!
+//! A failed login counts against the account's lockout limit, so obviously
+//! malformed credentials (an empty username from an unset variable, a
+//! password pasted with a trailing newline) are rejected locally.
+
+use crate::{Error, Result};
+
+/// Longest password the Librus login form accepts.
+const MAX_PASSWORD_LEN: usize = 128;
+
+/// How strictly [`validate()`] checks the credentials.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Leniency {
+    /// Accept usernames that do not look like an account ID.
+    pub username: bool,
+    /// Send the password as given, only rejecting a blank one.
+    pub password: bool,
+}
+
+/// Validates `username` and `password` and returns the trimmed username.
+///
+/// Surrounding whitespace is removed from the username, since it never
+/// belongs there. The password is not changed; surrounding whitespace in it
+/// is reported instead, as it is almost always a copy-paste accident from a
+/// password manager, and so is a password longer than [`MAX_PASSWORD_LEN`]
+/// characters.
+///
+/// With `lenient.username`, usernames that do not look like an account ID
+/// (digits with an optional `u` suffix) are accepted as well. With
+/// `lenient.password`, only a blank password is rejected.
+pub(crate) fn validate(username: &str, password: &str, lenient: Leniency) -> Result<String> {
+    let username = username.trim();
+    if username.is_empty() {
+        return Err(invalid("username", "is empty"));
+    }
+    if !lenient.username && !is_account_id(username) {
+        return Err(invalid(
+            "username",
+            "is not an account ID (digits, optionally followed by `u`)",
+        ));
+    }
+
+    if password.trim().is_empty() {
+        return Err(invalid("password", "is empty"));
+    }
+    if lenient.password {
+        return Ok(username.to_string());
+    }
+    if password.trim() != password {
+        return Err(invalid("password", "has leading or trailing whitespace"));
+    }
+    if password.chars().count() > MAX_PASSWORD_LEN {
+        return Err(invalid("password", "is longer than 128 characters"));
+    }
+    Ok(username.to_string())
+}
+
+/// Whether `username` looks like a Librus account ID, e.g. `1234567u`.
+fn is_account_id(username: &str) -> bool {
+    let digits = username.strip_suffix('u').unwrap_or(username);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn invalid(field: &'static str, reason: &'static str) -> Error {
+    Error::InvalidCredentialFormat { field, reason }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STRICT: Leniency = Leniency {
+        username: false,
+        password: false,
+    };
+    const LENIENT_USERNAME: Leniency = Leniency {
+        username: true,
+        password: false,
+    };
+
+    fn reason(result: Result<String>) -> (&'static str, &'static str) {
+        match result {
+            Err(Error::InvalidCredentialFormat { field, reason }) => (field, reason),
+            other => panic!("expected InvalidCredentialFormat, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_username_is_trimmed() {
+        assert_eq!(
+            validate(" 1234567u\n", "secret", STRICT).unwrap(),
+            "1234567u"
+        );
+        assert_eq!(validate("1234567", "secret", STRICT).unwrap(), "1234567");
+    }
+
+    #[test]
+    fn test_blank_fields_are_rejected() {
+        assert_eq!(reason(validate("  ", "secret", STRICT)).0, "username");
+        assert_eq!(reason(validate("", "secret", LENIENT_USERNAME)).0, "username");
+        assert_eq!(
+            reason(validate("1234567u", " \t", STRICT)),
+            ("password", "is empty")
+        );
+    }
+
+    #[test]
+    fn test_password_whitespace_is_reported() {
+        assert_eq!(
+            reason(validate("1234567u", "secret ", STRICT)),
+            ("password", "has leading or trailing whitespace")
+        );
+        assert!(validate("1234567u", "two words", STRICT).is_ok());
+    }
+
+    #[test]
+    fn test_password_length() {
+        let longest = "x".repeat(MAX_PASSWORD_LEN);
+        assert!(validate("1234567u", &longest, STRICT).is_ok());
+        let too_long = "x".repeat(MAX_PASSWORD_LEN + 1);
+        assert_eq!(reason(validate("1234567u", &too_long, STRICT)).0, "password");
+    }
+
+    #[test]
+    fn test_lenient_password_is_sent_as_given() {
+        let lenient = Leniency {
+            username: false,
+            password: true,
+        };
+        for password in ["secret ", " two words", &"x".repeat(1000)] {
+            assert!(validate("1234567u", password, lenient).is_ok());
+        }
+        assert_eq!(reason(validate("1234567u", " ", lenient)).0, "password");
+    }
+
+    #[test]
+    fn test_username_pattern_and_lenient_mode() {
+        for login in ["jan.kowalski", "u", "123u4", "1234567U"] {
+            assert_eq!(reason(validate(login, "secret", STRICT)).0, "username");
+            assert!(validate(login, "secret", LENIENT_USERNAME).is_ok());
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Minimal calendar arithmetic on `YYYY-MM-DD` strings.
+//!
+//! The API returns dates as strings; these helpers convert them to day numbers
+//! (days since 1970-01-01) so they can be compared and offset without pulling
+//! in a full date library.
+
+/// Parses the date part of a `YYYY-MM-DD` (optionally followed by a time)
+/// string into a day number.
+pub(crate) fn parse_day(date: &str) -> Option<i64> {
+    let date = date.get(..10)?;
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+/// Formats a day number as `YYYY-MM-DD`.
+pub(crate) fn format_day(day: i64) -> String {
+    let (y, m, d) = civil_from_days(day);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// Day of the week for a day number, with Monday = 0 and Sunday = 6.
+pub(crate) fn weekday(day: i64) -> u32 {
+    // 1970-01-01 was a Thursday.
+    (day + 3).rem_euclid(7) as u32
+}
+
+/// Year of a day number.
+pub(crate) fn year_of(day: i64) -> i64 {
+    civil_from_days(day).0
+}
+
+/// Day number of Easter Sunday in the given year (Gregorian calendar).
+pub(crate) fn easter_sunday(year: i64) -> i64 {
+    // Anonymous Gregorian algorithm (Meeus/Jones/Butcher).
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    days_from_civil(year, month as u32, day as u32)
+}
+
+/// Parses an HTTP `Date` header (`Sun, 06 Nov 1994 08:49:37 GMT`) into Unix
+/// seconds.
+pub(crate) fn parse_http_date(value: &str) -> Option<i64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let mut parts = value.split_whitespace().skip(1);
+    let day: u32 = parts.next()?.parse().ok()?;
+    let month = parts.next()?;
+    let month = MONTHS.iter().position(|m| *m == month)? as u32 + 1;
+    let year: i64 = parts.next()?.parse().ok()?;
+    let secs = parse_time(parts.next()?)?;
+    if parts.next()? != "GMT" || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day) * 86_400 + secs)
+}
+
+/// Converts a Polish local date (`YYYY-MM-DD`) and time (`HH:MM:SS`) into Unix
+/// seconds, applying Central European (Summer) Time.
+pub(crate) fn parse_warsaw_datetime(date: &str, time: &str) -> Option<i64> {
+    Some(warsaw_to_unix(parse_day(date)?, parse_time(time)?))
+}
+
+/// Converts a Polish local day number and seconds since midnight into Unix
+/// seconds.
+pub(crate) fn warsaw_to_unix(day: i64, secs: i64) -> i64 {
+    let local = day * 86_400 + secs;
+    let winter = local - 3_600;
+    local - warsaw_offset(winter)
+}
+
+/// Converts a Polish local timestamp (`YYYY-MM-DD HH:MM:SS`, or a bare
+/// `YYYY-MM-DD` meaning midnight) into Unix seconds.
+pub(crate) fn parse_warsaw_timestamp(value: &str) -> Option<i64> {
+    let value = value.trim();
+    match value.split_once([' ', 'T']) {
+        Some((date, time)) => parse_warsaw_datetime(date, time),
+        None => parse_warsaw_datetime(value, "00:00:00"),
+    }
+}
+
+/// Day number of the Polish local date at the given Unix time.
+pub(crate) fn warsaw_day(unix: i64) -> i64 {
+    (unix + warsaw_offset(unix)).div_euclid(86_400)
+}
+
+/// Seconds since Polish local midnight at the given Unix time.
+pub(crate) fn warsaw_time_of_day(unix: i64) -> i64 {
+    (unix + warsaw_offset(unix)).rem_euclid(86_400)
+}
+
+/// Formats Unix seconds as Polish local time, `YYYY-MM-DD HH:MM:SS`.
+pub(crate) fn format_warsaw_datetime(unix: i64) -> String {
+    let local = unix + warsaw_offset(unix);
+    let secs = local.rem_euclid(86_400);
+    format!(
+        "{} {:02}:{:02}:{:02}",
+        format_day(local.div_euclid(86_400)),
+        secs / 3_600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Formats Unix seconds as an RFC 3339 UTC timestamp, `YYYY-MM-DDTHH:MM:SSZ`.
+#[cfg(feature = "feed")]
+pub(crate) fn format_rfc3339_utc(unix: i64) -> String {
+    let secs = unix.rem_euclid(86_400);
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        format_day(unix.div_euclid(86_400)),
+        secs / 3_600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Offset of Polish local time from UTC at the given Unix time, in seconds.
+fn warsaw_offset(unix: i64) -> i64 {
+    // Summer time runs from 01:00 UTC on the last Sunday of March until 01:00
+    // UTC on the last Sunday of October.
+    let year = year_of(unix.div_euclid(86_400));
+    let summer_start = last_sunday(year, 3) * 86_400 + 3_600;
+    let summer_end = last_sunday(year, 10) * 86_400 + 3_600;
+    if (summer_start..summer_end).contains(&unix) {
+        7_200
+    } else {
+        3_600
+    }
+}
+
+/// Parses `HH:MM:SS` into seconds since midnight.
+fn parse_time(time: &str) -> Option<i64> {
+    let mut parts = time.splitn(3, ':');
+    let h: i64 = parts.next()?.parse().ok()?;
+    let m: i64 = parts.next()?.parse().ok()?;
+    let s: i64 = parts.next()?.parse().ok()?;
+    if !(0..24).contains(&h) || !(0..60).contains(&m) || !(0..61).contains(&s) {
+        return None;
+    }
+    Some(h * 3_600 + m * 60 + s)
+}
+
+fn last_sunday(year: i64, month: u32) -> i64 {
+    let last = days_from_civil(year, month, days_in_month(year, month));
+    last - i64::from((weekday(last) + 1) % 7)
+}
+
+fn is_leap(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Algorithms from Howard Hinnant's "chrono-Compatible Low-Level Date Algorithms".
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = i64::from(month);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + i64::from(m <= 2), m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_day() {
+        assert_eq!(parse_day("1970-01-01"), Some(0));
+        assert_eq!(parse_day("2024-03-01 08:15:00"), Some(19_783));
+        assert_eq!(
+            parse_day("2024-02-29").map(|d| d + 1),
+            parse_day("2024-03-01")
+        );
+        assert_eq!(parse_day("2023-02-29"), None);
+        assert_eq!(parse_day("garbage"), None);
+    }
+
+    #[test]
+    fn test_format_weekday_and_easter() {
+        let day = parse_day("2024-02-29").unwrap();
+        assert_eq!(format_day(day), "2024-02-29");
+        assert_eq!(format_day(day + 1), "2024-03-01");
+        assert_eq!(format_day(-1), "1969-12-31");
+        // 2024-02-29 was a Thursday.
+        assert_eq!(weekday(day), 3);
+        assert_eq!(format_day(easter_sunday(2024)), "2024-03-31");
+        assert_eq!(format_day(easter_sunday(2025)), "2025-04-20");
+    }
+
+    #[test]
+    fn test_http_date_and_warsaw_time() {
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(784_111_777)
+        );
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37"), None);
+        // Winter time is UTC+1, summer time UTC+2.
+        let winter = parse_http_date("Mon, 10 Mar 2025 11:00:00 GMT");
+        assert_eq!(parse_warsaw_datetime("2025-03-10", "12:00:00"), winter);
+        let summer = parse_http_date("Mon, 31 Mar 2025 10:00:00 GMT");
+        assert_eq!(parse_warsaw_datetime("2025-03-31", "12:00:00"), summer);
+        // The switch happens at 02:00 local time on 2025-03-30.
+        let switch = parse_http_date("Sun, 30 Mar 2025 01:00:00 GMT");
+        assert_eq!(parse_warsaw_datetime("2025-03-30", "03:00:00"), switch);
+        // 23:30 UTC in summer is already the next day in Poland.
+        let late = parse_http_date("Tue, 01 Jul 2025 23:30:00 GMT").unwrap();
+        assert_eq!(format_day(warsaw_day(late)), "2025-07-02");
+
+        assert_eq!(parse_warsaw_timestamp("2025-03-10 12:00:00"), winter);
+        assert_eq!(parse_warsaw_timestamp("2025-03-10T12:00:00"), winter);
+        assert_eq!(
+            parse_warsaw_timestamp("2025-03-10"),
+            winter.map(|t| t - 12 * 3_600)
+        );
+        assert_eq!(parse_warsaw_timestamp("2025-03-10 25:00:00"), None);
+        #[cfg(feature = "feed")]
+        assert_eq!(
+            winter.map(format_rfc3339_utc).as_deref(),
+            Some("2025-03-10T11:00:00Z")
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Decoding of base64 message bodies.
+//!
+//! Some message bodies, e.g. forwarded messages and messages sent from
+//! certain versions of the mobile app, are base64-encoded twice. A second
+//! round is only applied when its result looks like text, so a plain body
+//! that happens to be valid base64 (`"Test"`, `"abcd1234"`) is kept as is.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::content::{extract_tables, Table};
+
+/// Maximum number of base64 rounds undone.
+const MAX_ROUNDS: u8 = 2;
+
+/// Minimum share of printable characters for a decoded round to count as text.
+const MIN_PRINTABLE_RATIO: f64 = 0.95;
+
+/// A decoded message body, returned by
+/// [`Client::decode_message_content_ext()`](crate::Client::decode_message_content_ext).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedContent {
+    /// The decoded text.
+    pub text: String,
+    /// How many times the content was base64-decoded: `1` for normal bodies,
+    /// `2` for double-encoded ones.
+    pub rounds: u8,
+}
+
+impl DecodedContent {
+    /// Tables in the body, e.g. substitution plans, see
+    /// [`content::extract_tables()`](crate::content::extract_tables).
+    pub fn tables(&self) -> Vec<Table> {
+        extract_tables(&self.text)
+    }
+}
+
+pub(crate) fn decode(content: &str) -> Option<DecodedContent> {
+    let mut decoded = DecodedContent {
+        text: decode_round(content)?,
+        rounds: 1,
+    };
+    while decoded.rounds < MAX_ROUNDS {
+        match decode_round(&decoded.text).filter(|text| looks_like_text(text)) {
+            Some(text) => {
+                decoded.text = text;
+                decoded.rounds += 1;
+            }
+            None => break,
+        }
+    }
+    Some(decoded)
+}
+
+/// Decodes one round of base64 into UTF-8. Line breaks inside the encoded
+/// data are ignored.
+fn decode_round(content: &str) -> Option<String> {
+    let compact: String = content.split_ascii_whitespace().collect();
+    let bytes = STANDARD.decode(compact).ok()?;
+    String::from_utf8(bytes).ok()
+}
+
+fn looks_like_text(text: &str) -> bool {
+    let total = text.chars().count();
+    if total == 0 {
+        return false;
+    }
+    let printable = text
+        .chars()
+        .filter(|c| !c.is_control() || c.is_whitespace())
+        .count();
+    printable as f64 / total as f64 >= MIN_PRINTABLE_RATIO
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(text: &str) -> String {
+        STANDARD.encode(text)
+    }
+
+    #[test]
+    fn test_single_encoding() {
+        let decoded = decode(&encode("Dzień dobry, jutro wycieczka.")).unwrap();
+        assert_eq!(decoded.text, "Dzień dobry, jutro wycieczka.");
+        assert_eq!(decoded.rounds, 1);
+    }
+
+    #[test]
+    fn test_double_encoding() {
+        let decoded = decode(&encode(&encode("<p>Przekazana wiadomość</p>"))).unwrap();
+        assert_eq!(decoded.text, "<p>Przekazana wiadomość</p>");
+        assert_eq!(decoded.rounds, 2);
+    }
+
+    #[test]
+    fn test_stops_after_two_rounds() {
+        let triple = encode(&encode(&encode("Hello, World!")));
+        let decoded = decode(&triple).unwrap();
+        assert_eq!(decoded.rounds, 2);
+        assert_eq!(decoded.text, encode("Hello, World!"));
+    }
+
+    #[test]
+    fn test_text_that_looks_like_base64_is_kept() {
+        for text in ["Test", "abcd1234", "Zadanie", "OK", "Ocena5"] {
+            let decoded = decode(&encode(text)).unwrap();
+            assert_eq!((decoded.text.as_str(), decoded.rounds), (text, 1));
+        }
+    }
+
+    #[test]
+    fn test_not_base64() {
+        assert!(decode("Zwykły tekst, bez kodowania!").is_none());
+    }
+
+    #[test]
+    fn test_line_breaks_in_encoded_body() {
+        let encoded = encode("Hello, World! This body is wrapped.");
+        let wrapped = format!("{}\r\n{}", &encoded[..16], &encoded[16..]);
+        assert_eq!(
+            decode(&wrapped).unwrap().text,
+            "Hello, World! This body is wrapped."
+        );
+    }
+}
@@ -0,0 +1,553 @@
+//! A daily summary for school bots and notifications.
+//!
+//! [`daily_digest()`] collects what a parent usually wants to know about a
+//! day: new grades, homework due tomorrow, substitutions, unread messages and
+//! the lucky number, optionally with absences waiting for a justification.
+//! [`Digest::to_markdown()`] and [`Digest::to_plaintext()`] render it with
+//! Polish labels.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use librus_rs::digest::{self, DigestOptions};
+//! use librus_rs::Client;
+//!
+//! # async fn example() -> Result<(), librus_rs::Error> {
+//! let mut client = Client::from_env().await?;
+//! let digest = digest::daily_digest(&mut client, "2025-10-06", &DigestOptions::new()).await?;
+//! println!("{}", digest.to_markdown());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::structs::lessons::LessonSubject;
+use crate::structs::resources::Reference;
+use crate::{dates, Client, Error, JustificationPolicy, PendingJustification, Result};
+
+/// Default number of newest inbox messages checked for unread ones.
+const DEFAULT_MAX_UNREAD: u32 = 20;
+
+/// A section of a [`Digest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestSection {
+    /// New grades.
+    Grades,
+    /// Homework due the next day.
+    Homework,
+    /// Substitutions and cancellations.
+    Substitutions,
+    /// Unread messages.
+    Messages,
+    /// The lucky number.
+    LuckyNumber,
+    /// Absences to justify, see [`DigestOptions::justification_policy()`].
+    Justifications,
+    /// The subject list naming the subjects of the other sections. Without
+    /// it the sections are still filled in, with the subjects unnamed.
+    Subjects,
+}
+
+impl DigestSection {
+    /// Polish heading of the section.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Grades => "Nowe oceny",
+            Self::Homework => "Zadania na jutro",
+            Self::Substitutions => "Zastępstwa",
+            Self::Messages => "Nieprzeczytane wiadomości",
+            Self::LuckyNumber => "Szczęśliwy numerek",
+            Self::Justifications => "Nieobecności do usprawiedliwienia",
+            Self::Subjects => "Przedmioty",
+        }
+    }
+}
+
+/// Why a section of a [`Digest`] is missing.
+#[derive(Debug, Clone)]
+pub struct DigestError {
+    /// The section that could not be filled in.
+    pub section: DigestSection,
+    /// Description of the error that occurred.
+    pub message: String,
+}
+
+/// Options for [`daily_digest()`].
+#[derive(Debug, Clone)]
+pub struct DigestOptions {
+    grades_since: Option<String>,
+    max_unread: u32,
+    justification_policy: Option<JustificationPolicy>,
+}
+
+impl Default for DigestOptions {
+    fn default() -> Self {
+        Self {
+            grades_since: None,
+            max_unread: DEFAULT_MAX_UNREAD,
+            justification_policy: None,
+        }
+    }
+}
+
+impl DigestOptions {
+    /// Creates the default options: grades added on the digest day, unread
+    /// messages among the 20 newest.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lists grades added on or after `date` (`YYYY-MM-DD`) instead of only
+    /// those added on the digest day, e.g. to cover a weekend.
+    pub fn grades_since(mut self, date: impl Into<String>) -> Self {
+        self.grades_since = Some(date.into());
+        self
+    }
+
+    /// Sets how many of the newest inbox messages are checked for unread
+    /// ones (default 20).
+    pub fn max_unread(mut self, count: u32) -> Self {
+        self.max_unread = count.max(1);
+        self
+    }
+
+    /// Adds every absence that still needs a justification under the
+    /// school's `policy`, see
+    /// [`ResponseAttendances::needing_justification()`](crate::ResponseAttendances::needing_justification).
+    /// The section is left out without a policy.
+    pub fn justification_policy(mut self, policy: JustificationPolicy) -> Self {
+        self.justification_policy = Some(policy);
+        self
+    }
+}
+
+/// A new grade in a [`Digest`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestGrade {
+    /// Subject name, or `None` if it is not in the subject list.
+    pub subject: Option<String>,
+    /// The grade as shown in the register, e.g. `"4+"`.
+    pub grade: String,
+}
+
+/// A homework entry in a [`Digest`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestHomework {
+    /// Subject name, if the entry has one.
+    pub subject: Option<String>,
+    /// What is due.
+    pub content: String,
+}
+
+/// A substitution or cancellation in a [`Digest`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestSubstitution {
+    /// Lesson number in the day.
+    pub lesson_no: Option<String>,
+    /// Subject of the original lesson.
+    pub subject: Option<String>,
+    /// Whether the lesson is cancelled rather than substituted.
+    pub cancelled: bool,
+}
+
+/// A summary of one school day, returned by [`daily_digest()`].
+///
+/// Every section is optional: if fetching it failed, it is `None` and the
+/// reason is listed in [`errors`](Self::errors).
+#[derive(Debug, Clone, Default)]
+pub struct Digest {
+    /// The day summarized (`YYYY-MM-DD`).
+    pub date: String,
+    /// Grades added on the day, or since
+    /// [`DigestOptions::grades_since()`].
+    pub new_grades: Option<Vec<DigestGrade>>,
+    /// Homework due the next day.
+    pub homework_tomorrow: Option<Vec<DigestHomework>>,
+    /// Substitutions and cancellations on the day.
+    pub substitutions: Option<Vec<DigestSubstitution>>,
+    /// Senders of unread messages, each listed once.
+    pub unread_senders: Option<Vec<String>>,
+    /// The lucky number of the day. `Some(None)` if there is none that day.
+    pub lucky_number: Option<Option<u32>>,
+    /// Absences waiting for a justification, including overdue ones. Only
+    /// filled in with [`DigestOptions::justification_policy()`].
+    pub justifications: Option<Vec<PendingJustification>>,
+    /// Sections that could not be fetched.
+    pub errors: Vec<DigestError>,
+}
+
+impl Digest {
+    fn section<T>(&mut self, section: DigestSection, result: Result<T>) -> Option<T> {
+        result
+            .map_err(|e| {
+                self.errors.push(DigestError {
+                    section,
+                    message: e.to_string(),
+                })
+            })
+            .ok()
+    }
+
+    /// Renders the digest as Markdown.
+    ///
+    /// Text from the register, e.g. homework topics, is escaped, so
+    /// characters like `*` or `[` show up as typed.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Podsumowanie dnia {}\n", self.date);
+        for (section, lines) in self.sections() {
+            let _ = write!(out, "\n## {}\n\n", section.label());
+            match lines {
+                Some(lines) if lines.is_empty() => out.push_str("_Brak_\n"),
+                Some(lines) => {
+                    for line in lines {
+                        let _ = writeln!(out, "- {}", escape_markdown(&line));
+                    }
+                }
+                None => out.push_str("_Nie udało się pobrać_\n"),
+            }
+        }
+        out
+    }
+
+    /// Renders the digest as plain text.
+    pub fn to_plaintext(&self) -> String {
+        let mut out = format!("Podsumowanie dnia {}\n", self.date);
+        for (section, lines) in self.sections() {
+            let _ = write!(out, "\n{}:\n", section.label());
+            match lines {
+                Some(lines) if lines.is_empty() => out.push_str("  brak\n"),
+                Some(lines) => {
+                    for line in lines {
+                        let _ = writeln!(out, "  * {}", line);
+                    }
+                }
+                None => out.push_str("  nie udało się pobrać\n"),
+            }
+        }
+        out
+    }
+
+    /// The sections as lines of text, in display order.
+    fn sections(&self) -> Vec<(DigestSection, Option<Vec<String>>)> {
+        let unknown = |name: &Option<String>| name.clone().unwrap_or_else(|| "Inne".to_string());
+        let mut sections = vec![
+            (
+                DigestSection::Grades,
+                self.new_grades.as_ref().map(|grades| {
+                    grades
+                        .iter()
+                        .map(|g| format!("{}: {}", unknown(&g.subject), g.grade))
+                        .collect()
+                }),
+            ),
+            (
+                DigestSection::Homework,
+                self.homework_tomorrow.as_ref().map(|homework| {
+                    homework
+                        .iter()
+                        .map(|h| format!("{}: {}", unknown(&h.subject), h.content))
+                        .collect()
+                }),
+            ),
+            (
+                DigestSection::Substitutions,
+                self.substitutions.as_ref().map(|substitutions| {
+                    substitutions
+                        .iter()
+                        .map(|s| {
+                            format!(
+                                "lekcja {}, {}: {}",
+                                s.lesson_no.as_deref().unwrap_or("?"),
+                                unknown(&s.subject),
+                                if s.cancelled {
+                                    "odwołana"
+                                } else {
+                                    "zastępstwo"
+                                }
+                            )
+                        })
+                        .collect()
+                }),
+            ),
+            (DigestSection::Messages, self.unread_senders.clone()),
+            (
+                DigestSection::LuckyNumber,
+                self.lucky_number
+                    .map(|number| number.map(|n| n.to_string()).into_iter().collect()),
+            ),
+        ];
+        let requested = self.justifications.is_some()
+            || self
+                .errors
+                .iter()
+                .any(|e| e.section == DigestSection::Justifications);
+        if requested {
+            sections.push((
+                DigestSection::Justifications,
+                self.justifications.as_ref().map(|days| {
+                    days.iter()
+                        .map(|day| {
+                            let lessons: Vec<String> =
+                                day.lessons.iter().map(|n| n.to_string()).collect();
+                            format!(
+                                "{} (lekcje {}): {} {}",
+                                day.date,
+                                lessons.join(", "),
+                                if day.is_overdue() {
+                                    "termin minął"
+                                } else {
+                                    "termin"
+                                },
+                                day.deadline
+                            )
+                        })
+                        .collect()
+                }),
+            ));
+        }
+        sections
+    }
+}
+
+/// Builds the digest of `date` (`YYYY-MM-DD`).
+///
+/// Sections that cannot be fetched are left empty and reported in
+/// [`Digest::errors`]; the digest itself only fails on a malformed date.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidDate`] if `date` is malformed.
+pub async fn daily_digest(client: &mut Client, date: &str, opts: &DigestOptions) -> Result<Digest> {
+    let day = dates::parse_day(date).ok_or_else(|| Error::InvalidDate(date.to_string()))?;
+    let tomorrow = dates::format_day(day + 1);
+    let date = dates::format_day(day);
+
+    // The messages API needs `&mut Client`, so it runs before the rest.
+    let inbox = client.inbox_messages(1, opts.max_unread).await;
+    let policy = opts.justification_policy.as_ref();
+    let attendances = async {
+        match policy {
+            Some(_) => Some(client.attendances().await),
+            None => None,
+        }
+    };
+    let (grades, subjects, homeworks, substitutions, lucky, attendances) = tokio::join!(
+        client.grades(),
+        client.subjects(),
+        client.homeworks(),
+        client.substitutions(),
+        client.lucky_number(),
+        attendances,
+    );
+
+    let mut digest = Digest {
+        date: date.clone(),
+        ..Digest::default()
+    };
+    // Without the subject list the entries are still useful, just unnamed.
+    let subjects = digest
+        .section(DigestSection::Subjects, subjects)
+        .map(|s| s.subjects)
+        .unwrap_or_default();
+    let names = SubjectNames::new(&subjects);
+
+    let since = opts.grades_since.as_deref().unwrap_or(&date);
+    digest.new_grades = digest.section(DigestSection::Grades, grades).map(|g| {
+        g.grades
+            .iter()
+            .filter(|g| (since..=date.as_str()).contains(&g.add_date.date().as_str()))
+            .map(|g| DigestGrade {
+                subject: names.get(Some(&g.subject)),
+                grade: g.grade.clone(),
+            })
+            .collect()
+    });
+    digest.homework_tomorrow = digest.section(DigestSection::Homework, homeworks).map(|h| {
+        h.homeworks
+            .iter()
+            .filter(|h| h.date == tomorrow.as_str())
+            .map(|h| DigestHomework {
+                subject: names.get(h.subject.as_ref()),
+                content: h.content.clone(),
+            })
+            .collect()
+    });
+    digest.substitutions = digest
+        .section(DigestSection::Substitutions, substitutions)
+        .map(|s| {
+            s.substitutions
+                .iter()
+                .filter(|s| s.org_date == date.as_str())
+                .map(|s| DigestSubstitution {
+                    lesson_no: s.org_lesson_no.clone(),
+                    subject: names.get(s.org_subject.as_ref()),
+                    cancelled: s.is_cancelled,
+                })
+                .collect()
+        });
+    digest.unread_senders = digest.section(DigestSection::Messages, inbox).map(|inbox| {
+        let mut senders: Vec<String> = Vec::new();
+        for message in inbox.iter().filter(|m| m.read_date.is_none()) {
+            if !senders.contains(&message.sender_name) {
+                senders.push(message.sender_name.clone());
+            }
+        }
+        senders
+    });
+    digest.lucky_number = digest.section(DigestSection::LuckyNumber, lucky).map(|l| {
+        let lucky = l.lucky_number;
+        lucky
+            .lucky_number
+            .filter(|_| lucky.lucky_number_day == date.as_str())
+    });
+    if let (Some(policy), Some(attendances)) = (policy, attendances) {
+        let pending = attendances.and_then(|a| a.needing_justification(policy, &date, policy.days));
+        digest.justifications = digest.section(DigestSection::Justifications, pending);
+    }
+    Ok(digest)
+}
+
+/// Escapes the characters that Markdown would read as formatting.
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '~'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Subject names by ID.
+struct SubjectNames<'a>(HashMap<i64, &'a str>);
+
+impl<'a> SubjectNames<'a> {
+    fn new(subjects: &'a [LessonSubject]) -> Self {
+        Self(
+            subjects
+                .iter()
+                .map(|s| (i64::from(s.id), s.name.as_str()))
+                .collect(),
+        )
+    }
+
+    fn get(&self, subject: Option<&Reference>) -> Option<String> {
+        let id = subject?.id()?;
+        self.0.get(&id).map(|name| name.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ApiDate;
+
+    fn digest() -> Digest {
+        Digest {
+            date: "2025-10-06".to_string(),
+            new_grades: Some(vec![
+                DigestGrade {
+                    subject: Some("Matematyka".to_string()),
+                    grade: "5".to_string(),
+                },
+                DigestGrade {
+                    subject: None,
+                    grade: "3+".to_string(),
+                },
+            ]),
+            homework_tomorrow: Some(vec![DigestHomework {
+                subject: Some("Biologia".to_string()),
+                content: "Karta pracy str. 12".to_string(),
+            }]),
+            substitutions: None,
+            unread_senders: Some(vec![]),
+            lucky_number: Some(Some(13)),
+            justifications: None,
+            errors: vec![DigestError {
+                section: DigestSection::Substitutions,
+                message: "request failed".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        assert_eq!(
+            digest().to_markdown(),
+            include_str!("../tests/fixtures/digest.md")
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_escapes_register_text() {
+        let digest = Digest {
+            homework_tomorrow: Some(vec![DigestHomework {
+                subject: Some("J. angielski [gr. 2]".to_string()),
+                content: "*Unit 3* – słówka z_podręcznika <str. 5> #ważne".to_string(),
+            }]),
+            unread_senders: Some(vec!["Anna `Nowak`".to_string()]),
+            ..digest()
+        };
+        assert_eq!(
+            digest.to_markdown(),
+            include_str!("../tests/fixtures/digest_escaped.md")
+        );
+        // Plain text is left as typed.
+        assert!(digest.to_plaintext().contains("*Unit 3*"));
+    }
+
+    #[test]
+    fn test_to_plaintext() {
+        assert_eq!(
+            digest().to_plaintext(),
+            include_str!("../tests/fixtures/digest.txt")
+        );
+    }
+
+    #[test]
+    fn test_no_lucky_number_today() {
+        let digest = Digest {
+            lucky_number: Some(None),
+            ..digest()
+        };
+        assert!(digest
+            .to_plaintext()
+            .ends_with("Szczęśliwy numerek:\n  brak\n"));
+    }
+
+    #[test]
+    fn test_justifications_section() {
+        let digest = Digest {
+            justifications: Some(vec![
+                PendingJustification {
+                    date: ApiDate::from_raw("2025-09-26"),
+                    deadline: ApiDate::from_raw("2025-10-03"),
+                    days_left: -3,
+                    lessons: vec![2],
+                },
+                PendingJustification {
+                    date: ApiDate::from_raw("2025-10-01"),
+                    deadline: ApiDate::from_raw("2025-10-08"),
+                    days_left: 2,
+                    lessons: vec![1, 3],
+                },
+            ]),
+            ..digest()
+        };
+        assert!(digest.to_plaintext().ends_with(
+            "Nieobecności do usprawiedliwienia:\n  \
+             * 2025-09-26 (lekcje 2): termin minął 2025-10-03\n  \
+             * 2025-10-01 (lekcje 1, 3): termin 2025-10-08\n"
+        ));
+        // Left out entirely when no policy was given.
+        assert!(!super::tests::digest()
+            .to_markdown()
+            .contains("usprawiedliwienia"));
+    }
+}
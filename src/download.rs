@@ -0,0 +1,306 @@
+//! Streaming and resumable attachment downloads.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::{filenames, Error, Result};
+
+/// Outcome of [`Client::download_all_attachments()`](crate::Client::download_all_attachments).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DownloadSummary {
+    /// Files written, in the order of the message's attachments.
+    pub files: Vec<PathBuf>,
+    /// Whether the downloads stopped early because the token was cancelled.
+    pub cancelled: bool,
+}
+
+/// An attachment download.
+///
+/// Returned by [`Client::attachment_download()`](crate::Client::attachment_download),
+/// with the body already read, and by
+/// [`Client::attachment_resume()`](crate::Client::attachment_resume), which
+/// streams it. Read the body with [`chunk()`](Self::chunk) until it returns
+/// `None`, or all at once with [`bytes()`](Self::bytes).
+#[derive(Debug)]
+pub struct AttachmentDownload {
+    body: Body,
+    offset: u64,
+    total_len: Option<u64>,
+    content_type: Option<String>,
+    filename: Option<String>,
+}
+
+#[derive(Debug)]
+enum Body {
+    Stream(reqwest::Response),
+    Buffered(Vec<u8>),
+    Done,
+}
+
+impl AttachmentDownload {
+    /// A download streaming the body of `response`.
+    pub(crate) fn streaming(
+        response: reqwest::Response,
+        offset: u64,
+        total_len: Option<u64>,
+    ) -> Self {
+        let headers = response.headers().clone();
+        Self::with_headers(Body::Stream(response), offset, total_len, &headers)
+    }
+
+    /// A download whose body was already read.
+    pub(crate) fn buffered(
+        body: Vec<u8>,
+        offset: u64,
+        total_len: Option<u64>,
+        headers: &reqwest::header::HeaderMap,
+    ) -> Self {
+        Self::with_headers(Body::Buffered(body), offset, total_len, headers)
+    }
+
+    /// A download with nothing left to read.
+    pub(crate) fn finished(offset: u64) -> Self {
+        Self {
+            body: Body::Done,
+            offset,
+            total_len: Some(offset),
+            content_type: None,
+            filename: None,
+        }
+    }
+
+    fn with_headers(
+        body: Body,
+        offset: u64,
+        total_len: Option<u64>,
+        headers: &reqwest::header::HeaderMap,
+    ) -> Self {
+        let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+        Self {
+            body,
+            offset,
+            total_len,
+            content_type: header(reqwest::header::CONTENT_TYPE).map(str::to_string),
+            filename: header(reqwest::header::CONTENT_DISPOSITION).and_then(disposition_filename),
+        }
+    }
+
+    /// Position in the file of the first byte of this download.
+    ///
+    /// Equal to the requested `already_have` when the server resumed, and `0`
+    /// when it ignored the range and sends the whole file again. In the latter
+    /// case the bytes downloaded so far must be discarded.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Whether the server continues from the requested position.
+    pub fn resumed(&self) -> bool {
+        self.offset > 0
+    }
+
+    /// Size of the whole file, if the server reported it.
+    pub fn total_len(&self) -> Option<u64> {
+        self.total_len
+    }
+
+    /// The `Content-Type` header of the response, e.g. `application/pdf`.
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// The file name from the `Content-Disposition` header, decoded and made
+    /// safe to use as a path like [`Attachment::decoded_name()`](crate::Attachment::decoded_name).
+    pub fn filename_header(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
+
+    /// Reads the next part of the body, or `None` at the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns a network error such as [`Error::Request`] if the connection
+    /// breaks. Everything returned before the error is valid, so the download
+    /// can be resumed from there.
+    pub async fn chunk(&mut self) -> Result<Option<Vec<u8>>> {
+        match std::mem::replace(&mut self.body, Body::Done) {
+            Body::Stream(mut response) => {
+                let chunk = response.chunk().await.map_err(Error::from)?;
+                if chunk.is_some() {
+                    self.body = Body::Stream(response);
+                }
+                Ok(chunk.map(|c| c.to_vec()))
+            }
+            Body::Buffered(bytes) => Ok(Some(bytes)),
+            Body::Done => Ok(None),
+        }
+    }
+
+    /// Reads the rest of the body.
+    ///
+    /// # Errors
+    ///
+    /// Returns a network error such as [`Error::Request`] if the connection
+    /// breaks.
+    pub async fn bytes(mut self) -> Result<Vec<u8>> {
+        let mut body = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+}
+
+/// Extracts the file name from a `Content-Disposition` header, preferring the
+/// RFC 5987 `filename*=UTF-8''...` form.
+fn disposition_filename(header: &str) -> Option<String> {
+    let params: Vec<(String, &str)> = header
+        .split(';')
+        .filter_map(|param| {
+            let (name, value) = param.split_once('=')?;
+            Some((name.trim().to_ascii_lowercase(), value.trim()))
+        })
+        .collect();
+    let find = |wanted: &str| {
+        params
+            .iter()
+            .find(|(name, _)| name == wanted)
+            .map(|(_, value)| *value)
+    };
+    let raw = match find("filename*") {
+        Some(extended) => extended.split_once("''").map_or(extended, |(_, v)| v),
+        None => find("filename")?.trim_matches('"'),
+    };
+    (!raw.is_empty()).then(|| filenames::decode_filename(raw))
+}
+
+/// Recognizes the page Librus serves instead of an attachment that is still
+/// being scanned ("plik w trakcie skanowania antywirusowego").
+///
+/// `retry_after` is the `Retry-After` header, which wins over a wait time
+/// mentioned on the page.
+pub(crate) fn scanning_error(
+    content_type: Option<&str>,
+    retry_after: Option<&str>,
+    body: &[u8],
+) -> Option<Error> {
+    if !content_type.is_some_and(|t| t.trim_start().starts_with("text/html")) {
+        return None;
+    }
+    let page = String::from_utf8_lossy(body).to_lowercase();
+    if !(page.contains("skanowan") && page.contains("antywirus")) {
+        return None;
+    }
+    let header_hint = retry_after
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs);
+    Some(Error::AttachmentScanning {
+        retry_after_hint: header_hint.or_else(|| wait_time(&page)),
+    })
+}
+
+/// Finds the first wait time such as `2 minuty` or `30 sekund` in a
+/// lower-cased page.
+fn wait_time(page: &str) -> Option<Duration> {
+    let mut rest = page;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        let digits = &rest[start..];
+        let end = digits
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(digits.len());
+        let unit = digits[end..].trim_start();
+        if let Ok(n) = digits[..end].parse::<u64>() {
+            if unit.starts_with("sek") {
+                return Some(Duration::from_secs(n));
+            }
+            if unit.starts_with("min") {
+                return Some(Duration::from_secs(n * 60));
+            }
+            if unit.starts_with("godz") {
+                return Some(Duration::from_secs(n * 3_600));
+            }
+        }
+        rest = &digits[end..];
+    }
+    None
+}
+
+/// Parses a `Content-Range` header into the first byte and the total size.
+///
+/// Accepts `bytes 100-199/200`, `bytes 100-199/*` and, for `416` responses,
+/// `bytes */200` (reported with a start of `None`).
+pub(crate) fn parse_content_range(header: &str) -> Option<(Option<u64>, Option<u64>)> {
+    let (range, total) = header.trim().strip_prefix("bytes ")?.split_once('/')?;
+    let total = match total {
+        "*" => None,
+        total => Some(total.parse().ok()?),
+    };
+    let start = match range {
+        "*" => None,
+        range => Some(range.split_once('-')?.0.parse().ok()?),
+    };
+    Some((start, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scanning_page() {
+        let page = include_bytes!("../tests/fixtures/attachment_scanning.html");
+        match scanning_error(Some("text/html; charset=UTF-8"), None, page) {
+            Some(Error::AttachmentScanning { retry_after_hint }) => {
+                assert_eq!(retry_after_hint, Some(Duration::from_secs(120)))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(matches!(
+            scanning_error(Some("text/html"), Some("30"), page),
+            Some(Error::AttachmentScanning {
+                retry_after_hint: Some(d)
+            }) if d == Duration::from_secs(30)
+        ));
+        // The same bytes as a file, and other HTML pages, are left alone.
+        assert!(scanning_error(Some("application/octet-stream"), None, page).is_none());
+        assert!(scanning_error(Some("text/html"), None, b"<html>Zaloguj</html>").is_none());
+    }
+
+    #[test]
+    fn test_wait_time() {
+        assert_eq!(wait_time("za 45 sekund"), Some(Duration::from_secs(45)));
+        assert_eq!(
+            wait_time("rok 2025, za 1 godzinę"),
+            Some(Duration::from_secs(3_600))
+        );
+        assert_eq!(wait_time("spróbuj później"), None);
+    }
+
+    #[test]
+    fn test_disposition_filename() {
+        assert_eq!(
+            disposition_filename(r#"attachment; filename="plan lekcji.pdf""#).as_deref(),
+            Some("plan lekcji.pdf")
+        );
+        assert_eq!(
+            disposition_filename(
+                r#"attachment; filename="zgoda.pdf"; filename*=UTF-8''zgoda%20na%20wyj%C5%9Bcie.pdf"#
+            )
+            .as_deref(),
+            Some("zgoda na wyjście.pdf")
+        );
+        assert_eq!(disposition_filename("inline"), None);
+    }
+
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(
+            parse_content_range("bytes 100-199/200"),
+            Some((Some(100), Some(200)))
+        );
+        assert_eq!(parse_content_range("bytes 5-9/*"), Some((Some(5), None)));
+        assert_eq!(parse_content_range("bytes */200"), Some((None, Some(200))));
+        assert_eq!(parse_content_range("items 0-1/2"), None);
+        assert_eq!(parse_content_range("bytes x-1/2"), None);
+    }
+}
@@ -1,7 +1,16 @@
 //! Error types for the Librus API client.
 
+use std::borrow::Cow;
+use std::fmt;
+use std::time::Duration;
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
 use thiserror::Error;
 
+use crate::anonymize::fnv1a;
+use crate::auth_trace::AuthTrace;
+use crate::unit_selection::UnitChoice;
+
 /// Errors that can occur when using the Librus API client.
 ///
 /// # Example
@@ -13,17 +22,84 @@ use thiserror::Error;
 /// match Client::from_env().await {
 ///     Ok(_) => println!("Success"),
 ///     Err(Error::MissingEnvVar(var)) => eprintln!("Missing {}", var),
-///     Err(Error::Authentication) => eprintln!("Bad credentials"),
+///     Err(Error::InvalidCredentials { message }) => eprintln!("Bad credentials: {}", message),
 ///     Err(e) => eprintln!("Error: {}", e),
 /// }
 /// # }
 /// ```
+///
+/// New variants may be added in minor versions, so matches need a wildcard
+/// arm. [`Error::is_retryable()`] and [`Error::is_auth_related()`] group
+/// variants for the common cases.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
-    /// Authentication with Librus failed due to invalid credentials or server error.
+    /// Authentication with Librus failed with a response the client could not
+    /// interpret, e.g. a login form reply without an error or redirect target.
     #[error("authentication failed: invalid credentials or server error")]
     Authentication,
 
+    /// Same as [`Error::Authentication`], with the recorded login steps.
+    ///
+    /// Returned instead of it by clients built with
+    /// [`ClientBuilder::trace_auth()`](crate::ClientBuilder::trace_auth).
+    #[error("authentication failed after {} login steps", trace.steps().len())]
+    AuthenticationDetailed {
+        /// The steps of the failed login.
+        trace: AuthTrace,
+    },
+
+    /// The login form rejected the username or password.
+    ///
+    /// Contains the message reported by the Librus login form.
+    #[error("invalid credentials: {message}")]
+    InvalidCredentials {
+        /// Error message returned by the login form.
+        message: String,
+    },
+
+    /// A step of the login flow returned an unexpected response.
+    ///
+    /// Unlike [`Error::InvalidCredentials`], this usually means Librus changed
+    /// its login flow or is having an outage.
+    #[error("login step `{step}` failed (status {status})")]
+    GrantFailed {
+        /// The login step that failed.
+        step: AuthStep,
+        /// HTTP status code returned by that step.
+        status: u16,
+    },
+
+    /// The account is linked to several school units and one has to be
+    /// chosen to finish logging in.
+    ///
+    /// Build the client again with
+    /// [`ClientBuilder::unit()`](crate::ClientBuilder::unit) set to one of
+    /// the `units`. Also returned when the unit set there is not offered.
+    #[error("a school unit must be selected ({} available)", units.len())]
+    UnitSelectionRequired {
+        /// The units offered by the selection page.
+        units: Vec<UnitChoice>,
+    },
+
+    /// A redirect during login or session setup led away from the Librus
+    /// hosts, e.g. to a new interstitial page the login flow does not know.
+    #[error("unexpected redirect to {to}")]
+    UnexpectedRedirect {
+        /// Where the redirect pointed.
+        to: String,
+    },
+
+    /// A URL from a response points outside the Synergia API, so
+    /// [`Client::follow()`](crate::Client::follow) cannot fetch it.
+    #[error("cannot follow {kind} URL {url}")]
+    UnsupportedUrl {
+        /// The URL.
+        url: String,
+        /// Where it points.
+        kind: ApiKind,
+    },
+
     /// Required environment variable is not set.
     ///
     /// Returned by [`Client::from_env()`](crate::Client::from_env) when
@@ -38,23 +114,231 @@ pub enum Error {
     #[error("missing required credential: {0}")]
     MissingCredentials(&'static str),
 
+    /// A credential is malformed and was not sent to Librus.
+    ///
+    /// Returned by [`ClientBuilder::build()`](crate::ClientBuilder::build)
+    /// before any network request, e.g. for an empty username or a password
+    /// with trailing whitespace, unless
+    /// [`ClientBuilder::lenient_password()`](crate::ClientBuilder::lenient_password)
+    /// is set. `field` is `"username"` or `"password"`.
+    #[error("invalid {field}: {reason}")]
+    InvalidCredentialFormat {
+        /// The offending credential.
+        field: &'static str,
+        /// What is wrong with it.
+        reason: &'static str,
+    },
+
+    /// The account lacks a premium add-on required by the requested feature.
+    ///
+    /// Contains the add-on name, see [`Addon::name()`](crate::Addon::name).
+    #[error("premium add-on `{0}` is required")]
+    PremiumRequired(String),
+
+    /// The deadline of a homework assignment has already passed.
+    ///
+    /// Returned by [`Client::mark_assignment_done()`](crate::Client::mark_assignment_done)
+    /// when the server rejects the change because the assignment is overdue.
+    #[error("the assignment deadline has passed")]
+    DeadlinePassed,
+
+    /// A date argument is not a valid `YYYY-MM-DD` date.
+    #[error("invalid date `{0}`, expected YYYY-MM-DD")]
+    InvalidDate(String),
+
+    /// The requested module is disabled on this school's Librus instance.
+    ///
+    /// Contains the name of the module, e.g. `"Trips"`.
+    #[error("module `{0}` is disabled on this Librus instance")]
+    ModuleDisabled(String),
+
+    /// The session expired and could not be renewed.
+    ///
+    /// Returned by messages API methods when the server keeps answering with
+    /// the HTML login page, or with the session-expired `errorCode`, after the
+    /// session was re-initialized once. Synergia API methods return it when a
+    /// request is redirected to the login page without the takeover reason of
+    /// [`Error::SessionTakenOver`].
+    #[error("session expired")]
+    SessionExpired,
+
+    /// The requested message was deleted.
+    #[error("message was deleted")]
+    MessageDeleted,
+
+    /// The account is not allowed to perform the Messages API operation.
+    #[error("permission denied by the Messages API: {message}")]
+    MessagesPermissionDenied {
+        /// Message reported by the Messages API.
+        message: String,
+    },
+
+    /// The Messages API reported an error code this crate does not map to a
+    /// more specific variant.
+    ///
+    /// These errors are sent in the `errorCode` envelope, usually with a
+    /// `200` status.
+    #[error("Messages API error {code}: {message}")]
+    MessagesApiError {
+        /// The `errorCode` value.
+        code: i64,
+        /// Message reported by the Messages API.
+        message: String,
+    },
+
+    /// The Synergia session was ended because the same account logged in
+    /// elsewhere, e.g. in the mobile app.
+    ///
+    /// Returned unless the client was built with
+    /// [`TakeoverPolicy::Reauth`](crate::TakeoverPolicy::Reauth), or when
+    /// re-authenticating did not help.
+    #[error("session taken over by another login")]
+    SessionTakenOver,
+
+    /// A response body exceeded the limit set with
+    /// [`ClientBuilder::max_response_bytes()`](crate::ClientBuilder::max_response_bytes).
+    ///
+    /// The body is not buffered past the limit.
+    #[error("response exceeds the limit of {limit} bytes")]
+    ResponseTooLarge {
+        /// The configured limit in bytes.
+        limit: usize,
+    },
+
+    /// The server answered a ranged attachment request with a different
+    /// range than requested.
+    #[error("server did not resume at byte {requested} (Content-Range: {content_range:?})")]
+    RangeNotHonored {
+        /// The first byte that was requested.
+        requested: u64,
+        /// The `Content-Range` header of the response, if any.
+        content_range: Option<String>,
+    },
+
+    /// Librus served its "plik w trakcie skanowania antywirusowego" page
+    /// instead of an attachment that was added recently and is still being
+    /// scanned.
+    ///
+    /// Retry the download later, after `retry_after_hint` if the server gave
+    /// a wait time.
+    #[error("attachment is still being scanned by the antivirus, retry later")]
+    AttachmentScanning {
+        /// Wait time from the `Retry-After` header or the page, if any.
+        retry_after_hint: Option<Duration>,
+    },
+
+    /// The [`RequestBudget`](crate::RequestBudget) of the client is used up
+    /// and its policy is [`BudgetPolicy::Error`](crate::BudgetPolicy::Error).
+    ///
+    /// The request was not sent.
+    #[error("request budget exhausted, retry in {retry_after:?}")]
+    BudgetExhausted {
+        /// How long until the budget has room for the request.
+        retry_after: Duration,
+    },
+
+    /// The message does not ask for a read confirmation.
+    ///
+    /// Returned by [`Client::confirm_message_read()`](crate::Client::confirm_message_read).
+    #[error("message does not require a read confirmation")]
+    ReadConfirmationNotRequired,
+
+    /// Reading the message was already confirmed.
+    ///
+    /// Returned by [`Client::confirm_message_read()`](crate::Client::confirm_message_read).
+    #[error("message read was already confirmed")]
+    ReadAlreadyConfirmed,
+
+    /// The messages session could not be opened, e.g. because the Messages
+    /// module is disabled for the account or the init page redirected to an
+    /// error page.
+    #[error("Messages unavailable: {reason}")]
+    MessagesUnavailable {
+        /// What the init request ran into.
+        reason: String,
+    },
+
+    /// The school disabled messaging for this account.
+    ///
+    /// Remembered by the client, so later Messages calls fail with this
+    /// error without asking the server again, see
+    /// [`Client::recheck_messages_availability()`](crate::Client::recheck_messages_availability).
+    #[error("Messages are disabled for this account")]
+    MessagesDisabledForAccount,
+
+    /// A message topic or body would be rejected by the Messages API and was
+    /// not sent.
+    ///
+    /// Returned by [`Client::save_draft()`](crate::Client::save_draft), see
+    /// [`MessageDraftValidator`](crate::MessageDraftValidator).
+    #[error("message not sent: {}", .0.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(", "))]
+    InvalidMessage(Vec<crate::ValidationIssue>),
+
+    /// The message an export was to resume after is not in the folder.
+    ///
+    /// Returned by
+    /// [`Client::export_messages_jsonl()`](crate::Client::export_messages_jsonl)
+    /// when the ID given to
+    /// [`ExportOptions::since_message_id()`](crate::ExportOptions::since_message_id)
+    /// was not listed, e.g. because the message was deleted. Nothing was
+    /// written.
+    #[error("message `{message_id}` to resume the export after was not found")]
+    ResumePointNotFound {
+        /// The ID that was looked for.
+        message_id: String,
+    },
+
+    /// Writing exported data failed.
+    #[error("I/O error: {0}")]
+    Io(#[source] std::io::Error),
+
     /// HTTP client construction failed.
     #[error("failed to build HTTP client: {0}")]
     HttpClient(#[source] reqwest::Error),
 
-    /// HTTP request failed due to network or connection error.
+    /// A request did not complete within the timeout set with
+    /// [`ClientBuilder::timeout()`](crate::ClientBuilder::timeout).
+    #[error("request to {endpoint} timed out")]
+    Timeout {
+        /// URL of the request, without the query string.
+        endpoint: String,
+        /// The underlying error.
+        #[source]
+        source: reqwest::Error,
+    },
+
+    /// The connection to the server could not be established, e.g. because
+    /// the host name did not resolve or the connection was refused.
+    #[error("could not connect to {endpoint}")]
+    Connect {
+        /// URL of the request, without the query string.
+        endpoint: String,
+        /// The underlying error.
+        #[source]
+        source: reqwest::Error,
+    },
+
+    /// HTTP request failed due to another network error, e.g. a connection
+    /// that broke while reading the body.
     #[error("request failed: {0}")]
     Request(#[source] reqwest::Error),
 
     /// API returned an error response.
     ///
     /// Contains the HTTP status code and response body for debugging.
-    #[error("API error (status {status}): {body}")]
+    #[error("{api} API error (status {status}): {body}")]
     ApiError {
+        /// Which API returned the error.
+        api: ApiKind,
         /// HTTP status code returned by the API.
         status: u16,
         /// Response body content.
         body: String,
+        /// The underlying error, when converted from a [`reqwest::Error`]
+        /// with a status, e.g. from `error_for_status()`. The body is empty
+        /// then.
+        #[source]
+        source: Option<reqwest::Error>,
     },
 
     /// Failed to parse API response as JSON.
@@ -65,7 +349,924 @@ pub enum Error {
         /// The underlying JSON parsing error.
         #[source]
         source: serde_json::Error,
-        /// The raw response body that failed to parse.
-        body: String,
+        /// The response body that failed to parse, shortened to an excerpt
+        /// unless the client was built with
+        /// [`ClientBuilder::keep_full_error_bodies()`](crate::ClientBuilder::keep_full_error_bodies).
+        body: ErrorBody,
     },
 }
+
+/// Longest excerpt of a response body kept in an error, in bytes.
+const BODY_EXCERPT_BYTES: usize = 1024;
+
+/// The response body attached to [`Error::Parse`].
+///
+/// Only the start of the body is kept by default, so holding on to errors
+/// does not hold on to large responses. The length and a hash of the whole
+/// body are kept as well, to tell failing responses apart.
+///
+/// # Example
+///
+/// ```rust
+/// use librus_rs::ErrorBody;
+///
+/// let body = ErrorBody::from("x".repeat(10_000));
+/// assert_eq!(body.len(), 10_000);
+/// assert!(body.is_truncated());
+/// assert!(body.full().is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorBody {
+    excerpt: String,
+    len: usize,
+    hash: u64,
+    full: Option<String>,
+}
+
+impl ErrorBody {
+    /// Keeps the whole body, for debugging.
+    pub fn full_body(body: String) -> Self {
+        Self {
+            excerpt: String::new(),
+            len: body.len(),
+            hash: fnv1a(body.as_bytes()),
+            full: Some(body),
+        }
+    }
+
+    /// The body as kept: the whole body if it was kept, otherwise its start.
+    pub fn as_str(&self) -> &str {
+        self.full.as_deref().unwrap_or(&self.excerpt)
+    }
+
+    /// The whole body, if it was kept.
+    pub fn full(&self) -> Option<&str> {
+        self.full.as_deref()
+    }
+
+    /// Length of the whole body in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the body was empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether part of the body was dropped.
+    pub fn is_truncated(&self) -> bool {
+        self.as_str().len() < self.len
+    }
+
+    /// Stable hash of the whole body, the same for identical responses.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Wraps a body that failed to parse: all of it with `keep_full`, see
+    /// [`ClientBuilder::keep_full_error_bodies()`](crate::ClientBuilder::keep_full_error_bodies),
+    /// otherwise its start. Only the kept part of a borrowed body is copied.
+    pub(crate) fn new(body: Cow<'_, str>, keep_full: bool) -> Self {
+        match body {
+            _ if keep_full => Self::full_body(body.into_owned()),
+            Cow::Owned(body) => Self::from(body),
+            Cow::Borrowed(body) => Self::from(body),
+        }
+    }
+}
+
+/// Keeps the start of `body`, at most 1 KiB.
+impl From<String> for ErrorBody {
+    fn from(body: String) -> Self {
+        if body.len() > BODY_EXCERPT_BYTES {
+            // A new string, so the capacity of the whole body is released.
+            return Self::from(body.as_str());
+        }
+        Self {
+            hash: fnv1a(body.as_bytes()),
+            len: body.len(),
+            excerpt: body,
+            full: None,
+        }
+    }
+}
+
+/// Keeps a copy of the start of `body`, at most 1 KiB.
+impl From<&str> for ErrorBody {
+    fn from(body: &str) -> Self {
+        let mut end = body.len().min(BODY_EXCERPT_BYTES);
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        Self {
+            excerpt: body[..end].to_string(),
+            len: body.len(),
+            hash: fnv1a(body.as_bytes()),
+            full: None,
+        }
+    }
+}
+
+impl fmt::Display for ErrorBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Stable identifier of an error kind, see [`Error::code()`].
+///
+/// Meant for FFI layers and structured logs. The numeric values and the
+/// strings returned by [`ErrorCode::as_str()`] do not change between minor
+/// versions; new kinds get new values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[repr(u16)]
+pub enum ErrorCode {
+    /// [`Error::Authentication`] and [`Error::AuthenticationDetailed`].
+    AuthFailed = 1,
+    /// [`Error::InvalidCredentials`].
+    InvalidCredentials = 2,
+    /// [`Error::GrantFailed`].
+    AuthFlowFailed = 3,
+    /// [`Error::MissingEnvVar`].
+    MissingEnvVar = 4,
+    /// [`Error::MissingCredentials`].
+    MissingCredentials = 5,
+    /// [`Error::InvalidCredentialFormat`].
+    InvalidCredentialFormat = 6,
+    /// [`Error::PremiumRequired`].
+    PremiumRequired = 7,
+    /// [`Error::DeadlinePassed`].
+    DeadlinePassed = 8,
+    /// [`Error::InvalidDate`].
+    InvalidDate = 9,
+    /// [`Error::ModuleDisabled`].
+    ModuleDisabled = 10,
+    /// [`Error::SessionExpired`].
+    SessionExpired = 11,
+    /// [`Error::MessageDeleted`].
+    MessageDeleted = 12,
+    /// [`Error::MessagesPermissionDenied`].
+    PermissionDenied = 13,
+    /// [`Error::MessagesApiError`].
+    MessagesApi = 14,
+    /// [`Error::SessionTakenOver`].
+    SessionTakenOver = 15,
+    /// [`Error::ResponseTooLarge`].
+    ResponseTooLarge = 16,
+    /// [`Error::RangeNotHonored`].
+    RangeNotHonored = 17,
+    /// [`Error::Io`].
+    Io = 18,
+    /// [`Error::HttpClient`].
+    HttpClient = 19,
+    /// [`Error::Request`].
+    Network = 20,
+    /// [`Error::ApiError`] other than status 429.
+    Api = 21,
+    /// [`Error::ApiError`] with status 429 (Too Many Requests).
+    RateLimited = 22,
+    /// [`Error::Parse`].
+    Parse = 23,
+    /// [`Error::InvalidMessage`].
+    InvalidMessage = 24,
+    /// [`Error::MessagesUnavailable`].
+    MessagesUnavailable = 25,
+    /// [`Error::AttachmentScanning`].
+    AttachmentScanning = 26,
+    /// [`Error::Timeout`].
+    Timeout = 27,
+    /// [`Error::Connect`].
+    Connect = 28,
+    /// [`Error::UnitSelectionRequired`].
+    UnitSelectionRequired = 29,
+    /// [`Error::UnexpectedRedirect`].
+    UnexpectedRedirect = 30,
+    /// [`Error::UnsupportedUrl`].
+    UnsupportedUrl = 31,
+    /// [`Error::MessagesDisabledForAccount`].
+    MessagesDisabledForAccount = 32,
+    /// [`Error::BudgetExhausted`].
+    BudgetExhausted = 33,
+    /// [`Error::ReadConfirmationNotRequired`].
+    ReadConfirmationNotRequired = 34,
+    /// [`Error::ReadAlreadyConfirmed`].
+    ReadAlreadyConfirmed = 35,
+    /// [`Error::ResumePointNotFound`].
+    ResumePointNotFound = 36,
+}
+
+impl ErrorCode {
+    /// The numeric code.
+    pub fn as_u16(self) -> u16 {
+        self as u16
+    }
+
+    /// The code as an upper-case string, e.g. `"AUTH_FAILED"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::AuthFailed => "AUTH_FAILED",
+            ErrorCode::InvalidCredentials => "INVALID_CREDENTIALS",
+            ErrorCode::AuthFlowFailed => "AUTH_FLOW_FAILED",
+            ErrorCode::MissingEnvVar => "MISSING_ENV_VAR",
+            ErrorCode::MissingCredentials => "MISSING_CREDENTIALS",
+            ErrorCode::InvalidCredentialFormat => "INVALID_CREDENTIAL_FORMAT",
+            ErrorCode::PremiumRequired => "PREMIUM_REQUIRED",
+            ErrorCode::DeadlinePassed => "DEADLINE_PASSED",
+            ErrorCode::InvalidDate => "INVALID_DATE",
+            ErrorCode::ModuleDisabled => "MODULE_DISABLED",
+            ErrorCode::SessionExpired => "SESSION_EXPIRED",
+            ErrorCode::MessageDeleted => "MESSAGE_DELETED",
+            ErrorCode::PermissionDenied => "PERMISSION_DENIED",
+            ErrorCode::MessagesApi => "MESSAGES_API",
+            ErrorCode::SessionTakenOver => "SESSION_TAKEN_OVER",
+            ErrorCode::ResponseTooLarge => "RESPONSE_TOO_LARGE",
+            ErrorCode::RangeNotHonored => "RANGE_NOT_HONORED",
+            ErrorCode::Io => "IO",
+            ErrorCode::HttpClient => "HTTP_CLIENT",
+            ErrorCode::Network => "NETWORK",
+            ErrorCode::Api => "API",
+            ErrorCode::RateLimited => "RATE_LIMITED",
+            ErrorCode::Parse => "PARSE",
+            ErrorCode::InvalidMessage => "INVALID_MESSAGE",
+            ErrorCode::MessagesUnavailable => "MESSAGES_UNAVAILABLE",
+            ErrorCode::AttachmentScanning => "ATTACHMENT_SCANNING",
+            ErrorCode::Timeout => "TIMEOUT",
+            ErrorCode::Connect => "CONNECT",
+            ErrorCode::UnitSelectionRequired => "UNIT_SELECTION_REQUIRED",
+            ErrorCode::UnexpectedRedirect => "UNEXPECTED_REDIRECT",
+            ErrorCode::UnsupportedUrl => "UNSUPPORTED_URL",
+            ErrorCode::MessagesDisabledForAccount => "MESSAGES_DISABLED_FOR_ACCOUNT",
+            ErrorCode::BudgetExhausted => "BUDGET_EXHAUSTED",
+            ErrorCode::ReadConfirmationNotRequired => "READ_CONFIRMATION_NOT_REQUIRED",
+            ErrorCode::ReadAlreadyConfirmed => "READ_ALREADY_CONFIRMED",
+            ErrorCode::ResumePointNotFound => "RESUME_POINT_NOT_FOUND",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The Librus service a request or URL goes to, see [`Error::api()`] and
+/// [`ApiUrl::kind()`](crate::types::ApiUrl::kind).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiKind {
+    /// The Synergia gateway API (grades, attendance, notices, ...).
+    Synergia,
+    /// The Messages API.
+    Messages,
+    /// Other Librus services, such as older API versions or web pages.
+    Legacy,
+    /// A host outside Librus.
+    External,
+}
+
+impl fmt::Display for ApiKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ApiKind::Synergia => "Synergia",
+            ApiKind::Messages => "Messages",
+            ApiKind::Legacy => "Legacy",
+            ApiKind::External => "External",
+        })
+    }
+}
+
+/// Host of the Messages API, used to classify network errors.
+pub(crate) const MESSAGES_HOST: &str = "wiadomosci.librus.pl";
+
+/// A step of the Librus login flow, reported by [`Error::GrantFailed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthStep {
+    /// Opening the Synergia portal to start the OAuth flow.
+    Init,
+    /// Submitting the login form.
+    Login,
+    /// Following the OAuth grant redirect back to Synergia.
+    Grant,
+    /// Choosing the school unit of a district-wide account.
+    Unit,
+    /// Verifying the session token.
+    TokenInfo,
+}
+
+impl fmt::Display for AuthStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AuthStep::Init => "init",
+            AuthStep::Login => "login",
+            AuthStep::Grant => "grant",
+            AuthStep::Unit => "unit selection",
+            AuthStep::TokenInfo => "token info",
+        })
+    }
+}
+
+/// Extracts the `Code` field of a Synergia gateway error body such as
+/// `{"Status":"Error","Code":"...","Message":"..."}`.
+pub(crate) fn gateway_error_code(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    value.get("Code")?.as_str().map(str::to_string)
+}
+
+/// `errorCode` values of the Messages API error envelope.
+const MESSAGES_SESSION_EXPIRED_CODE: i64 = 11;
+const MESSAGES_PERMISSION_DENIED_CODE: i64 = 13;
+const MESSAGE_DELETED_CODE: i64 = 20;
+
+/// Maps a Messages API error envelope such as
+/// `{"errorCode": 11, "message": "..."}` to an error.
+///
+/// Returns `None` for regular responses, including ones with `errorCode: 0`.
+pub(crate) fn messages_envelope_error(body: &str) -> Option<Error> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let code = value.get("errorCode")?;
+    let code = code
+        .as_i64()
+        .or_else(|| code.as_str()?.trim().parse().ok())?;
+    let message = value
+        .get("message")
+        .and_then(|m| m.as_str())
+        .unwrap_or_default()
+        .to_string();
+    Some(match code {
+        0 => return None,
+        MESSAGES_SESSION_EXPIRED_CODE => Error::SessionExpired,
+        MESSAGES_PERMISSION_DENIED_CODE => Error::MessagesPermissionDenied { message },
+        MESSAGE_DELETED_CODE => Error::MessageDeleted,
+        code => Error::MessagesApiError { code, message },
+    })
+}
+
+/// Gateway error codes returned for modules the school has not enabled.
+const MODULE_DISABLED_CODES: &[&str] = &["ModuleDisabled", "Disabled", "NotActive"];
+
+/// Gateway error codes returned when another login to the same account
+/// invalidated the session.
+const SESSION_TAKEN_OVER_CODES: &[&str] = &["SessionTakenOver", "TokenIsRevoked"];
+
+/// Whether a Synergia gateway error body reports a session taken over by
+/// another login.
+pub(crate) fn is_session_taken_over(body: &str) -> bool {
+    gateway_error_code(body).is_some_and(|code| SESSION_TAKEN_OVER_CODES.contains(&code.as_str()))
+}
+
+/// Whether a redirected Synergia request reports a session taken over by
+/// another login, in the body or in the `Location` header, e.g.
+/// `/loguj/portalRodzina?reason=SessionTakenOver`.
+pub(crate) fn is_takeover_redirect(location: &str, body: &str) -> bool {
+    let location = location.to_ascii_lowercase();
+    is_session_taken_over(body)
+        || SESSION_TAKEN_OVER_CODES
+            .iter()
+            .any(|code| location.contains(&code.to_ascii_lowercase()))
+}
+
+/// Whether a Synergia gateway error body reports a disabled module.
+pub(crate) fn is_module_disabled(body: &str) -> bool {
+    gateway_error_code(body).is_some_and(|code| MODULE_DISABLED_CODES.contains(&code.as_str()))
+}
+
+/// Classifies a network error as [`Error::Timeout`], [`Error::Connect`] or
+/// [`Error::Request`], and an error status as [`Error::ApiError`].
+impl From<reqwest::Error> for Error {
+    fn from(source: reqwest::Error) -> Self {
+        let Some(endpoint) = source.url().map(endpoint) else {
+            return Self::Request(source);
+        };
+        if let Some(status) = source.status() {
+            Self::ApiError {
+                api: api_of(source.url()).unwrap_or(ApiKind::Synergia),
+                status: status.as_u16(),
+                body: String::new(),
+                source: Some(source),
+            }
+        } else if source.is_timeout() {
+            Self::Timeout { endpoint, source }
+        } else if source.is_connect() {
+            Self::Connect { endpoint, source }
+        } else {
+            Self::Request(source)
+        }
+    }
+}
+
+/// Which API a request URL goes to, by its host.
+fn api_of(url: Option<&reqwest::Url>) -> Option<ApiKind> {
+    url.and_then(|url| url.host_str()).map(|host| {
+        if host == MESSAGES_HOST {
+            ApiKind::Messages
+        } else {
+            ApiKind::Synergia
+        }
+    })
+}
+
+/// A request URL without the query string, which may carry IDs.
+fn endpoint(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    url.set_query(None);
+    url.to_string()
+}
+
+impl Error {
+    /// The stable code of this error's kind.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::{Error, ErrorCode};
+    ///
+    /// let error = Error::MissingCredentials("username");
+    /// assert_eq!(error.code(), ErrorCode::MissingCredentials);
+    /// assert_eq!(error.code().as_u16(), 5);
+    /// ```
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Authentication | Self::AuthenticationDetailed { .. } => ErrorCode::AuthFailed,
+            Self::InvalidCredentials { .. } => ErrorCode::InvalidCredentials,
+            Self::GrantFailed { .. } => ErrorCode::AuthFlowFailed,
+            Self::UnitSelectionRequired { .. } => ErrorCode::UnitSelectionRequired,
+            Self::UnexpectedRedirect { .. } => ErrorCode::UnexpectedRedirect,
+            Self::UnsupportedUrl { .. } => ErrorCode::UnsupportedUrl,
+            Self::MissingEnvVar(_) => ErrorCode::MissingEnvVar,
+            Self::MissingCredentials(_) => ErrorCode::MissingCredentials,
+            Self::InvalidCredentialFormat { .. } => ErrorCode::InvalidCredentialFormat,
+            Self::PremiumRequired(_) => ErrorCode::PremiumRequired,
+            Self::DeadlinePassed => ErrorCode::DeadlinePassed,
+            Self::InvalidDate(_) => ErrorCode::InvalidDate,
+            Self::ModuleDisabled(_) => ErrorCode::ModuleDisabled,
+            Self::SessionExpired => ErrorCode::SessionExpired,
+            Self::MessageDeleted => ErrorCode::MessageDeleted,
+            Self::MessagesPermissionDenied { .. } => ErrorCode::PermissionDenied,
+            Self::MessagesApiError { .. } => ErrorCode::MessagesApi,
+            Self::SessionTakenOver => ErrorCode::SessionTakenOver,
+            Self::ResponseTooLarge { .. } => ErrorCode::ResponseTooLarge,
+            Self::RangeNotHonored { .. } => ErrorCode::RangeNotHonored,
+            Self::AttachmentScanning { .. } => ErrorCode::AttachmentScanning,
+            Self::BudgetExhausted { .. } => ErrorCode::BudgetExhausted,
+            Self::ReadConfirmationNotRequired => ErrorCode::ReadConfirmationNotRequired,
+            Self::ReadAlreadyConfirmed => ErrorCode::ReadAlreadyConfirmed,
+            Self::InvalidMessage(_) => ErrorCode::InvalidMessage,
+            Self::MessagesUnavailable { .. } => ErrorCode::MessagesUnavailable,
+            Self::MessagesDisabledForAccount => ErrorCode::MessagesDisabledForAccount,
+            Self::ResumePointNotFound { .. } => ErrorCode::ResumePointNotFound,
+            Self::Io(_) => ErrorCode::Io,
+            Self::HttpClient(_) => ErrorCode::HttpClient,
+            Self::Timeout { .. } => ErrorCode::Timeout,
+            Self::Connect { .. } => ErrorCode::Connect,
+            Self::Request(_) => ErrorCode::Network,
+            Self::ApiError { status: 429, .. } => ErrorCode::RateLimited,
+            Self::ApiError { .. } => ErrorCode::Api,
+            Self::Parse { .. } => ErrorCode::Parse,
+        }
+    }
+
+    /// Whether retrying the request may succeed: the request timed out, could
+    /// not connect or broke off on the network, the server was overloaded or
+    /// briefly down (status 408, 429, 500, 502, 503 or 504), the attachment
+    /// is still being scanned or the request budget is used up.
+    ///
+    /// Wait before retrying; [`Error::AttachmentScanning`] and
+    /// [`Error::BudgetExhausted`] tell how long.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let grades = match client.grades().await {
+    ///     Err(e) if e.is_retryable() => client.grades().await?,
+    ///     other => other?,
+    /// };
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        self.is_network()
+            || matches!(
+                self,
+                Self::ApiError {
+                    status: 408 | 429 | 500 | 502 | 503 | 504,
+                    ..
+                } | Self::AttachmentScanning { .. }
+                    | Self::BudgetExhausted { .. }
+            )
+    }
+
+    /// Whether the request failed on the network, see [`Error::Timeout`],
+    /// [`Error::Connect`] and [`Error::Request`].
+    pub(crate) fn is_network(&self) -> bool {
+        matches!(
+            self,
+            Self::Timeout { .. } | Self::Connect { .. } | Self::Request(_)
+        )
+    }
+
+    /// Whether the error comes from logging in or from a lost session:
+    /// missing or rejected credentials, a failed login step, or a session
+    /// that expired, was taken over or got status 401.
+    ///
+    /// Logging in again, possibly with other credentials, is the way out.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::Error;
+    ///
+    /// assert!(Error::SessionExpired.is_auth_related());
+    /// assert!(!Error::MessageDeleted.is_auth_related());
+    /// ```
+    pub fn is_auth_related(&self) -> bool {
+        matches!(
+            self,
+            Self::Authentication
+                | Self::AuthenticationDetailed { .. }
+                | Self::InvalidCredentials { .. }
+                | Self::GrantFailed { .. }
+                | Self::UnitSelectionRequired { .. }
+                | Self::UnexpectedRedirect { .. }
+                | Self::MissingEnvVar(_)
+                | Self::MissingCredentials(_)
+                | Self::InvalidCredentialFormat { .. }
+                | Self::SessionExpired
+                | Self::SessionTakenOver
+                | Self::ApiError { status: 401, .. }
+        )
+    }
+
+    /// The stable string code of this error's kind, e.g. `"PARSE"`.
+    ///
+    /// Same as `self.code().as_str()`.
+    pub fn code_str(&self) -> &'static str {
+        self.code().as_str()
+    }
+
+    /// HTTP status code reported with the error, if any.
+    fn status(&self) -> Option<u16> {
+        match self {
+            Self::GrantFailed { status, .. } | Self::ApiError { status, .. } => Some(*status),
+            Self::Request(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Which API the failed request went to, if known.
+    ///
+    /// Lets callers tell a Messages outage apart from a Synergia one and
+    /// degrade only the affected part of their UI. Known for
+    /// [`Error::ApiError`] and the Messages errors; for
+    /// network errors it is inferred from the request URL.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{ApiKind, Client};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// match client.unread_counts().await {
+    ///     Ok(counts) => println!("Unread: {}", counts.inbox),
+    ///     Err(e) if e.api() == Some(ApiKind::Messages) => println!("Messages unavailable"),
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn api(&self) -> Option<ApiKind> {
+        match self {
+            Self::ApiError { api, .. } => Some(*api),
+            Self::MessageDeleted
+            | Self::MessagesPermissionDenied { .. }
+            | Self::MessagesApiError { .. }
+            | Self::MessagesUnavailable { .. }
+            | Self::MessagesDisabledForAccount => Some(ApiKind::Messages),
+            Self::SessionTakenOver => Some(ApiKind::Synergia),
+            Self::Timeout { source, .. } | Self::Connect { source, .. } | Self::Request(source) => {
+                api_of(source.url())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the response body attached to this error with personal data scrubbed.
+    ///
+    /// Available for [`Error::Parse`] and [`Error::ApiError`]. The body is passed
+    /// through [`anonymize::scrub_json()`](crate::anonymize::scrub_json), so the
+    /// result is safe to paste into a public issue while still reproducing the
+    /// parse failure. A parse error keeps only the start of large bodies,
+    /// which is then no valid JSON and is redacted as a whole; build the
+    /// client with
+    /// [`ClientBuilder::keep_full_error_bodies()`](crate::ClientBuilder::keep_full_error_bodies)
+    /// to reproduce such failures.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// if let Err(e) = client.grades().await {
+    ///     if let Some(body) = e.scrubbed_body() {
+    ///         eprintln!("{e}\n{body}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn scrubbed_body(&self) -> Option<String> {
+        match self {
+            Self::Parse { body, .. } => Some(crate::anonymize::scrub_json(body.as_str())),
+            Self::ApiError { body, .. } => Some(crate::anonymize::scrub_json(body)),
+            _ => None,
+        }
+    }
+}
+
+/// Serializes as `{ "code", "message", "status"?, "endpoint"? }`.
+///
+/// `code` is [`Error::code_str()`] and `message` the `Display` output.
+/// `status` is present for errors carrying an HTTP status, `endpoint` for
+/// network errors with a known URL (without the query string).
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let endpoint = match self {
+            Self::Timeout { endpoint, .. } | Self::Connect { endpoint, .. } => {
+                Some(endpoint.clone())
+            }
+            Self::Request(e) | Self::HttpClient(e) => e.url().map(endpoint),
+            _ => None,
+        };
+        let status = self.status();
+        let len = 2 + usize::from(status.is_some()) + usize::from(endpoint.is_some());
+        let mut map = serializer.serialize_map(Some(len))?;
+        map.serialize_entry("code", self.code_str())?;
+        map.serialize_entry("message", &self.to_string())?;
+        if let Some(status) = status {
+            map.serialize_entry("status", &status)?;
+        }
+        if let Some(endpoint) = endpoint {
+            map.serialize_entry("endpoint", &endpoint)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn reqwest_error() -> reqwest::Error {
+        reqwest::Client::new()
+            .get("http://[::1")
+            .build()
+            .unwrap_err()
+    }
+
+    fn parse_error() -> Error {
+        Error::Parse {
+            source: serde_json::from_str::<u8>("x").unwrap_err(),
+            body: "x".into(),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_keeps_an_excerpt() {
+        let body = format!("{{\"Grades\": \"{}ż\"}}", "x".repeat(10 << 20));
+        let len = body.len();
+        let error = Error::Parse {
+            source: serde_json::from_str::<u8>(&body).unwrap_err(),
+            body: body.clone().into(),
+        };
+        let Error::Parse { body: kept, .. } = &error else {
+            unreachable!()
+        };
+        // The error and what it owns stay within a few KB.
+        let owned = std::mem::size_of::<Error>() + kept.excerpt.capacity();
+        assert!(owned < 4096, "{owned} bytes");
+        assert!(kept.as_str().starts_with("{\"Grades\": \"xxx"));
+        assert_eq!(kept.len(), len);
+        assert!(kept.is_truncated() && kept.full().is_none());
+        assert_eq!(kept.hash(), ErrorBody::full_body(body.clone()).hash());
+
+        // Cut at a character boundary.
+        let polish = ErrorBody::from("ż".repeat(1000));
+        assert_eq!(polish.as_str().len(), 1024);
+        let full = ErrorBody::full_body(body);
+        assert!(!full.is_truncated());
+        assert_eq!(full.full().map(str::len), Some(len));
+    }
+
+    /// One error of each kind, in [`ErrorCode`] order where possible.
+    fn every_variant() -> Vec<Error> {
+        vec![
+            Error::Authentication,
+            Error::AuthenticationDetailed {
+                trace: AuthTrace::default(),
+            },
+            Error::InvalidCredentials {
+                message: String::new(),
+            },
+            Error::GrantFailed {
+                step: AuthStep::Grant,
+                status: 500,
+            },
+            Error::UnitSelectionRequired { units: Vec::new() },
+            Error::UnexpectedRedirect { to: String::new() },
+            Error::UnsupportedUrl {
+                url: String::new(),
+                kind: ApiKind::External,
+            },
+            Error::MissingEnvVar("LIBRUS_USERNAME"),
+            Error::MissingCredentials("password"),
+            Error::InvalidCredentialFormat {
+                field: "username",
+                reason: "is empty",
+            },
+            Error::PremiumRequired("Mobilne dodatki".to_string()),
+            Error::DeadlinePassed,
+            Error::InvalidDate("2024-13-01".to_string()),
+            Error::ModuleDisabled("Homeworks".to_string()),
+            Error::SessionExpired,
+            Error::MessageDeleted,
+            Error::MessagesPermissionDenied {
+                message: String::new(),
+            },
+            Error::MessagesApiError {
+                code: 99,
+                message: String::new(),
+            },
+            Error::SessionTakenOver,
+            Error::ResponseTooLarge { limit: 1 },
+            Error::RangeNotHonored {
+                requested: 1,
+                content_range: None,
+            },
+            Error::AttachmentScanning {
+                retry_after_hint: None,
+            },
+            Error::BudgetExhausted {
+                retry_after: Duration::ZERO,
+            },
+            Error::ReadConfirmationNotRequired,
+            Error::ReadAlreadyConfirmed,
+            Error::InvalidMessage(vec![crate::ValidationIssue::EmptyBody]),
+            Error::MessagesUnavailable {
+                reason: String::new(),
+            },
+            Error::MessagesDisabledForAccount,
+            Error::ResumePointNotFound {
+                message_id: String::new(),
+            },
+            Error::Io(std::io::Error::other("disk full")),
+            Error::HttpClient(reqwest_error()),
+            Error::Timeout {
+                endpoint: String::new(),
+                source: reqwest_error(),
+            },
+            Error::Connect {
+                endpoint: String::new(),
+                source: reqwest_error(),
+            },
+            Error::Request(reqwest_error()),
+            Error::ApiError {
+                api: ApiKind::Synergia,
+                status: 500,
+                body: String::new(),
+                source: None,
+            },
+            Error::ApiError {
+                api: ApiKind::Synergia,
+                status: 429,
+                body: String::new(),
+                source: None,
+            },
+            parse_error(),
+        ]
+    }
+
+    #[test]
+    fn test_every_variant_has_a_distinct_code() {
+        let errors = every_variant();
+        let codes: HashSet<u16> = errors.iter().map(|e| e.code().as_u16()).collect();
+        let names: HashSet<&str> = errors.iter().map(Error::code_str).collect();
+        // The traced login failure shares AUTH_FAILED with the plain one.
+        assert_eq!(errors[1].code(), ErrorCode::AuthFailed);
+        assert_eq!(codes.len(), errors.len() - 1);
+        assert_eq!(names.len(), errors.len() - 1);
+    }
+
+    #[test]
+    fn test_stable_codes() {
+        assert_eq!(Error::Authentication.code_str(), "AUTH_FAILED");
+        assert_eq!(parse_error().code(), ErrorCode::Parse);
+        assert_eq!(ErrorCode::Parse.as_u16(), 23);
+        let rate_limited = Error::ApiError {
+            api: ApiKind::Messages,
+            status: 429,
+            body: String::new(),
+            source: None,
+        };
+        assert_eq!(rate_limited.code_str(), "RATE_LIMITED");
+        assert_eq!(rate_limited.code().as_u16(), 22);
+    }
+
+    #[test]
+    fn test_serialized_shape() {
+        let value = serde_json::to_value(Error::ApiError {
+            api: ApiKind::Synergia,
+            status: 503,
+            body: "down".to_string(),
+            source: None,
+        })
+        .unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "code": "API",
+                "message": "Synergia API error (status 503): down",
+                "status": 503,
+            })
+        );
+
+        let value = serde_json::to_value(Error::SessionExpired).unwrap();
+        assert_eq!(value.as_object().unwrap().len(), 2);
+        assert_eq!(value["code"], "SESSION_EXPIRED");
+    }
+
+    #[test]
+    fn test_predicates() {
+        use ErrorCode::*;
+        for error in every_variant() {
+            let retryable = matches!(
+                error.code(),
+                Network | Timeout | Connect | RateLimited | AttachmentScanning | BudgetExhausted
+            ) || matches!(error, Error::ApiError { status: 500, .. });
+            let auth = matches!(
+                error.code(),
+                AuthFailed
+                    | InvalidCredentials
+                    | AuthFlowFailed
+                    | UnitSelectionRequired
+                    | UnexpectedRedirect
+                    | MissingEnvVar
+                    | MissingCredentials
+                    | InvalidCredentialFormat
+                    | SessionExpired
+                    | SessionTakenOver
+            );
+            assert_eq!(error.is_retryable(), retryable, "{error:?}");
+            assert_eq!(error.is_auth_related(), auth, "{error:?}");
+        }
+
+        let api_error = |status| Error::ApiError {
+            api: ApiKind::Synergia,
+            status,
+            body: String::new(),
+            source: None,
+        };
+        assert!(api_error(503).is_retryable() && !api_error(503).is_auth_related());
+        assert!(!api_error(404).is_retryable() && !api_error(403).is_auth_related());
+        assert!(api_error(401).is_auth_related() && !api_error(401).is_retryable());
+    }
+
+    #[test]
+    fn test_source_chains() {
+        use std::error::Error as _;
+        for error in every_variant() {
+            let source = error.source();
+            match &error {
+                Error::Io(_)
+                | Error::HttpClient(_)
+                | Error::Timeout { .. }
+                | Error::Connect { .. }
+                | Error::Request(_)
+                | Error::Parse { .. } => assert!(source.is_some(), "{error:?}"),
+                _ => assert!(source.is_none(), "{error:?}"),
+            }
+        }
+
+        let Error::Parse { source, .. } = parse_error() else {
+            unreachable!()
+        };
+        let parse = parse_error();
+        let chained = parse.source().unwrap();
+        assert_eq!(chained.to_string(), source.to_string());
+        assert!(chained.is::<serde_json::Error>());
+
+        let timeout = Error::Timeout {
+            endpoint: String::new(),
+            source: reqwest_error(),
+        };
+        assert!(timeout.source().unwrap().is::<reqwest::Error>());
+    }
+}
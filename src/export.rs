@@ -0,0 +1,175 @@
+//! Exporting message folders to JSON Lines.
+
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::structs::messages::{MessageDetail, ReceiverReadStatus};
+use crate::types::ApiDateTime;
+use crate::Client;
+
+/// A message folder that can be exported with
+/// [`Client::export_messages_jsonl()`](crate::Client::export_messages_jsonl).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageFolder {
+    /// Received messages.
+    Inbox,
+    /// Sent messages.
+    Outbox,
+}
+
+/// Options for [`Client::export_messages_jsonl()`](crate::Client::export_messages_jsonl).
+///
+/// # Example
+///
+/// ```rust
+/// use librus_rs::ExportOptions;
+///
+/// let opts = ExportOptions::new()
+///     .since_message_id("12345")
+///     .download_attachments();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    pub(crate) page_size: Option<u32>,
+    pub(crate) since_message_id: Option<String>,
+    pub(crate) download_attachments: bool,
+    pub(crate) cancel: Option<CancellationToken>,
+}
+
+impl ExportOptions {
+    /// Creates options exporting the whole folder without attachment contents.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many messages are listed per request, overriding
+    /// [`ClientBuilder::messages_page_size()`](crate::ClientBuilder::messages_page_size).
+    ///
+    /// At most one page of message IDs is held in memory at a time.
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = Some(page_size.max(1));
+        self
+    }
+
+    /// Resumes an earlier export that ended at `message_id`.
+    ///
+    /// Messages are listed newest first. Every message up to and including
+    /// `message_id` is skipped, so pass
+    /// [`ExportSummary::last_message_id`] of the interrupted run. If the
+    /// message is no longer in the folder, the export fails with
+    /// [`Error::ResumePointNotFound`](crate::Error::ResumePointNotFound)
+    /// without writing anything.
+    pub fn since_message_id(mut self, message_id: impl Into<String>) -> Self {
+        self.since_message_id = Some(message_id.into());
+        self
+    }
+
+    /// Includes attachment contents, base64-encoded, in the exported lines.
+    ///
+    /// By default attachments are only listed by ID, name and size.
+    pub fn download_attachments(mut self) -> Self {
+        self.download_attachments = true;
+        self
+    }
+
+    /// Stops the export early once `token` is cancelled, e.g. on shutdown.
+    ///
+    /// The token is checked before each message and each page request, never
+    /// while a line is written, so the output ends with a complete line. The
+    /// export then returns normally with [`ExportSummary::cancelled`] set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::{CancellationToken, ExportOptions};
+    ///
+    /// let shutdown = CancellationToken::new();
+    /// let opts = ExportOptions::new().cancel_on(shutdown.clone());
+    /// // On SIGTERM:
+    /// shutdown.cancel();
+    /// ```
+    pub fn cancel_on(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|t| t.is_cancelled())
+    }
+}
+
+/// Outcome of [`Client::export_messages_jsonl()`](crate::Client::export_messages_jsonl).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    /// Number of lines written.
+    pub exported: usize,
+    /// ID of the last message written, to resume from with
+    /// [`ExportOptions::since_message_id()`].
+    pub last_message_id: Option<String>,
+    /// Whether the export stopped early because the token given to
+    /// [`ExportOptions::cancel_on()`] was cancelled.
+    pub cancelled: bool,
+}
+
+/// One line of the export.
+#[derive(Debug, Serialize)]
+pub(crate) struct ExportLine {
+    folder: MessageFolder,
+    message_id: String,
+    sender_id: Option<String>,
+    sender_name: String,
+    topic: String,
+    send_date: ApiDateTime,
+    read_date: Option<ApiDateTime>,
+    /// Decoded body, or `None` if it is not valid base64 UTF-8.
+    body: Option<String>,
+    attachments: Vec<ExportedAttachment>,
+    receivers: Vec<ReceiverReadStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ExportedAttachment {
+    id: String,
+    name: String,
+    size: Option<u64>,
+    /// Base64-encoded contents, when downloading was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<String>,
+}
+
+impl ExportLine {
+    pub(crate) fn new(folder: MessageFolder, detail: MessageDetail) -> Self {
+        Self {
+            folder,
+            body: Client::decode_message_content(&detail.message),
+            message_id: detail.message_id,
+            sender_id: detail.sender_id,
+            sender_name: detail.sender_name,
+            topic: detail.topic,
+            send_date: detail.send_date,
+            read_date: detail.read_date,
+            attachments: detail
+                .attachments
+                .into_iter()
+                .map(|a| ExportedAttachment {
+                    id: a.id,
+                    name: a.name,
+                    size: a.size,
+                    data: None,
+                })
+                .collect(),
+            receivers: detail.receivers,
+        }
+    }
+
+    /// Attachments whose contents can be filled in with [`Self::set_data()`].
+    pub(crate) fn attachment_ids(&self) -> Vec<String> {
+        self.attachments.iter().map(|a| a.id.clone()).collect()
+    }
+
+    pub(crate) fn set_data(&mut self, index: usize, bytes: &[u8]) {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+        self.attachments[index].data = Some(STANDARD.encode(bytes));
+    }
+}
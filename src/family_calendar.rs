@@ -0,0 +1,250 @@
+//! Merged family calendar built from several Synergia endpoints.
+
+use std::collections::HashSet;
+
+use crate::structs::calendars::{
+    ClassFreeDay, ParentTeacherConference, SchoolFreeDay, Substitution, TeacherFreeDay,
+};
+use crate::structs::events::Homework;
+use crate::types::ApiDate;
+
+/// Kind of a [`FamilyEvent`].
+///
+/// Variants are ordered so that, on the same day and time, all-day events such
+/// as free days sort before lesson-level events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FamilyEventKind {
+    /// A school-wide day off.
+    SchoolFreeDay,
+    /// A day off for the student's class only.
+    ClassFreeDay,
+    /// A teacher absence.
+    TeacherFreeDay,
+    /// A parent-teacher conference.
+    ParentTeacherConference,
+    /// A homework or test deadline.
+    HomeworkDeadline,
+    /// A lesson substitution.
+    Substitution,
+    /// A cancelled lesson.
+    Cancellation,
+}
+
+/// A single entry of the merged family calendar.
+///
+/// Returned by [`Client::family_calendar()`](crate::Client::family_calendar).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FamilyEvent {
+    /// What kind of event this is.
+    pub kind: FamilyEventKind,
+    /// ID of the entry in its source endpoint.
+    pub source_id: i64,
+    /// First day of the event (`YYYY-MM-DD`).
+    pub date: ApiDate,
+    /// Last day of the event, inclusive (`YYYY-MM-DD`). Equal to `date` for
+    /// single-day events.
+    pub end_date: ApiDate,
+    /// Start time, if the event is not all-day.
+    pub time: Option<String>,
+    /// Short human-readable description.
+    pub title: String,
+}
+
+impl FamilyEvent {
+    fn overlaps(&self, from: &str, to: &str) -> bool {
+        self.date.as_str() <= to && self.end_date.as_str() >= from
+    }
+
+    fn sort_key(&self) -> (&str, &str, FamilyEventKind, i64) {
+        (
+            &self.date,
+            self.time.as_deref().unwrap_or(""),
+            self.kind,
+            self.source_id,
+        )
+    }
+}
+
+impl From<&Homework> for FamilyEvent {
+    fn from(hw: &Homework) -> Self {
+        Self {
+            kind: FamilyEventKind::HomeworkDeadline,
+            source_id: hw.id,
+            date: hw.date.clone(),
+            end_date: hw.date.clone(),
+            time: Some(hw.time_from.clone()).filter(|t| !t.is_empty()),
+            title: hw.content.clone(),
+        }
+    }
+}
+
+impl From<&SchoolFreeDay> for FamilyEvent {
+    fn from(day: &SchoolFreeDay) -> Self {
+        Self {
+            kind: FamilyEventKind::SchoolFreeDay,
+            source_id: day.id,
+            date: day.date_from.clone(),
+            end_date: day.date_to.clone(),
+            time: None,
+            title: day.name.clone(),
+        }
+    }
+}
+
+impl From<&ClassFreeDay> for FamilyEvent {
+    fn from(day: &ClassFreeDay) -> Self {
+        Self {
+            kind: FamilyEventKind::ClassFreeDay,
+            source_id: day.id,
+            date: day.date_from.clone(),
+            end_date: day.date_to.clone(),
+            time: None,
+            title: day
+                .name
+                .clone()
+                .unwrap_or_else(|| "Class free day".to_string()),
+        }
+    }
+}
+
+impl From<&TeacherFreeDay> for FamilyEvent {
+    fn from(day: &TeacherFreeDay) -> Self {
+        Self {
+            kind: FamilyEventKind::TeacherFreeDay,
+            source_id: day.id,
+            date: day.date_from.clone(),
+            end_date: day.date_to.clone(),
+            time: day.time_from.clone(),
+            title: "Teacher absence".to_string(),
+        }
+    }
+}
+
+impl From<&ParentTeacherConference> for FamilyEvent {
+    fn from(conf: &ParentTeacherConference) -> Self {
+        Self {
+            kind: FamilyEventKind::ParentTeacherConference,
+            source_id: conf.id,
+            date: conf.date.clone(),
+            end_date: conf.date.clone(),
+            time: conf.time.clone(),
+            title: conf.name.clone(),
+        }
+    }
+}
+
+impl From<&Substitution> for FamilyEvent {
+    fn from(sub: &Substitution) -> Self {
+        let (kind, title) = if sub.is_cancelled {
+            (FamilyEventKind::Cancellation, "Lesson cancelled")
+        } else {
+            (FamilyEventKind::Substitution, "Lesson substitution")
+        };
+        Self {
+            kind,
+            source_id: sub.id,
+            date: sub.org_date.clone(),
+            end_date: sub.org_date.clone(),
+            time: None,
+            title: title.to_string(),
+        }
+    }
+}
+
+/// Filters events to the `from..=to` range, removes duplicates and sorts them.
+///
+/// The same entry may be reported by more than one endpoint (e.g. the
+/// `Calendars` aggregate and a dedicated endpoint). Events with the same kind,
+/// start date and source ID are considered duplicates; the first one wins.
+pub(crate) fn merge<I>(events: I, from: &str, to: &str) -> Vec<FamilyEvent>
+where
+    I: IntoIterator<Item = FamilyEvent>,
+{
+    let mut seen = HashSet::new();
+    let mut merged: Vec<FamilyEvent> = events
+        .into_iter()
+        .filter(|e| e.overlaps(from, to))
+        .filter(|e| seen.insert((e.kind, e.date.clone(), e.source_id)))
+        .collect();
+    merged.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: FamilyEventKind, id: i64, date: &str, end: &str) -> FamilyEvent {
+        FamilyEvent {
+            kind,
+            source_id: id,
+            date: date.try_into().unwrap(),
+            end_date: end.try_into().unwrap(),
+            time: None,
+            title: format!("{kind:?} {id}"),
+        }
+    }
+
+    #[test]
+    fn test_merge_deduplicates_overlapping_sources() {
+        use FamilyEventKind::*;
+        let calendars = vec![
+            event(HomeworkDeadline, 1, "2025-03-05", "2025-03-05"),
+            event(SchoolFreeDay, 7, "2025-03-03", "2025-03-04"),
+        ];
+        let dedicated = vec![
+            event(HomeworkDeadline, 1, "2025-03-05", "2025-03-05"),
+            // Same ID but a different kind is a different event.
+            event(Substitution, 1, "2025-03-05", "2025-03-05"),
+            // Same ID on another date is kept too.
+            event(HomeworkDeadline, 1, "2025-03-06", "2025-03-06"),
+            event(SchoolFreeDay, 7, "2025-03-03", "2025-03-04"),
+        ];
+
+        let merged = merge(
+            calendars.into_iter().chain(dedicated),
+            "2025-03-01",
+            "2025-03-31",
+        );
+        let keys: Vec<_> = merged
+            .iter()
+            .map(|e| (e.kind, e.source_id, e.date.as_str()))
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                (SchoolFreeDay, 7, "2025-03-03"),
+                (HomeworkDeadline, 1, "2025-03-05"),
+                (Substitution, 1, "2025-03-05"),
+                (HomeworkDeadline, 1, "2025-03-06"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_keeps_ranges_overlapping_window() {
+        use FamilyEventKind::*;
+        let events = vec![
+            event(SchoolFreeDay, 1, "2024-12-23", "2025-01-01"),
+            event(SchoolFreeDay, 2, "2024-11-11", "2024-11-11"),
+            event(ParentTeacherConference, 3, "2025-01-02", "2025-01-02"),
+        ];
+        let merged = merge(events, "2025-01-01", "2025-01-01");
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].source_id, 1);
+    }
+
+    #[test]
+    fn test_merge_orders_by_time_within_day() {
+        use FamilyEventKind::*;
+        let mut late = event(ParentTeacherConference, 1, "2025-02-10", "2025-02-10");
+        late.time = Some("17:00:00".to_string());
+        let mut early = event(HomeworkDeadline, 2, "2025-02-10", "2025-02-10");
+        early.time = Some("08:00:00".to_string());
+        let all_day = event(TeacherFreeDay, 3, "2025-02-10", "2025-02-10");
+
+        let merged = merge(vec![late, early, all_day], "2025-02-10", "2025-02-10");
+        let ids: Vec<_> = merged.iter().map(|e| e.source_id).collect();
+        assert_eq!(ids, [3, 2, 1]);
+    }
+}
@@ -0,0 +1,291 @@
+//! Atom feeds of school notices and grades, for feed readers.
+//!
+//! Requires the `feed` feature. [`notices_to_atom()`] and [`grades_to_atom()`]
+//! render an Atom 1.0 document. Entry IDs are derived from the feed ID and
+//! the entity ID, so an entry keeps its ID across runs and readers show each
+//! notice or grade once.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use librus_rs::feed::{self, FeedMeta};
+//! use librus_rs::Client;
+//!
+//! # async fn example() -> Result<(), librus_rs::Error> {
+//! let client = Client::from_env().await?;
+//! let notices = client.school_notices().await?;
+//! let meta = FeedMeta::new("urn:librus:notices:1234", "Ogłoszenia szkolne")
+//!     .self_link("https://example.com/notices.atom");
+//! std::fs::write("notices.atom", feed::notices_to_atom(&notices, &meta)).unwrap();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::dates;
+use crate::structs::announcements::{ResponseSchoolNotices, SchoolNotice};
+use crate::structs::grades::Grade;
+use crate::structs::lessons::LessonSubject;
+
+/// Author of the feed when none is set.
+const DEFAULT_AUTHOR: &str = "Librus Synergia";
+
+/// Feed-level data of an Atom document.
+#[derive(Debug, Clone)]
+pub struct FeedMeta {
+    id: String,
+    title: String,
+    author: String,
+    self_link: Option<String>,
+}
+
+impl FeedMeta {
+    /// Creates the feed data from its `id`, an IRI that must not change
+    /// between runs (e.g. `urn:librus:notices:1234`), and its `title`.
+    pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            author: DEFAULT_AUTHOR.to_string(),
+            self_link: None,
+        }
+    }
+
+    /// Sets the author shown for every entry (default `Librus Synergia`).
+    pub fn author(mut self, name: impl Into<String>) -> Self {
+        self.author = name.into();
+        self
+    }
+
+    /// Sets the URL the feed is published at.
+    pub fn self_link(mut self, url: impl Into<String>) -> Self {
+        self.self_link = Some(url.into());
+        self
+    }
+
+    /// ID of the entry for an entity of `kind`.
+    fn entry_id(&self, kind: &str, id: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            self.id.trim_end_matches('/'),
+            kind,
+            percent_encode(id)
+        )
+    }
+}
+
+/// An entry before rendering.
+struct Entry {
+    id: String,
+    title: String,
+    updated: i64,
+    /// `(type, text)` of the content, e.g. `("html", "<p>...</p>")`.
+    content: (&'static str, String),
+}
+
+/// Renders school notices as an Atom feed.
+///
+/// Each entry is timed by the creation date of the notice. The content is
+/// kept as HTML and escaped, so it need not be well-formed XML. Inline
+/// images are kept as well; strip them first with
+/// [`SchoolNotice::strip_inline_images()`] to keep the feed small.
+pub fn notices_to_atom(notices: &ResponseSchoolNotices, meta: &FeedMeta) -> String {
+    let entries = notices
+        .school_notices
+        .iter()
+        .map(|notice| Entry {
+            id: meta.entry_id("notice", &notice.id),
+            title: notice.subject.clone(),
+            updated: notice_time(notice),
+            content: ("html", notice.content.clone()),
+        })
+        .collect();
+    render(meta, entries)
+}
+
+/// Renders grades as an Atom feed, e.g. the new ones since the last run.
+///
+/// Entries are titled with the subject name from `subjects` and timed by
+/// when the grade was added. Embedded comment texts are included in the
+/// content.
+pub fn grades_to_atom(grades: &[Grade], subjects: &[LessonSubject], meta: &FeedMeta) -> String {
+    let names: HashMap<i64, &str> = subjects
+        .iter()
+        .map(|s| (i64::from(s.id), s.name.as_str()))
+        .collect();
+    let entries = grades
+        .iter()
+        .map(|grade| {
+            let subject = grade
+                .subject
+                .id()
+                .and_then(|id| names.get(&id).copied())
+                .unwrap_or("Ocena");
+            let mut content = format!("Ocena {} z dnia {}.", grade.grade, grade.date);
+            for text in grade.comment_texts() {
+                let _ = write!(content, " Komentarz: {}", text);
+            }
+            Entry {
+                id: meta.entry_id("grade", &grade.id.to_string()),
+                title: format!("{}: {}", subject, grade.grade),
+                updated: grade.add_date.unix_timestamp().unwrap_or(0),
+                content: ("text", content),
+            }
+        })
+        .collect();
+    render(meta, entries)
+}
+
+/// Creation time of a notice, falling back to the midnight starting it.
+fn notice_time(notice: &SchoolNotice) -> i64 {
+    notice
+        .creation_date
+        .unix_timestamp()
+        .or_else(|| dates::parse_warsaw_timestamp(notice.start_date.as_str()))
+        .unwrap_or(0)
+}
+
+fn render(meta: &FeedMeta, mut entries: Vec<Entry>) -> String {
+    // Newest first, with ties broken by ID so the output is stable.
+    entries.sort_by(|a, b| b.updated.cmp(&a.updated).then_with(|| a.id.cmp(&b.id)));
+    let updated = entries.first().map_or_else(now, |e| e.updated);
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    out.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    let _ = writeln!(out, "  <id>{}</id>", escape(&meta.id));
+    let _ = writeln!(
+        out,
+        "  <title type=\"text\">{}</title>",
+        escape(&meta.title)
+    );
+    let _ = writeln!(
+        out,
+        "  <updated>{}</updated>",
+        dates::format_rfc3339_utc(updated)
+    );
+    let _ = writeln!(
+        out,
+        "  <author>\n    <name>{}</name>\n  </author>",
+        escape(&meta.author)
+    );
+    if let Some(link) = &meta.self_link {
+        let _ = writeln!(out, "  <link rel=\"self\" href=\"{}\"/>", escape(link));
+    }
+    for entry in entries {
+        out.push_str("  <entry>\n");
+        let _ = writeln!(out, "    <id>{}</id>", escape(&entry.id));
+        let _ = writeln!(
+            out,
+            "    <title type=\"text\">{}</title>",
+            escape(&entry.title)
+        );
+        let _ = writeln!(
+            out,
+            "    <updated>{}</updated>",
+            dates::format_rfc3339_utc(entry.updated)
+        );
+        let (kind, content) = &entry.content;
+        let _ = writeln!(
+            out,
+            "    <content type=\"{}\">{}</content>",
+            kind,
+            escape(content)
+        );
+        out.push_str("  </entry>\n");
+    }
+    out.push_str("</feed>\n");
+    out
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Escapes text for XML element content and attribute values, dropping
+/// characters that XML 1.0 does not allow.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c < ' ' || c == '\u{FFFE}' || c == '\u{FFFF}' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Percent-encodes everything but unreserved URI characters.
+fn percent_encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            let _ = write!(out, "%{:02X}", byte);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ResponseSubjects;
+
+    fn meta() -> FeedMeta {
+        FeedMeta::new("urn:librus:test", "Szkoła").self_link("https://example.com/feed.atom")
+    }
+
+    #[test]
+    fn test_notices_to_atom() {
+        let notices: ResponseSchoolNotices =
+            serde_json::from_str(include_str!("../tests/fixtures/school_notices.json")).unwrap();
+        assert_eq!(
+            notices_to_atom(&notices, &meta()),
+            include_str!("../tests/fixtures/school_notices.atom")
+        );
+    }
+
+    #[test]
+    fn test_grades_to_atom() {
+        let grades: crate::ResponseGrades =
+            serde_json::from_str(include_str!("../tests/fixtures/grades.json")).unwrap();
+        let subjects: ResponseSubjects =
+            serde_json::from_str(include_str!("../tests/fixtures/subjects.json")).unwrap();
+        let meta = FeedMeta::new("urn:librus:test", "Oceny").author("Jan Kowalski");
+        assert_eq!(
+            grades_to_atom(&grades.grades, &subjects.subjects, &meta),
+            include_str!("../tests/fixtures/grades.atom")
+        );
+    }
+
+    #[test]
+    fn test_content_is_escaped() {
+        let mut notices: ResponseSchoolNotices =
+            serde_json::from_str(include_str!("../tests/fixtures/school_notices.json")).unwrap();
+        notices.school_notices.truncate(1);
+        let notice = &mut notices.school_notices[0];
+        notice.id = "a/b c".to_string();
+        notice.subject = "Tom & Jerry".to_string();
+        notice.content = "<p>R&D ]]> <b>\u{1}ważne</p>".to_string();
+
+        let atom = notices_to_atom(&notices, &meta());
+        assert!(atom.contains("<id>urn:librus:test/notice/a%2Fb%20c</id>"));
+        assert!(atom.contains("<title type=\"text\">Tom &amp; Jerry</title>"));
+        assert!(atom.contains(
+            "<content type=\"html\">&lt;p&gt;R&amp;D ]]&gt; &lt;b&gt;ważne&lt;/p&gt;</content>"
+        ));
+        assert!(!atom.contains("]]>"));
+    }
+}
@@ -0,0 +1,202 @@
+//! Repairing attachment filenames for saving to disk.
+//!
+//! Depending on the endpoint, names come percent-encoded, as UTF-8 that was
+//! read as Latin-1 (`zaÅ‚Ä…cznik`), or as ISO-8859-2 bytes read as Latin-1
+//! (`za³±cznik`). They are also passed through from the uploader unchecked, so
+//! they may contain path separators.
+
+/// Longest name, in bytes, accepted by common filesystems.
+const MAX_LEN: usize = 255;
+
+/// Name used when nothing usable is left of the original.
+const FALLBACK: &str = "attachment";
+
+/// Decodes and sanitizes a raw attachment name into a safe filename.
+pub(crate) fn decode_filename(raw: &str) -> String {
+    let decoded = percent_decode(raw);
+    let decoded = fix_utf8_read_as_latin1(&decoded)
+        .or_else(|| fix_latin2_read_as_latin1(&decoded))
+        .unwrap_or(decoded);
+    truncate(&sanitize(&decoded))
+}
+
+fn percent_decode(raw: &str) -> String {
+    if !raw.contains('%') {
+        return raw.to_string();
+    }
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    match String::from_utf8(out) {
+        Ok(s) => s,
+        // Not UTF-8, so the escaped bytes are ISO-8859-2.
+        Err(e) => e.into_bytes().into_iter().map(latin2_char).collect(),
+    }
+}
+
+/// Undoes UTF-8 bytes having been decoded as Latin-1 or Windows-1252.
+fn fix_utf8_read_as_latin1(s: &str) -> Option<String> {
+    if s.is_ascii() {
+        return None;
+    }
+    let bytes = s.chars().map(cp1252_byte).collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok().filter(|fixed| fixed != s)
+}
+
+/// Undoes ISO-8859-2 bytes having been decoded as Latin-1.
+///
+/// Only applied when the name contains symbols that are Polish letters in
+/// ISO-8859-2 but unlikely in a real filename (`±`, `³`, `¿`, ...), so that
+/// names with genuine Latin-1 letters such as `café` are left alone.
+fn fix_latin2_read_as_latin1(s: &str) -> Option<String> {
+    const MARKERS: &[char] = &['¡', '£', '¦', '¬', '¯', '±', '³', '¶', '¼', '¿'];
+    if !s.contains(MARKERS) || s.chars().any(|c| u32::from(c) > 0xFF) {
+        return None;
+    }
+    Some(s.chars().map(|c| latin2_char(c as u8)).collect())
+}
+
+/// Decodes an ISO-8859-2 byte. Only the letters that differ from Latin-1 and
+/// are used in Polish are mapped; others are taken as Latin-1.
+fn latin2_char(byte: u8) -> char {
+    match byte {
+        0xA1 => 'Ą',
+        0xA3 => 'Ł',
+        0xA6 => 'Ś',
+        0xAC => 'Ź',
+        0xAF => 'Ż',
+        0xB1 => 'ą',
+        0xB3 => 'ł',
+        0xB6 => 'ś',
+        0xBC => 'ź',
+        0xBF => 'ż',
+        0xC6 => 'Ć',
+        0xCA => 'Ę',
+        0xD1 => 'Ń',
+        0xE6 => 'ć',
+        0xEA => 'ę',
+        0xF1 => 'ń',
+        _ => char::from(byte),
+    }
+}
+
+/// Encodes a character back to its Windows-1252 byte, which is what UTF-8
+/// continuation bytes 0x80-0x9F turn into when misread.
+fn cp1252_byte(c: char) -> Option<u8> {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž',
+        '\u{8F}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}',
+        'ž', 'Ÿ',
+    ];
+    match u32::from(c) {
+        n @ (0..=0x7F | 0xA0..=0xFF) => Some(n as u8),
+        _ => HIGH.iter().position(|&h| h == c).map(|i| 0x80 + i as u8),
+    }
+}
+
+/// Keeps only the last path component and replaces characters that are
+/// reserved on common filesystems.
+fn sanitize(name: &str) -> String {
+    let name = name
+        .split(['/', '\\'])
+        .rfind(|part| !matches!(part.trim(), "" | "." | ".."))
+        .unwrap_or("");
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Windows drops trailing dots and spaces; leading dots hide the file.
+    let name = name.trim_matches(|c: char| c == ' ' || c == '.');
+    if name.is_empty() {
+        return FALLBACK.to_string();
+    }
+    let stem = name.split('.').next().unwrap_or(name);
+    const RESERVED: &[&str] = &[
+        "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+        "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+    ];
+    if RESERVED.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        return format!("_{name}");
+    }
+    name.to_string()
+}
+
+/// Shortens the name to [`MAX_LEN`] bytes, keeping a short extension.
+fn truncate(name: &str) -> String {
+    if name.len() <= MAX_LEN {
+        return name.to_string();
+    }
+    let extension = name
+        .rfind('.')
+        .map(|i| &name[i..])
+        .filter(|ext| ext.len() <= 16)
+        .unwrap_or("");
+    let mut end = MAX_LEN - extension.len();
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", &name[..end], extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_filename() {
+        for (raw, expected) in [
+            ("załącznik.pdf", "załącznik.pdf"),
+            ("za%C5%82%C4%85cznik.pdf", "załącznik.pdf"),
+            ("zaÅ‚Ä…cznik.pdf", "załącznik.pdf"),
+            ("za³±cznik.pdf", "załącznik.pdf"),
+            ("za%B3%B1cznik.pdf", "załącznik.pdf"),
+            ("Ä†wiczenia.docx", "Ćwiczenia.docx"),
+            ("%C4%86wiczenia z %C5%BCycia.docx", "Ćwiczenia z życia.docx"),
+            ("¯ó³w.png", "Żółw.png"),
+            ("café.jpg", "café.jpg"),
+            ("100%.txt", "100%.txt"),
+            ("../../etc/passwd.pdf", "passwd.pdf"),
+            ("..%2F..%2Fetc%2Fpasswd.pdf", "passwd.pdf"),
+            ("C:\\Users\\x\\plan.pdf", "plan.pdf"),
+            ("raport: \"Q1\"?.xlsx", "raport_ _Q1__.xlsx"),
+            ("..", "attachment"),
+            ("", "attachment"),
+            (".htaccess", "htaccess"),
+            ("con.txt", "_con.txt"),
+            ("tab\there.txt", "tab_here.txt"),
+        ] {
+            assert_eq!(decode_filename(raw), expected, "{raw}");
+        }
+    }
+
+    #[test]
+    fn test_decode_filename_truncates() {
+        let long = format!("{}.pdf", "ż".repeat(200));
+        let name = decode_filename(&long);
+        assert!(name.len() <= MAX_LEN);
+        assert!(name.ends_with("ż.pdf"));
+
+        let no_ext = "a".repeat(300);
+        assert_eq!(decode_filename(&no_ext).len(), MAX_LEN);
+    }
+}
@@ -0,0 +1,610 @@
+//! Analysis of a student's grades.
+
+use std::collections::HashMap;
+
+use crate::structs::grades::{CategoryAverage, Grade, GradeCategory, GradeValue, ResponseGrades};
+use crate::types::ApiDate;
+
+/// Tolerance when comparing a projected average with a target, so that e.g.
+/// a target of `4.1` is reached by an average computed as `4.0999…`.
+const TARGET_EPSILON: f64 = 1e-9;
+
+/// Smallest change of the moving average reported as a trend by default,
+/// the difference between e.g. `4` and `4-`.
+const DEFAULT_TREND_THRESHOLD: f64 = 0.25;
+
+/// A student's grades with helpers for comparing and analysing them.
+///
+/// Build it from [`Client::grades()`](crate::Client::grades) with `From`.
+#[derive(Debug)]
+pub struct GradeBook {
+    grades: Vec<Grade>,
+    weights: HashMap<i64, u32>,
+    trend_threshold: f64,
+}
+
+impl Default for GradeBook {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+/// The student's average in a grade category next to the class average.
+///
+/// Returned by [`GradeBook::vs_class()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassComparison {
+    /// Subject ID.
+    pub subject_id: i64,
+    /// Grade category ID.
+    pub category_id: i64,
+    /// Unweighted average of the student's numeric partial grades, or `None`
+    /// if the student has none in this category.
+    pub student_average: Option<f64>,
+    /// Class average reported by the server.
+    pub class_average: f64,
+}
+
+impl ClassComparison {
+    /// How far the student is above (positive) or below (negative) the class.
+    pub fn difference(&self) -> Option<f64> {
+        self.student_average.map(|avg| avg - self.class_average)
+    }
+}
+
+/// Whether a subject's grades are going up or down, see [`Trend`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// The moving average rose by more than the threshold.
+    Improving,
+    /// The moving average fell by more than the threshold.
+    Declining,
+    /// The moving average changed less than the threshold, or there are
+    /// too few grades to tell.
+    Stable,
+}
+
+/// How a subject's grades developed over time.
+///
+/// Returned by [`GradeBook::trend()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trend {
+    /// Direction of the change from the first to the last point.
+    pub direction: Direction,
+    /// The last point minus the first one, `0.0` with fewer than two points.
+    pub delta: f64,
+    /// The moving average after each grade, by the date of that grade, in
+    /// chronological order. Starts at the grade completing the first window.
+    pub points: Vec<(ApiDate, f64)>,
+}
+
+/// A grade that has not been given yet, see [`GradeBook::projection()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlannedGrade {
+    /// The mark.
+    pub value: GradeValue,
+    /// Category of the grade, which sets its weight.
+    pub category_id: i64,
+}
+
+/// A subject's weighted average with planned grades added.
+///
+/// Returned by [`GradeBook::projection()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectedAverage {
+    /// Weighted average of the grades given so far, or `None` without any.
+    pub current: Option<f64>,
+    /// Weighted average with the planned grades, or `None` if there is
+    /// still nothing to average.
+    pub projected: Option<f64>,
+}
+
+impl ProjectedAverage {
+    /// How much the planned grades raise (positive) or lower (negative) the
+    /// average.
+    pub fn difference(&self) -> Option<f64> {
+        Some(self.projected? - self.current?)
+    }
+}
+
+impl GradeBook {
+    /// Creates a grade book from a list of grades.
+    pub fn new(grades: Vec<Grade>) -> Self {
+        Self {
+            grades,
+            weights: HashMap::new(),
+            trend_threshold: DEFAULT_TREND_THRESHOLD,
+        }
+    }
+
+    /// Weighs grades by the weight of their category, as returned by
+    /// [`Client::grade_categories()`](crate::Client::grade_categories) or
+    /// held by a [`CategoryIndex`](crate::CategoryIndex).
+    ///
+    /// Grades of categories not given, or without a weight, weigh `1`.
+    pub fn with_categories<'a>(
+        mut self,
+        categories: impl IntoIterator<Item = &'a GradeCategory>,
+    ) -> Self {
+        self.weights.extend(
+            categories
+                .into_iter()
+                .filter_map(|c| c.weight.map(|weight| (c.id, weight))),
+        );
+        self
+    }
+
+    /// Sets the smallest change of the moving average that
+    /// [`GradeBook::trend()`] reports as improving or declining (default
+    /// `0.25`).
+    pub fn trend_threshold(mut self, threshold: f64) -> Self {
+        self.trend_threshold = threshold.abs();
+        self
+    }
+
+    /// The grades in the book.
+    pub fn grades(&self) -> &[Grade] {
+        &self.grades
+    }
+
+    /// Compares the student's grades with class averages per category, as
+    /// returned by [`Client::category_averages()`](crate::Client::category_averages).
+    ///
+    /// Categories without a class average or IDs are skipped. The order of
+    /// `averages` is kept.
+    pub fn vs_class(&self, averages: &[CategoryAverage]) -> Vec<ClassComparison> {
+        let mut sums: HashMap<(i64, i64), (f64, usize)> = HashMap::new();
+        for grade in self.grades.iter().filter(|g| g.is_partial()) {
+            if let (Some(value), Some(subject), Some(category)) =
+                (grade.value(), grade.subject.id(), grade.category.id())
+            {
+                let entry = sums.entry((subject, category)).or_default();
+                entry.0 += value;
+                entry.1 += 1;
+            }
+        }
+
+        averages
+            .iter()
+            .filter_map(|avg| {
+                let key = (avg.subject.id()?, avg.category.id()?);
+                Some(ClassComparison {
+                    subject_id: key.0,
+                    category_id: key.1,
+                    student_average: sums.get(&key).map(|(sum, count)| sum / *count as f64),
+                    class_average: avg.average?,
+                })
+            })
+            .collect()
+    }
+
+    /// Computes the trend of a subject's grades as a moving weighted average
+    /// over `window` consecutive grades.
+    ///
+    /// Only numeric partial grades with a valid date are used, in the order
+    /// they were given; grades weighing `0` are skipped. With fewer grades
+    /// than `window` there are no points and the trend is
+    /// [`Direction::Stable`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, GradeBook};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let book = GradeBook::from(client.grades().await?);
+    /// let trend = book.trend(21, 3);
+    /// println!("{:?} by {:.2}", trend.direction, trend.delta);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn trend(&self, subject_id: i64, window: usize) -> Trend {
+        let window = window.max(1);
+        let mut grades: Vec<(&Grade, f64, f64)> = self
+            .weighted(subject_id)
+            .filter(|(g, ..)| g.date.components().is_some())
+            .collect();
+        grades.sort_by(|(a, ..), (b, ..)| {
+            (&a.date, &a.add_date, a.id).cmp(&(&b.date, &b.add_date, b.id))
+        });
+
+        let points: Vec<(ApiDate, f64)> = grades
+            .windows(window)
+            .map(|slice| {
+                let (sum, weights) = slice
+                    .iter()
+                    .fold((0.0, 0.0), |(sum, weights), (_, value, weight)| {
+                        (sum + value * weight, weights + weight)
+                    });
+                let (last, ..) = slice[slice.len() - 1];
+                (last.date.clone(), sum / weights)
+            })
+            .collect();
+
+        let delta = match (points.first(), points.last()) {
+            (Some((_, first)), Some((_, last))) => last - first,
+            _ => 0.0,
+        };
+        let direction = if delta > self.trend_threshold {
+            Direction::Improving
+        } else if delta < -self.trend_threshold {
+            Direction::Declining
+        } else {
+            Direction::Stable
+        };
+        Trend {
+            direction,
+            delta,
+            points,
+        }
+    }
+
+    /// Computes a subject's weighted average as if the `planned` grades had
+    /// been given too.
+    ///
+    /// Like [`GradeBook::trend()`], only numeric partial grades count, each
+    /// weighing its category's weight.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, GradeBook, GradeValue, PlannedGrade};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let test = client.grade_category(41).await?.category;
+    /// let book = GradeBook::from(client.grades().await?).with_categories(&[test]);
+    /// let five = PlannedGrade {
+    ///     value: GradeValue::parse("5").unwrap(),
+    ///     category_id: 41,
+    /// };
+    /// println!("{:?}", book.projection(21, &[five]).projected);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn projection(&self, subject_id: i64, planned: &[PlannedGrade]) -> ProjectedAverage {
+        let (sum, weights) = self
+            .weighted(subject_id)
+            .fold((0.0, 0.0), |(sum, weights), (_, value, weight)| {
+                (sum + value * weight, weights + weight)
+            });
+        let (planned_sum, planned_weights) = planned
+            .iter()
+            .map(|grade| (grade.value.value(), self.weight(Some(grade.category_id))))
+            .fold((0.0, 0.0), |(sum, weights), (value, weight)| {
+                (sum + value * weight, weights + weight)
+            });
+        let average = |sum: f64, weights: f64| (weights > 0.0).then(|| sum / weights);
+        ProjectedAverage {
+            current: average(sum, weights),
+            projected: average(sum + planned_sum, weights + planned_weights),
+        }
+    }
+
+    /// Finds the lowest mark in category `category_id` that brings the
+    /// subject's weighted average to at least `target`.
+    ///
+    /// Returns `None` if even a `6` is not enough, or if the category weighs
+    /// `0` and the average is below `target`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, GradeBook};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let test = client.grade_category(41).await?.category;
+    /// let book = GradeBook::from(client.grades().await?).with_categories(&[test]);
+    /// match book.required_for_target(21, 4.5, 41) {
+    ///     Some(mark) => println!("A test graded {mark:?} gets the average to 4.5"),
+    ///     None => println!("One test is not enough for 4.5"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn required_for_target(
+        &self,
+        subject_id: i64,
+        target: f64,
+        category_id: i64,
+    ) -> Option<GradeValue> {
+        GradeValue::scale().find(|&value| {
+            let planned = PlannedGrade { value, category_id };
+            self.projection(subject_id, &[planned])
+                .projected
+                .is_some_and(|average| average + TARGET_EPSILON >= target)
+        })
+    }
+
+    /// Numeric partial grades of a subject with their values and weights,
+    /// skipping grades weighing `0`.
+    fn weighted(&self, subject_id: i64) -> impl Iterator<Item = (&Grade, f64, f64)> {
+        self.grades
+            .iter()
+            .filter(move |g| g.is_partial() && g.subject.id() == Some(subject_id))
+            .filter_map(|g| Some((g, g.value()?, self.weight(g.category.id()))))
+            .filter(|(_, _, weight)| *weight > 0.0)
+    }
+
+    /// Weight of a category, `1` if unknown.
+    fn weight(&self, category_id: Option<i64>) -> f64 {
+        category_id
+            .and_then(|id| self.weights.get(&id))
+            .map_or(1.0, |w| f64::from(*w))
+    }
+}
+
+impl From<Vec<Grade>> for GradeBook {
+    fn from(grades: Vec<Grade>) -> Self {
+        Self::new(grades)
+    }
+}
+
+impl From<ResponseGrades> for GradeBook {
+    fn from(resp: ResponseGrades) -> Self {
+        Self::new(resp.grades)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::grades::{ResponseCategoryAverages, ResponseGradesCategories};
+
+    /// Partial grades of subject 1 in category 1, one per day in order.
+    fn grades(values: &[&str]) -> Vec<Grade> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| grade(i as i64 + 1, value, &format!("2025-10-{:02}", i + 1), 1))
+            .collect()
+    }
+
+    fn grade(id: i64, value: &str, date: &str, category: i64) -> Grade {
+        serde_json::from_value(serde_json::json!({
+            "Id": id,
+            "Lesson": {"Id": 1, "Url": ""},
+            "Subject": {"Id": 1, "Url": ""},
+            "Student": {"Id": 1, "Url": ""},
+            "Category": {"Id": category, "Url": ""},
+            "AddedBy": {"Id": 1, "Url": ""},
+            "Grade": value,
+            "Date": date,
+            "AddDate": format!("{date} 12:00:00"),
+            "Semester": 1,
+            "IsConstituent": true,
+            "IsSemester": false,
+            "IsSemesterProposition": false,
+            "IsFinal": false,
+            "IsFinalProposition": false,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_vs_class() {
+        let grades: ResponseGrades =
+            serde_json::from_str(include_str!("../tests/fixtures/grades.json")).unwrap();
+        let averages: ResponseCategoryAverages = serde_json::from_str(include_str!(
+            "../tests/fixtures/grades_categories_averages.json"
+        ))
+        .unwrap();
+
+        let book = GradeBook::from(grades);
+        let cmp = book.vs_class(&averages.categories_averages);
+        assert_eq!(cmp.len(), 2);
+
+        assert_eq!((cmp[0].subject_id, cmp[0].category_id), (21, 41));
+        assert_eq!(cmp[0].student_average, Some(5.0));
+        assert_eq!(cmp[0].difference(), Some(0.75));
+
+        // 3+ counts as 3.5, same as the class.
+        assert_eq!(cmp[1].difference(), Some(0.0));
+    }
+
+    #[test]
+    fn test_vs_class_without_own_grades() {
+        let averages: ResponseCategoryAverages = serde_json::from_str(include_str!(
+            "../tests/fixtures/grades_categories_averages.json"
+        ))
+        .unwrap();
+        let cmp = GradeBook::default().vs_class(&averages.categories_averages);
+        assert!(cmp.iter().all(|c| c.student_average.is_none()));
+        assert_eq!(cmp[1].class_average, 3.5);
+        assert_eq!(cmp[1].difference(), None);
+    }
+
+    #[test]
+    fn test_trend_improving() {
+        let book = GradeBook::new(grades(&["2", "3", "3", "4", "5"]));
+        let trend = book.trend(1, 2);
+        let averages: Vec<f64> = trend.points.iter().map(|(_, avg)| *avg).collect();
+        assert_eq!(averages, [2.5, 3.0, 3.5, 4.5]);
+        assert_eq!(trend.points[0].0, "2025-10-02");
+        assert_eq!(trend.delta, 2.0);
+        assert_eq!(trend.direction, Direction::Improving);
+        // Other subjects have no grades.
+        assert!(book.trend(2, 2).points.is_empty());
+    }
+
+    #[test]
+    fn test_trend_declining_in_date_order() {
+        let mut grades = grades(&["5", "5", "4", "3"]);
+        grades.reverse();
+        let trend = GradeBook::new(grades).trend(1, 1);
+        assert_eq!(trend.delta, -2.0);
+        assert_eq!(trend.direction, Direction::Declining);
+        assert_eq!(trend.points.last().unwrap().0, "2025-10-04");
+    }
+
+    #[test]
+    fn test_trend_threshold() {
+        let book = GradeBook::new(grades(&["4", "4", "4+"]));
+        assert_eq!(book.trend(1, 1).delta, 0.5);
+        assert_eq!(book.trend(1, 1).direction, Direction::Improving);
+        let book = book.trend_threshold(0.5);
+        assert_eq!(book.trend(1, 1).direction, Direction::Stable);
+    }
+
+    #[test]
+    fn test_trend_weighted_by_category() {
+        let categories: ResponseGradesCategories =
+            serde_json::from_str(include_str!("../tests/fixtures/grades_category.json")).unwrap();
+        let book = GradeBook::new(vec![
+            grade(1, "2", "2025-10-01", 7),
+            grade(2, "6", "2025-10-02", 41),
+            grade(3, "2", "2025-10-03", 7),
+        ])
+        .with_categories(&[categories.category]);
+        let trend = book.trend(1, 2);
+        // The test in category 41 weighs 3.
+        let averages: Vec<f64> = trend.points.iter().map(|(_, avg)| *avg).collect();
+        assert_eq!(averages, [5.0, 5.0]);
+        assert_eq!(trend.direction, Direction::Stable);
+    }
+
+    #[test]
+    fn test_trend_too_few_points() {
+        let book = GradeBook::new(grades(&["1", "6"]));
+        let trend = book.trend(1, 3);
+        assert!(trend.points.is_empty());
+        assert_eq!((trend.direction, trend.delta), (Direction::Stable, 0.0));
+
+        let trend = book.trend(1, 2);
+        assert_eq!(trend.points.len(), 1);
+        assert_eq!((trend.direction, trend.delta), (Direction::Stable, 0.0));
+
+        // A window of 0 is read as 1.
+        assert_eq!(book.trend(1, 0).delta, 5.0);
+    }
+
+    #[test]
+    fn test_trend_ignores_non_numeric_grades() {
+        let trend = GradeBook::new(grades(&["np", "+", "zw"])).trend(1, 1);
+        assert!(trend.points.is_empty());
+        assert_eq!(trend.direction, Direction::Stable);
+
+        let trend = GradeBook::new(grades(&["3", "np", "bz", "5"])).trend(1, 1);
+        let dates: Vec<&str> = trend.points.iter().map(|(date, _)| date.as_str()).collect();
+        assert_eq!(dates, ["2025-10-01", "2025-10-04"]);
+        assert_eq!(trend.direction, Direction::Improving);
+    }
+
+    fn planned(value: &str, category_id: i64) -> PlannedGrade {
+        PlannedGrade {
+            value: GradeValue::parse(value).unwrap(),
+            category_id,
+        }
+    }
+
+    fn test_category() -> GradeCategory {
+        let categories: ResponseGradesCategories =
+            serde_json::from_str(include_str!("../tests/fixtures/grades_category.json")).unwrap();
+        categories.category
+    }
+
+    #[test]
+    fn test_projection() {
+        let book = GradeBook::new(grades(&["4", "5", "np"])).with_categories(&[test_category()]);
+        // A 6 on a test, weighing 3: (4 + 5 + 3 * 6) / 5.
+        let projection = book.projection(1, &[planned("6", 41)]);
+        assert_eq!(projection.current, Some(4.5));
+        assert_eq!(projection.projected, Some(5.4));
+        assert!((projection.difference().unwrap() - 0.9).abs() < 1e-9);
+
+        let projection = book.projection(1, &[planned("1", 1), planned("2+", 1)]);
+        assert_eq!(projection.projected, Some(3.125));
+        assert_eq!(book.projection(1, &[]).difference(), Some(0.0));
+    }
+
+    #[test]
+    fn test_projection_without_grades() {
+        let book = GradeBook::default();
+        assert_eq!(
+            book.projection(1, &[]),
+            ProjectedAverage {
+                current: None,
+                projected: None
+            }
+        );
+        let projection = book.projection(1, &[planned("4-", 7)]);
+        assert_eq!(
+            (projection.current, projection.projected),
+            (None, Some(3.75))
+        );
+        assert_eq!(projection.difference(), None);
+    }
+
+    #[test]
+    fn test_required_for_target() {
+        let book = GradeBook::new(grades(&["3", "4"])).with_categories(&[test_category()]);
+        // (3 + 4 + 3 * x) / 5 >= 4 needs x >= 13 / 3, so a 4+.
+        assert_eq!(
+            book.required_for_target(1, 4.0, 41).unwrap().to_string(),
+            "4+"
+        );
+        // With weight 1, (7 + x) / 3 >= 4 needs a 5.
+        assert_eq!(
+            book.required_for_target(1, 4.0, 7).unwrap().to_string(),
+            "5"
+        );
+        // Already above the target: any mark will do.
+        assert_eq!(
+            book.required_for_target(1, 2.5, 7).unwrap().to_string(),
+            "1"
+        );
+        // Not even a 6 on a test is enough.
+        assert_eq!(book.required_for_target(1, 5.5, 41), None);
+        // Without grades the mark itself is the average.
+        let book = GradeBook::default();
+        assert_eq!(
+            book.required_for_target(1, 4.5, 7).unwrap().to_string(),
+            "4+"
+        );
+        assert_eq!(book.required_for_target(1, 6.5, 7), None);
+    }
+
+    #[test]
+    fn test_required_grade_reaches_target() {
+        let histories: [&[&str]; 5] = [
+            &[],
+            &["1", "1", "2"],
+            &["3+", "4-", "5", "2"],
+            &["6", "6", "5+"],
+            &["1", "6", "3", "np", "4+", "2-"],
+        ];
+        let scale: Vec<GradeValue> = GradeValue::scale().collect();
+        for history in histories {
+            let book = GradeBook::new(grades(history)).with_categories(&[test_category()]);
+            for category in [41, 7] {
+                for tenths in 10..=60 {
+                    let target = f64::from(tenths) / 10.0;
+                    let average = |value: GradeValue| {
+                        book.projection(
+                            1,
+                            &[PlannedGrade {
+                                value,
+                                category_id: category,
+                            }],
+                        )
+                        .projected
+                        .unwrap()
+                    };
+                    match book.required_for_target(1, target, category) {
+                        Some(value) => {
+                            assert!(average(value) + TARGET_EPSILON >= target);
+                            // The mark below does not reach the target.
+                            let index = scale.iter().position(|v| *v == value).unwrap();
+                            if index > 0 {
+                                assert!(average(scale[index - 1]) + TARGET_EPSILON < target);
+                            }
+                        }
+                        None => assert!(average(scale[scale.len() - 1]) + TARGET_EPSILON < target),
+                    }
+                }
+            }
+        }
+    }
+}
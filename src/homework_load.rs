@@ -0,0 +1,249 @@
+//! Warnings about days with too many tests.
+
+use std::collections::HashMap;
+
+use crate::dates;
+use crate::structs::events::{Homework, HomeworkCategory};
+use crate::types::ApiDate;
+
+/// What a homework category stands for, judged by its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CategoryKind {
+    /// A test, e.g. "Sprawdzian", "Praca klasowa" or "Test".
+    Test,
+    /// A short quiz ("Kartkówka").
+    Quiz,
+    /// Anything else, e.g. regular homework.
+    Other,
+}
+
+impl CategoryKind {
+    /// Classifies a category by its name, ignoring case.
+    pub fn from_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        if name.contains("kartków") || name.contains("kartkow") {
+            Self::Quiz
+        } else if ["sprawdzian", "praca klasowa", "test", "egzamin"]
+            .iter()
+            .any(|word| name.contains(word))
+        {
+            Self::Test
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// How many homeworks of each [`CategoryKind`] are allowed within a window,
+/// see [`homework_load()`].
+///
+/// Kinds without a limit are not checked.
+///
+/// # Example
+///
+/// ```rust
+/// use librus_rs::{CategoryKind, LoadThresholds};
+///
+/// // At most one test and two quizzes a day.
+/// let thresholds = LoadThresholds::new()
+///     .limit(CategoryKind::Test, 1)
+///     .limit(CategoryKind::Quiz, 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadThresholds {
+    limits: HashMap<CategoryKind, usize>,
+}
+
+impl LoadThresholds {
+    /// Creates thresholds without any limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows at most `max` homeworks of `kind` within a window.
+    pub fn limit(mut self, kind: CategoryKind, max: usize) -> Self {
+        self.limits.insert(kind, max);
+        self
+    }
+}
+
+/// Homeworks of one kind exceeding their limit, returned by
+/// [`homework_load()`].
+#[derive(Debug, Clone)]
+pub struct LoadWarning<'a> {
+    /// First day of the window.
+    pub date: ApiDate,
+    /// Kind of the homeworks.
+    pub kind: CategoryKind,
+    /// Number of homeworks in the window.
+    pub count: usize,
+    /// The homeworks in the window, by date.
+    pub items: Vec<&'a Homework>,
+}
+
+/// Finds windows of `window_days` days with more homeworks of a kind than
+/// `thresholds` allow, e.g. three tests on one day.
+///
+/// Use a window of 1 for limits per day and 7 for limits per week. Windows
+/// start on each day with a homework of the kind; one whose homeworks are
+/// all part of the previous warning is not reported again. Categories are
+/// matched by ID, see [`Client::homework_categories()`](crate::Client::homework_categories).
+/// Homeworks with an unknown category or an unparsable date are skipped.
+/// Warnings are sorted by date, then kind.
+pub fn homework_load<'a>(
+    homeworks: &'a [Homework],
+    categories: &[HomeworkCategory],
+    window_days: u32,
+    thresholds: &LoadThresholds,
+) -> Vec<LoadWarning<'a>> {
+    let kinds: HashMap<i64, CategoryKind> = categories
+        .iter()
+        .map(|category| (category.id, CategoryKind::from_name(&category.name)))
+        .collect();
+    let window = i64::from(window_days.max(1));
+    let mut warnings = Vec::new();
+    for (&kind, &max) in &thresholds.limits {
+        let mut dated: Vec<(i64, &Homework)> = homeworks
+            .iter()
+            .filter(|homework| {
+                let category = homework.category.id();
+                category.and_then(|id| kinds.get(&id)) == Some(&kind)
+            })
+            .filter_map(|homework| Some((dates::parse_day(homework.date.as_str())?, homework)))
+            .collect();
+        dated.sort_by_key(|&(day, homework)| (day, homework.id));
+        let mut reported_until = 0;
+        for (start, &(day, first)) in dated.iter().enumerate() {
+            if start > 0 && dated[start - 1].0 == day {
+                continue;
+            }
+            let end = start + dated[start..].partition_point(|&(d, _)| d < day + window);
+            if end - start > max && end > reported_until {
+                reported_until = end;
+                warnings.push(LoadWarning {
+                    date: first.date.clone(),
+                    kind,
+                    count: end - start,
+                    items: dated[start..end].iter().map(|&(_, h)| h).collect(),
+                });
+            }
+        }
+    }
+    warnings.sort_by(|a, b| {
+        a.date
+            .as_str()
+            .cmp(b.date.as_str())
+            .then(a.kind.cmp(&b.kind))
+    });
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn homework(id: i64, date: &str, category: i64) -> Homework {
+        serde_json::from_value(serde_json::json!({
+            "Id": id,
+            "Content": format!("Zadanie {id}"),
+            "Date": date,
+            "Category": {"Id": category, "Url": "https://api.librus.pl/2.0/HomeWorks/Categories"},
+            "TimeFrom": "",
+            "TimeTo": "",
+            "CreatedBy": {"Id": 77, "Url": "https://api.librus.pl/2.0/Users/77"},
+            "AddDate": "2025-03-01 08:00:00",
+        }))
+        .unwrap()
+    }
+
+    fn categories() -> Vec<HomeworkCategory> {
+        serde_json::from_str::<crate::ResponseHomeworkCategories>(include_str!(
+            "../tests/fixtures/homework_categories.json"
+        ))
+        .unwrap()
+        .categories
+    }
+
+    /// A week with three tests on Wednesday, a quiz on Tuesday and Thursday
+    /// and homework every day.
+    fn week() -> Vec<Homework> {
+        vec![
+            homework(1, "2025-03-10", 4),
+            homework(2, "2025-03-11", 2),
+            homework(3, "2025-03-12", 1),
+            homework(4, "2025-03-12", 1),
+            homework(5, "2025-03-12", 1),
+            homework(6, "2025-03-12", 4),
+            homework(7, "2025-03-13", 2),
+            homework(8, "2025-03-14", 1),
+            homework(9, "2025-03-14", 4),
+        ]
+    }
+
+    fn ids(warning: &LoadWarning) -> Vec<i64> {
+        warning.items.iter().map(|h| h.id).collect()
+    }
+
+    #[test]
+    fn test_category_kind() {
+        assert_eq!(CategoryKind::from_name("Sprawdzian"), CategoryKind::Test);
+        assert_eq!(CategoryKind::from_name("praca klasowa"), CategoryKind::Test);
+        assert_eq!(CategoryKind::from_name("KARTKÓWKA"), CategoryKind::Quiz);
+        assert_eq!(
+            CategoryKind::from_name("Zadanie domowe"),
+            CategoryKind::Other
+        );
+    }
+
+    #[test]
+    fn test_tests_per_day() {
+        let homeworks = week();
+        let thresholds = LoadThresholds::new().limit(CategoryKind::Test, 1);
+        let warnings = homework_load(&homeworks, &categories(), 1, &thresholds);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].date, "2025-03-12");
+        assert_eq!(warnings[0].kind, CategoryKind::Test);
+        assert_eq!(warnings[0].count, 3);
+        assert_eq!(ids(&warnings[0]), [3, 4, 5]);
+
+        // Two quizzes a day are allowed, and there is never more than one.
+        let thresholds = thresholds.limit(CategoryKind::Quiz, 2);
+        assert_eq!(
+            homework_load(&homeworks, &categories(), 1, &thresholds).len(),
+            1
+        );
+        // Without limits nothing is checked.
+        assert!(homework_load(&homeworks, &categories(), 1, &LoadThresholds::new()).is_empty());
+    }
+
+    #[test]
+    fn test_tests_per_week() {
+        let homeworks = week();
+        let thresholds = LoadThresholds::new()
+            .limit(CategoryKind::Test, 3)
+            .limit(CategoryKind::Quiz, 1);
+        let warnings = homework_load(&homeworks, &categories(), 7, &thresholds);
+        // The window from Tuesday holds both quizzes. The one from Wednesday
+        // holds all four tests; later windows are part of it.
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].date, "2025-03-11");
+        assert_eq!(warnings[0].kind, CategoryKind::Quiz);
+        assert_eq!(ids(&warnings[0]), [2, 7]);
+        assert_eq!(warnings[1].date, "2025-03-12");
+        assert_eq!(warnings[1].count, 4);
+        assert_eq!(ids(&warnings[1]), [3, 4, 5, 8]);
+    }
+
+    #[test]
+    fn test_unknown_category_and_date_skipped() {
+        let homeworks = vec![
+            homework(1, "2025-03-12", 1),
+            homework(2, "2025-03-12", 99),
+            homework(3, "", 1),
+        ];
+        let thresholds = LoadThresholds::new().limit(CategoryKind::Test, 0);
+        let warnings = homework_load(&homeworks, &categories(), 1, &thresholds);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(ids(&warnings[0]), [1]);
+    }
+}
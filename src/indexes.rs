@@ -0,0 +1,220 @@
+//! Lookup of referenced records by ID.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::structs::colors::{Color, ResponseColors};
+use crate::structs::grades::{GradeCategory, ResponseGradeCategoryList};
+use crate::structs::lessons::{
+    AttendanceType, LessonSubject, ResponseAttendancesType, ResponseSubjects,
+};
+use crate::structs::resources::Reference;
+use crate::structs::users::{ResponseUsers, User};
+
+/// Records of one kind by ID, for resolving [`Reference`]s without building
+/// a `HashMap` each time.
+///
+/// Cloning is cheap, as clones share the records. When a list holds an ID
+/// more than once the last record wins; such IDs are listed by
+/// [`IdIndex::duplicate_ids()`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use librus_rs::{Client, UserIndex};
+///
+/// # async fn example() -> Result<(), librus_rs::Error> {
+/// let client = Client::from_env().await?;
+/// let users = UserIndex::from(client.users().await?);
+/// for lesson in client.lessons().await?.lessons {
+///     if let Some(teacher) = users.resolve(&lesson.teacher) {
+///         println!("{} {}", teacher.first_name, teacher.last_name);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct IdIndex<T> {
+    inner: Arc<Inner<T>>,
+}
+
+struct Inner<T> {
+    records: HashMap<i64, T>,
+    duplicate_ids: Vec<i64>,
+}
+
+/// Attendance types by ID.
+pub type AttendanceTypeIndex = IdIndex<AttendanceType>;
+/// Colors by ID.
+pub type ColorIndex = IdIndex<Color>;
+/// Subjects by ID.
+pub type SubjectIndex = IdIndex<LessonSubject>;
+/// Grade categories by ID.
+pub type CategoryIndex = IdIndex<GradeCategory>;
+/// Users by ID.
+pub type UserIndex = IdIndex<User>;
+
+impl<T> IdIndex<T> {
+    fn build(records: impl IntoIterator<Item = T>, id: impl Fn(&T) -> i64) -> Self {
+        let mut inner = Inner {
+            records: HashMap::new(),
+            duplicate_ids: Vec::new(),
+        };
+        for record in records {
+            let id = id(&record);
+            if inner.records.insert(id, record).is_some() && !inner.duplicate_ids.contains(&id) {
+                inner.duplicate_ids.push(id);
+            }
+        }
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// The record with `id`.
+    pub fn get(&self, id: i64) -> Option<&T> {
+        self.inner.records.get(&id)
+    }
+
+    /// The record a reference points to.
+    pub fn resolve(&self, reference: &Reference) -> Option<&T> {
+        self.get(reference.id()?)
+    }
+
+    /// Number of records.
+    pub fn len(&self) -> usize {
+        self.inner.records.len()
+    }
+
+    /// Whether the index holds no records.
+    pub fn is_empty(&self) -> bool {
+        self.inner.records.is_empty()
+    }
+
+    /// The records, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.inner.records.values()
+    }
+
+    /// IDs that occurred more than once when building the index, in the
+    /// order they were first repeated.
+    ///
+    /// The API should not send these, so a non-empty list is worth logging.
+    pub fn duplicate_ids(&self) -> &[i64] {
+        &self.inner.duplicate_ids
+    }
+}
+
+impl<T> Clone for IdIndex<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> Default for IdIndex<T> {
+    fn default() -> Self {
+        Self::build(Vec::new(), |_| 0)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for IdIndex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdIndex")
+            .field("records", &self.inner.records)
+            .field("duplicate_ids", &self.inner.duplicate_ids)
+            .finish()
+    }
+}
+
+macro_rules! impl_index_from {
+    ($record:ty, $response:ty => $list:ident, |$r:ident| $id:expr) => {
+        impl FromIterator<$record> for IdIndex<$record> {
+            fn from_iter<I: IntoIterator<Item = $record>>(records: I) -> Self {
+                Self::build(records, |$r: &$record| $id)
+            }
+        }
+
+        impl From<Vec<$record>> for IdIndex<$record> {
+            fn from(records: Vec<$record>) -> Self {
+                records.into_iter().collect()
+            }
+        }
+
+        impl From<$response> for IdIndex<$record> {
+            fn from(response: $response) -> Self {
+                response.$list.into_iter().collect()
+            }
+        }
+    };
+}
+
+impl_index_from!(AttendanceType, ResponseAttendancesType => types, |t| i64::from(t.id));
+impl_index_from!(Color, ResponseColors => colors, |c| c.id);
+impl_index_from!(LessonSubject, ResponseSubjects => subjects, |s| i64::from(s.id));
+impl_index_from!(GradeCategory, ResponseGradeCategoryList => categories, |c| c.id);
+impl_index_from!(User, ResponseUsers => users, |u| u.id);
+
+/// All indexes of commonly referenced records, returned by
+/// [`Client::reference_indexes()`](crate::Client::reference_indexes).
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceIndexes {
+    /// Attendance types.
+    pub attendance_types: AttendanceTypeIndex,
+    /// Colors.
+    pub colors: ColorIndex,
+    /// Subjects.
+    pub subjects: SubjectIndex,
+    /// Grade categories.
+    pub categories: CategoryIndex,
+    /// Users visible to the account.
+    pub users: UserIndex,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn users() -> ResponseUsers {
+        serde_json::from_str(include_str!("../tests/fixtures/users.json")).unwrap()
+    }
+
+    #[test]
+    fn test_index_from_response() {
+        let response = users();
+        let count = response.users.len();
+        let index = UserIndex::from(response);
+        assert_eq!(index.len(), count);
+        assert_eq!(index.get(51).unwrap().last_name, "Nowak");
+        assert!(index.get(1).is_none());
+        assert!(index.duplicate_ids().is_empty());
+
+        let reference: Reference =
+            serde_json::from_str(r#"{"Id": 52, "Url": "https://api.librus.pl/2.0/Users/52"}"#)
+                .unwrap();
+        assert_eq!(index.resolve(&reference).unwrap().first_name, "Piotr");
+
+        let colors: ResponseColors =
+            serde_json::from_str(include_str!("../tests/fixtures/colors.json")).unwrap();
+        assert_eq!(ColorIndex::from(colors).get(3).unwrap().rgb, "00FF00");
+    }
+
+    #[test]
+    fn test_duplicate_ids_last_wins() {
+        let mut users = users().users;
+        let mut renamed = users[0].clone();
+        renamed.last_name = "Nowak-Zielińska".to_string();
+        users.push(renamed.clone());
+        users.push(renamed);
+        let index = UserIndex::from(users);
+        assert_eq!(index.get(51).unwrap().last_name, "Nowak-Zielińska");
+        assert_eq!(index.duplicate_ids(), [51]);
+
+        // Clones share the records.
+        let clone = index.clone();
+        assert!(std::ptr::eq(clone.get(51).unwrap(), index.get(51).unwrap()));
+        assert!(UserIndex::default().is_empty());
+    }
+}
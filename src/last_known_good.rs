@@ -0,0 +1,131 @@
+//! The last successful fetch of a polled source, kept through outages.
+
+use std::time::SystemTime;
+
+use crate::Error;
+
+/// What changed when a fetch result was recorded in a [`LastKnownGood`].
+///
+/// A successful fetch of a source that was not stale reports nothing.
+#[derive(Debug)]
+pub enum SourceEvent {
+    /// A fetch failed. The last successful data is still served.
+    SourceStale {
+        /// Name of the source, as given to [`LastKnownGood::new()`].
+        source: String,
+        /// When the source was last fetched successfully, if ever.
+        last_success: Option<SystemTime>,
+        /// Why the fetch failed.
+        error: Error,
+    },
+    /// A fetch succeeded after one or more failed.
+    SourceRecovered {
+        /// Name of the source.
+        source: String,
+        /// When the first of the failed fetches happened.
+        stale_since: SystemTime,
+    },
+}
+
+/// The last successful result of fetching one source, e.g. grades, for
+/// polling loops that should keep showing data while Librus is down.
+///
+/// Record each fetch with [`record()`](Self::record). Failures are reported
+/// as [`SourceEvent::SourceStale`] and leave [`current()`](Self::current)
+/// unchanged; the first success afterwards is reported as
+/// [`SourceEvent::SourceRecovered`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::SystemTime;
+/// use librus_rs::{Client, LastKnownGood, SourceEvent};
+///
+/// # async fn example() -> Result<(), librus_rs::Error> {
+/// let client = Client::from_env().await?;
+/// let mut grades = LastKnownGood::new("grades");
+/// loop {
+///     let result = client.grades().await;
+///     match grades.record(result, SystemTime::now()) {
+///         Some(SourceEvent::SourceStale { error, .. }) => eprintln!("showing cached grades: {error}"),
+///         Some(SourceEvent::SourceRecovered { .. }) => eprintln!("grades are live again"),
+///         None => {}
+///     }
+///     if let Some(current) = grades.current() {
+///         println!("{} grades", current.grades.len());
+///     }
+///     tokio::time::sleep(std::time::Duration::from_secs(600)).await;
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LastKnownGood<T> {
+    source: String,
+    value: Option<T>,
+    last_success: Option<SystemTime>,
+    stale_since: Option<SystemTime>,
+}
+
+impl<T> LastKnownGood<T> {
+    /// An empty store for the source named `source`.
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            value: None,
+            last_success: None,
+            stale_since: None,
+        }
+    }
+
+    /// Records the result of a fetch made at `at`.
+    ///
+    /// A success replaces the kept value; a failure keeps it.
+    pub fn record(&mut self, result: Result<T, Error>, at: SystemTime) -> Option<SourceEvent> {
+        match result {
+            Ok(value) => {
+                self.value = Some(value);
+                self.last_success = Some(at);
+                self.stale_since
+                    .take()
+                    .map(|stale_since| SourceEvent::SourceRecovered {
+                        source: self.source.clone(),
+                        stale_since,
+                    })
+            }
+            Err(error) => {
+                self.stale_since.get_or_insert(at);
+                Some(SourceEvent::SourceStale {
+                    source: self.source.clone(),
+                    last_success: self.last_success,
+                    error,
+                })
+            }
+        }
+    }
+
+    /// Name of the source.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The last successfully fetched value, if any fetch succeeded.
+    pub fn current(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+
+    /// When the source was last fetched successfully.
+    pub fn last_success(&self) -> Option<SystemTime> {
+        self.last_success
+    }
+
+    /// When the current run of failed fetches started, or `None` if the last
+    /// fetch succeeded.
+    pub fn stale_since(&self) -> Option<SystemTime> {
+        self.stale_since
+    }
+
+    /// Whether the last fetch failed.
+    pub fn is_stale(&self) -> bool {
+        self.stale_since.is_some()
+    }
+}
@@ -83,17 +83,69 @@
 //! | Method | Description |
 //! |--------|-------------|
 //! | [`Client::me()`] | Current user info |
+//! | [`Client::purge_local_data()`] | Delete everything stored locally for the account |
+//! | [`Client::capabilities()`] | Premium status and add-ons (cached) |
+//! | [`Client::follow()`] | Fetch the resource a [`Reference`] points to |
+//! | [`Client::last_login()`] | Time of the previous login, if reported |
+//! | [`Client::select_unit()`] | Switch to another school unit (district-wide accounts) |
 //! | [`Client::grades()`] | All grades |
+//! | [`Client::grades_raw()`] | All grades as the raw body, see [`borrowed`] |
+//! | [`Client::grade()`] | Single grade by ID |
+//! | [`Client::grade_categories()`] | All grade categories |
 //! | [`Client::grade_category()`] | Grade category by ID |
 //! | [`Client::grade_comment()`] | Grade comment by ID |
+//! | [`Client::unpreparedness()`] | Unpreparedness ("np") counters per subject |
+//! | [`Client::category_averages()`] | Class averages per subject and grade category |
+//! | [`Client::students_averages()`] | Server-computed averages per subject |
 //! | [`Client::lesson()`] | Lesson info by ID |
 //! | [`Client::subject()`] | Subject info by ID |
+//! | [`Client::subjects()`] | All subjects |
+//! | [`Client::lessons()`] | All lessons (teacher, subject, class) |
+//! | [`Client::subject_teachers()`] | Teachers of each subject |
+//! | [`Client::teacher_office_hours()`] | Teachers' weekly office hours |
+//! | [`Client::teacher_office_hours_with_names()`] | Office hours with teacher names |
+//! | [`Client::class()`] | School class by ID |
+//! | [`Client::school_year()`] | School year of the student's class |
+//! | [`Client::units()`] | School units with their lesson hours |
 //! | [`Client::attendances()`] | All attendances |
+//! | [`Client::attendances_raw()`] | All attendances as the raw body, see [`borrowed`] |
 //! | [`Client::attendance_types()`] | Attendance types |
+//! | [`Client::attendance_type()`] | Attendance type by ID |
+//! | [`Client::attendances_filled_by_teacher()`] | Lessons whose attendance was filled in |
+//! | [`Client::attendance_calendar()`] | Attendance by day and lesson number |
+//! | [`Client::attendance_summary()`] | Attendance counts by kind |
+//! | [`Client::trips()`] | School trips |
+//! | [`Client::trip()`] | School trip by ID |
+//! | [`Client::notes()`] | Notes about the student's behaviour |
+//! | [`Client::note_categories()`] | Note categories with behaviour points |
 //! | [`Client::homeworks()`] | All homeworks |
+//! | [`Client::homework_categories()`] | Homework categories with their colors |
+//! | [`Client::home_work_assignments()`] | Homework assignments |
+//! | [`Client::mark_assignment_done()`] | Mark an assignment as done |
+//! | [`Client::mark_assignment_undone()`] | Revert marking an assignment as done |
+//! | [`Client::school_free_days()`] | School-wide free days |
+//! | [`Client::class_free_days()`] | Free days of the student's class |
+//! | [`Client::teacher_free_days()`] | Teacher absences |
+//! | [`Client::parent_teacher_conferences()`] | Parent-teacher conferences |
+//! | [`Client::substitutions()`] | Lesson substitutions and cancellations |
+//! | [`Client::lucky_number()`] | Lucky number of the day |
+//! | [`Client::family_calendar()`] | Merged, sorted calendar of the above |
+//! | [`Client::timetable()`] | Lessons of one week |
+//! | [`Client::now_and_next()`] | Lesson in progress and the next one |
+//! | [`Client::school_calendar()`] | School-day calendar (next school day, day counts) |
 //! | [`Client::school_notices()`] | School notices (announcements) |
+//! | [`Client::school_notices_light()`] | School notices with inline images stripped |
+//! | [`Client::school_notice()`] | School notice by ID |
+//! | [`Client::notice_attachment()`] | Download a notice or homework attachment |
 //! | [`Client::user()`] | User by ID |
+//! | [`Client::users()`] | All visible users |
+//! | [`Client::colors()`] | Display colors |
+//! | [`Client::color()`] | Display color by ID |
+//! | [`Client::reference_indexes()`] | Referenced records indexed by ID |
 //! | [`Client::current_user()`] | Current user details |
+//! | [`Client::student_card()`] | Student overview: class, averages, absences, messages, next homework |
+//! | [`Client::server_time()`] | Current server time |
+//! | [`Client::clock_skew()`] | Server clock offset from local time |
 //!
 //! ## Messages API
 //!
@@ -101,11 +153,32 @@
 //!
 //! | Method | Description |
 //! |--------|-------------|
+//! | [`Client::messages_available()`] | Whether the Messages service is reachable |
+//! | [`Client::recheck_messages_availability()`] | Check again whether the account has messaging |
+//! | [`Client::messages_me()`] | The account's user ID, name and roles in the Messages API |
 //! | [`Client::unread_counts()`] | Unread message counts |
 //! | [`Client::inbox_messages()`] | Received messages |
+//! | [`Client::archived_messages()`] | Archived messages (Messages Plus add-on) |
 //! | [`Client::outbox_messages()`] | Sent messages |
 //! | [`Client::message()`] | Full message details |
+//! | [`Client::outbox_message()`] | Sent message details with receivers |
+//! | [`Client::message_read_receipts()`] | Read status of each receiver of a sent message |
+//! | [`Client::detected_message_page_cap()`] | Page size limit enforced by the server, once seen |
+//! | [`Client::export_messages_jsonl()`] | Stream a whole folder as JSON Lines |
 //! | [`Client::attachment()`] | Download attachment |
+//! | [`Client::attachment_download()`] | Download attachment with its content type and file name |
+//! | [`Client::message_board()`] | Notice board of the Messages module |
+//! | [`Client::justification_requests()`] | Absence justification requests and their status |
+//! | [`Client::justification_request()`] | Justification request details by ID |
+//! | [`Client::confirm_board_entry()`] | Confirm reading a notice board entry |
+//! | [`Client::confirm_message_read()`] | Confirm reading a message that asks for it |
+//! | [`Client::save_draft()`] | Save a message draft |
+//! | [`Client::drafts()`] | List saved drafts |
+//! | [`Client::delete_draft()`] | Delete a draft |
+//! | [`Client::send_draft()`] | Send a saved draft |
+//! | [`Client::attachment_resume()`] | Continue an interrupted attachment download |
+//! | [`Client::download_attachment_to_file()`] | Download an attachment to a file, resuming on failure |
+//! | [`Client::download_all_attachments()`] | Download every attachment of a message to a directory |
 //!
 //! # Error Handling
 //!
@@ -119,48 +192,287 @@
 //! match result {
 //!     Ok(client) => println!("Authenticated successfully"),
 //!     Err(Error::MissingEnvVar(var)) => eprintln!("Missing: {}", var),
-//!     Err(Error::Authentication) => eprintln!("Invalid credentials"),
+//!     Err(Error::InvalidCredentials { message }) => eprintln!("Invalid credentials: {}", message),
 //!     Err(e) => eprintln!("Error: {}", e),
 //! }
 //! # }
 //! ```
+//!
+//! When reporting parse failures, use [`Error::scrubbed_body()`] to get the
+//! offending response with personal data replaced by stable pseudonyms
+//! (see [`anonymize`]).
 
+pub mod anonymize;
+mod attendance;
+mod attendance_calendar;
+mod auth_profile;
+mod auth_trace;
+mod behaviour_points;
+pub mod borrowed;
+mod budget;
+mod client_pool;
+pub mod content;
+mod cookies;
+mod credentials;
+mod dates;
+mod decode;
+pub mod digest;
+mod download;
 mod error;
-mod serde_helpers;
+mod export;
+mod family_calendar;
+#[cfg(feature = "feed")]
+pub mod feed;
+mod filenames;
+mod grade_book;
+mod homework_load;
+mod indexes;
+mod last_known_good;
+mod message_validation;
+mod multi_child;
+mod normalize;
+mod office_hours;
+mod proxy;
+mod redirects;
+mod school_calendar;
+pub mod search;
+pub mod serde_util;
+pub mod simple;
+pub mod snapshot;
+pub mod storage;
 mod structs;
+mod student_card;
+mod subject_teachers;
+pub mod timeline;
+mod tracker;
+pub mod types;
+mod unit_selection;
+mod urls;
+mod watch_schedule;
+
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use reqwest::Client as HttpClient;
 
-pub use crate::error::Error;
-pub use crate::structs::announcements::{ResponseSchoolNotices, SchoolNotice};
-pub use crate::structs::events::{Homework, ResponseHomeworks};
+use crate::budget::{Cost, Http};
+use reqwest::Method;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub use crate::attendance::{AttendanceSummary, JustificationPolicy, PendingJustification};
+pub use crate::attendance_calendar::{AttendanceDayView, DaySlot};
+pub use crate::auth_profile::AuthProfile;
+use crate::auth_trace::AuthRecorder;
+pub use crate::auth_trace::{AuthTrace, AuthTraceStep};
+pub use crate::behaviour_points::{BehaviourPointsSummary, SemesterPoints};
+pub use crate::borrowed::RawResponse;
+pub use crate::budget::{BudgetPolicy, RequestBudget};
+pub use crate::client_pool::ClientPool;
+pub use crate::cookies::CookieFilter;
+use crate::cookies::FilteredJar;
+pub use crate::decode::DecodedContent;
+pub use crate::download::{AttachmentDownload, DownloadSummary};
+use crate::error::{
+    gateway_error_code, is_module_disabled, is_session_taken_over, is_takeover_redirect,
+    messages_envelope_error,
+};
+pub use crate::error::{ApiKind, AuthStep, Error, ErrorBody, ErrorCode};
+use crate::export::ExportLine;
+pub use crate::export::{ExportOptions, ExportSummary, MessageFolder};
+pub use crate::family_calendar::{FamilyEvent, FamilyEventKind};
+pub use crate::grade_book::{
+    ClassComparison, Direction, GradeBook, PlannedGrade, ProjectedAverage, Trend,
+};
+pub use crate::homework_load::{homework_load, CategoryKind, LoadThresholds, LoadWarning};
+pub use crate::indexes::{
+    AttendanceTypeIndex, CategoryIndex, ColorIndex, IdIndex, ReferenceIndexes, SubjectIndex,
+    UserIndex,
+};
+pub use crate::last_known_good::{LastKnownGood, SourceEvent};
+pub use crate::message_validation::{
+    MessageDraftValidator, ValidationIssue, ValidationMode, MAX_BODY_CHARS, MAX_TOPIC_CHARS,
+};
+pub use crate::multi_child::{LinkedAccount, MultiChildClient};
+pub use crate::office_hours::TeacherOfficeHours;
+use crate::proxy::ProxyAuth;
+use crate::redirects::Followed;
+pub use crate::school_calendar::SchoolCalendar;
+pub use crate::structs::announcements::{
+    LightNotice, NoticeAttachment, ResponseSchoolNotice, ResponseSchoolNotices, SchoolNotice,
+};
+pub use crate::structs::assignments::{HomeWorkAssignment, ResponseHomeWorkAssignments};
+pub use crate::structs::calendars::{
+    AffectedDay, ClassFreeDay, ParentTeacherConference, ResponseClassFreeDays,
+    ResponseParentTeacherConferences, ResponseSchoolFreeDays, ResponseSubstitutions,
+    ResponseTeacherFreeDays, SchoolFreeDay, Substitution, TeacherFreeDay,
+};
+pub use crate::structs::classes::{ResponseClass, SchoolClass};
+pub use crate::structs::colors::{Color, ResponseColor, ResponseColors};
+pub use crate::structs::events::{
+    Homework, HomeworkCategory, ResponseHomeworkCategories, ResponseHomeworks,
+};
 pub use crate::structs::grades::{
-    Grade, GradeCategory, GradeComment, ResponseGrades, ResponseGradesCategories,
-    ResponseGradesComments,
+    CategoryAverage, CommentRef, Grade, GradeCategory, GradeComment, GradeValue, Limit,
+    ResponseCategoryAverages, ResponseGrade, ResponseGradeCategoryList, ResponseGrades,
+    ResponseGradesCategories, ResponseGradesComments, ResponseStudentAverages,
+    ResponseUnpreparedness, StudentAverage, UnpreparednessEntry,
 };
 pub use crate::structs::lessons::{
-    Attendance, AttendanceType, Lesson, LessonSubject, ResponseAttendances,
-    ResponseAttendancesType, ResponseLesson, ResponseLessonSubject,
+    Attendance, AttendanceType, FilledAttendance, Lesson, LessonSubject, ResponseAttendanceType,
+    ResponseAttendances, ResponseAttendancesType, ResponseFilledAttendances, ResponseLesson,
+    ResponseLessonSubject, ResponseLessons, ResponseSubjects,
 };
-pub use crate::structs::me::{Me, ResponseMe};
+pub use crate::structs::lucky_number::{LuckyNumber, ResponseLuckyNumber};
+pub use crate::structs::me::{Addon, Capabilities, Me, ResponseMe};
 pub use crate::structs::messages::{
-    Attachment, InboxMessage, MessageDetail, OutboxMessage, UnreadCounts,
+    Attachment, BoardEntry, DraftId, InboxMessage, JustificationRequest,
+    JustificationRequestDetail, JustificationStatus, MessageCategory, MessageDetail, MessagesUser,
+    OutboxMessage, ReceiverReadStatus, UnreadCounts, UnreadCountsPresence,
 };
-pub use crate::structs::users::{ResponseUser, User};
+pub use crate::structs::notes::{Note, NoteCategory, ResponseNoteCategories, ResponseNotes};
+pub use crate::structs::office_hours::OfficeHours;
+pub use crate::structs::resources::{Reference, ResourceUrl};
+pub use crate::structs::semester::{SchoolYear, Semester};
+pub use crate::structs::timetable::{
+    DaySummary, NowAndNext, ResponseTimetable, Timetable, TimetableClass, TimetableClassroom,
+    TimetableDay, TimetableEntry, TimetableLesson, TimetableLessonSubject, TimetablePages,
+    TimetableResources, TimetableSlot, TimetableTeacher,
+};
+pub use crate::structs::trips::{ResponseTrip, ResponseTrips, Trip};
+pub use crate::structs::units::{LessonRange, ResponseUnits, Unit};
+pub use crate::structs::users::{ResponseUser, ResponseUsers, User};
+pub use crate::student_card::{CardSection, SectionError, StudentCard, SubjectAverage};
+pub use crate::subject_teachers::SubjectTeacher;
+pub use crate::tracker::{ChangeTracker, GradeChange, GradeSnapshot, ItemChange};
+pub use crate::unit_selection::UnitChoice;
+pub use crate::watch_schedule::WatchSchedule;
+pub use tokio_util::sync::CancellationToken;
 
+use crate::structs::announcements::ResponseLightNotices;
 use crate::structs::messages::{
-    ResponseInboxMessages, ResponseMessageDetail, ResponseOutboxMessages, ResponseUnreadCounts,
+    MessageRef, ResponseBoardEntries, ResponseInboxMessages, ResponseJustificationRequestDetail,
+    ResponseJustificationRequests, ResponseMessageDetail, ResponseMessagePage,
+    ResponseMessagesUser, ResponseOutboxMessages, ResponseSavedDraft, ResponseUnreadCounts,
 };
+use crate::structs::office_hours::ResponseOfficeHours;
+use crate::structs::system::ResponseSystemData;
 
 /// A specialized `Result` type for librus-rs operations.
 pub type Result<T> = std::result::Result<T, Error>;
 
 const SYNERGIA_API_BASE: &str = "https://synergia.librus.pl/gateway/api/2.0/";
 const MESSAGES_API_BASE: &str = "https://wiadomosci.librus.pl/api/";
-const AUTH_URL: &str = "https://api.librus.pl/OAuth/Authorization?client_id=46";
-const PORTAL_RODZINA_URL: &str = "https://synergia.librus.pl/loguj/portalRodzina";
 const TOKEN_INFO_URL: &str = "https://synergia.librus.pl/gateway/api/2.0/Auth/TokenInfo/";
 const MESSAGES_INIT_URL: &str = "https://synergia.librus.pl/wiadomosci3";
+/// Shown by the messages init page when the school disabled messaging for
+/// the account, compared in lower case.
+const MESSAGES_DISABLED_MARKER: &str = "moduł wiadomości niedostępny";
+const DEADLINE_PASSED_CODE: &str = "HomeWorkAssignmentDeadlinePassed";
+const NOT_FOUND_CODE: &str = "NotFound";
+const DEFAULT_MESSAGES_PAGE_SIZE: u32 = 50;
+/// Connections tried by [`Client::download_attachment_to_file()`].
+const ATTACHMENT_DOWNLOAD_ATTEMPTS: usize = 4;
+
+/// URLs of the Librus services used by the client.
+#[derive(Debug, Clone)]
+struct Endpoints {
+    api: String,
+    messages: String,
+    auth_host: String,
+    auth: String,
+    portal: String,
+    token_info: String,
+    unit_select: String,
+    messages_init: String,
+    login_field: String,
+    password_field: String,
+}
+
+impl Endpoints {
+    /// Switches the login flow to `profile`.
+    fn apply(&mut self, profile: &AuthProfile) {
+        self.auth_host = profile.auth_host.clone();
+        self.auth = profile.auth_url();
+        self.portal = profile.portal_url.clone();
+        self.unit_select = profile.unit_select_url();
+        self.login_field = profile.login_field.clone();
+        self.password_field = profile.password_field.clone();
+    }
+
+    /// Whether the login flow may follow a redirect to `url`: a `librus.pl`
+    /// host, or the host of a configured endpoint.
+    fn allows_redirect_to(&self, url: &reqwest::Url) -> bool {
+        let same_host = |endpoint: &String| {
+            reqwest::Url::parse(endpoint).is_ok_and(|endpoint| {
+                endpoint.host_str() == url.host_str()
+                    && endpoint.port_or_known_default() == url.port_or_known_default()
+            })
+        };
+        redirects::is_librus_host(url)
+            || [
+                &self.api,
+                &self.messages,
+                &self.auth_host,
+                &self.portal,
+                &self.messages_init,
+            ]
+            .into_iter()
+            .any(same_host)
+    }
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        let mut endpoints = Self {
+            api: SYNERGIA_API_BASE.to_string(),
+            messages: MESSAGES_API_BASE.to_string(),
+            auth_host: String::new(),
+            auth: String::new(),
+            portal: String::new(),
+            token_info: TOKEN_INFO_URL.to_string(),
+            unit_select: String::new(),
+            messages_init: MESSAGES_INIT_URL.to_string(),
+            login_field: String::new(),
+            password_field: String::new(),
+        };
+        endpoints.apply(&AuthProfile::default());
+        endpoints
+    }
+}
+
+/// Fails with [`Error::AttachmentScanning`] if an attachment response is the
+/// antivirus scanning page.
+fn check_not_scanning(headers: &reqwest::header::HeaderMap, body: &[u8]) -> Result<()> {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+    };
+    match download::scanning_error(
+        header(reqwest::header::CONTENT_TYPE),
+        header(reqwest::header::RETRY_AFTER),
+        body,
+    ) {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Reply of the login form, e.g. `{"status":"ok","goTo":"/OAuth/..."}` or
+/// `{"status":"error","errors":[{"code":..., "message":"..."}]}`.
+#[derive(Debug, serde::Deserialize)]
+struct LoginReply {
+    status: Option<String>,
+    #[serde(rename = "goTo")]
+    go_to: Option<String>,
+    #[serde(default)]
+    errors: Vec<LoginReplyError>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct LoginReplyError {
+    message: Option<String>,
+}
 
 /// Builder for creating a [`Client`] instance with custom configuration.
 ///
@@ -178,10 +490,66 @@ const MESSAGES_INIT_URL: &str = "https://synergia.librus.pl/wiadomosci3";
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct ClientBuilder {
     username: Option<String>,
     password: Option<String>,
+    disable_compression: bool,
+    normalize_responses: bool,
+    max_response_bytes: Option<usize>,
+    messages_page_size: Option<u32>,
+    takeover_policy: TakeoverPolicy,
+    lenient_username: bool,
+    lenient_password: bool,
+    timeout: Option<Duration>,
+    cookie_filter: CookieFilter,
+    trace_auth: bool,
+    unit: Option<String>,
+    ignore_system_proxy: bool,
+    proxy_auth: Option<ProxyAuth>,
+    auth_profiles: Vec<AuthProfile>,
+    keep_full_error_bodies: bool,
+    request_budget: Option<RequestBudget>,
+}
+
+/// What the client does when its session is ended by another login to the
+/// same account, see [`ClientBuilder::on_session_takeover()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TakeoverPolicy {
+    /// Fail the request with [`Error::SessionTakenOver`].
+    ///
+    /// Leaves the other login (e.g. the mobile app) signed in.
+    #[default]
+    Fail,
+    /// Log in again and retry the request once.
+    ///
+    /// This in turn ends the other login's session.
+    Reauth,
+}
+
+/// Whether the account can use the Messages API, see
+/// [`Client::messages_availability()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MessagesAvailability {
+    /// Not checked yet, or the last check failed for another reason.
+    #[default]
+    Unknown,
+    /// The messages session was opened.
+    Available,
+    /// The school disabled messaging for the account.
+    ///
+    /// Messages calls fail with [`Error::MessagesDisabledForAccount`]
+    /// without a request until
+    /// [`Client::recheck_messages_availability()`] is called.
+    Disabled,
+}
+
+// The password set on the builder is wiped once it is built or dropped.
+impl Drop for ClientBuilder {
+    fn drop(&mut self) {
+        self.username.zeroize();
+        self.password.zeroize();
+    }
 }
 
 impl ClientBuilder {
@@ -220,6 +588,340 @@ impl ClientBuilder {
         self
     }
 
+    /// Accepts usernames that do not look like a Librus account ID.
+    ///
+    /// By default [`build()`](Self::build) only accepts account IDs, i.e.
+    /// digits optionally followed by `u` (`1234567u`), and fails with
+    /// [`Error::InvalidCredentialFormat`] otherwise without contacting the
+    /// server. Some older or school-managed accounts use other logins; enable
+    /// this for them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// let builder = ClientBuilder::new()
+    ///     .username("jan.kowalski")
+    ///     .lenient_username();
+    /// ```
+    pub fn lenient_username(mut self) -> Self {
+        self.lenient_username = true;
+        self
+    }
+
+    /// Sends the password as given, as long as it is not blank.
+    ///
+    /// By default [`build()`](Self::build) fails with
+    /// [`Error::InvalidCredentialFormat`] without contacting the server if the
+    /// password has leading or trailing whitespace, usually pasted along from
+    /// a password manager, or is longer than the 128 characters the Librus
+    /// login form accepts. Enable this for a password that really has them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// let builder = ClientBuilder::new()
+    ///     .password(" padded password ")
+    ///     .lenient_password();
+    /// ```
+    pub fn lenient_password(mut self) -> Self {
+        self.lenient_password = true;
+        self
+    }
+
+    /// Disables gzip/brotli response compression.
+    ///
+    /// By default the client advertises `Accept-Encoding: gzip, br` and
+    /// transparently decompresses API responses. Disabling it makes traffic
+    /// readable in debugging proxies at the cost of larger transfers.
+    ///
+    /// Compression requires the `gzip` and/or `brotli` features (both on by
+    /// default); without them this option has no effect.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// let builder = ClientBuilder::new().disable_compression();
+    /// ```
+    pub fn disable_compression(mut self) -> Self {
+        self.disable_compression = true;
+        self
+    }
+
+    /// De-duplicates and sorts grades, attendances and homeworks.
+    ///
+    /// When enabled, [`Client::grades()`], [`Client::attendances()`] and
+    /// [`Client::homeworks()`] return their lists already passed through
+    /// `normalized()` (e.g. [`ResponseGrades::normalized()`]), so repeated
+    /// fetches can be compared item by item.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// let builder = ClientBuilder::new().normalize_responses();
+    /// ```
+    pub fn normalize_responses(mut self) -> Self {
+        self.normalize_responses = true;
+        self
+    }
+
+    /// Limits the size of response bodies, in bytes.
+    ///
+    /// Bodies are read in chunks and the request fails with
+    /// [`Error::ResponseTooLarge`] as soon as the limit is exceeded, so an
+    /// oversized response (e.g. notices with embedded images) is never fully
+    /// buffered. The limit applies to the decompressed body and to attachment
+    /// downloads. Unlimited by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// let builder = ClientBuilder::new().max_response_bytes(4 * 1024 * 1024);
+    /// ```
+    pub fn max_response_bytes(mut self, limit: usize) -> Self {
+        self.max_response_bytes = Some(limit);
+        self
+    }
+
+    /// Sets a timeout for each request, from sending it until the body is
+    /// read. No timeout by default.
+    ///
+    /// Requests that take longer fail with [`Error::Timeout`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// let builder = ClientBuilder::new().timeout(Duration::from_secs(30));
+    /// ```
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Whether to use the proxy set in the `HTTPS_PROXY`, `HTTP_PROXY` or
+    /// `ALL_PROXY` environment variables, or else in the system settings.
+    /// Enabled by default.
+    ///
+    /// Hosts listed in `NO_PROXY` are reached directly. The proxy is used for
+    /// every request, including login and attachment downloads. The
+    /// variables are ignored in CGI processes, where `REQUEST_METHOD` is set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// let builder = ClientBuilder::new().use_system_proxy(false);
+    /// ```
+    pub fn use_system_proxy(mut self, enabled: bool) -> Self {
+        self.ignore_system_proxy = !enabled;
+        self
+    }
+
+    /// Sets the credentials for a proxy from the environment that requires
+    /// authentication, overriding any given in the proxy URL.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// let builder = ClientBuilder::new().proxy_auth("jan", "proxy-password");
+    /// ```
+    pub fn proxy_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.proxy_auth = Some(ProxyAuth {
+            username: username.into(),
+            password: password.into(),
+        });
+        self
+    }
+
+    /// Sets which cookies set by Librus are kept and sent back (default
+    /// [`CookieFilter::All`]).
+    ///
+    /// With [`CookieFilter::EssentialOnly`] tracking cookies set during login
+    /// are dropped, while login and both APIs keep working.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::{ClientBuilder, CookieFilter};
+    ///
+    /// let builder = ClientBuilder::new().cookie_filter(CookieFilter::EssentialOnly);
+    /// ```
+    pub fn cookie_filter(mut self, filter: CookieFilter) -> Self {
+        self.cookie_filter = filter;
+        self
+    }
+
+    /// Sets the school unit to log in to, for accounts linked to several
+    /// schools.
+    ///
+    /// Such accounts get [`Error::UnitSelectionRequired`] listing the units
+    /// when this is not set. Ignored for accounts with a single unit.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// let builder = ClientBuilder::new().unit("101");
+    /// ```
+    pub fn unit(mut self, unit_id: impl Into<String>) -> Self {
+        self.unit = Some(unit_id.into());
+        self
+    }
+
+    /// Logs in with another login flow, for schools that do not sign in
+    /// through the family portal. See [`AuthProfile`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::{AuthProfile, ClientBuilder};
+    ///
+    /// let builder = ClientBuilder::new().auth_profile(AuthProfile::synergia_login());
+    /// ```
+    pub fn auth_profile(self, profile: AuthProfile) -> Self {
+        self.auth_profiles([profile])
+    }
+
+    /// Tries the login flows of `profiles` in order until one succeeds.
+    ///
+    /// The next profile is tried only when the login flow itself fails: a
+    /// step answers with an error status ([`Error::GrantFailed`]), a redirect
+    /// leaves Librus ([`Error::UnexpectedRedirect`]) or the login response is
+    /// not recognized ([`Error::Authentication`]). Any other error, such as
+    /// rejected credentials, a required school unit or a network failure, is
+    /// returned at once. If every profile fails, the error of the first is
+    /// returned. The client keeps the profile that worked for later logins.
+    ///
+    /// [`AuthProfile::known()`] ships one alternate profile,
+    /// [`AuthProfile::synergia_login()`], after the default; no other login
+    /// flow is known to work.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::{AuthProfile, ClientBuilder};
+    ///
+    /// let builder = ClientBuilder::new().auth_profiles(AuthProfile::known());
+    /// ```
+    pub fn auth_profiles(mut self, profiles: impl IntoIterator<Item = AuthProfile>) -> Self {
+        self.auth_profiles = profiles.into_iter().collect();
+        self
+    }
+
+    /// Keeps the whole response body in [`Error::Parse`], for debugging
+    /// (default `false`).
+    ///
+    /// By default only the first kilobyte is kept, see [`ErrorBody`], so
+    /// that errors held for later do not hold on to large responses.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// let builder = ClientBuilder::new().keep_full_error_bodies(true);
+    /// ```
+    pub fn keep_full_error_bodies(mut self, keep: bool) -> Self {
+        self.keep_full_error_bodies = keep;
+        self
+    }
+
+    /// Counts every request of the client, including the login flow and
+    /// attachment downloads, against `budget`.
+    ///
+    /// Pass clones of one budget to several builders to limit the requests
+    /// of all their clients together.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::{ClientBuilder, RequestBudget};
+    ///
+    /// let budget = RequestBudget::per_hour(500);
+    /// let builder = ClientBuilder::new().request_budget(budget.clone());
+    /// ```
+    pub fn request_budget(mut self, budget: RequestBudget) -> Self {
+        self.request_budget = Some(budget);
+        self
+    }
+
+    /// Records each step of the login flow, see [`Client::last_auth_trace()`].
+    ///
+    /// A login failing with a response the client cannot interpret then
+    /// returns [`Error::AuthenticationDetailed`] instead of
+    /// [`Error::Authentication`]. The password and cookie values are left out
+    /// of the trace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// let builder = ClientBuilder::new().trace_auth();
+    /// ```
+    pub fn trace_auth(mut self) -> Self {
+        self.trace_auth = true;
+        self
+    }
+
+    /// Sets how many messages are requested per page when the client walks a
+    /// whole folder, e.g. in [`Client::export_messages_jsonl()`] (default 50).
+    ///
+    /// The Messages API silently caps the page size. When a page comes back
+    /// shorter than requested although more messages follow, the client
+    /// continues with the server's limit instead of stopping early, see
+    /// [`Client::detected_message_page_cap()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// let builder = ClientBuilder::new().messages_page_size(200);
+    /// ```
+    pub fn messages_page_size(mut self, page_size: u32) -> Self {
+        self.messages_page_size = Some(page_size.max(1));
+        self
+    }
+
+    /// Sets what happens when the session is ended by another login to the
+    /// same account.
+    ///
+    /// Librus allows one session per account, so logging in from the mobile
+    /// app invalidates the client's session and vice versa. With the default
+    /// [`TakeoverPolicy::Fail`] requests fail with
+    /// [`Error::SessionTakenOver`]; with [`TakeoverPolicy::Reauth`] the client
+    /// keeps the username and password in memory and logs in again, which
+    /// signs the other device out.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::{ClientBuilder, TakeoverPolicy};
+    ///
+    /// let builder = ClientBuilder::new().on_session_takeover(TakeoverPolicy::Reauth);
+    /// ```
+    pub fn on_session_takeover(mut self, policy: TakeoverPolicy) -> Self {
+        self.takeover_policy = policy;
+        self
+    }
+
     /// Builds and authenticates the client.
     ///
     /// This method consumes the builder and attempts to authenticate with Librus.
@@ -229,8 +931,13 @@ impl ClientBuilder {
     /// Returns an error if:
     /// - Username is missing ([`Error::MissingCredentials`])
     /// - Password is missing ([`Error::MissingCredentials`])
-    /// - Authentication fails ([`Error::Authentication`])
-    /// - Network error occurs ([`Error::Request`])
+    /// - A credential is empty or malformed, e.g. a username that is not an
+    ///   account ID ([`Error::InvalidCredentialFormat`])
+    /// - The HTTP client cannot be built ([`Error::HttpClient`])
+    /// - The login form rejects the credentials ([`Error::InvalidCredentials`])
+    /// - A later step of the login flow fails ([`Error::GrantFailed`])
+    /// - A network error occurs ([`Error::Timeout`], [`Error::Connect`] or
+    ///   [`Error::Request`])
     ///
     /// # Example
     ///
@@ -247,9 +954,110 @@ impl ClientBuilder {
     /// # }
     /// ```
     pub async fn build(self) -> Result<Client> {
-        let username = self.username.ok_or(Error::MissingCredentials("username"))?;
-        let password = self.password.ok_or(Error::MissingCredentials("password"))?;
-        Client::authenticate(&username, &password).await
+        self.build_with(Endpoints::default()).await
+    }
+
+    async fn build_with(self, endpoints: Endpoints) -> Result<Client> {
+        // Built first, so TLS and proxy problems surface even without
+        // credentials.
+        let http = self.http_client()?;
+        self.build_on(http, endpoints).await
+    }
+
+    /// Logs in over `http`, e.g. a connection pool shared by a
+    /// [`ClientPool`].
+    async fn build_on(mut self, http: Http, endpoints: Endpoints) -> Result<Client> {
+        let username = self.username.as_deref();
+        let username = username.ok_or(Error::MissingCredentials("username"))?;
+        let password = self.password.as_deref();
+        let password = password.ok_or(Error::MissingCredentials("password"))?;
+        let credentials = Credentials {
+            username: credentials::validate(
+                username,
+                password,
+                credentials::Leniency {
+                    username: self.lenient_username,
+                    password: self.lenient_password,
+                },
+            )?,
+            password: password.to_string(),
+        };
+        let mut client = Client::from_parts(http, endpoints);
+        client.trace_auth = self.trace_auth;
+        client.unit = self.unit.take();
+        client
+            .log_in_with_profiles(&credentials, &self.auth_profiles)
+            .await?;
+        client.normalize_responses = self.normalize_responses;
+        client.max_response_bytes = self.max_response_bytes;
+        client.keep_full_error_bodies = self.keep_full_error_bodies;
+        if let Some(page_size) = self.messages_page_size {
+            client.messages_page_size = page_size;
+        }
+        if self.takeover_policy == TakeoverPolicy::Reauth {
+            client.reauth = Some(credentials);
+        }
+        Ok(client)
+    }
+
+    fn http_client(&self) -> Result<Http> {
+        self.http_client_with_env(|name| std::env::var(name).ok())
+    }
+
+    /// Builds the HTTP client, reading proxy variables through `env`.
+    fn http_client_with_env(&self, env: impl Fn(&str) -> Option<String>) -> Result<Http> {
+        Ok(self.http_on(self.connection_pool_with_env(env)?))
+    }
+
+    /// The HTTP client of one [`Client`] sending over `pool`, with a cookie
+    /// jar of its own.
+    fn http_on(&self, pool: HttpClient) -> Http {
+        Http::new(
+            pool,
+            FilteredJar::new(self.cookie_filter),
+            self.request_budget.clone(),
+        )
+    }
+
+    fn connection_pool(&self) -> Result<HttpClient> {
+        self.connection_pool_with_env(|name| std::env::var(name).ok())
+    }
+
+    /// Builds the reqwest client, which holds the connection pool. Cookies
+    /// are kept by [`Http`] instead, so the pool can be shared.
+    fn connection_pool_with_env(
+        &self,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<HttpClient> {
+        let mut builder = HttpClient::builder();
+        // `rustls-tls` is opt-in, so it wins when the default `native-tls` is
+        // still enabled alongside it.
+        #[cfg(feature = "rustls-tls")]
+        {
+            builder = builder.tls_backend_rustls();
+        }
+        #[cfg(all(feature = "native-tls", not(feature = "rustls-tls")))]
+        {
+            builder = builder.tls_backend_native();
+        }
+        if self.disable_compression {
+            builder = builder.no_gzip().no_brotli();
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        // Followed explicitly where expected, see `redirects`.
+        builder = builder.redirect(reqwest::redirect::Policy::none());
+        if self.ignore_system_proxy {
+            builder = builder.no_proxy();
+        } else {
+            for proxy in
+                proxy::from_env(env, self.proxy_auth.as_ref()).map_err(Error::HttpClient)?
+            {
+                builder = builder.proxy(proxy);
+            }
+        }
+        builder.build().map_err(Error::HttpClient)
     }
 }
 
@@ -280,10 +1088,47 @@ impl ClientBuilder {
 /// }
 /// ```
 pub struct Client {
-    http: HttpClient,
+    http: Http,
+    endpoints: Endpoints,
     messages_initialized: bool,
+    messages_availability: MessagesAvailability,
+    normalize_responses: bool,
+    max_response_bytes: Option<usize>,
+    keep_full_error_bodies: bool,
+    capabilities: OnceLock<Capabilities>,
+    messages_page_size: u32,
+    detected_message_page_cap: Option<u32>,
+    /// The account in the Messages API, once fetched.
+    messages_me: Option<MessagesUser>,
+    /// Credentials kept for [`TakeoverPolicy::Reauth`].
+    reauth: Option<Credentials>,
+    /// School unit chosen for district-wide accounts.
+    unit: Option<String>,
+    trace_auth: bool,
+    last_auth_trace: Mutex<Option<AuthTrace>>,
+}
+
+/// Login credentials kept for [`TakeoverPolicy::Reauth`], wiped when dropped.
+struct Credentials {
+    username: String,
+    password: String,
+}
+
+impl Zeroize for Credentials {
+    fn zeroize(&mut self) {
+        self.username.zeroize();
+        self.password.zeroize();
+    }
+}
+
+impl Drop for Credentials {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
 }
 
+impl ZeroizeOnDrop for Credentials {}
+
 impl Client {
     /// Creates a new client from environment variables.
     ///
@@ -295,7 +1140,8 @@ impl Client {
     /// Returns an error if:
     /// - `LIBRUS_USERNAME` is not set ([`Error::MissingEnvVar`])
     /// - `LIBRUS_PASSWORD` is not set ([`Error::MissingEnvVar`])
-    /// - Authentication fails ([`Error::Authentication`])
+    /// - The credentials are rejected ([`Error::InvalidCredentials`])
+    /// - A later step of the login flow fails ([`Error::GrantFailed`])
     ///
     /// # Example
     ///
@@ -313,15 +1159,17 @@ impl Client {
             .map_err(|_| Error::MissingEnvVar("LIBRUS_USERNAME"))?;
         let password = std::env::var("LIBRUS_PASSWORD")
             .map_err(|_| Error::MissingEnvVar("LIBRUS_PASSWORD"))?;
-        Self::authenticate(&username, &password).await
+        Self::new(&username, &password).await
     }
 
     /// Creates a new client with explicit credentials.
     ///
     /// # Errors
     ///
-    /// Returns an error if authentication fails ([`Error::Authentication`])
-    /// or a network error occurs ([`Error::Request`]).
+    /// Returns an error if the credentials are rejected ([`Error::InvalidCredentials`]),
+    /// a later step of the login flow fails ([`Error::GrantFailed`])
+    /// or a network error occurs ([`Error::Timeout`], [`Error::Connect`] or
+    /// [`Error::Request`]).
     ///
     /// # Example
     ///
@@ -334,7 +1182,11 @@ impl Client {
     /// # }
     /// ```
     pub async fn new(username: &str, password: &str) -> Result<Self> {
-        Self::authenticate(username, password).await
+        Self::builder()
+            .username(username)
+            .password(password)
+            .build()
+            .await
     }
 
     /// Creates a builder for configuring the client.
@@ -357,112 +1209,691 @@ impl Client {
         ClientBuilder::new()
     }
 
-    async fn authenticate(username: &str, password: &str) -> Result<Self> {
-        let http = HttpClient::builder()
-            .cookie_store(true)
-            .build()
-            .map_err(Error::HttpClient)?;
+    /// Logs the session in as another account, replacing the current one.
+    ///
+    /// Session state tied to the previous account (the Messages session,
+    /// Messages user and cached capabilities) is dropped.
+    pub(crate) async fn relogin(&mut self, username: &str, password: &str) -> Result<()> {
+        self.messages_initialized = false;
+        self.messages_availability = MessagesAvailability::Unknown;
+        self.messages_me = None;
+        self.capabilities = OnceLock::new();
+        self.log_in(username, password).await?;
+        if let Some(reauth) = &mut self.reauth {
+            *reauth = Credentials {
+                username: username.to_string(),
+                password: password.to_string(),
+            };
+        }
+        Ok(())
+    }
 
+    /// Runs the login flow, keeping its trace if enabled.
+    async fn log_in(&self, username: &str, password: &str) -> Result<()> {
+        let mut recorder = AuthRecorder::new(self.trace_auth, password);
+        let result = Self::login(
+            &self.http,
+            &self.endpoints,
+            username,
+            password,
+            self.unit.as_deref(),
+            &mut recorder,
+        )
+        .await;
+        let Some(trace) = recorder.finish() else {
+            return result;
+        };
+        *self
+            .last_auth_trace
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(trace.clone());
+        match result {
+            Err(Error::Authentication) => Err(Error::AuthenticationDetailed { trace }),
+            other => other,
+        }
+    }
+
+    /// Logs in with the first of `profiles` that works, or with the
+    /// configured endpoints if there are none. See
+    /// [`ClientBuilder::auth_profiles()`].
+    async fn log_in_with_profiles(
+        &mut self,
+        credentials: &Credentials,
+        profiles: &[AuthProfile],
+    ) -> Result<()> {
+        let mut first_error = None;
+        for profile in profiles {
+            self.endpoints.apply(profile);
+            match self
+                .log_in(&credentials.username, &credentials.password)
+                .await
+            {
+                Ok(()) => return Ok(()),
+                // Only a flow that does not fit the school's login pages
+                // calls for another profile.
+                Err(
+                    e @ (Error::GrantFailed { .. }
+                    | Error::UnexpectedRedirect { .. }
+                    | Error::Authentication
+                    | Error::AuthenticationDetailed { .. }),
+                ) => {
+                    first_error.get_or_insert(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => {
+                self.log_in(&credentials.username, &credentials.password)
+                    .await
+            }
+        }
+    }
+
+    /// The steps of the most recent login, including logins repeated after
+    /// a session takeover.
+    ///
+    /// Only recorded when the client was built with
+    /// [`ClientBuilder::trace_auth()`]; `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::ClientBuilder;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = ClientBuilder::new()
+    ///     .username("my_username")
+    ///     .password("my_password")
+    ///     .trace_auth()
+    ///     .build()
+    ///     .await?;
+    /// if let Some(trace) = client.last_auth_trace() {
+    ///     println!("{trace}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn last_auth_trace(&self) -> Option<AuthTrace> {
+        self.last_auth_trace
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    async fn login(
+        http: &Http,
+        endpoints: &Endpoints,
+        username: &str,
+        password: &str,
+        unit: Option<&str>,
+        recorder: &mut AuthRecorder,
+    ) -> Result<()> {
         // Initiate OAuth flow from synergia to set oauth_state cookie and prime the session.
         // The redirect chain lands on the api.librus.pl login form.
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
-        let init_url = format!("{PORTAL_RODZINA_URL}?v={timestamp}");
-        http.get(&init_url)
-            .send()
-            .await
-            .map_err(Error::Request)?;
-
-        let form_params = [("action", "login"), ("login", username), ("pass", password)];
-        let login_response = http
-            .post(AUTH_URL)
-            .form(&form_params)
-            .send()
-            .await
-            .map_err(Error::Request)?;
+        let init_url = format!("{}?v={timestamp}", endpoints.portal);
+        let init = Self::send_auth(http, endpoints, http.get(&init_url)).await?;
+        let checked = check_auth_step(AuthStep::Init, &init.response);
+        recorder
+            .record_response(AuthStep::Init, &init_url, init)
+            .await;
+        checked?;
 
-        let login_json: serde_json::Value = login_response.json().await.map_err(|_| Error::Authentication)?;
-        let go_to = login_json["goTo"]
-            .as_str()
-            .ok_or(Error::Authentication)?;
+        // The form replies with JSON in both cases, sometimes with a 200 status
+        // on failure, so the body decides whether the credentials were accepted.
+        let form_params = [
+            ("action", "login"),
+            (endpoints.login_field.as_str(), username),
+            (endpoints.password_field.as_str(), password),
+        ];
+        let login = Self::send_auth(
+            http,
+            endpoints,
+            http.post(&endpoints.auth).form(&form_params),
+        )
+        .await?;
+        let login_status = login.response.status();
+        let captured = recorder.capture(&login);
+        let login_body = login.response.text().await.map_err(Error::from)?;
+        let reply = serde_json::from_str::<LoginReply>(&login_body);
+        if let Some(captured) = captured {
+            let redirect = reply.as_ref().ok().and_then(|r| r.go_to.as_deref());
+            let redirect = redirect.map(|go_to| {
+                let url = urls::join(&endpoints.auth_host, go_to);
+                url.split('?').next().unwrap_or_default().to_string()
+            });
+            recorder.record(
+                AuthStep::Login,
+                &endpoints.auth,
+                captured,
+                &login_body,
+                redirect,
+            );
+        }
+        let go_to = match reply {
+            Ok(reply) if reply.status.as_deref() == Some("error") => {
+                let message = reply
+                    .errors
+                    .into_iter()
+                    .filter_map(|e| e.message)
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                return Err(Error::InvalidCredentials { message });
+            }
+            _ if !login_status.is_success() => {
+                return Err(Error::GrantFailed {
+                    step: AuthStep::Login,
+                    status: login_status.as_u16(),
+                });
+            }
+            Ok(LoginReply {
+                go_to: Some(go_to), ..
+            }) => go_to,
+            _ => return Err(Error::Authentication),
+        };
 
         // Follow 2FA → PerformLogin → Grant → portalRodzina?code=&state= chain.
         // The final portalRodzina response sets oauth_token, activating the session.
-        let redirect_url = format!("https://api.librus.pl{go_to}");
-        http.get(&redirect_url)
-            .send()
-            .await
-            .map_err(Error::Request)?;
-
-        let token_response = http
-            .get(TOKEN_INFO_URL)
-            .send()
-            .await
-            .map_err(Error::Request)?;
+        let redirect_url = urls::join(&endpoints.auth_host, &go_to);
+        let grant = Self::send_auth(http, endpoints, http.get(&redirect_url)).await?;
+        let checked = check_auth_step(AuthStep::Grant, &grant.response);
+        let captured = recorder.capture(&grant);
+        let grant_body = grant.response.text().await.map_err(Error::from)?;
+        if let Some(captured) = captured {
+            recorder.record_page(AuthStep::Grant, &redirect_url, captured, &grant_body);
+        }
+        checked?;
 
-        if token_response.status() != 200 {
-            return Err(Error::Authentication);
+        // District-wide accounts pick a school before the session is active.
+        if let Some(page) = unit_selection::parse(&grant_body) {
+            let Some(unit) = unit.filter(|id| page.units.iter().any(|u| u.id == *id)) else {
+                return Err(Error::UnitSelectionRequired { units: page.units });
+            };
+            let url = match &page.action {
+                Some(action) if action.starts_with("http") => action.clone(),
+                Some(action) => urls::join(&endpoints.auth_host, action),
+                None => endpoints.unit_select.clone(),
+            };
+            let selected = Self::submit_unit(http, endpoints, &url, unit).await?;
+            let checked = check_auth_step(AuthStep::Unit, &selected.response);
+            recorder
+                .record_response(AuthStep::Unit, &url, selected)
+                .await;
+            checked?;
         }
 
-        Ok(Self {
+        let token = Self::send_auth(http, endpoints, http.get(&endpoints.token_info)).await?;
+        let checked = check_auth_step(AuthStep::TokenInfo, &token.response);
+        recorder
+            .record_response(AuthStep::TokenInfo, &endpoints.token_info, token)
+            .await;
+        checked
+    }
+
+    /// Sends a request of the login flow or session setup, following
+    /// redirects as long as they stay on the Librus hosts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedRedirect`] for a redirect elsewhere.
+    async fn send_auth(
+        http: &Http,
+        endpoints: &Endpoints,
+        request: reqwest::RequestBuilder,
+    ) -> Result<Followed> {
+        let request = request.build().map_err(Error::from)?;
+        redirects::follow(http, request, Cost::Request, |url| {
+            endpoints.allows_redirect_to(url)
+        })
+        .await
+    }
+
+    async fn submit_unit(
+        http: &Http,
+        endpoints: &Endpoints,
+        url: &str,
+        unit_id: &str,
+    ) -> Result<Followed> {
+        let request = http.post(url).form(&[("unit", unit_id)]);
+        Self::send_auth(http, endpoints, request).await
+    }
+
+    /// Switches the session of a district-wide account to another school
+    /// unit, e.g. one from [`Error::UnitSelectionRequired`].
+    ///
+    /// The unit is also used when the client logs in again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::GrantFailed`] if the selection or the following
+    /// session check is rejected, or an error if a request fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{ClientBuilder, Error};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let builder = ClientBuilder::new().username("user").password("pass");
+    /// let units = match builder.clone().build().await {
+    ///     Err(Error::UnitSelectionRequired { units }) => units,
+    ///     other => return other.map(drop),
+    /// };
+    /// let mut client = builder.unit(&units[0].id).build().await?;
+    /// // Later, switch the session to the second school.
+    /// client.select_unit(&units[1].id).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn select_unit(&mut self, unit_id: &str) -> Result<()> {
+        let selected = Self::submit_unit(
+            &self.http,
+            &self.endpoints,
+            &self.endpoints.unit_select,
+            unit_id,
+        )
+        .await?;
+        check_auth_step(AuthStep::Unit, &selected.response)?;
+        let token = Self::send_auth(
+            &self.http,
+            &self.endpoints,
+            self.http.get(&self.endpoints.token_info),
+        )
+        .await?;
+        check_auth_step(AuthStep::TokenInfo, &token.response)?;
+        self.unit = Some(unit_id.to_string());
+        self.messages_initialized = false;
+        self.messages_availability = MessagesAvailability::Unknown;
+        self.messages_me = None;
+        self.capabilities = OnceLock::new();
+        Ok(())
+    }
+
+    fn from_parts(http: Http, endpoints: Endpoints) -> Self {
+        Self {
             http,
+            endpoints,
             messages_initialized: false,
-        })
+            messages_availability: MessagesAvailability::Unknown,
+            normalize_responses: false,
+            max_response_bytes: None,
+            keep_full_error_bodies: false,
+            capabilities: OnceLock::new(),
+            messages_page_size: DEFAULT_MESSAGES_PAGE_SIZE,
+            detected_message_page_cap: None,
+            messages_me: None,
+            reauth: None,
+            unit: None,
+            trace_auth: false,
+            last_auth_trace: Mutex::new(None),
+        }
+    }
+
+    /// Logs in again after [`Error::SessionTakenOver`] if the takeover policy
+    /// allows it, and returns any other error unchanged.
+    async fn reauth_after(&self, error: Error) -> Result<()> {
+        match (&error, &self.reauth) {
+            (Error::SessionTakenOver, Some(creds)) => {
+                self.log_in(&creds.username, &creds.password).await
+            }
+            _ => Err(error),
+        }
+    }
+
+    /// The error for a Synergia request that was redirected, usually to the
+    /// login page. API requests never follow redirects.
+    ///
+    /// Some instances answer this way after another login took the session
+    /// over, which the body or the target then reports; any other redirect
+    /// means the session expired.
+    async fn redirect_error(&self, response: reqwest::Response) -> Error {
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = self.read_text(response).await.unwrap_or_default();
+        if is_takeover_redirect(&location, &body) {
+            Error::SessionTakenOver
+        } else {
+            Error::SessionExpired
+        }
+    }
+
+    /// Wraps a body that failed to parse for [`Error::Parse`], see
+    /// [`ClientBuilder::keep_full_error_bodies()`].
+    fn error_body(&self, body: String) -> ErrorBody {
+        ErrorBody::new(body.into(), self.keep_full_error_bodies)
     }
 
     async fn get_api(&self, endpoint: &str) -> Result<String> {
-        let url = format!("{}{}", SYNERGIA_API_BASE, endpoint);
-        let response = self
+        match self.fetch_api(endpoint).await {
+            Err(error) => {
+                self.reauth_after(error).await?;
+                self.fetch_api(endpoint).await
+            }
+            ok => ok,
+        }
+    }
+
+    async fn fetch_api(&self, endpoint: &str) -> Result<String> {
+        let url = urls::join(&self.endpoints.api, endpoint);
+        let request = self
             .http
             .get(&url)
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(Error::Request)?;
+            .header("Content-Type", "application/json");
+        let response = self.http.send(request, Cost::Request).await?;
+        if response.status().is_redirection() {
+            return Err(self.redirect_error(response).await);
+        }
+
+        let status = response.status();
+        let text = self.read_text(response).await?;
+
+        if !status.is_success() {
+            if is_session_taken_over(&text) {
+                return Err(Error::SessionTakenOver);
+            }
+            if is_module_disabled(&text) {
+                let module = endpoint.split(['/', '?']).next().unwrap_or(endpoint);
+                return Err(Error::ModuleDisabled(module.to_string()));
+            }
+            return Err(Error::ApiError {
+                api: ApiKind::Synergia,
+                status: status.as_u16(),
+                body: text,
+                source: None,
+            });
+        }
+
+        Ok(text)
+    }
+
+    async fn post_api(&self, endpoint: &str, body: &serde_json::Value) -> Result<String> {
+        match self.send_post_api(endpoint, body).await {
+            Err(error) => {
+                self.reauth_after(error).await?;
+                self.send_post_api(endpoint, body).await
+            }
+            ok => ok,
+        }
+    }
+
+    async fn send_post_api(&self, endpoint: &str, body: &serde_json::Value) -> Result<String> {
+        let url = urls::join(&self.endpoints.api, endpoint);
+        let request = self.http.post(&url).json(body);
+        let response = self.http.send(request, Cost::Request).await?;
+        if response.status().is_redirection() {
+            return Err(self.redirect_error(response).await);
+        }
 
         let status = response.status();
-        let text = response.text().await.map_err(Error::Request)?;
+        let text = self.read_text(response).await?;
 
         if !status.is_success() {
+            if is_session_taken_over(&text) {
+                return Err(Error::SessionTakenOver);
+            }
             return Err(Error::ApiError {
+                api: ApiKind::Synergia,
                 status: status.as_u16(),
                 body: text,
+                source: None,
             });
         }
 
         Ok(text)
     }
 
-    async fn get_messages_api(&self, endpoint: &str) -> Result<String> {
-        let url = format!("{}{}", MESSAGES_API_BASE, endpoint);
-        let response = self.http.get(&url).send().await.map_err(Error::Request)?;
+    /// GETs a messages API endpoint, re-initializing the messages session once
+    /// if it has expired.
+    async fn get_messages_api(&mut self, endpoint: &str) -> Result<String> {
+        self.call_messages_api(Method::GET, endpoint, None).await
+    }
+
+    /// POSTs JSON to a messages API endpoint, re-initializing the messages
+    /// session once if it has expired.
+    async fn post_messages_api(
+        &mut self,
+        endpoint: &str,
+        body: &serde_json::Value,
+    ) -> Result<String> {
+        self.call_messages_api(Method::POST, endpoint, Some(body))
+            .await
+    }
+
+    /// DELETEs a messages API resource, re-initializing the messages session
+    /// once if it has expired.
+    async fn delete_messages_api(&mut self, endpoint: &str) -> Result<String> {
+        self.call_messages_api(Method::DELETE, endpoint, None).await
+    }
+
+    async fn call_messages_api(
+        &mut self,
+        method: Method,
+        endpoint: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<String> {
+        match self
+            .fetch_messages_api(method.clone(), endpoint, body)
+            .await
+        {
+            Err(Error::SessionExpired) => {
+                self.messages_initialized = false;
+                self.ensure_messages_initialized().await?;
+                self.fetch_messages_api(method, endpoint, body).await
+            }
+            other => other,
+        }
+    }
+
+    async fn fetch_messages_api(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<String> {
+        let url = urls::join(&self.endpoints.messages, endpoint);
+        let mut request = self.http.request(method, &url);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = self.http.send(request, Cost::Request).await?;
 
         let status = response.status();
-        let text = response.text().await.map_err(Error::Request)?;
+        // A redirect, like the HTML login page, means the session is gone.
+        if status.is_redirection() {
+            return Err(Error::SessionExpired);
+        }
+        let is_html = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/html"));
+        let text = self.read_text(response).await?;
+
+        // An expired session is answered with the HTML login page, often with 200.
+        if is_html || text.trim_start().starts_with('<') {
+            return Err(Error::SessionExpired);
+        }
+
+        // Some failures come as `{"errorCode": ..}` with a 200 status.
+        if text.contains("\"errorCode\"") {
+            if let Some(error) = messages_envelope_error(&text) {
+                return Err(error);
+            }
+        }
 
         if !status.is_success() {
             return Err(Error::ApiError {
+                api: ApiKind::Messages,
                 status: status.as_u16(),
                 body: text,
+                source: None,
             });
         }
 
         Ok(text)
     }
 
+    async fn download(&self, url: &str, api: ApiKind) -> Result<AttachmentDownload> {
+        // Attachments are mostly already-compressed files (PDF, JPEG, ZIP), so ask
+        // for the raw bytes to make sure they are never transparently decoded.
+        let request = self
+            .http
+            .get(url)
+            .header(reqwest::header::ACCEPT_ENCODING, "identity")
+            .build()
+            .map_err(Error::from)?;
+        // Files may be served from another host.
+        let response = redirects::follow(&self.http, request, Cost::Attachment, |_| true)
+            .await?
+            .response;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = self.read_text(response).await.unwrap_or_default();
+            return Err(Error::ApiError {
+                api,
+                status: status.as_u16(),
+                body,
+                source: None,
+            });
+        }
+
+        let headers = response.headers().clone();
+        let body = self.read_body(response).await?;
+        check_not_scanning(&headers, &body)?;
+        let len = body.len() as u64;
+        Ok(AttachmentDownload::buffered(body, 0, Some(len), &headers))
+    }
+
+    /// Streams an attachment response, unless it is the antivirus scanning
+    /// page.
+    async fn stream_attachment(
+        &self,
+        response: reqwest::Response,
+        offset: u64,
+        total_len: Option<u64>,
+    ) -> Result<AttachmentDownload> {
+        let is_html = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/html"));
+        if !is_html {
+            return Ok(AttachmentDownload::streaming(response, offset, total_len));
+        }
+        // Only pages are buffered to look at, files are streamed untouched.
+        let headers = response.headers().clone();
+        let body = self.read_body(response).await?;
+        check_not_scanning(&headers, &body)?;
+        Ok(AttachmentDownload::buffered(
+            body, offset, total_len, &headers,
+        ))
+    }
+
+    /// Reads a response body, enforcing `max_response_bytes`.
+    async fn read_body(&self, mut response: reqwest::Response) -> Result<Vec<u8>> {
+        let Some(limit) = self.max_response_bytes else {
+            let bytes = response.bytes().await.map_err(Error::from)?;
+            return Ok(bytes.to_vec());
+        };
+        // Reject early when the size is announced up front.
+        if response
+            .content_length()
+            .is_some_and(|len| len > limit as u64)
+        {
+            return Err(Error::ResponseTooLarge { limit });
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(Error::from)? {
+            if body.len() + chunk.len() > limit {
+                return Err(Error::ResponseTooLarge { limit });
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    async fn read_text(&self, response: reqwest::Response) -> Result<String> {
+        if self.max_response_bytes.is_none() {
+            return response.text().await.map_err(Error::from);
+        }
+        let body = self.read_body(response).await?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
     async fn ensure_messages_initialized(&mut self) -> Result<()> {
+        if self.messages_availability == MessagesAvailability::Disabled {
+            return Err(Error::MessagesDisabledForAccount);
+        }
         if self.messages_initialized {
             return Ok(());
         }
-        self.http
-            .get(MESSAGES_INIT_URL)
-            .send()
+        self.open_messages().await
+    }
+
+    /// Opens the messages session, remembering whether the account has
+    /// messaging at all.
+    async fn open_messages(&mut self) -> Result<()> {
+        let result = self.init_messages().await;
+        self.messages_initialized = result.is_ok();
+        self.messages_availability = match result {
+            Ok(()) => MessagesAvailability::Available,
+            Err(Error::MessagesDisabledForAccount) => MessagesAvailability::Disabled,
+            Err(_) => MessagesAvailability::Unknown,
+        };
+        result
+    }
+
+    /// Opens the messages session and checks that it worked.
+    ///
+    /// The init page answers a disabled module with `403` and some failures
+    /// with a redirect to an error page, both of which would otherwise only
+    /// show up as confusing errors from the next Messages call.
+    async fn init_messages(&self) -> Result<()> {
+        let response = Self::send_auth(
+            &self.http,
+            &self.endpoints,
+            self.http.get(&self.endpoints.messages_init),
+        )
+        .await?
+        .response;
+        let status = response.status();
+        let landed = response.url().clone();
+        if status.is_success() && landed.as_str() == self.endpoints.messages_init {
+            return Ok(());
+        }
+        let page = self.read_text(response).await.unwrap_or_default();
+        if page.to_lowercase().contains(MESSAGES_DISABLED_MARKER) {
+            return Err(Error::MessagesDisabledForAccount);
+        }
+        if !status.is_success() {
+            return Err(Error::MessagesUnavailable {
+                reason: format!("init page returned status {}", status.as_u16()),
+            });
+        }
+
+        // Redirected away from the init page, which is how it hands over to
+        // the messages app, but also how it reports errors. A cheap API
+        // call tells the two apart.
+        let landed = landed.path();
+        match self
+            .fetch_messages_api(Method::GET, "inbox/unreadMessagesCount", None)
             .await
-            .map_err(Error::Request)?;
-        self.messages_initialized = true;
-        Ok(())
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MessagesUnavailable {
+                reason: format!("init page redirected to {landed}, API probe failed: {e}"),
+            }),
+        }
     }
 
     /// Gets current user information.
@@ -488,53 +1919,57 @@ impl Client {
     /// ```
     pub async fn me(&self) -> Result<ResponseMe> {
         let json = self.get_api("Me").await?;
-        serde_json::from_str(&json).map_err(|e| Error::Parse {
+        let me: ResponseMe = serde_json::from_str(&json).map_err(|e| Error::Parse {
             source: e,
-            body: json,
-        })
+            body: self.error_body(json),
+        })?;
+        let _ = self.capabilities.set(me.me.account.capabilities());
+        Ok(me)
     }
 
-    /// Gets all grades for the student.
+    /// Deletes everything stored in `storage` for the authenticated
+    /// account: cached responses, exported session state and change
+    /// tracker snapshots.
     ///
-    /// Returns a list of all grades across all subjects.
+    /// The account is identified by its ID, fetched with [`Client::me()`].
+    /// Data of other accounts in the same storage is kept.
+    /// See [`storage`] for the layout of the keys.
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails or response parsing fails.
+    /// Returns an error if fetching [`Client::me()`] fails, or
+    /// [`Error::Io`] if the storage fails or values of the account remain.
     ///
     /// # Example
     ///
     /// ```rust,no_run
+    /// use librus_rs::storage::MemoryStorage;
     /// use librus_rs::Client;
     ///
-    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// # async fn example(storage: &MemoryStorage) -> Result<(), librus_rs::Error> {
     /// let client = Client::from_env().await?;
-    /// let grades = client.grades().await?;
-    /// for grade in grades.grades {
-    ///     println!("{}: {} ({})", grade.date, grade.grade, grade.semester);
-    /// }
+    /// let report = client.purge_local_data(storage).await?;
+    /// println!("Removed {} values", report.removed.len());
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn grades(&self) -> Result<ResponseGrades> {
-        let json = self.get_api("Grades").await?;
-        serde_json::from_str(&json).map_err(|e| Error::Parse {
-            source: e,
-            body: json,
-        })
+    pub async fn purge_local_data(
+        &self,
+        storage: &dyn storage::Storage,
+    ) -> Result<storage::PurgeReport> {
+        let account_id = self.me().await?.me.account.id;
+        storage::purge_account(storage, account_id).map_err(Error::Io)
     }
 
-    /// Gets a grade category by ID.
-    ///
-    /// Categories describe the type of grade (e.g., test, homework, quiz).
+    /// Gets the time of the previous login to this account.
     ///
-    /// # Arguments
-    ///
-    /// * `id` - The category ID from a [`Grade`]'s `category` field
+    /// Grades added after it are the ones the web interface highlights as
+    /// new, see [`ResponseGrades::new_since()`].
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails or the category is not found.
+    /// Returns an error if fetching [`Client::me()`] fails. Instances that do
+    /// not report the last login return `Ok(None)`.
     ///
     /// # Example
     ///
@@ -543,30 +1978,36 @@ impl Client {
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
     /// let client = Client::from_env().await?;
-    /// let category = client.grade_category(123).await?;
-    /// println!("Category: {}", category.category.name);
+    /// if let Some(last_login) = client.last_login().await? {
+    ///     let grades = client.grades().await?;
+    ///     for grade in grades.new_since(last_login) {
+    ///         println!("New: {}", grade.grade);
+    ///     }
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn grade_category(&self, id: i32) -> Result<ResponseGradesCategories> {
-        let json = self.get_api(&format!("Grades/Categories/{}", id)).await?;
-        serde_json::from_str(&json).map_err(|e| Error::Parse {
-            source: e,
-            body: json,
-        })
+    pub async fn last_login(&self) -> Result<Option<SystemTime>> {
+        let me = self.me().await?;
+        Ok(me
+            .me
+            .account
+            .last_login
+            .as_deref()
+            .and_then(dates::parse_warsaw_timestamp)
+            .and_then(|secs| u64::try_from(secs).ok())
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs)))
     }
 
-    /// Gets a grade comment by ID.
-    ///
-    /// Comments provide additional context for a grade.
-    ///
-    /// # Arguments
+    /// Gets the premium status and add-ons of the account.
     ///
-    /// * `id` - The comment ID from a [`Grade`]'s `comments` field
+    /// The result is cached after the first successful [`Client::me()`] call,
+    /// so this only hits the API if `me()` was never called.
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails or the comment is not found.
+    /// Returns an error if the capabilities are not cached yet and fetching
+    /// [`Client::me()`] fails.
     ///
     /// # Example
     ///
@@ -575,64 +2016,69 @@ impl Client {
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
     /// let client = Client::from_env().await?;
-    /// let comment = client.grade_comment(456).await?;
-    /// if let Some(c) = comment.comment {
-    ///     println!("Comment: {}", c.text);
+    /// let caps = client.capabilities().await?;
+    /// if caps.has_messages_plus() {
+    ///     println!("Messages Plus enabled");
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn grade_comment(&self, id: i32) -> Result<ResponseGradesComments> {
-        let json = self.get_api(&format!("Grades/Comments/{}", id)).await?;
-        serde_json::from_str(&json).map_err(|e| Error::Parse {
-            source: e,
-            body: json,
-        })
+    pub async fn capabilities(&self) -> Result<&Capabilities> {
+        if let Some(caps) = self.capabilities.get() {
+            return Ok(caps);
+        }
+        let me = self.me().await?;
+        Ok(self
+            .capabilities
+            .get_or_init(|| me.me.account.capabilities()))
     }
 
-    /// Gets a lesson by ID.
-    ///
-    /// Lessons contain information about which teacher teaches which subject to which class.
-    ///
-    /// # Arguments
+    /// Fetches the resource a URL from a response points to, e.g. the
+    /// [`Reference`] to a grade's subject.
     ///
-    /// * `id` - The lesson ID
+    /// The URL is resolved against this client's API base, so absolute
+    /// `api.librus.pl` links and relative paths both go through the
+    /// gateway the client is logged in to.
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails or the lesson is not found.
+    /// Returns [`Error::UnsupportedUrl`] if the URL does not point into the
+    /// Synergia API (see [`ApiUrl::kind()`](types::ApiUrl::kind)), or an error
+    /// if the request fails or the response does not match `T`.
     ///
     /// # Example
     ///
     /// ```rust,no_run
-    /// use librus_rs::Client;
+    /// use librus_rs::{Client, ResponseLessonSubject};
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
     /// let client = Client::from_env().await?;
-    /// let lesson = client.lesson(789).await?;
-    /// println!("Lesson ID: {}", lesson.lesson.id);
+    /// let grades = client.grades().await?;
+    /// let subject: ResponseLessonSubject = client.follow(&grades.grades[0].subject.url).await?;
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn lesson(&self, id: i32) -> Result<ResponseLesson> {
-        let json = self.get_api(&format!("Lessons/{}", id)).await?;
+    pub async fn follow<T: serde::de::DeserializeOwned>(&self, url: &types::ApiUrl) -> Result<T> {
+        let Some(path) = url.path_relative_to_base() else {
+            return Err(Error::UnsupportedUrl {
+                url: url.to_string(),
+                kind: url.kind(),
+            });
+        };
+        let json = self.get_api(path).await?;
         serde_json::from_str(&json).map_err(|e| Error::Parse {
             source: e,
-            body: json,
+            body: self.error_body(json),
         })
     }
 
-    /// Gets a subject by ID.
-    ///
-    /// Subjects contain the name and short code for academic subjects.
-    ///
-    /// # Arguments
+    /// Gets all grades for the student.
     ///
-    /// * `id` - The subject ID
+    /// Returns a list of all grades across all subjects.
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails or the subject is not found.
+    /// Returns an error if the request fails or response parsing fails.
     ///
     /// # Example
     ///
@@ -641,56 +2087,68 @@ impl Client {
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
     /// let client = Client::from_env().await?;
-    /// let subject = client.subject(101).await?;
-    /// if let Some(s) = subject.subject {
-    ///     println!("Subject: {} ({})", s.name, s.short);
+    /// let grades = client.grades().await?;
+    /// for grade in grades.grades {
+    ///     println!("{}: {} ({})", grade.date, grade.grade, grade.semester());
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn subject(&self, id: i32) -> Result<ResponseLessonSubject> {
-        let json = self.get_api(&format!("Subjects/{}", id)).await?;
-        serde_json::from_str(&json).map_err(|e| Error::Parse {
+    pub async fn grades(&self) -> Result<ResponseGrades> {
+        let json = self.get_api("Grades").await?;
+        let grades: ResponseGrades = serde_json::from_str(&json).map_err(|e| Error::Parse {
             source: e,
-            body: json,
+            body: self.error_body(json),
+        })?;
+        Ok(if self.normalize_responses {
+            grades.normalized()
+        } else {
+            grades
         })
     }
 
-    /// Gets all attendances for the student.
+    /// Gets all grades as the raw response body.
     ///
-    /// Returns attendance records for all lessons.
+    /// For large accounts, parse it into [`borrowed::ResponseGrades`] to
+    /// avoid allocating every string. [`ClientBuilder::normalize_responses()`]
+    /// does not apply.
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails or response parsing fails.
+    /// Returns an error if the request fails.
     ///
     /// # Example
     ///
     /// ```rust,no_run
-    /// use librus_rs::Client;
+    /// use librus_rs::{borrowed, Client};
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
     /// let client = Client::from_env().await?;
-    /// let attendances = client.attendances().await?;
-    /// println!("Total records: {}", attendances.attendances.len());
+    /// let raw = client.grades_raw().await?;
+    /// let grades: borrowed::ResponseGrades<'_> = raw.parse()?;
+    /// println!("Total grades: {}", grades.grades.len());
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn attendances(&self) -> Result<ResponseAttendances> {
-        let json = self.get_api("Attendances/").await?;
-        serde_json::from_str(&json).map_err(|e| Error::Parse {
-            source: e,
-            body: json,
-        })
+    pub async fn grades_raw(&self) -> Result<RawResponse> {
+        let body = self.get_api("Grades").await?;
+        Ok(RawResponse::new(body, self.keep_full_error_bodies))
     }
 
-    /// Gets all attendance types.
+    /// Gets a single grade by ID.
     ///
-    /// Types describe the kind of attendance (present, absent, late, etc.).
+    /// Useful for resolving grade references, e.g. from a [`GradeComment`]'s
+    /// `grade` field.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The grade ID
     ///
     /// # Errors
     ///
     /// Returns an error if the request fails or response parsing fails.
+    /// A grade that does not exist, reported by the API as a 404 `NotFound`
+    /// error, is returned as `Ok` with `grade: None`.
     ///
     /// # Example
     ///
@@ -699,24 +2157,34 @@ impl Client {
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
     /// let client = Client::from_env().await?;
-    /// let types = client.attendance_types().await?;
-    /// for t in types.types {
-    ///     println!("{}: {} ({})", t.id, t.name, t.short);
+    /// let grade = client.grade(1001).await?;
+    /// if let Some(g) = grade.grade {
+    ///     println!("{}: {}", g.date, g.grade);
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn attendance_types(&self) -> Result<ResponseAttendancesType> {
-        let json = self.get_api("Attendances/Types/").await?;
+    pub async fn grade(&self, id: i64) -> Result<ResponseGrade> {
+        let endpoint = format!("Grades/{}", id);
+        let json = match self.get_api(&endpoint).await {
+            Err(Error::ApiError {
+                status: 404, body, ..
+            }) if gateway_error_code(&body).as_deref() == Some(NOT_FOUND_CODE) => {
+                return Ok(ResponseGrade {
+                    grade: None,
+                    resources: None,
+                    url: urls::join(&self.endpoints.api, &endpoint),
+                });
+            }
+            other => other?,
+        };
         serde_json::from_str(&json).map_err(|e| Error::Parse {
             source: e,
-            body: json,
+            body: self.error_body(json),
         })
     }
 
-    /// Gets all homeworks.
-    ///
-    /// Returns a list of all homework assignments.
+    /// Gets all grade categories.
     ///
     /// # Errors
     ///
@@ -729,28 +2197,32 @@ impl Client {
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
     /// let client = Client::from_env().await?;
-    /// let homeworks = client.homeworks().await?;
-    /// for hw in homeworks.homeworks {
-    ///     println!("{}: {}", hw.date, hw.content);
+    /// let categories = client.grade_categories().await?;
+    /// for category in categories.categories {
+    ///     println!("{}: {}", category.id, category.name);
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn homeworks(&self) -> Result<ResponseHomeworks> {
-        let json = self.get_api("HomeWorks/").await?;
+    pub async fn grade_categories(&self) -> Result<ResponseGradeCategoryList> {
+        let json = self.get_api("Grades/Categories").await?;
         serde_json::from_str(&json).map_err(|e| Error::Parse {
             source: e,
-            body: json,
+            body: self.error_body(json),
         })
     }
 
-    /// Gets school notices (announcements).
+    /// Gets a grade category by ID.
     ///
-    /// Returns a list of school notices.
+    /// Categories describe the type of grade (e.g., test, homework, quiz).
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The category ID from a [`Grade`]'s `category` field
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails or response parsing fails.
+    /// Returns an error if the request fails or the category is not found.
     ///
     /// # Example
     ///
@@ -759,93 +2231,30 @@ impl Client {
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
     /// let client = Client::from_env().await?;
-    /// let notices = client.school_notices().await?;
-    /// for notice in notices.school_notices {
-    ///     println!("{}: {}", notice.creation_date, notice.subject);
-    /// }
+    /// let category = client.grade_category(123).await?;
+    /// println!("Category: {}", category.category.name);
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn school_notices(&self) -> Result<ResponseSchoolNotices> {
-        let json = self.get_api("SchoolNotices").await?;
-        serde_json::from_str(&json).map_err(|e| Error::Parse {
-            source: e,
-            body: json,
-        })
-    }
-
-    /// Gets school notices (announcements) with pagination.
-    ///
-    /// # Arguments
-    ///
-    /// * `page` - Page number (1-indexed)
-    /// * `limit` - Number of notices per page
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the request fails or response parsing fails.
-    pub async fn school_notices_page(
-        &self,
-        page: u32,
-        limit: u32,
-    ) -> Result<ResponseSchoolNotices> {
-        let endpoint = format!("SchoolNotices?page={}&limit={}", page, limit);
-        let json = self.get_api(&endpoint).await?;
+    pub async fn grade_category(&self, id: i32) -> Result<ResponseGradesCategories> {
+        let json = self.get_api(&format!("Grades/Categories/{}", id)).await?;
         serde_json::from_str(&json).map_err(|e| Error::Parse {
             source: e,
-            body: json,
+            body: self.error_body(json),
         })
     }
 
-    /// Gets the latest school notices (announcements).
-    ///
-    /// This paginates through all notices, sorts them by `creation_date` (descending),
-    /// and returns the newest `limit` items.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the request fails or response parsing fails.
-    pub async fn school_notices_latest(&self, limit: usize) -> Result<Vec<SchoolNotice>> {
-        if limit == 0 {
-            return Ok(Vec::new());
-        }
-
-        let page_size: u32 = 50;
-        let mut page = 1;
-        let mut all = Vec::new();
-
-        loop {
-            let resp = self.school_notices_page(page, page_size).await?;
-            if resp.school_notices.is_empty() {
-                break;
-            }
-
-            let count = resp.school_notices.len();
-            all.extend(resp.school_notices);
-
-            if count < page_size as usize {
-                break;
-            }
-
-            page += 1;
-        }
-
-        all.sort_by(|a, b| b.creation_date.cmp(&a.creation_date));
-        all.truncate(limit);
-        Ok(all)
-    }
-
-    /// Gets a user by ID.
+    /// Gets a grade comment by ID.
     ///
-    /// Users include teachers, students, and parents.
+    /// Comments provide additional context for a grade.
     ///
     /// # Arguments
     ///
-    /// * `id` - The user ID
+    /// * `id` - The comment ID from a [`Grade`]'s `comments` field
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails or the user is not found.
+    /// Returns an error if the request fails or the comment is not found.
     ///
     /// # Example
     ///
@@ -854,39 +2263,25 @@ impl Client {
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
     /// let client = Client::from_env().await?;
-    /// let user = client.user(12345).await?;
-    /// if let Some(u) = user.user {
-    ///     println!("{} {}", u.first_name, u.last_name);
+    /// let comment = client.grade_comment(456).await?;
+    /// if let Some(c) = comment.comment {
+    ///     println!("Comment: {}", c.text);
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn user(&self, id: i32) -> Result<ResponseUser> {
-        let json = self.get_api(&format!("Users/{}", id)).await?;
-        serde_json::from_str(&json).map_err(|e| Error::Parse {
-            source: e,
-            body: json,
-        })
-    }
-
-    /// Gets current user details.
-    ///
-    /// Returns detailed information about the authenticated user.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the request fails or response parsing fails.
-    pub async fn current_user(&self) -> Result<ResponseUser> {
-        let json = self.get_api("Users").await?;
+    pub async fn grade_comment(&self, id: i32) -> Result<ResponseGradesComments> {
+        let json = self.get_api(&format!("Grades/Comments/{}", id)).await?;
         serde_json::from_str(&json).map_err(|e| Error::Parse {
             source: e,
-            body: json,
+            body: self.error_body(json),
         })
     }
 
-    /// Gets unread message counts for all folders.
+    /// Gets the unpreparedness ("np") counters per subject and semester.
     ///
-    /// Returns counts for inbox, notes, alerts, and other message categories.
+    /// Each entry tells how many times the student reported being unprepared
+    /// and how many times it is allowed, see [`Limit`].
     ///
     /// # Errors
     ///
@@ -895,32 +2290,35 @@ impl Client {
     /// # Example
     ///
     /// ```rust,no_run
-    /// use librus_rs::Client;
+    /// use librus_rs::{Client, Limit};
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
-    /// let mut client = Client::from_env().await?;
-    /// let counts = client.unread_counts().await?;
-    /// println!("Unread inbox: {}", counts.inbox);
-    /// println!("Unread alerts: {}", counts.alerts);
+    /// let client = Client::from_env().await?;
+    /// for entry in client.unpreparedness().await? {
+    ///     match entry.allowed {
+    ///         Limit::Count(n) => println!("{:?}: {} of {n} np used", entry.subject.id(), entry.used),
+    ///         Limit::Unlimited => println!("{:?}: {} np used", entry.subject.id(), entry.used),
+    ///     }
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn unread_counts(&mut self) -> Result<UnreadCounts> {
-        self.ensure_messages_initialized().await?;
-        let json = self.get_messages_api("inbox/unreadMessagesCount").await?;
-        let resp: ResponseUnreadCounts = serde_json::from_str(&json).map_err(|e| Error::Parse {
-            source: e,
-            body: json,
-        })?;
-        Ok(resp.data)
+    pub async fn unpreparedness(&self) -> Result<Vec<UnpreparednessEntry>> {
+        let json = self
+            .get_api("Grades/UnpreparednessPerSemesterAndSubject")
+            .await?;
+        let resp: ResponseUnpreparedness =
+            serde_json::from_str(&json).map_err(|e| Error::Parse {
+                source: e,
+                body: self.error_body(json),
+            })?;
+        Ok(resp.unpreparedness_per_semester_and_subject)
     }
 
-    /// Gets inbox messages (received).
-    ///
-    /// # Arguments
+    /// Gets the class averages per subject and grade category.
     ///
-    /// * `page` - Page number (1-indexed)
-    /// * `limit` - Number of messages per page
+    /// Compare them with the student's own grades using
+    /// [`GradeBook::vs_class()`].
     ///
     /// # Errors
     ///
@@ -929,35 +2327,28 @@ impl Client {
     /// # Example
     ///
     /// ```rust,no_run
-    /// use librus_rs::Client;
+    /// use librus_rs::{Client, GradeBook};
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
-    /// let mut client = Client::from_env().await?;
-    /// let messages = client.inbox_messages(1, 10).await?;
-    /// for msg in messages {
-    ///     println!("{}: {}", msg.sender_name, msg.topic);
+    /// let client = Client::from_env().await?;
+    /// let book = GradeBook::from(client.grades().await?);
+    /// for cmp in book.vs_class(&client.category_averages().await?) {
+    ///     println!("{}/{}: {:?} vs {:.2}", cmp.subject_id, cmp.category_id, cmp.student_average, cmp.class_average);
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn inbox_messages(&mut self, page: u32, limit: u32) -> Result<Vec<InboxMessage>> {
-        self.ensure_messages_initialized().await?;
-        let endpoint = format!("inbox/messages?page={}&limit={}", page, limit);
-        let json = self.get_messages_api(&endpoint).await?;
-        let resp: ResponseInboxMessages =
+    pub async fn category_averages(&self) -> Result<Vec<CategoryAverage>> {
+        let json = self.get_api("Grades/CategoriesAverages").await?;
+        let resp: ResponseCategoryAverages =
             serde_json::from_str(&json).map_err(|e| Error::Parse {
                 source: e,
-                body: json,
+                body: self.error_body(json),
             })?;
-        Ok(resp.data)
+        Ok(resp.categories_averages)
     }
 
-    /// Gets outbox messages (sent).
-    ///
-    /// # Arguments
-    ///
-    /// * `page` - Page number (1-indexed)
-    /// * `limit` - Number of messages per page
+    /// Gets the student's averages per subject as computed by the server.
     ///
     /// # Errors
     ///
@@ -969,37 +2360,34 @@ impl Client {
     /// use librus_rs::Client;
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
-    /// let mut client = Client::from_env().await?;
-    /// let messages = client.outbox_messages(1, 10).await?;
-    /// for msg in messages {
-    ///     println!("To {}: {}", msg.receiver_name, msg.topic);
+    /// let client = Client::from_env().await?;
+    /// for avg in client.students_averages().await? {
+    ///     println!("{:?}: {:?}", avg.subject.id(), avg.average);
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn outbox_messages(&mut self, page: u32, limit: u32) -> Result<Vec<OutboxMessage>> {
-        self.ensure_messages_initialized().await?;
-        let endpoint = format!("outbox/messages?page={}&limit={}", page, limit);
-        let json = self.get_messages_api(&endpoint).await?;
-        let resp: ResponseOutboxMessages =
+    pub async fn students_averages(&self) -> Result<Vec<StudentAverage>> {
+        let json = self.get_api("Grades/StudentsAverages").await?;
+        let resp: ResponseStudentAverages =
             serde_json::from_str(&json).map_err(|e| Error::Parse {
                 source: e,
-                body: json,
+                body: self.error_body(json),
             })?;
-        Ok(resp.data)
+        Ok(resp.students_averages)
     }
 
-    /// Gets full message details by ID.
+    /// Gets a lesson by ID.
     ///
-    /// Returns the complete message including body content and attachments.
+    /// Lessons contain information about which teacher teaches which subject to which class.
     ///
     /// # Arguments
     ///
-    /// * `message_id` - The message ID from an [`InboxMessage`] or [`OutboxMessage`]
+    /// * `id` - The lesson ID
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails or the message is not found.
+    /// Returns an error if the request fails or the lesson is not found.
     ///
     /// # Example
     ///
@@ -1007,147 +2395,6453 @@ impl Client {
     /// use librus_rs::Client;
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
-    /// let mut client = Client::from_env().await?;
-    /// let detail = client.message("12345").await?;
-    /// if let Some(content) = Client::decode_message_content(&detail.message) {
-    ///     println!("Content: {}", content);
-    /// }
+    /// let client = Client::from_env().await?;
+    /// let lesson = client.lesson(789).await?;
+    /// println!("Lesson ID: {}", lesson.lesson.id);
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn message(&mut self, message_id: &str) -> Result<MessageDetail> {
-        self.ensure_messages_initialized().await?;
-        let endpoint = format!("inbox/messages/{}", message_id);
-        let json = self.get_messages_api(&endpoint).await?;
-        let resp: ResponseMessageDetail =
-            serde_json::from_str(&json).map_err(|e| Error::Parse {
-                source: e,
-                body: json,
-            })?;
-        Ok(resp.data)
+    pub async fn lesson(&self, id: i32) -> Result<ResponseLesson> {
+        let json = self.get_api(&format!("Lessons/{}", id)).await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
     }
 
-    /// Downloads attachment bytes.
-    ///
-    /// # Arguments
+    /// Gets all lessons of the student's classes and groups.
     ///
-    /// * `attachment_id` - The attachment ID from a [`MessageDetail`]'s attachments
-    /// * `message_id` - The message ID containing the attachment
+    /// Each lesson links a teacher to a subject taught to a class.
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails or the attachment is not found.
+    /// Returns an error if the request fails or response parsing fails.
     ///
     /// # Example
     ///
     /// ```rust,no_run
     /// use librus_rs::Client;
-    /// use std::fs;
     ///
     /// # async fn example() -> Result<(), librus_rs::Error> {
-    /// let mut client = Client::from_env().await?;
-    /// let detail = client.message("12345").await?;
-    /// for attachment in &detail.attachments {
-    ///     let bytes = client.attachment(&attachment.id, &detail.message_id).await?;
-    ///     fs::write(&attachment.name, &bytes).expect("Failed to save file");
+    /// let client = Client::from_env().await?;
+    /// for lesson in client.lessons().await?.lessons {
+    ///     println!(
+    ///         "Lesson {}: subject {:?}, teacher {:?}",
+    ///         lesson.id,
+    ///         lesson.subject.id(),
+    ///         lesson.teacher.id()
+    ///     );
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn attachment(&mut self, attachment_id: &str, message_id: &str) -> Result<Vec<u8>> {
-        self.ensure_messages_initialized().await?;
-        let url = format!(
-            "https://wiadomosci.librus.pl/api/attachments/{}/messages/{}",
-            attachment_id, message_id
-        );
-        let response = self.http.get(&url).send().await.map_err(Error::Request)?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let body = response.text().await.unwrap_or_default();
-            return Err(Error::ApiError {
-                status: status.as_u16(),
-                body,
-            });
-        }
-
-        let bytes = response.bytes().await.map_err(Error::Request)?;
-        Ok(bytes.to_vec())
+    pub async fn lessons(&self) -> Result<ResponseLessons> {
+        let json = self.get_api("Lessons").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
     }
 
-    /// Decodes base64-encoded message content to a string.
+    /// Gets a subject by ID.
     ///
-    /// Message bodies in Librus are base64-encoded. Use this helper to decode them.
+    /// Subjects contain the name and short code for academic subjects.
     ///
     /// # Arguments
     ///
-    /// * `content` - The base64-encoded content string
+    /// * `id` - The subject ID
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// `Some(String)` if decoding succeeds, `None` if the content is invalid.
+    /// Returns an error if the request fails or the subject is not found.
     ///
     /// # Example
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// use librus_rs::Client;
     ///
-    /// let encoded = "SGVsbG8sIFdvcmxkIQ==";
-    /// let decoded = Client::decode_message_content(encoded);
-    /// assert_eq!(decoded, Some("Hello, World!".to_string()));
-    /// ```
-    pub fn decode_message_content(content: &str) -> Option<String> {
-        use base64::{engine::general_purpose::STANDARD, Engine};
-        STANDARD
-            .decode(content)
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let subject = client.subject(101).await?;
+    /// if let Some(s) = subject.subject {
+    ///     println!("Subject: {} ({})", s.name, s.short);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subject(&self, id: i32) -> Result<ResponseLessonSubject> {
+        let json = self.get_api(&format!("Subjects/{}", id)).await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets all subjects taught in the student's school.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// for subject in client.subjects().await?.subjects {
+    ///     println!("{} ({})", subject.name, subject.short);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subjects(&self) -> Result<ResponseSubjects> {
+        let json = self.get_api("Subjects").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets a school class by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The class ID, e.g. from [`Me::class`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let me = client.me().await?;
+    /// if let Some(id) = me.me.class.id() {
+    ///     if let Some(class) = client.class(id).await?.class {
+    ///         println!("Class: {}", class.name());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn class(&self, id: i64) -> Result<ResponseClass> {
+        let json = self.get_api(&format!("Classes/{}", id)).await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets the school units, with the start and end time of each lesson.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// for unit in client.units().await?.units {
+    ///     if let Some((from, to)) = unit.lesson_hours(1) {
+    ///         println!("Lesson 1: {from}-{to}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn units(&self) -> Result<ResponseUnits> {
+        let json = self.get_api("Units").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets the school year of the student's class, from its first or last
+    /// day in the Classes data.
+    ///
+    /// `None` if the profile names no class or the class has no dates. Pass
+    /// the year to [`ChangeTracker::set_school_year()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching [`Client::me()`] or [`Client::class()`]
+    /// fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{ChangeTracker, Client};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let mut tracker = ChangeTracker::new();
+    /// if let Some(year) = client.school_year().await? {
+    ///     tracker.set_school_year(year);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn school_year(&self) -> Result<Option<SchoolYear>> {
+        let Some(id) = self.me().await?.me.class.id() else {
+            return Ok(None);
+        };
+        Ok(self.class(id).await?.class.and_then(|c| c.school_year()))
+    }
+
+    /// Gets all attendances for the student.
+    ///
+    /// Returns attendance records for all lessons.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let attendances = client.attendances().await?;
+    /// println!("Total records: {}", attendances.attendances.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn attendances(&self) -> Result<ResponseAttendances> {
+        let json = self.get_api("Attendances").await?;
+        let attendances: ResponseAttendances =
+            serde_json::from_str(&json).map_err(|e| Error::Parse {
+                source: e,
+                body: self.error_body(json),
+            })?;
+        Ok(if self.normalize_responses {
+            attendances.normalized()
+        } else {
+            attendances
+        })
+    }
+
+    /// Gets all attendances as the raw response body.
+    ///
+    /// For large accounts, parse it into [`borrowed::ResponseAttendances`] to
+    /// avoid allocating every string. [`ClientBuilder::normalize_responses()`]
+    /// does not apply.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{borrowed, Client};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let raw = client.attendances_raw().await?;
+    /// let attendances: borrowed::ResponseAttendances<'_> = raw.parse()?;
+    /// println!("Total records: {}", attendances.attendances.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn attendances_raw(&self) -> Result<RawResponse> {
+        let body = self.get_api("Attendances").await?;
+        Ok(RawResponse::new(body, self.keep_full_error_bodies))
+    }
+
+    /// Gets all attendance types.
+    ///
+    /// Types describe the kind of attendance (present, absent, late, etc.).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let types = client.attendance_types().await?;
+    /// for t in types.types {
+    ///     println!("{}: {} ({})", t.id, t.name, t.short);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn attendance_types(&self) -> Result<ResponseAttendancesType> {
+        let json = self.get_api("Attendances/Types").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets an attendance type by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The type ID from an [`Attendance`]'s `attendance_type` field
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let response = client.attendance_type(1).await?;
+    /// if let Some(t) = response.attendance_type {
+    ///     println!("{} ({})", t.name, t.short);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn attendance_type(&self, id: i64) -> Result<ResponseAttendanceType> {
+        let json = self.get_api(&format!("Attendances/Types/{}", id)).await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets attendance counts by kind (present, late, excused, unexcused, ...).
+    ///
+    /// Absences recorded during a school trip are counted separately and do
+    /// not increase the unexcused count. See [`AttendanceSummary`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the requests fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let summary = client.attendance_summary().await?;
+    /// println!("Unexcused: {} of {}", summary.unexcused, summary.total);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn attendance_summary(&self) -> Result<AttendanceSummary> {
+        let (attendances, types) = tokio::try_join!(self.attendances(), self.attendance_types())?;
+        Ok(AttendanceSummary::from_records(
+            &attendances.attendances,
+            &types.types,
+        ))
+    }
+
+    /// Gets the lessons whose attendance the teachers filled in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let filled = client.attendances_filled_by_teacher().await?;
+    /// for lesson in &filled.attendances {
+    ///     println!("{} lesson {}", lesson.date, lesson.lesson_no);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn attendances_filled_by_teacher(&self) -> Result<ResponseFilledAttendances> {
+        let json = self.get_api("Attendances/FilledByTeacher").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets the attendance of the days from `from` to `to` (`YYYY-MM-DD`,
+    /// inclusive), laid out by lesson number. See [`AttendanceDayView`].
+    ///
+    /// The lesson numbers are the school's lessons from
+    /// [`Unit::lessons_range`]: from 1, or 0 if the school uses lesson 0, to
+    /// the last lesson with hours, so every week of a school has the same
+    /// slots. If the school set no lesson hours, they run from 1 to the
+    /// highest lesson the teachers filled in within the window. Records with
+    /// other lesson numbers are put in [`AttendanceDayView::outside_range`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDate`] if `from` or `to` is malformed, or an
+    /// error if any of the requests fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let view = client.attendance_calendar("2025-10-06", "2025-10-10").await?;
+    /// println!("{} days", view.days.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn attendance_calendar(&self, from: &str, to: &str) -> Result<AttendanceDayView> {
+        let parse = |d: &str| dates::parse_day(d).ok_or_else(|| Error::InvalidDate(d.to_string()));
+        let (from, to) = (parse(from)?, parse(to)?);
+        let (attendances, filled, units) = tokio::try_join!(
+            self.attendances(),
+            self.attendances_filled_by_teacher(),
+            self.units()
+        )?;
+        let in_range = |date: &types::ApiDate| {
+            dates::parse_day(date.as_str()).is_some_and(|day| (from..=to).contains(&day))
+        };
+        let records: Vec<Attendance> = attendances
+            .attendances
+            .into_iter()
+            .filter(|record| in_range(&record.date))
+            .collect();
+        let filled: Vec<FilledAttendance> = filled
+            .attendances
+            .into_iter()
+            .filter(|lesson| in_range(&lesson.date))
+            .collect();
+
+        let lessons = units
+            .units
+            .first()
+            .and_then(|unit| attendance_calendar::school_lessons(&unit.lessons_range))
+            .unwrap_or_else(|| {
+                let last = filled.iter().map(|lesson| lesson.lesson_no).max();
+                1..=last.unwrap_or(0)
+            });
+        Ok(AttendanceDayView::build(records, &filled, lessons))
+    }
+
+    /// Gets all school trips.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ModuleDisabled`] if the school does not use the trips
+    /// module, or an error if the request or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, Error};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// match client.trips().await {
+    ///     Ok(trips) => {
+    ///         for trip in trips.trips {
+    ///             println!("{} ({} - {})", trip.name, trip.date_from, trip.date_to);
+    ///         }
+    ///     }
+    ///     Err(Error::ModuleDisabled(_)) => println!("No trips module"),
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn trips(&self) -> Result<ResponseTrips> {
+        let json = self.get_api("Trips").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets a school trip by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The trip ID from an [`Attendance`]'s `trip` field
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ModuleDisabled`] if the school does not use the trips
+    /// module, or an error if the request or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, Error};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// match client.trip(12).await {
+    ///     Ok(response) => {
+    ///         if let Some(trip) = response.trip {
+    ///             println!("{} ({} - {})", trip.name, trip.date_from, trip.date_to);
+    ///         }
+    ///     }
+    ///     Err(Error::ModuleDisabled(_)) => println!("No trips module"),
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn trip(&self, id: i64) -> Result<ResponseTrip> {
+        let json = self.get_api(&format!("Trips/{}", id)).await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets the student's notes ("uwagi").
+    ///
+    /// On schools with a behaviour point system notes carry points, see
+    /// [`BehaviourPointsSummary`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// for note in client.notes().await?.notes {
+    ///     let kind = if note.positive { "+" } else { "-" };
+    ///     println!("{} {kind} {}", note.date, note.text);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn notes(&self) -> Result<ResponseNotes> {
+        let json = self.get_api("Notes").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets note categories, with their behaviour points on schools using a
+    /// point system.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// for category in client.note_categories().await?.categories {
+    ///     match category.points {
+    ///         Some(points) => println!("{}: {points} points", category.name),
+    ///         None => println!("{}", category.name),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn note_categories(&self) -> Result<ResponseNoteCategories> {
+        let json = self.get_api("Notes/Categories").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets all homeworks.
+    ///
+    /// Returns a list of all homework assignments.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let homeworks = client.homeworks().await?;
+    /// for hw in homeworks.homeworks {
+    ///     println!("{}: {}", hw.date, hw.content);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn homeworks(&self) -> Result<ResponseHomeworks> {
+        let json = self.get_api("HomeWorks").await?;
+        let homeworks: ResponseHomeworks =
+            serde_json::from_str(&json).map_err(|e| Error::Parse {
+                source: e,
+                body: self.error_body(json),
+            })?;
+        Ok(if self.normalize_responses {
+            homeworks.normalized()
+        } else {
+            homeworks
+        })
+    }
+
+    /// Gets homework categories, e.g. tests and quizzes, with their colors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let categories = client.homework_categories().await?;
+    /// for category in categories.categories {
+    ///     println!("{}: {}", category.id, category.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn homework_categories(&self) -> Result<ResponseHomeworkCategories> {
+        let json = self.get_api("HomeWorks/Categories").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets homework assignments handed in through Librus.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let assignments = client.home_work_assignments().await?;
+    /// for a in assignments.home_work_assignments {
+    ///     let status = if a.marked_as_done { "done" } else { "todo" };
+    ///     println!("{} (due {}): {}", a.topic, a.due_date, status);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn home_work_assignments(&self) -> Result<ResponseHomeWorkAssignments> {
+        let json = self.get_api("HomeWorkAssignments").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Marks a homework assignment as done.
+    ///
+    /// # Arguments
+    ///
+    /// * `assignment_id` - The ID of a [`HomeWorkAssignment`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DeadlinePassed`] if the assignment is already overdue,
+    /// or another error if the request fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, Error};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// match client.mark_assignment_done(5).await {
+    ///     Err(Error::DeadlinePassed) => println!("Too late"),
+    ///     other => other?,
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn mark_assignment_done(&self, assignment_id: i64) -> Result<()> {
+        self.set_assignment_done(assignment_id, "MarkAsDone").await
+    }
+
+    /// Reverts marking a homework assignment as done.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DeadlinePassed`] if the assignment is already overdue,
+    /// or another error if the request fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, Error};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// match client.mark_assignment_undone(5).await {
+    ///     Err(Error::DeadlinePassed) => println!("Too late"),
+    ///     other => other?,
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn mark_assignment_undone(&self, assignment_id: i64) -> Result<()> {
+        self.set_assignment_done(assignment_id, "MarkAsUndone")
+            .await
+    }
+
+    async fn set_assignment_done(&self, assignment_id: i64, action: &str) -> Result<()> {
+        let endpoint = format!("HomeWorkAssignments/{}", action);
+        let body = serde_json::json!({ "homework": assignment_id });
+        match self.post_api(&endpoint, &body).await {
+            Err(Error::ApiError { body, .. })
+                if gateway_error_code(&body).as_deref() == Some(DEADLINE_PASSED_CODE) =>
+            {
+                Err(Error::DeadlinePassed)
+            }
+            other => other.map(drop),
+        }
+    }
+
+    /// Gets school-wide free days.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let free_days = client.school_free_days().await?;
+    /// for day in free_days.school_free_days {
+    ///     println!("{} - {}: {}", day.date_from, day.date_to, day.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn school_free_days(&self) -> Result<ResponseSchoolFreeDays> {
+        let json = self.get_api("SchoolFreeDays").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets free days of the student's class.
+    ///
+    /// Unlike [`Client::school_free_days()`], these apply only to the
+    /// student's class (e.g. class trips or exams of other grades).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let free_days = client.class_free_days().await?;
+    /// for day in free_days.class_free_days {
+    ///     println!("{} - {}: {:?}", day.date_from, day.date_to, day.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn class_free_days(&self) -> Result<ResponseClassFreeDays> {
+        let json = self.get_api("ClassFreeDays").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets teacher absences.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let absences = client.teacher_free_days().await?;
+    /// for absence in absences.teacher_free_days {
+    ///     println!("{} - {}: {:?}", absence.date_from, absence.date_to, absence.teacher.id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn teacher_free_days(&self) -> Result<ResponseTeacherFreeDays> {
+        let json = self.get_api("TeacherFreeDays").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets parent-teacher conferences.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let conferences = client.parent_teacher_conferences().await?;
+    /// for conference in conferences.parent_teacher_conferences {
+    ///     println!("{} {:?}: {}", conference.date, conference.time, conference.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn parent_teacher_conferences(&self) -> Result<ResponseParentTeacherConferences> {
+        let json = self.get_api("ParentTeacherConferences").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets lesson substitutions and cancellations.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let substitutions = client.substitutions().await?;
+    /// for substitution in substitutions.substitutions.iter().filter(|s| s.is_cancelled) {
+    ///     println!("Cancelled: {} lesson {:?}", substitution.org_date, substitution.org_lesson_no);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn substitutions(&self) -> Result<ResponseSubstitutions> {
+        let json = self.get_api("Calendars/Substitutions").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets the school's lucky number.
+    ///
+    /// Check [`LuckyNumber::lucky_number_day`]: outside school days the
+    /// endpoint returns the number of the next or previous school day.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let lucky = client.lucky_number().await?.lucky_number;
+    /// if let Some(number) = lucky.lucky_number {
+    ///     println!("{}: {}", lucky.lucky_number_day, number);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn lucky_number(&self) -> Result<ResponseLuckyNumber> {
+        let json = self.get_api("LuckyNumbers").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets a merged, chronologically sorted family calendar.
+    ///
+    /// Combines homework deadlines, school and class free days, teacher
+    /// absences, parent-teacher conferences and substitutions into one list of
+    /// [`FamilyEvent`]s overlapping the `from..=to` range. Entries reported by
+    /// more than one endpoint are only returned once.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - First day of the range (`YYYY-MM-DD`)
+    /// * `to` - Last day of the range, inclusive (`YYYY-MM-DD`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the underlying requests fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// for event in client.family_calendar("2025-03-01", "2025-03-31").await? {
+    ///     println!("{} {:?}: {}", event.date, event.kind, event.title);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn family_calendar(&self, from: &str, to: &str) -> Result<Vec<FamilyEvent>> {
+        let (homeworks, free_days, class_free_days, absences, conferences, substitutions) = tokio::try_join!(
+            self.homeworks(),
+            self.school_free_days(),
+            self.class_free_days(),
+            self.teacher_free_days(),
+            self.parent_teacher_conferences(),
+            self.substitutions(),
+        )?;
+
+        let events = homeworks
+            .homeworks
+            .iter()
+            .map(FamilyEvent::from)
+            .chain(free_days.school_free_days.iter().map(FamilyEvent::from))
+            .chain(
+                class_free_days
+                    .class_free_days
+                    .iter()
+                    .map(FamilyEvent::from),
+            )
+            .chain(absences.teacher_free_days.iter().map(FamilyEvent::from))
+            .chain(
+                conferences
+                    .parent_teacher_conferences
+                    .iter()
+                    .map(FamilyEvent::from),
+            )
+            .chain(substitutions.substitutions.iter().map(FamilyEvent::from));
+        Ok(family_calendar::merge(events, from, to))
+    }
+
+    /// Builds a [`SchoolCalendar`] for the `from..=to` range.
+    ///
+    /// Combines weekends, Polish public holidays, school free days and free
+    /// days of the student's class, so callers can ask for the next school day
+    /// or count school days.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - First day of the range (`YYYY-MM-DD`)
+    /// * `to` - Last day of the range, inclusive (`YYYY-MM-DD`)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDate`] if `from` or `to` is malformed, or an
+    /// error if the request fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let calendar = client.school_calendar("2025-09-01", "2026-06-26").await?;
+    /// if let Some(day) = calendar.next_school_day("2025-12-19") {
+    ///     println!("Back to school on {}", day);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn school_calendar(&self, from: &str, to: &str) -> Result<SchoolCalendar> {
+        let (school, class) = tokio::try_join!(self.school_free_days(), self.class_free_days())?;
+        let school = school
+            .school_free_days
+            .iter()
+            .map(|d| (d.date_from.as_str(), d.date_to.as_str()));
+        let class = class
+            .class_free_days
+            .iter()
+            .map(|d| (d.date_from.as_str(), d.date_to.as_str()));
+        SchoolCalendar::new(from, to, school.chain(class))
+    }
+
+    /// Gets the timetable of one week.
+    ///
+    /// # Arguments
+    ///
+    /// * `week_start` - Monday of the week (`YYYY-MM-DD`)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDate`] if `week_start` is malformed, or an
+    /// error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let week = client.timetable("2025-09-01").await?;
+    /// println!("Next week: {}", week.pages.next);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn timetable(&self, week_start: &str) -> Result<ResponseTimetable> {
+        dates::parse_day(week_start).ok_or_else(|| Error::InvalidDate(week_start.to_string()))?;
+        let json = self
+            .get_api(&format!("Timetables?weekStart={}", week_start))
+            .await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets the lesson in progress and the next lesson, at the server's time.
+    ///
+    /// Cancelled lessons are skipped. After the last lesson of the week the
+    /// following week is fetched to find the next one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`Client::server_time()`] or a timetable request
+    /// fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let lessons = client.now_and_next().await?;
+    /// if let Some(next) = lessons.next.and_then(|slot| slot.subject) {
+    ///     println!("Next: {} on {:?}", next.name, lessons.next_date);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn now_and_next(&self) -> Result<NowAndNext> {
+        let server = unix_seconds(self.server_time().await?);
+        let now = types::ApiDateTime::try_from(dates::format_warsaw_datetime(server))?;
+        let today = dates::warsaw_day(server);
+        let monday = today - i64::from(dates::weekday(today));
+
+        let week = self.timetable(&dates::format_day(monday)).await?;
+        let current = week.lesson_at(&now).cloned();
+        let mut next = week
+            .next_lesson_after(&now)
+            .map(|(date, slot)| (date.clone(), slot.clone()));
+        if next.is_none() {
+            let following = self.timetable(&dates::format_day(monday + 7)).await?;
+            next = following
+                .next_lesson_after(&now)
+                .map(|(date, slot)| (date.clone(), slot.clone()));
+        }
+        let (next_date, next) = next.unzip();
+        Ok(NowAndNext {
+            current,
+            next,
+            next_date,
+        })
+    }
+
+    /// Gets school notices (announcements).
+    ///
+    /// Returns a list of school notices.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let notices = client.school_notices().await?;
+    /// for notice in notices.school_notices {
+    ///     println!("{}: {}", notice.creation_date, notice.subject);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn school_notices(&self) -> Result<ResponseSchoolNotices> {
+        let json = self.get_api("SchoolNotices").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets a single school notice by ID.
+    ///
+    /// Useful for notice IDs referenced by notifications, without fetching
+    /// the whole list.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The notice ID
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    /// A notice that does not exist is returned as `Ok(None)`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// if let Some(notice) = client.school_notice("a1b2c3").await? {
+    ///     println!("{}: {}", notice.creation_date, notice.subject);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn school_notice(&self, id: &str) -> Result<Option<SchoolNotice>> {
+        let endpoint = format!("SchoolNotices/{}", urls::encode_segment(id));
+        let json = match self.get_api(&endpoint).await {
+            Err(Error::ApiError {
+                status: 404, body, ..
+            }) if gateway_error_code(&body).as_deref() == Some(NOT_FOUND_CODE) => {
+                return Ok(None);
+            }
+            other => other?,
+        };
+        let resp: ResponseSchoolNotice = serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })?;
+        Ok(resp.school_notice)
+    }
+
+    /// Gets school notices with inline images stripped from their content.
+    ///
+    /// Some notices embed pictures as base64 `data:` URIs, which can make the
+    /// content megabytes long. Each such image is replaced with an `[image]`
+    /// placeholder and the number of removed bytes is reported per notice.
+    /// The images are dropped while the response is parsed, so the full
+    /// content is never copied out of the response body. Fields outside the
+    /// model are not collected into `extra`.
+    /// Combine with [`ClientBuilder::max_response_bytes()`] to also bound the
+    /// size of the response itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// for light in client.school_notices_light().await? {
+    ///     let text = Client::notice_content_to_text(&light.notice.content);
+    ///     println!("{} ({} bytes of images removed)", text, light.stripped_bytes);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn school_notices_light(&self) -> Result<Vec<LightNotice>> {
+        let json = self.get_api("SchoolNotices").await?;
+        let resp: ResponseLightNotices = serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })?;
+        Ok(resp.into_notices())
+    }
+
+    /// Gets school notices (announcements) with pagination.
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - Page number (1-indexed)
+    /// * `limit` - Number of notices per page
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    pub async fn school_notices_page(
+        &self,
+        page: u32,
+        limit: u32,
+    ) -> Result<ResponseSchoolNotices> {
+        let endpoint = format!("SchoolNotices?page={}&limit={}", page, limit);
+        let json = self.get_api(&endpoint).await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets the latest school notices (announcements).
+    ///
+    /// This paginates through all notices, sorts them by `creation_date` (descending),
+    /// and returns the newest `limit` items.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    pub async fn school_notices_latest(&self, limit: usize) -> Result<Vec<SchoolNotice>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let page_size: u32 = 50;
+        let mut page = 1;
+        let mut all = Vec::new();
+
+        loop {
+            let resp = self.school_notices_page(page, page_size).await?;
+            if resp.school_notices.is_empty() {
+                break;
+            }
+
+            let count = resp.school_notices.len();
+            all.extend(resp.school_notices);
+
+            if count < page_size as usize {
+                break;
+            }
+
+            page += 1;
+        }
+
+        all.sort_by(|a, b| b.creation_date.cmp(&a.creation_date));
+        all.truncate(limit);
+        Ok(all)
+    }
+
+    /// Downloads a file attached to a school notice or homework.
+    ///
+    /// Uses the attachment's URL when the API reports one and falls back to
+    /// the gateway `Attachments/{id}` endpoint otherwise. Unlike
+    /// [`Client::attachment()`], this goes through the Synergia gateway and
+    /// does not need the messages session.
+    ///
+    /// # Arguments
+    ///
+    /// * `attachment` - An attachment from [`SchoolNotice::attachments`] or
+    ///   [`Homework::attachments`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the file is not found.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    /// use std::fs;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// for notice in client.school_notices().await?.school_notices {
+    ///     for file in notice.attachments.iter().flatten() {
+    ///         let bytes = client.notice_attachment(file).await?;
+    ///         fs::write(&file.name, bytes).unwrap();
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn notice_attachment(&self, attachment: &NoticeAttachment) -> Result<Vec<u8>> {
+        let url = match attachment.url.as_deref() {
+            Some(url) if url.starts_with("http") => url.to_string(),
+            Some(path) => urls::join(&self.endpoints.api, path),
+            None => urls::join(
+                &self.endpoints.api,
+                &format!("Attachments/{}", urls::encode_segment(&attachment.id)),
+            ),
+        };
+        self.download(&url, ApiKind::Synergia).await?.bytes().await
+    }
+
+    /// Gets a user by ID.
+    ///
+    /// Users include teachers, students, and parents.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The user ID
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the user is not found.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let user = client.user(12345).await?;
+    /// if let Some(u) = user.user {
+    ///     println!("{} {}", u.first_name, u.last_name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn user(&self, id: i32) -> Result<ResponseUser> {
+        let json = self.get_api(&format!("Users/{}", id)).await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets all users visible to the account: teachers, classmates and parents.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let users = client.users().await?;
+    /// for teacher in users.users.iter().filter(|u| u.is_employee) {
+    ///     println!("{} {}", teacher.first_name, teacher.last_name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn users(&self) -> Result<ResponseUsers> {
+        let json = self.get_api("Users").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Lists the teachers of each subject.
+    ///
+    /// Joins [`Client::lessons()`], [`Client::subjects()`] and
+    /// [`Client::users()`], which are fetched concurrently. Subjects are kept
+    /// in the order returned by the API; subjects the student has no lessons
+    /// of are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the three requests fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// for entry in client.subject_teachers().await? {
+    ///     let names: Vec<_> = entry
+    ///         .teachers
+    ///         .iter()
+    ///         .map(|t| format!("{} {}", t.first_name, t.last_name))
+    ///         .collect();
+    ///     println!("{}: {}", entry.subject.name, names.join(", "));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subject_teachers(&self) -> Result<Vec<SubjectTeacher>> {
+        let (lessons, subjects, users) =
+            tokio::join!(self.lessons(), self.subjects(), self.users());
+        Ok(SubjectTeacher::join(
+            &lessons?.lessons,
+            &subjects?.subjects,
+            &users?.into(),
+        ))
+    }
+
+    /// Gets the weekly office hours of the student's teachers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ModuleDisabled`] if the school does not publish
+    /// office hours, or an error if the request or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// for hours in client.teacher_office_hours().await? {
+    ///     println!(
+    ///         "day {}: {}-{} in {}",
+    ///         hours.weekday,
+    ///         hours.time_from,
+    ///         hours.time_to,
+    ///         hours.room.as_deref().unwrap_or("?")
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn teacher_office_hours(&self) -> Result<Vec<OfficeHours>> {
+        let json = self.get_api("TeacherOfficeHours").await?;
+        serde_json::from_str::<ResponseOfficeHours>(&json)
+            .map(|r| r.teacher_office_hours)
+            .map_err(|e| Error::Parse {
+                source: e,
+                body: self.error_body(json),
+            })
+    }
+
+    /// Gets the teachers' office hours together with the teachers.
+    ///
+    /// Joins [`Client::teacher_office_hours()`] and [`Client::users()`],
+    /// which are fetched concurrently. Entries are sorted by teacher last
+    /// name, then day and time; hours of teachers missing from the user list
+    /// come last.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ModuleDisabled`] if the school does not publish
+    /// office hours, or an error if either request fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// for entry in client.teacher_office_hours_with_names().await? {
+    ///     let name = entry.teacher_name().unwrap_or_else(|| "?".to_string());
+    ///     println!("{name}: {}-{}", entry.hours.time_from, entry.hours.time_to);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn teacher_office_hours_with_names(&self) -> Result<Vec<TeacherOfficeHours>> {
+        let (hours, users) = tokio::join!(self.teacher_office_hours(), self.users());
+        Ok(TeacherOfficeHours::join(hours?, &users?.into()))
+    }
+
+    /// Gets all display colors, referenced by e.g. grade categories.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let colors = client.colors().await?;
+    /// for color in colors.colors {
+    ///     println!("{}: #{}", color.name, color.rgb);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn colors(&self) -> Result<ResponseColors> {
+        let json = self.get_api("Colors").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Gets a display color by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let response = client.color(1).await?;
+    /// if let Some(color) = response.color {
+    ///     println!("{}: #{}", color.name, color.rgb);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn color(&self, id: i64) -> Result<ResponseColor> {
+        let json = self.get_api(&format!("Colors/{}", id)).await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Fetches attendance types, colors, subjects, grade categories and users
+    /// concurrently and indexes them by ID.
+    ///
+    /// Build the indexes once and pass them to helpers such as
+    /// [`AttendanceSummary::from_index()`] or [`TeacherOfficeHours::join()`]
+    /// instead of fetching the lists for every call.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of any of the requests.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{AttendanceSummary, Client};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let indexes = client.reference_indexes().await?;
+    /// let attendances = client.attendances().await?.attendances;
+    /// let summary = AttendanceSummary::from_index(&attendances, &indexes.attendance_types);
+    /// println!("Unexcused: {}", summary.unexcused);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reference_indexes(&self) -> Result<ReferenceIndexes> {
+        let (attendance_types, colors, subjects, categories, users) = tokio::try_join!(
+            self.attendance_types(),
+            self.colors(),
+            self.subjects(),
+            self.grade_categories(),
+            self.users(),
+        )?;
+        Ok(ReferenceIndexes {
+            attendance_types: attendance_types.into(),
+            colors: colors.into(),
+            subjects: subjects.into(),
+            categories: categories.into(),
+            users: users.into(),
+        })
+    }
+
+    /// Gets current user details.
+    ///
+    /// Returns detailed information about the authenticated user.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    pub async fn current_user(&self) -> Result<ResponseUser> {
+        let json = self.get_api("Users").await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Builds a one-screen overview of the student.
+    ///
+    /// Combines the student's name and class, current average per subject,
+    /// unexcused absences, unread messages and the next homework. The
+    /// sections are fetched concurrently; a section that fails is left as
+    /// `None` and the reason is recorded in [`StudentCard::errors`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only if the student profile ([`Client::me()`]) cannot
+    /// be fetched.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let card = client.student_card().await?;
+    /// println!("{} ({})", card.name, card.class.as_deref().unwrap_or("?"));
+    /// for avg in card.averages.iter().flatten() {
+    ///     println!("  {}: {:.2}", avg.subject_name.as_deref().unwrap_or("?"), avg.average);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn student_card(&mut self) -> Result<StudentCard> {
+        // The messages API needs `&mut self`, so it runs before the rest.
+        let unread = self.unread_counts().await;
+
+        let (me, grades, subjects, attendance, homeworks) = tokio::join!(
+            self.me(),
+            self.grades(),
+            self.subjects(),
+            self.attendance_summary(),
+            self.homeworks(),
+        );
+        let me = me?.me;
+        let class = match me.class.id() {
+            Some(id) => self.class(id).await.map(|c| c.class),
+            None => Ok(None),
+        };
+
+        let mut card = StudentCard {
+            name: format!("{} {}", me.user.first_name, me.user.last_name),
+            ..StudentCard::default()
+        };
+        card.class = card
+            .section(CardSection::Class, class)
+            .flatten()
+            .map(|c| c.name());
+        card.unread_messages = card.section(CardSection::Messages, unread).map(|c| c.inbox);
+        card.unexcused_absences = card
+            .section(CardSection::Attendance, attendance)
+            .map(|s| s.unexcused);
+        let subjects = card
+            .section(CardSection::Averages, subjects)
+            .map(|s| s.subjects)
+            .unwrap_or_default();
+        card.averages = card
+            .section(CardSection::Averages, grades)
+            .map(|g| student_card::subject_averages(&g.grades, &subjects));
+        let today = dates::format_day(dates::warsaw_day(unix_seconds(SystemTime::now())));
+        card.next_homework = card
+            .section(CardSection::Homework, homeworks)
+            .and_then(|h| student_card::next_homework(h.homeworks, &today));
+        Ok(card)
+    }
+
+    /// Gets the current time according to the Librus server.
+    ///
+    /// Reads the `SystemData` endpoint, which reports the server's Polish local
+    /// time. If that endpoint is unavailable, falls back to the `Date` header
+    /// of its response. Both have a resolution of one second.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or neither the body nor the
+    /// `Date` header contain a usable time ([`Error::ApiError`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let now = client.server_time().await?;
+    /// println!("Server time: {:?}", now);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn server_time(&self) -> Result<SystemTime> {
+        let url = urls::join(&self.endpoints.api, "SystemData");
+        let response = self.http.send(self.http.get(&url), Cost::Request).await?;
+
+        let header_time = response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(dates::parse_http_date);
+        let status = response.status();
+        let text = self.read_text(response).await?;
+        let body_time = serde_json::from_str::<ResponseSystemData>(&text)
             .ok()
-            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .filter(|_| status.is_success())
+            .and_then(|data| dates::parse_warsaw_datetime(&data.date, &data.time));
+
+        body_time
+            .or(header_time)
+            .and_then(|secs| u64::try_from(secs).ok())
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+            .ok_or(Error::ApiError {
+                api: ApiKind::Synergia,
+                status: status.as_u16(),
+                body: text,
+                source: None,
+            })
+    }
+
+    /// Gets the offset of the server clock from the local clock, in seconds.
+    ///
+    /// Positive values mean the server is ahead of the local machine. Add the
+    /// skew to local timestamps before comparing them with server-side
+    /// timestamps such as `AddDate`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`Client::server_time()`] fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let skew = client.clock_skew().await?;
+    /// if skew.abs() > 60 {
+    ///     eprintln!("Local clock is off by {} seconds", skew);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn clock_skew(&self) -> Result<i64> {
+        let server = unix_seconds(self.server_time().await?);
+        Ok(server - unix_seconds(SystemTime::now()))
+    }
+
+    /// Checks whether the Messages service is reachable.
+    ///
+    /// Opens the messages session, which is cheap and needed by every
+    /// Messages API call anyway. Use it to show the messages part of a
+    /// dashboard as unavailable while Synergia keeps working; failed calls
+    /// can also be told apart with [`Error::api()`]. Returns `false` without
+    /// a request once messaging is known to be disabled for the account.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// if client.messages_available().await {
+    ///     println!("Unread: {}", client.unread_counts().await?.inbox);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn messages_available(&mut self) -> bool {
+        self.messages_availability != MessagesAvailability::Disabled
+            && self.open_messages().await.is_ok()
+    }
+
+    /// Whether the account can use the Messages API, as far as the client
+    /// knows.
+    ///
+    /// Becomes known with the first Messages call or
+    /// [`Client::messages_available()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, MessagesAvailability};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// if client.messages_availability() == MessagesAvailability::Disabled {
+    ///     println!("The school turned messaging off for this account");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn messages_availability(&self) -> MessagesAvailability {
+        self.messages_availability
+    }
+
+    /// Checks again whether the account can use the Messages API, e.g. after
+    /// the school enabled messaging.
+    ///
+    /// Clears a remembered [`MessagesAvailability::Disabled`] and opens the
+    /// messages session.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, MessagesAvailability};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// if client.recheck_messages_availability().await == MessagesAvailability::Available {
+    ///     println!("Unread: {}", client.unread_counts().await?.inbox);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn recheck_messages_availability(&mut self) -> MessagesAvailability {
+        self.messages_availability = MessagesAvailability::Unknown;
+        // The outcome is recorded in `messages_availability`.
+        let _ = self.open_messages().await;
+        self.messages_availability
+    }
+
+    /// Gets the account as seen by the Messages API: its user ID there,
+    /// name and roles.
+    ///
+    /// The result is cached on the client after the first successful call,
+    /// until another account or school unit is selected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let me = client.messages_me().await?;
+    /// println!("{} ({}), roles: {:?}", me.name, me.user_id, me.roles);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn messages_me(&mut self) -> Result<MessagesUser> {
+        if let Some(me) = &self.messages_me {
+            return Ok(me.clone());
+        }
+        self.ensure_messages_initialized().await?;
+        let json = self.get_messages_api("me").await?;
+        let resp: ResponseMessagesUser = serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })?;
+        self.messages_me = Some(resp.data.clone());
+        Ok(resp.data)
+    }
+
+    /// Gets unread message counts for all folders.
+    ///
+    /// Returns counts for inbox, notes, alerts, and other message categories.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let counts = client.unread_counts().await?;
+    /// println!("Unread inbox: {}", counts.inbox);
+    /// println!("Unread alerts: {}", counts.alerts);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn unread_counts(&mut self) -> Result<UnreadCounts> {
+        self.ensure_messages_initialized().await?;
+        let json = self.get_messages_api("inbox/unreadMessagesCount").await?;
+        let resp: ResponseUnreadCounts = serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })?;
+        Ok(resp.data)
+    }
+
+    /// Gets inbox messages (received).
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - Page number (1-indexed)
+    /// * `limit` - Number of messages per page
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let messages = client.inbox_messages(1, 10).await?;
+    /// for msg in messages {
+    ///     println!("{}: {}", msg.sender_name, msg.topic);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn inbox_messages(&mut self, page: u32, limit: u32) -> Result<Vec<InboxMessage>> {
+        self.ensure_messages_initialized().await?;
+        let endpoint = format!("inbox/messages?page={}&limit={}", page, limit);
+        let json = self.get_messages_api(&endpoint).await?;
+        let resp: ResponseInboxMessages =
+            serde_json::from_str(&json).map_err(|e| Error::Parse {
+                source: e,
+                body: self.error_body(json),
+            })?;
+        Ok(resp.data)
+    }
+
+    /// Gets archived messages, kept in the archive folder of the Messages
+    /// Plus add-on.
+    ///
+    /// The add-on is checked with [`Client::capabilities()`] before the
+    /// folder is requested.
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - Page number (1-indexed)
+    /// * `limit` - Number of messages per page
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PremiumRequired`] if the account lacks
+    /// [`Addon::MessagesPlus`], or an error if a request fails or response
+    /// parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, Error};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// match client.archived_messages(1, 10).await {
+    ///     Ok(messages) => println!("{} archived messages", messages.len()),
+    ///     Err(Error::PremiumRequired(addon)) => println!("Needs the {addon} add-on"),
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn archived_messages(&mut self, page: u32, limit: u32) -> Result<Vec<InboxMessage>> {
+        self.capabilities().await?.require(&Addon::MessagesPlus)?;
+        self.ensure_messages_initialized().await?;
+        let endpoint = format!("archive/messages?page={}&limit={}", page, limit);
+        let json = self.get_messages_api(&endpoint).await?;
+        let resp: ResponseInboxMessages =
+            serde_json::from_str(&json).map_err(|e| Error::Parse {
+                source: e,
+                body: self.error_body(json),
+            })?;
+        Ok(resp.data)
+    }
+
+    /// Gets outbox messages (sent).
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - Page number (1-indexed)
+    /// * `limit` - Number of messages per page
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let messages = client.outbox_messages(1, 10).await?;
+    /// for msg in messages {
+    ///     println!("To {}: {}", msg.receiver_name, msg.topic);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn outbox_messages(&mut self, page: u32, limit: u32) -> Result<Vec<OutboxMessage>> {
+        self.ensure_messages_initialized().await?;
+        let endpoint = format!("outbox/messages?page={}&limit={}", page, limit);
+        let json = self.get_messages_api(&endpoint).await?;
+        let resp: ResponseOutboxMessages =
+            serde_json::from_str(&json).map_err(|e| Error::Parse {
+                source: e,
+                body: self.error_body(json),
+            })?;
+        Ok(resp.data)
+    }
+
+    /// Gets full message details by ID.
+    ///
+    /// Returns the complete message including body content and attachments.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_id` - The message ID from an [`InboxMessage`] or [`OutboxMessage`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the message is not found.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let detail = client.message("12345").await?;
+    /// if let Some(content) = Client::decode_message_content(&detail.message) {
+    ///     println!("Content: {}", content);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn message(&mut self, message_id: &str) -> Result<MessageDetail> {
+        self.ensure_messages_initialized().await?;
+        let endpoint = format!("inbox/messages/{}", urls::encode_segment(message_id));
+        let json = self.get_messages_api(&endpoint).await?;
+        let resp: ResponseMessageDetail =
+            serde_json::from_str(&json).map_err(|e| Error::Parse {
+                source: e,
+                body: self.error_body(json),
+            })?;
+        Ok(resp.data)
+    }
+
+    /// Gets full details of a sent message by ID.
+    ///
+    /// Unlike [`Client::message()`], the result lists every receiver in
+    /// [`MessageDetail::receivers`] together with their read status.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_id` - The message ID from an [`OutboxMessage`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the message is not found.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let detail = client.outbox_message("12345").await?;
+    /// println!("Topic: {}", detail.topic);
+    /// for receiver in &detail.receivers {
+    ///     println!("{}: read {}", receiver.name, receiver.is_read());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn outbox_message(&mut self, message_id: &str) -> Result<MessageDetail> {
+        self.ensure_messages_initialized().await?;
+        let endpoint = format!("outbox/messages/{}", urls::encode_segment(message_id));
+        let json = self.get_messages_api(&endpoint).await?;
+        let resp: ResponseMessageDetail =
+            serde_json::from_str(&json).map_err(|e| Error::Parse {
+                source: e,
+                body: self.error_body(json),
+            })?;
+        Ok(resp.data)
+    }
+
+    /// Gets the receivers of a sent message and whether each has read it.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_id` - The message ID from an [`OutboxMessage`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the message is not found.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let receipts = client.message_read_receipts("12345").await?;
+    /// let read = receipts.iter().filter(|r| r.is_read()).count();
+    /// println!("Read by {} of {}", read, receipts.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn message_read_receipts(
+        &mut self,
+        message_id: &str,
+    ) -> Result<Vec<ReceiverReadStatus>> {
+        Ok(self.outbox_message(message_id).await?.receivers)
+    }
+
+    /// Gets absence justification requests from the Messages API folder of
+    /// the same name, with their [`JustificationStatus`].
+    ///
+    /// Join them with [`Client::attendances()`] using
+    /// [`JustificationRequest::attendances()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - Page number (1-indexed)
+    /// * `limit` - Number of requests per page
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, JustificationStatus};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// for request in client.justification_requests(1, 20).await? {
+    ///     if request.status == JustificationStatus::Pending {
+    ///         println!("Waiting: {}", request.topic);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn justification_requests(
+        &mut self,
+        page: u32,
+        limit: u32,
+    ) -> Result<Vec<JustificationRequest>> {
+        self.ensure_messages_initialized().await?;
+        let endpoint = format!("justifications/messages?page={}&limit={}", page, limit);
+        let json = self.get_messages_api(&endpoint).await?;
+        let resp: ResponseJustificationRequests =
+            serde_json::from_str(&json).map_err(|e| Error::Parse {
+                source: e,
+                body: self.error_body(json),
+            })?;
+        Ok(resp.data)
+    }
+
+    /// Gets full details of an absence justification request by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_id` - The ID from [`JustificationRequest::message_id`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the request is not found.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let requests = client.justification_requests(1, 20).await?;
+    /// if let Some(first) = requests.first() {
+    ///     let detail = client.justification_request(&first.message_id).await?;
+    ///     println!("{} ({:?}): {}", detail.topic, detail.status, detail.content);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn justification_request(
+        &mut self,
+        message_id: &str,
+    ) -> Result<JustificationRequestDetail> {
+        self.ensure_messages_initialized().await?;
+        let endpoint = format!(
+            "justifications/messages/{}",
+            urls::encode_segment(message_id)
+        );
+        let json = self.get_messages_api(&endpoint).await?;
+        let resp: ResponseJustificationRequestDetail =
+            serde_json::from_str(&json).map_err(|e| Error::Parse {
+                source: e,
+                body: self.error_body(json),
+            })?;
+        Ok(resp.data)
+    }
+
+    /// Gets the notice board of the Messages module.
+    ///
+    /// These entries are separate from [`Client::school_notices()`]. Some of
+    /// them must be confirmed as read, see [`BoardEntry::needs_confirmation()`].
+    /// Schools without the notice board return an empty list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// for entry in client.message_board().await? {
+    ///     if entry.needs_confirmation() {
+    ///         println!("To confirm: {}", entry.topic);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn message_board(&mut self) -> Result<Vec<BoardEntry>> {
+        self.ensure_messages_initialized().await?;
+        let json = match self.get_messages_api("notices").await {
+            // Instances without the module do not have the endpoint.
+            Err(Error::ApiError { status: 404, .. }) => return Ok(Vec::new()),
+            other => other?,
+        };
+        let resp: ResponseBoardEntries = serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })?;
+        Ok(resp.data)
+    }
+
+    /// Confirms having read a notice board entry ("potwierdzam zapoznanie się").
+    ///
+    /// # Arguments
+    ///
+    /// * `notice_id` - The ID from [`BoardEntry::notice_id`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the entry is not found.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// for entry in client.message_board().await? {
+    ///     if entry.needs_confirmation() {
+    ///         client.confirm_board_entry(&entry.notice_id).await?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn confirm_board_entry(&mut self, notice_id: &str) -> Result<()> {
+        self.ensure_messages_initialized().await?;
+        let endpoint = format!("notices/{}/confirm", urls::encode_segment(notice_id));
+        self.post_messages_api(&endpoint, &serde_json::json!({}))
+            .await?;
+        Ok(())
+    }
+
+    /// Confirms having read a message whose sender asked for it, as with the
+    /// banner in the web interface. This is separate from the read state.
+    ///
+    /// The message is fetched first to check
+    /// [`MessageDetail::requires_read_confirmation`] and
+    /// [`MessageDetail::read_confirmation_date`].
+    ///
+    /// # Arguments
+    ///
+    /// * `message_id` - The message ID from an [`InboxMessage`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ReadConfirmationNotRequired`] if the message does not
+    /// ask for a confirmation, [`Error::ReadAlreadyConfirmed`] if it was
+    /// confirmed before, or another error if a request fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, Error};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// match client.confirm_message_read("12345").await {
+    ///     Ok(()) | Err(Error::ReadAlreadyConfirmed) => println!("confirmed"),
+    ///     Err(Error::ReadConfirmationNotRequired) => {}
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn confirm_message_read(&mut self, message_id: &str) -> Result<()> {
+        let detail = self.message(message_id).await?;
+        if detail.requires_read_confirmation != Some(true) {
+            return Err(Error::ReadConfirmationNotRequired);
+        }
+        if detail.is_read_confirmed() {
+            return Err(Error::ReadAlreadyConfirmed);
+        }
+        let endpoint = format!(
+            "inbox/messages/{}/confirm",
+            urls::encode_segment(message_id)
+        );
+        self.post_messages_api(&endpoint, &serde_json::json!({}))
+            .await?;
+        Ok(())
+    }
+
+    /// Saves a message as a draft.
+    ///
+    /// The topic and body are checked with [`MessageDraftValidator`] first.
+    /// With [`ValidationMode::AutoFix`] they are sanitized before the check.
+    ///
+    /// # Arguments
+    ///
+    /// * `receivers` - Messages API user IDs of the receivers
+    /// * `topic` - Message subject
+    /// * `body` - Message content, as plain text
+    /// * `mode` - Whether to reject or fix a topic or body the API would refuse
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidMessage`] if the topic or body fails the check,
+    /// or an error if the request fails or the response has no draft ID.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, ValidationMode};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let draft = client
+    ///     .save_draft(&["1234"], "Nieobecność", "Syn będzie jutro nieobecny.", ValidationMode::AutoFix)
+    ///     .await?;
+    /// client.send_draft(draft.as_str()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn save_draft(
+        &mut self,
+        receivers: &[&str],
+        topic: &str,
+        body: &str,
+        mode: ValidationMode,
+    ) -> Result<DraftId> {
+        let validator = MessageDraftValidator::new();
+        let (topic, body) = match mode {
+            ValidationMode::Strict => (topic.to_string(), body.to_string()),
+            ValidationMode::AutoFix => (validator.sanitize_topic(topic), validator.sanitize(body)),
+        };
+        let issues = validator.validate(&topic, &body);
+        if !issues.is_empty() {
+            return Err(Error::InvalidMessage(issues));
+        }
+
+        self.ensure_messages_initialized().await?;
+        let payload = serde_json::json!({
+            "receivers": receivers,
+            "topic": topic,
+            "content": body,
+        });
+        let json = self.post_messages_api("drafts", &payload).await?;
+        let resp: ResponseSavedDraft = serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })?;
+        Ok(resp.data.draft_id)
+    }
+
+    /// Gets saved drafts with pagination.
+    ///
+    /// Drafts have the shape of outbox messages with an empty
+    /// [`OutboxMessage::send_date`].
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - Page number (1-based)
+    /// * `limit` - Number of drafts per page
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or response parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// for draft in client.drafts(1, 10).await? {
+    ///     println!("{}: {}", draft.message_id, draft.topic);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn drafts(&mut self, page: u32, limit: u32) -> Result<Vec<OutboxMessage>> {
+        self.ensure_messages_initialized().await?;
+        let endpoint = format!("drafts/messages?page={}&limit={}", page, limit);
+        let json = self.get_messages_api(&endpoint).await?;
+        let resp: ResponseOutboxMessages =
+            serde_json::from_str(&json).map_err(|e| Error::Parse {
+                source: e,
+                body: self.error_body(json),
+            })?;
+        Ok(resp.data)
+    }
+
+    /// Deletes a draft.
+    ///
+    /// # Arguments
+    ///
+    /// * `draft_id` - A [`DraftId`] or the `message_id` of a listed draft
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the draft is not found.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// for draft in client.drafts(1, 50).await? {
+    ///     client.delete_draft(&draft.message_id).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_draft(&mut self, draft_id: &str) -> Result<()> {
+        self.ensure_messages_initialized().await?;
+        let endpoint = format!("drafts/{}", urls::encode_segment(draft_id));
+        self.delete_messages_api(&endpoint).await?;
+        Ok(())
+    }
+
+    /// Sends a saved draft to its receivers.
+    ///
+    /// The draft is removed from the drafts folder and appears in the outbox.
+    ///
+    /// # Arguments
+    ///
+    /// * `draft_id` - A [`DraftId`] or the `message_id` of a listed draft
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the draft is not found.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, ValidationMode};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let draft = client
+    ///     .save_draft(&["1234"], "Pytanie", "Kiedy jest zebranie?", ValidationMode::Strict)
+    ///     .await?;
+    /// client.send_draft(draft.as_str()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_draft(&mut self, draft_id: &str) -> Result<()> {
+        self.ensure_messages_initialized().await?;
+        let endpoint = format!("drafts/{}/send", urls::encode_segment(draft_id));
+        self.post_messages_api(&endpoint, &serde_json::json!({}))
+            .await?;
+        Ok(())
+    }
+
+    /// Exports every message of a folder as JSON Lines.
+    ///
+    /// Each message is fetched in full and written as one JSON object per
+    /// line with the decoded body (`body`), the sender, dates, receivers and
+    /// the list of attachments. Messages are streamed page by page, so memory
+    /// use does not grow with the size of the mailbox.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder` - The folder to export
+    /// * `writer` - Where the lines are written; flushed at the end
+    /// * `opts` - Paging, resume point and whether to include attachment contents
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request fails ([`Error::Request`], [`Error::ApiError`])
+    /// or writing fails ([`Error::Io`]). Lines written before the error are kept;
+    /// resume with [`ExportOptions::since_message_id()`] and the ID of the last
+    /// complete line. Returns [`Error::ResumePointNotFound`] if that ID is not
+    /// in the folder.
+    ///
+    /// # Cancellation
+    ///
+    /// With [`ExportOptions::cancel_on()`] the export stops between messages
+    /// once the token is cancelled and returns the summary so far, with
+    /// [`ExportSummary::cancelled`] set. Dropping the future instead may cut
+    /// a line short.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, ExportOptions, MessageFolder};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let file = tokio::fs::File::create("inbox.jsonl").await.map_err(librus_rs::Error::Io)?;
+    /// let summary = client
+    ///     .export_messages_jsonl(MessageFolder::Inbox, file, &ExportOptions::new())
+    ///     .await?;
+    /// println!("Exported {} messages", summary.exported);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn export_messages_jsonl<W>(
+        &mut self,
+        folder: MessageFolder,
+        mut writer: W,
+        opts: &ExportOptions,
+    ) -> Result<ExportSummary>
+    where
+        W: tokio::io::AsyncWrite + Unpin,
+    {
+        use tokio::io::AsyncWriteExt;
+
+        let mut summary = ExportSummary::default();
+        let mut skipping = opts.since_message_id.is_some();
+        let page_size = opts.page_size.unwrap_or(self.messages_page_size);
+        let mut listed = 0;
+        let mut page = 1;
+        loop {
+            if opts.is_cancelled() {
+                summary.cancelled = true;
+                break;
+            }
+            let (refs, more) = self
+                .walk_message_page::<MessageRef>(folder, page, page_size, &mut listed)
+                .await?;
+
+            for id in refs.into_iter().map(|m| m.message_id) {
+                if skipping {
+                    skipping = opts.since_message_id.as_deref() != Some(id.as_str());
+                    continue;
+                }
+                if opts.is_cancelled() {
+                    summary.cancelled = true;
+                    break;
+                }
+                let detail = match folder {
+                    MessageFolder::Inbox => self.message(&id).await?,
+                    MessageFolder::Outbox => self.outbox_message(&id).await?,
+                };
+                let mut line = ExportLine::new(folder, detail);
+                if opts.download_attachments {
+                    for (index, attachment_id) in line.attachment_ids().iter().enumerate() {
+                        let bytes = self.attachment(attachment_id, &id).await?;
+                        line.set_data(index, &bytes);
+                    }
+                }
+                let mut json =
+                    serde_json::to_vec(&line).expect("export lines are always serializable");
+                json.push(b'\n');
+                writer.write_all(&json).await.map_err(Error::Io)?;
+                summary.exported += 1;
+                summary.last_message_id = Some(id);
+            }
+
+            if !more || summary.cancelled {
+                break;
+            }
+            page += 1;
+        }
+        if skipping && !summary.cancelled {
+            return Err(Error::ResumePointNotFound {
+                message_id: opts.since_message_id.clone().unwrap_or_default(),
+            });
+        }
+        writer.flush().await.map_err(Error::Io)?;
+        Ok(summary)
+    }
+
+    /// Page size limit the Messages API was seen to enforce, if any.
+    ///
+    /// Set once a folder listing returns fewer messages than requested while
+    /// reporting that more follow. Later folder walks request at most this
+    /// many messages per page.
+    pub fn detected_message_page_cap(&self) -> Option<u32> {
+        self.detected_message_page_cap
+    }
+
+    /// Lists page `page` of `folder` while walking through every page.
+    ///
+    /// Asks for `page_size` messages, or fewer once the server was seen to
+    /// cap pages, and detects such a cap. `listed` counts the messages of the
+    /// pages so far. Returns the page and whether more pages follow.
+    pub(crate) async fn walk_message_page<T: serde::de::DeserializeOwned>(
+        &mut self,
+        folder: MessageFolder,
+        page: u32,
+        page_size: u32,
+        listed: &mut u64,
+    ) -> Result<(Vec<T>, bool)> {
+        let limit = self
+            .detected_message_page_cap
+            .map_or(page_size, |cap| page_size.min(cap));
+        let resp: ResponseMessagePage<T> = self.message_page(folder, page, limit).await?;
+        let count = resp.data.len();
+        *listed += count as u64;
+        let more = match resp.total {
+            Some(total) => count > 0 && *listed < total,
+            None => count >= limit as usize,
+        };
+        if more && count < limit as usize {
+            // The server capped the page size; page numbers now follow its limit.
+            self.detected_message_page_cap = Some(count as u32);
+        }
+        Ok((resp.data, more))
+    }
+
+    /// Lists one page of a folder.
+    async fn message_page<T: serde::de::DeserializeOwned>(
+        &mut self,
+        folder: MessageFolder,
+        page: u32,
+        limit: u32,
+    ) -> Result<ResponseMessagePage<T>> {
+        self.ensure_messages_initialized().await?;
+        let folder = match folder {
+            MessageFolder::Inbox => "inbox",
+            MessageFolder::Outbox => "outbox",
+        };
+        let endpoint = format!("{folder}/messages?page={page}&limit={limit}");
+        let json = self.get_messages_api(&endpoint).await?;
+        serde_json::from_str(&json).map_err(|e| Error::Parse {
+            source: e,
+            body: self.error_body(json),
+        })
+    }
+
+    /// Downloads attachment bytes.
+    ///
+    /// Use [`Client::attachment_download()`] to also get the response headers.
+    ///
+    /// # Arguments
+    ///
+    /// * `attachment_id` - The attachment ID from a [`MessageDetail`]'s attachments
+    /// * `message_id` - The message ID containing the attachment
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AttachmentScanning`] if the file was added recently
+    /// and is still being scanned, or another error if the request fails or
+    /// the attachment is not found.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    /// use std::fs;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let detail = client.message("12345").await?;
+    /// for attachment in &detail.attachments {
+    ///     let bytes = client.attachment(&attachment.id, &detail.message_id).await?;
+    ///     fs::write(attachment.decoded_name(), &bytes).expect("Failed to save file");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn attachment(&mut self, attachment_id: &str, message_id: &str) -> Result<Vec<u8>> {
+        self.attachment_download(attachment_id, message_id)
+            .await?
+            .bytes()
+            .await
+    }
+
+    /// Downloads an attachment together with its `Content-Type` and
+    /// `Content-Disposition` file name.
+    ///
+    /// The body is read before returning, so
+    /// [`ClientBuilder::max_response_bytes()`] applies. Take it with
+    /// [`AttachmentDownload::bytes()`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Client::attachment()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{Client, Error};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// match client.attachment_download("a1", "12345").await {
+    ///     Ok(download) => {
+    ///         let name = download.filename_header().unwrap_or("attachment").to_string();
+    ///         std::fs::write(name, download.bytes().await?).unwrap();
+    ///     }
+    ///     Err(Error::AttachmentScanning { retry_after_hint }) => {
+    ///         println!("still scanning, retry in {:?}", retry_after_hint);
+    ///     }
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn attachment_download(
+        &mut self,
+        attachment_id: &str,
+        message_id: &str,
+    ) -> Result<AttachmentDownload> {
+        self.ensure_messages_initialized().await?;
+        let path = format!(
+            "attachments/{}/messages/{}",
+            urls::encode_segment(attachment_id),
+            urls::encode_segment(message_id)
+        );
+        let url = urls::join(&self.endpoints.messages, &path);
+        self.download(&url, ApiKind::Messages).await
+    }
+
+    /// Continues an attachment download from byte `already_have`.
+    ///
+    /// Sends a `Range` request and checks the `206` answer starts at the
+    /// requested byte. If the server ignores the range and answers `200`, the
+    /// whole file is sent again and [`AttachmentDownload::resumed()`] is
+    /// `false`: discard the bytes you already have. The body is not buffered,
+    /// so [`ClientBuilder::max_response_bytes()`] does not apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `attachment_id` - The attachment ID from a [`MessageDetail`]'s attachments
+    /// * `message_id` - The message ID containing the attachment
+    /// * `already_have` - Number of bytes downloaded before
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::RangeNotHonored`] if the server sends a different
+    /// range, [`Error::AttachmentScanning`] if the file is still being
+    /// scanned, or another error if the request fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example(mut partial: Vec<u8>) -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let mut download = client
+    ///     .attachment_resume("a1", "12345", partial.len() as u64)
+    ///     .await?;
+    /// if !download.resumed() {
+    ///     partial.clear();
+    /// }
+    /// while let Some(chunk) = download.chunk().await? {
+    ///     partial.extend_from_slice(&chunk);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn attachment_resume(
+        &mut self,
+        attachment_id: &str,
+        message_id: &str,
+        already_have: u64,
+    ) -> Result<AttachmentDownload> {
+        self.ensure_messages_initialized().await?;
+        let path = format!(
+            "attachments/{}/messages/{}",
+            urls::encode_segment(attachment_id),
+            urls::encode_segment(message_id)
+        );
+        let url = urls::join(&self.endpoints.messages, &path);
+
+        let mut request = self
+            .http
+            .get(&url)
+            .header(reqwest::header::ACCEPT_ENCODING, "identity");
+        if already_have > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={already_have}-"));
+        }
+        let request = request.build().map_err(Error::from)?;
+        let response = redirects::follow(&self.http, request, Cost::Attachment, |_| true)
+            .await?
+            .response;
+
+        let status = response.status();
+        let content_range = response
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let range = content_range
+            .as_deref()
+            .and_then(download::parse_content_range);
+        match status {
+            reqwest::StatusCode::OK => {
+                let total = response.content_length();
+                self.stream_attachment(response, 0, total).await
+            }
+            reqwest::StatusCode::PARTIAL_CONTENT => match range {
+                Some((Some(start), total)) if start == already_have => {
+                    self.stream_attachment(response, already_have, total).await
+                }
+                _ => Err(Error::RangeNotHonored {
+                    requested: already_have,
+                    content_range,
+                }),
+            },
+            // Nothing left to download.
+            reqwest::StatusCode::RANGE_NOT_SATISFIABLE
+                if range.is_some_and(|(_, total)| total == Some(already_have)) =>
+            {
+                Ok(AttachmentDownload::finished(already_have))
+            }
+            _ => Err(Error::ApiError {
+                api: ApiKind::Messages,
+                status: status.as_u16(),
+                body: self.read_text(response).await.unwrap_or_default(),
+                source: None,
+            }),
+        }
+    }
+
+    /// Downloads an attachment to `path`, resuming after dropped connections.
+    ///
+    /// Progress is kept in `<path>.part`, which is renamed to `path` when the
+    /// download completes. If a previous call was interrupted, the download
+    /// continues from the existing `.part` file. A broken connection is
+    /// retried from the last written byte up to three times.
+    ///
+    /// # Arguments
+    ///
+    /// * `attachment_id` - The attachment ID from a [`MessageDetail`]'s attachments
+    /// * `message_id` - The message ID containing the attachment
+    /// * `path` - Where to save the file
+    ///
+    /// # Returns
+    ///
+    /// The size of the file in bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if writing the file fails, or the last download
+    /// error if retries are exhausted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let detail = client.message("12345").await?;
+    /// for attachment in &detail.attachments {
+    ///     client
+    ///         .download_attachment_to_file(&attachment.id, &detail.message_id, attachment.decoded_name())
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download_attachment_to_file(
+        &mut self,
+        attachment_id: &str,
+        message_id: &str,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<u64> {
+        use tokio::io::AsyncWriteExt;
+
+        let path = path.as_ref();
+        let mut part = path.as_os_str().to_owned();
+        part.push(".part");
+        let part = std::path::PathBuf::from(part);
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&part)
+            .await
+            .map_err(Error::Io)?;
+        let mut written = file.metadata().await.map_err(Error::Io)?.len();
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let result = async {
+                let mut download = self
+                    .attachment_resume(attachment_id, message_id, written)
+                    .await?;
+                if download.offset() != written {
+                    file.set_len(0).await.map_err(Error::Io)?;
+                    written = 0;
+                }
+                while let Some(chunk) = download.chunk().await? {
+                    file.write_all(&chunk).await.map_err(Error::Io)?;
+                    written += chunk.len() as u64;
+                }
+                Ok::<_, Error>(())
+            }
+            .await;
+            match result {
+                Ok(()) => break,
+                Err(e) if e.is_network() && attempts < ATTACHMENT_DOWNLOAD_ATTEMPTS => {
+                    file.flush().await.map_err(Error::Io)?;
+                }
+                Err(e) => {
+                    file.flush().await.map_err(Error::Io)?;
+                    return Err(e);
+                }
+            }
+        }
+
+        file.sync_all().await.map_err(Error::Io)?;
+        drop(file);
+        tokio::fs::rename(&part, path).await.map_err(Error::Io)?;
+        Ok(written)
+    }
+
+    /// Downloads every attachment of a message to `dir`, one after another.
+    ///
+    /// Each attachment is saved under its [`Attachment::decoded_name()`],
+    /// or its ID if the name is empty, with
+    /// [`Client::download_attachment_to_file()`]. `cancel` is checked before
+    /// each attachment, never while a file is written: once it is cancelled,
+    /// the files downloaded so far are returned with
+    /// [`DownloadSummary::cancelled`] set.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first failed download. Files written before it are kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{CancellationToken, Client};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let mut client = Client::from_env().await?;
+    /// let shutdown = CancellationToken::new();
+    /// let detail = client.message("12345").await?;
+    /// let summary = client
+    ///     .download_all_attachments(&detail, "attachments", &shutdown)
+    ///     .await?;
+    /// println!("{} files, cancelled: {}", summary.files.len(), summary.cancelled);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download_all_attachments(
+        &mut self,
+        detail: &MessageDetail,
+        dir: impl AsRef<std::path::Path>,
+        cancel: &CancellationToken,
+    ) -> Result<DownloadSummary> {
+        let mut summary = DownloadSummary::default();
+        for attachment in &detail.attachments {
+            if cancel.is_cancelled() {
+                summary.cancelled = true;
+                break;
+            }
+            let name = match attachment.decoded_name() {
+                name if name.is_empty() => attachment.id.clone(),
+                name => name,
+            };
+            let path = dir.as_ref().join(name);
+            self.download_attachment_to_file(&attachment.id, &detail.message_id, &path)
+                .await?;
+            summary.files.push(path);
+        }
+        Ok(summary)
+    }
+
+    /// Decodes base64-encoded message content to a string.
+    ///
+    /// Message bodies in Librus are base64-encoded. Use this helper to decode them.
+    /// Double-encoded bodies are decoded twice, see
+    /// [`Client::decode_message_content_ext()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The base64-encoded content string
+    ///
+    /// # Returns
+    ///
+    /// `Some(String)` if decoding succeeds, `None` if the content is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::Client;
+    ///
+    /// let encoded = "SGVsbG8sIFdvcmxkIQ==";
+    /// let decoded = Client::decode_message_content(encoded);
+    /// assert_eq!(decoded, Some("Hello, World!".to_string()));
+    /// ```
+    pub fn decode_message_content(content: &str) -> Option<String> {
+        decode::decode(content).map(|decoded| decoded.text)
+    }
+
+    /// Decodes message content like [`Client::decode_message_content()`] and
+    /// reports how many base64 rounds were undone.
+    ///
+    /// Forwarded messages and some sent from the mobile app are encoded twice.
+    /// A second round is decoded only if the result is mostly printable text,
+    /// so bodies that merely look like base64 are not mangled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::Client;
+    ///
+    /// let decoded = Client::decode_message_content_ext("U0dWc2JHOD0=").unwrap();
+    /// assert_eq!(decoded.text, "Hello");
+    /// assert_eq!(decoded.rounds, 2);
+    /// ```
+    pub fn decode_message_content_ext(content: &str) -> Option<DecodedContent> {
+        decode::decode(content)
+    }
+
+    /// Formats API-provided HTML content into readable text.
+    ///
+    /// School notices (announcements) are often HTML-formatted. Tags are
+    /// removed and entities decoded, keeping line breaks, paragraphs and list
+    /// items; link targets follow their text in parentheses. This is the same
+    /// rendering as [`Homework::content_text()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::Client;
+    ///
+    /// let html = "<p>Hello&nbsp;<b>World</b> &amp; friends</p><p>See you</p>";
+    /// let text = Client::notice_content_to_text(html);
+    /// assert_eq!(text, "Hello World & friends\n\nSee you");
+    /// ```
+    pub fn notice_content_to_text(content: &str) -> String {
+        content::render(content, content::Style::Text)
+    }
+}
+
+/// Seconds since the Unix epoch, negative for earlier times.
+pub(crate) fn unix_seconds(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    }
+}
+
+/// Fails with [`Error::GrantFailed`] unless a login step ended on a successful
+/// response. OAuth errors redirect with an `error` query parameter and a 200
+/// status, so the final URL is checked as well.
+fn check_auth_step(step: AuthStep, response: &reqwest::Response) -> Result<()> {
+    let oauth_error = response.url().query_pairs().any(|(key, _)| key == "error");
+    if !response.status().is_success() || oauth_error {
+        return Err(Error::GrantFailed {
+            step,
+            status: response.status().as_u16(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use wiremock::matchers::{
+        body_json, body_string, body_string_contains, header, method, path, query_param,
+    };
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn mock_endpoints(server: &MockServer) -> Endpoints {
+        endpoints_at(&server.uri())
+    }
+
+    fn endpoints_at(base: &str) -> Endpoints {
+        Endpoints {
+            api: format!("{base}/"),
+            messages: format!("{base}/"),
+            auth_host: base.to_string(),
+            auth: format!("{base}/OAuth/Authorization?client_id=46"),
+            portal: format!("{base}/loguj/portalRodzina"),
+            token_info: format!("{base}/Auth/TokenInfo/"),
+            unit_select: format!("{base}/OAuth/Authorization/Unit"),
+            messages_init: format!("{base}/wiadomosci3"),
+            login_field: "login".to_string(),
+            password_field: "pass".to_string(),
+        }
+    }
+
+    fn mock_client(builder: ClientBuilder, server: &MockServer) -> Client {
+        Client::from_parts(builder.http_client().unwrap(), mock_endpoints(server))
+    }
+
+    /// Mounts the login flow with the given form reply and grant/token statuses.
+    async fn mount_login(server: &MockServer, login_reply: &str, grant: u16, token: u16) {
+        Mock::given(method("GET"))
+            .and(path("/loguj/portalRodzina"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/OAuth/Authorization"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(login_reply))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/OAuth/Authorization/Grant"))
+            .respond_with(ResponseTemplate::new(grant))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Auth/TokenInfo/"))
+            .respond_with(ResponseTemplate::new(token))
+            .mount(server)
+            .await;
+    }
+
+    async fn mock_login(server: &MockServer) -> Result<Client> {
+        let client = mock_client(ClientBuilder::new(), server);
+        client.log_in("user", "pass").await?;
+        Ok(client)
+    }
+
+    fn gzip(body: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(body).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_login_succeeds() {
+        let server = MockServer::start().await;
+        mount_login(
+            &server,
+            include_str!("../tests/fixtures/login_ok.json"),
+            200,
+            200,
+        )
+        .await;
+        assert!(mock_login(&server).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_request_budget_shared_by_clients() {
+        let server = MockServer::start().await;
+        mount_login(
+            &server,
+            include_str!("../tests/fixtures/login_ok.json"),
+            200,
+            200,
+        )
+        .await;
+        Mock::given(method("GET"))
+            .and(path("/LuckyNumbers"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"LuckyNumber": {"LuckyNumber": 13, "LuckyNumberDay": "2025-10-06"}}"#,
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/attachments/a1/messages/m1"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"plik".to_vec()))
+            .mount(&server)
+            .await;
+
+        let budget = RequestBudget::per_hour(10)
+            .policy(BudgetPolicy::Error)
+            .attachment_weight(3);
+        let builder = ClientBuilder::new().request_budget(budget.clone());
+        let first = mock_client(builder.clone(), &server);
+        let mut second = mock_client(builder, &server);
+        second.messages_initialized = true;
+
+        // The login flow takes four requests.
+        first.log_in("user", "pass").await.unwrap();
+        assert_eq!(budget.spent(), 4);
+        first.lucky_number().await.unwrap();
+        second.lucky_number().await.unwrap();
+        second.attachment("a1", "m1").await.unwrap();
+        assert_eq!((budget.spent(), budget.remaining()), (9, 1));
+
+        // The attachment no longer fits, a plain request still does.
+        assert!(matches!(
+            second.attachment("a1", "m1").await,
+            Err(Error::BudgetExhausted { .. })
+        ));
+        second.lucky_number().await.unwrap();
+        for client in [&first, &second] {
+            match client.lucky_number().await {
+                Err(e @ Error::BudgetExhausted { .. }) => {
+                    assert_eq!(e.code(), ErrorCode::BudgetExhausted)
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+        assert_eq!(budget.remaining(), 0);
+        assert_eq!(server.received_requests().await.unwrap().len(), 8);
+    }
+
+    /// A profile on the mock server whose login flow starts at `portal`.
+    fn profile_at(server: &MockServer, name: &str, portal: &str) -> AuthProfile {
+        AuthProfile {
+            name: name.to_string(),
+            auth_host: server.uri(),
+            portal_url: format!("{}{portal}", server.uri()),
+            ..AuthProfile::family_portal()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth_profile_fallback() {
+        let server = MockServer::start().await;
+        // Only the second profile's portal and client are served.
+        Mock::given(method("GET"))
+            .and(path("/loguj"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/OAuth/Authorization"))
+            .and(query_param("client_id", "52"))
+            .and(body_string_contains("email=1234567u"))
+            .and(body_string_contains("haslo=secret"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/login_ok.json")),
+            )
+            .mount(&server)
+            .await;
+        for route in ["/OAuth/Authorization/Grant", "/Auth/TokenInfo/"] {
+            Mock::given(method("GET"))
+                .and(path(route))
+                .respond_with(ResponseTemplate::new(200))
+                .mount(&server)
+                .await;
+        }
+
+        let regional = AuthProfile {
+            client_id: "52".to_string(),
+            login_field: "email".to_string(),
+            password_field: "haslo".to_string(),
+            ..profile_at(&server, "regional", "/loguj")
+        };
+        let profiles = [
+            profile_at(&server, "portal", "/loguj/portalRodzina"),
+            regional,
+        ];
+        let client = ClientBuilder::new()
+            .username("1234567u")
+            .password("secret")
+            .auth_profiles(profiles.clone())
+            .build_with(mock_endpoints(&server))
+            .await
+            .unwrap();
+        assert_eq!(
+            client.endpoints.auth,
+            format!("{}/OAuth/Authorization?client_id=52", server.uri())
+        );
+
+        // With only the first profile its error is returned.
+        let result = ClientBuilder::new()
+            .username("1234567u")
+            .password("secret")
+            .auth_profile(profiles[0].clone())
+            .build_with(mock_endpoints(&server))
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::GrantFailed {
+                step: AuthStep::Init,
+                status: 404
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_auth_profile_no_fallback_on_invalid_credentials() {
+        let server = MockServer::start().await;
+        mount_login(
+            &server,
+            include_str!("../tests/fixtures/login_invalid_credentials.json"),
+            200,
+            200,
+        )
+        .await;
+        Mock::given(method("GET"))
+            .and(path("/loguj"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let result = ClientBuilder::new()
+            .username("1234567u")
+            .password("wrong")
+            .auth_profiles([
+                profile_at(&server, "portal", "/loguj/portalRodzina"),
+                profile_at(&server, "legacy", "/loguj"),
+            ])
+            .build_with(mock_endpoints(&server))
+            .await;
+        assert!(matches!(result, Err(Error::InvalidCredentials { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_auth_profile_no_fallback_on_network_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/loguj"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        // Nothing listens on port 1, so the first profile cannot connect.
+        let unreachable = AuthProfile {
+            portal_url: "http://127.0.0.1:1/loguj/portalRodzina".to_string(),
+            ..profile_at(&server, "portal", "/loguj/portalRodzina")
+        };
+        let result = ClientBuilder::new()
+            .username("1234567u")
+            .password("secret")
+            .auth_profiles([unreachable, profile_at(&server, "legacy", "/loguj")])
+            .build_with(mock_endpoints(&server))
+            .await;
+        assert!(matches!(result, Err(Error::Connect { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_auth_trace_is_recorded_and_redacted() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/loguj/portalRodzina"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .append_header("Set-Cookie", "DZIENNIKSID=sesja123; Path=/")
+                    .set_body_string("<html>session sesja123</html>"),
+            )
+            .mount(&server)
+            .await;
+        mount_login(
+            &server,
+            r#"{"status":"ok","goTo":"/OAuth/Authorization/Grant?client_id=46","echo":"tajne haslo&1"}"#,
+            200,
+            200,
+        )
+        .await;
+
+        let client = ClientBuilder::new()
+            .username("1234567u")
+            .password("tajne haslo&1")
+            .trace_auth()
+            .build_with(mock_endpoints(&server))
+            .await
+            .unwrap();
+        let trace = client.last_auth_trace().unwrap();
+        let steps: Vec<(AuthStep, u16)> =
+            trace.steps().iter().map(|s| (s.step, s.status)).collect();
+        assert_eq!(
+            steps,
+            [
+                (AuthStep::Init, 200),
+                (AuthStep::Login, 200),
+                (AuthStep::Grant, 200),
+                (AuthStep::TokenInfo, 200),
+            ]
+        );
+        assert_eq!(
+            trace.steps()[0].url,
+            format!("{}/loguj/portalRodzina", server.uri())
+        );
+        assert_eq!(
+            trace.steps()[1].redirect,
+            Some(format!("{}/OAuth/Authorization/Grant", server.uri()))
+        );
+        assert_eq!(
+            trace.steps()[0].body_excerpt,
+            "<html>session [redacted]</html>"
+        );
+        let printed = trace.to_string();
+        assert!(printed.contains("\"echo\":\"[redacted]\""));
+        assert!(!printed.contains("tajne") && !printed.contains("sesja123"));
+
+        // Without the flag nothing is kept.
+        assert!(mock_login(&server)
+            .await
+            .unwrap()
+            .last_auth_trace()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_auth_trace_attached_to_error() {
+        let server = MockServer::start().await;
+        mount_login(&server, r#"{"status":"ok"}"#, 200, 200).await;
+        let result = ClientBuilder::new()
+            .username("1234567u")
+            .password("secret")
+            .trace_auth()
+            .build_with(mock_endpoints(&server))
+            .await;
+        match result {
+            Err(e @ Error::AuthenticationDetailed { .. }) => {
+                assert_eq!(e.code(), ErrorCode::AuthFailed);
+                let Error::AuthenticationDetailed { trace } = e else {
+                    unreachable!()
+                };
+                assert_eq!(trace.steps().len(), 2);
+                assert_eq!(trace.steps()[1].redirect, None);
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    async fn mount_unit_selection(server: &MockServer) {
+        // Mounted before `mount_login`, so it answers the grant instead.
+        Mock::given(method("GET"))
+            .and(path("/OAuth/Authorization/Grant"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/unit_selection.html")),
+            )
+            .mount(server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/OAuth/Authorization/Unit"))
+            .and(body_string("unit=102"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(server)
+            .await;
+        mount_login(
+            server,
+            include_str!("../tests/fixtures/login_ok.json"),
+            200,
+            200,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_login_requires_unit_selection() {
+        let server = MockServer::start().await;
+        mount_unit_selection(&server).await;
+        let builder = ClientBuilder::new().username("1234567u").password("p");
+
+        match builder.clone().build_with(mock_endpoints(&server)).await {
+            Err(e @ Error::UnitSelectionRequired { .. }) => {
+                assert_eq!(e.code(), ErrorCode::UnitSelectionRequired);
+                let Error::UnitSelectionRequired { units } = e else {
+                    unreachable!()
+                };
+                let ids: Vec<&str> = units.iter().map(|u| u.id.as_str()).collect();
+                assert_eq!(ids, ["101", "102"]);
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+
+        // An unknown unit is reported the same way instead of being sent.
+        assert!(matches!(
+            builder
+                .clone()
+                .unit("999")
+                .build_with(mock_endpoints(&server))
+                .await,
+            Err(Error::UnitSelectionRequired { .. })
+        ));
+
+        builder
+            .unit("102")
+            .build_with(mock_endpoints(&server))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_select_unit() {
+        let server = MockServer::start().await;
+        mount_unit_selection(&server).await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.select_unit("102").await.unwrap();
+        assert_eq!(client.unit.as_deref(), Some("102"));
+    }
+
+    #[tokio::test]
+    async fn test_login_invalid_credentials() {
+        let server = MockServer::start().await;
+        mount_login(
+            &server,
+            include_str!("../tests/fixtures/login_invalid_credentials.json"),
+            200,
+            200,
+        )
+        .await;
+        match mock_login(&server).await {
+            Err(Error::InvalidCredentials { message }) => {
+                assert_eq!(message, "Nieprawidłowy login i/lub hasło.")
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_login_reports_failing_step() {
+        for (grant, token, step, status) in [
+            (500, 200, AuthStep::Grant, 500),
+            (200, 401, AuthStep::TokenInfo, 401),
+        ] {
+            let server = MockServer::start().await;
+            mount_login(
+                &server,
+                include_str!("../tests/fixtures/login_ok.json"),
+                grant,
+                token,
+            )
+            .await;
+            match mock_login(&server).await {
+                Err(Error::GrantFailed { step: s, status: c }) => {
+                    assert_eq!((s, c), (step, status))
+                }
+                other => panic!("unexpected result: {:?}", other.map(|_| ())),
+            }
+        }
+    }
+
+    /// Mounts a login flow where each child's session is marked by a cookie
+    /// and `Me` answers with the login of the session's child.
+    async fn mount_children(server: &MockServer, logins: &[&str]) {
+        use wiremock::matchers::{body_string_contains, query_param};
+        Mock::given(method("GET"))
+            .and(path("/loguj/portalRodzina"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Auth/TokenInfo/"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(server)
+            .await;
+        for login in logins {
+            Mock::given(method("POST"))
+                .and(path("/OAuth/Authorization"))
+                .and(body_string_contains(format!("login={login}&")))
+                .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                    r#"{{"status":"ok","goTo":"/OAuth/Authorization/Grant?child={login}"}}"#
+                )))
+                .mount(server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/OAuth/Authorization/Grant"))
+                .and(query_param("child", *login))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .insert_header("Set-Cookie", format!("child={login}; Path=/")),
+                )
+                .mount(server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/Me"))
+                .and(header("cookie", format!("child={login}")))
+                .respond_with(ResponseTemplate::new(200).set_body_string(*login))
+                .mount(server)
+                .await;
+        }
+    }
+
+    /// Logins posted to the login form, in order.
+    async fn posted_logins(server: &MockServer) -> Vec<String> {
+        let requests = server.received_requests().await.unwrap();
+        requests
+            .iter()
+            .filter(|r| r.method.as_str() == "POST")
+            .filter_map(|r| {
+                let body = String::from_utf8_lossy(&r.body).into_owned();
+                body.split('&')
+                    .find_map(|kv| kv.strip_prefix("login=").map(str::to_string))
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_essential_cookies_only() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/loguj/portalRodzina"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .append_header("Set-Cookie", "DZIENNIKSID=abc; Path=/")
+                    .append_header("Set-Cookie", "_ga=GA1.2.3; Path=/"),
+            )
+            .mount(&server)
+            .await;
+        mount_login(
+            &server,
+            include_str!("../tests/fixtures/login_ok.json"),
+            200,
+            200,
+        )
+        .await;
+        Mock::given(method("GET"))
+            .and(path("/Me"))
+            .and(header("cookie", "DZIENNIKSID=abc"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .username("1234567u")
+            .password("secret")
+            .cookie_filter(CookieFilter::EssentialOnly)
+            .build_with(mock_endpoints(&server))
+            .await
+            .unwrap();
+        assert_eq!(client.get_api("Me").await.unwrap(), "ok");
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests
+            .iter()
+            .flat_map(|r| r.headers.get_all("cookie"))
+            .all(|cookie| !cookie.to_str().unwrap().contains("_ga")));
+    }
+
+    #[tokio::test]
+    async fn test_build_rejects_malformed_credentials_offline() {
+        let server = MockServer::start().await;
+        mount_login(
+            &server,
+            include_str!("../tests/fixtures/login_ok.json"),
+            200,
+            200,
+        )
+        .await;
+
+        let build = |username: &str, password: &str| {
+            ClientBuilder::new()
+                .username(username)
+                .password(password)
+                .build_with(mock_endpoints(&server))
+        };
+        assert!(matches!(
+            build("   ", "secret").await,
+            Err(Error::InvalidCredentialFormat {
+                field: "username",
+                ..
+            })
+        ));
+        assert!(matches!(
+            build("1234567u", " \n").await,
+            Err(Error::InvalidCredentialFormat {
+                field: "password",
+                ..
+            })
+        ));
+        assert!(matches!(
+            build("1234567u", "secret\n").await,
+            Err(Error::InvalidCredentialFormat {
+                field: "password",
+                ..
+            })
+        ));
+        assert!(server.received_requests().await.unwrap().is_empty());
+
+        let client = build(" 1234567u ", "secret").await;
+        assert!(client.is_ok());
+        assert_eq!(posted_logins(&server).await, ["1234567u"]);
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_many_keeps_going_after_bad_login() {
+        use wiremock::matchers::body_string_contains;
+        let server = MockServer::start().await;
+        mount_children(&server, &["anna", "jan"]).await;
+        Mock::given(method("POST"))
+            .and(path("/OAuth/Authorization"))
+            .and(body_string_contains("login=bad&"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(include_str!(
+                "../tests/fixtures/login_invalid_credentials.json"
+            )))
+            .mount(&server)
+            .await;
+
+        let pool = ClientPool::new(ClientBuilder::new().lenient_username())
+            .login_interval(Duration::from_millis(50))
+            .with_endpoints(mock_endpoints(&server));
+        let credentials = ["anna", "bad", "jan"]
+            .map(|login| (login.to_string(), "secret".to_string()))
+            .to_vec();
+        let started = std::time::Instant::now();
+        let results = pool.authenticate_many(credentials, 2).await;
+        assert!(started.elapsed() >= Duration::from_millis(100));
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(Error::InvalidCredentials { .. })));
+        assert!(results[2].is_ok());
+
+        // Each client keeps its own session cookies.
+        let anna = results[0].as_ref().unwrap().get_api("Me").await.unwrap();
+        let jan = results[2].as_ref().unwrap().get_api("Me").await.unwrap();
+        assert_eq!((anna.as_str(), jan.as_str()), ("anna", "jan"));
+        let mut logins = posted_logins(&server).await;
+        logins.sort();
+        assert_eq!(logins, ["anna", "bad", "jan"]);
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_many_shares_connections() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        let server = MockServer::start().await;
+        mount_children(&server, &["anna", "jan"]).await;
+        // Forwards to the mock server, counting the connections opened.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let forwarder = format!("http://{}", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let upstream = *server.address();
+        let opened = Arc::clone(&connections);
+        tokio::spawn(async move {
+            while let Ok((mut inbound, _)) = listener.accept().await {
+                opened.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut outbound = tokio::net::TcpStream::connect(upstream).await.unwrap();
+                    let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+                });
+            }
+        });
+
+        let pool = ClientPool::new(ClientBuilder::new().lenient_username())
+            .login_interval(Duration::ZERO)
+            .with_endpoints(endpoints_at(&forwarder));
+        let credentials = ["anna", "jan"]
+            .map(|login| (login.to_string(), "secret".to_string()))
+            .to_vec();
+        let results = pool.authenticate_many(credentials, 1).await;
+        let anna = results[0].as_ref().unwrap().get_api("Me").await.unwrap();
+        let jan = results[1].as_ref().unwrap().get_api("Me").await.unwrap();
+        assert_eq!((anna.as_str(), jan.as_str()), ("anna", "jan"));
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    async fn family(server: &MockServer) -> MultiChildClient {
+        let client = mock_client(ClientBuilder::new(), server);
+        client.log_in("anna", "a").await.unwrap();
+        MultiChildClient::new(client, LinkedAccount::new("anna"), "a")
+            .add_child(LinkedAccount::new("jan"), "j")
+    }
+
+    #[tokio::test]
+    async fn test_for_each_child_switches_and_restores() {
+        let server = MockServer::start().await;
+        mount_children(&server, &["anna", "jan"]).await;
+        let family = family(&server).await;
+
+        let results = family
+            .for_each_child(async |client: &mut Client| client.get_api("Me").await)
+            .await
+            .unwrap();
+        assert_eq!(
+            results[&LinkedAccount::new("anna")].as_deref().unwrap(),
+            "anna"
+        );
+        assert_eq!(
+            results[&LinkedAccount::new("jan")].as_deref().unwrap(),
+            "jan"
+        );
+        // Initial login, switch to the second child, restore the first.
+        assert_eq!(posted_logins(&server).await, ["anna", "jan", "anna"]);
+
+        let client = family.into_client().await.unwrap();
+        assert_eq!(client.get_api("Me").await.unwrap(), "anna");
+        assert_eq!(posted_logins(&server).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_for_each_child_restores_after_error() {
+        let server = MockServer::start().await;
+        mount_children(&server, &["anna", "jan"]).await;
+        let family = family(&server).await;
+
+        let results = family
+            .for_each_child(async |client: &mut Client| {
+                let me = client.get_api("Me").await?;
+                if me == "jan" {
+                    return Err(Error::ApiError {
+                        api: ApiKind::Synergia,
+                        status: 500,
+                        body: me,
+                        source: None,
+                    });
+                }
+                Ok(me)
+            })
+            .await
+            .unwrap();
+        assert!(results[&LinkedAccount::new("anna")].is_ok());
+        assert!(matches!(
+            results[&LinkedAccount::new("jan")],
+            Err(Error::ApiError { status: 500, .. })
+        ));
+        assert_eq!(posted_logins(&server).await, ["anna", "jan", "anna"]);
+        let client = family.into_client().await.unwrap();
+        assert_eq!(client.get_api("Me").await.unwrap(), "anna");
+    }
+
+    fn giant_notices() -> String {
+        let image = "A".repeat(2 * 1024 * 1024);
+        let content = format!("<p>Plan</p><img src=\\\"data:image/png;base64,{image}\\\">");
+        include_str!("../tests/fixtures/school_notices.json")
+            .replace("Zapraszamy rodziców w piątek.", &content)
+    }
+
+    #[tokio::test]
+    async fn test_school_notices_light_strips_images() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/SchoolNotices"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(giant_notices()))
+            .mount(&server)
+            .await;
+        let client = mock_client(ClientBuilder::new(), &server);
+
+        let notices = client.school_notices_light().await.unwrap();
+        assert_eq!(notices[0].stripped_bytes, 0);
+        assert_eq!(notices[1].notice.content, "<p>Plan</p>[image]");
+        assert!(notices[1].stripped_bytes > 2 * 1024 * 1024);
+    }
+
+    #[tokio::test]
+    async fn test_max_response_bytes() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/SchoolNotices"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(giant_notices()))
+            .mount(&server)
+            .await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.max_response_bytes = Some(1024 * 1024);
+        assert!(matches!(
+            client.school_notices_light().await,
+            Err(Error::ResponseTooLarge { limit: 1_048_576 })
+        ));
+
+        client.max_response_bytes = Some(4 * 1024 * 1024);
+        assert_eq!(client.school_notices_light().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_body_excerpt() {
+        let server = MockServer::start().await;
+        let body = format!(r#"{{"LuckyNumber": "{}"}}"#, "7".repeat(5000));
+        Mock::given(method("GET"))
+            .and(path("/LuckyNumbers"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body.clone()))
+            .mount(&server)
+            .await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        match client.lucky_number().await {
+            Err(Error::Parse { body: kept, .. }) => {
+                assert_eq!((kept.as_str().len(), kept.len()), (1024, body.len()));
+                assert!(kept.full().is_none());
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        client.keep_full_error_bodies = true;
+        match client.lucky_number().await {
+            Err(Error::Parse { body: kept, .. }) => assert_eq!(kept.full(), Some(body.as_str())),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_last_known_good_through_outage() {
+        let server = MockServer::start().await;
+        let up = |number: u32| {
+            Mock::given(method("GET"))
+                .and(path("/LuckyNumbers"))
+                .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"LuckyNumber": {{"LuckyNumber": {number}, "LuckyNumberDay": "2025-10-06"}}}}"#
+            )))
+        };
+        let down = || {
+            Mock::given(method("GET"))
+                .and(path("/LuckyNumbers"))
+                .respond_with(ResponseTemplate::new(503))
+        };
+        let client = mock_client(ClientBuilder::new(), &server);
+        let mut lucky = LastKnownGood::new("lucky-number");
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+        let shown = |lucky: &LastKnownGood<ResponseLuckyNumber>| {
+            lucky
+                .current()
+                .map(|r| r.lucky_number.lucky_number.unwrap())
+        };
+
+        up(13).mount(&server).await;
+        assert!(lucky.record(client.lucky_number().await, at(0)).is_none());
+        assert_eq!(shown(&lucky), Some(13));
+
+        server.reset().await;
+        down().mount(&server).await;
+        for secs in [600, 1200] {
+            match lucky.record(client.lucky_number().await, at(secs)) {
+                Some(SourceEvent::SourceStale {
+                    source,
+                    last_success,
+                    error: Error::ApiError { status: 503, .. },
+                }) => {
+                    assert_eq!(source, "lucky-number");
+                    assert_eq!(last_success, Some(at(0)));
+                }
+                other => panic!("unexpected event: {:?}", other),
+            }
+            assert_eq!(shown(&lucky), Some(13));
+            assert_eq!(lucky.stale_since(), Some(at(600)));
+        }
+
+        server.reset().await;
+        up(7).mount(&server).await;
+        match lucky.record(client.lucky_number().await, at(1800)) {
+            Some(SourceEvent::SourceRecovered {
+                source,
+                stale_since,
+            }) => assert_eq!((source.as_str(), stale_since), ("lucky-number", at(600))),
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert_eq!(shown(&lucky), Some(7));
+        assert!(!lucky.is_stale());
+        assert!(lucky
+            .record(client.lucky_number().await, at(2400))
+            .is_none());
+        assert_eq!(lucky.last_success(), Some(at(2400)));
+    }
+
+    #[tokio::test]
+    async fn test_attendance_calendar() {
+        async fn mount_calendar(attendances: &str, units: &str) -> MockServer {
+            let server = MockServer::start().await;
+            for (route, body) in [
+                ("/Attendances", attendances),
+                (
+                    "/Attendances/FilledByTeacher",
+                    include_str!("../tests/fixtures/attendances_filled_by_teacher.json"),
+                ),
+                ("/Units", units),
+            ] {
+                Mock::given(method("GET"))
+                    .and(path(route))
+                    .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                    .mount(&server)
+                    .await;
+            }
+            server
+        }
+        let attendances = include_str!("../tests/fixtures/attendances.json");
+        let units = include_str!("../tests/fixtures/units.json");
+
+        // The school has hours for lessons 1 to 8, so every day has 8 slots.
+        let server = mount_calendar(attendances, units).await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        let view = client
+            .attendance_calendar("2025-03-04", "2025-03-05")
+            .await
+            .unwrap();
+        assert_eq!(view.first_lesson, 1);
+        assert_eq!(view.days.len(), 2);
+        let (date, slots) = view.days.iter().next().unwrap();
+        assert_eq!((date.as_str(), slots.len()), ("2025-03-04", 8));
+        assert!(matches!(&slots[0], DaySlot::Recorded(record) if record.lesson_no == 1));
+        assert!(slots[1..]
+            .iter()
+            .all(|slot| matches!(slot, DaySlot::NoLesson)));
+        assert!(view.outside_range.is_empty());
+
+        assert!(matches!(
+            client.attendance_calendar("2025-03-04", "wtorek").await,
+            Err(Error::InvalidDate(_))
+        ));
+
+        // A bogus lesson number is set aside instead of stretching every day.
+        let bogus = attendances.replace("\"LessonNo\": 3", "\"LessonNo\": 9999");
+        let server = mount_calendar(&bogus, units).await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        let view = client
+            .attendance_calendar("2025-03-04", "2025-03-05")
+            .await
+            .unwrap();
+        assert!(view.days.values().all(|slots| slots.len() == 8));
+        assert_eq!(view.outside_range.len(), 1);
+        assert_eq!(view.outside_range[0].lesson_no, 9999);
+
+        // Without lesson hours the slots run to the last lesson filled in
+        // within the window; lesson 12 was filled in outside it.
+        let no_hours = r#"{"Units": [{"Id": 3, "LessonsRange": []}], "Url": ""}"#;
+        let server = mount_calendar(attendances, no_hours).await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        let view = client
+            .attendance_calendar("2025-03-04", "2025-03-05")
+            .await
+            .unwrap();
+        assert!(view.days.values().all(|slots| slots.len() == 3));
+    }
+
+    #[tokio::test]
+    async fn test_school_notice_by_id() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/SchoolNotices/2025%2F03%20zebranie"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/school_notice.json")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/SchoolNotices/missing"))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_string(include_str!("../tests/fixtures/not_found.json")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/SchoolNotices/broken"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+            .mount(&server)
+            .await;
+        let client = mock_client(ClientBuilder::new(), &server);
+
+        let notice = client
+            .school_notice("2025/03 zebranie")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(notice.id, "2025/03 zebranie");
+        assert_eq!(notice.subject, "Zebranie z rodzicami");
+
+        assert!(client.school_notice("missing").await.unwrap().is_none());
+        // A 404 without the not-found envelope, e.g. from a proxy, is an error.
+        assert!(matches!(
+            client.school_notice("broken").await,
+            Err(Error::ApiError { status: 404, .. })
+        ));
+    }
+
+    /// Mounts a Grades endpoint that reports a session takeover once, then
+    /// succeeds.
+    async fn mount_takeover(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/Grades"))
+            .respond_with(ResponseTemplate::new(401).set_body_string(
+                r#"{"Status":"Error","Code":"SessionTakenOver","Message":"Logged in elsewhere"}"#,
+            ))
+            .up_to_n_times(1)
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Grades"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/grades.json")),
+            )
+            .mount(server)
+            .await;
+        mount_login(
+            server,
+            include_str!("../tests/fixtures/login_ok.json"),
+            200,
+            200,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_session_takeover_fails_by_default() {
+        let server = MockServer::start().await;
+        mount_takeover(&server).await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        let err = client.grades().await.unwrap_err();
+        assert!(matches!(err, Error::SessionTakenOver), "{err:?}");
+        assert_eq!(err.api(), Some(ApiKind::Synergia));
+        assert!(posted_logins(&server).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_session_takeover_reauth() {
+        let server = MockServer::start().await;
+        mount_takeover(&server).await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.reauth = Some(Credentials {
+            username: "user".to_string(),
+            password: "pass".to_string(),
+        });
+        assert_eq!(client.grades().await.unwrap().grades.len(), 2);
+        assert_eq!(posted_logins(&server).await, ["user"]);
+    }
+
+    #[test]
+    fn test_credentials_zeroize() {
+        let mut credentials = Credentials {
+            username: "1234567u".to_string(),
+            password: "tajne-hasło".to_string(),
+        };
+        let (ptr, capacity) = (
+            credentials.password.as_ptr(),
+            credentials.password.capacity(),
+        );
+        credentials.zeroize();
+        assert!(credentials.username.is_empty());
+        assert!(credentials.password.is_empty());
+        // The allocation is kept, so the old bytes can be checked in place.
+        assert_eq!(credentials.password.capacity(), capacity);
+        let buffer = unsafe { std::slice::from_raw_parts(ptr, capacity) };
+        assert!(buffer.iter().all(|&byte| byte == 0));
+    }
+
+    #[tokio::test]
+    async fn test_login_redirect_is_expired_session() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Me"))
+            .respond_with(
+                ResponseTemplate::new(302).insert_header("Location", "/loguj/portalRodzina"),
+            )
+            .mount(&server)
+            .await;
+        // API requests do not follow redirects.
+        Mock::given(method("GET"))
+            .and(path("/loguj/portalRodzina"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        assert!(matches!(client.me().await, Err(Error::SessionExpired)));
+    }
+
+    #[tokio::test]
+    async fn test_takeover_redirect_is_takeover() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Me"))
+            .respond_with(ResponseTemplate::new(302).insert_header(
+                "Location",
+                "/loguj/portalRodzina?reason=SessionTakenOver",
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Grades"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("Location", "/loguj/portalRodzina")
+                    .set_body_json(serde_json::json!({"Code": "TokenIsRevoked"})),
+            )
+            .mount(&server)
+            .await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        assert!(matches!(client.me().await, Err(Error::SessionTakenOver)));
+        assert!(matches!(client.grades().await, Err(Error::SessionTakenOver)));
+    }
+
+    #[tokio::test]
+    async fn test_messages_redirect_is_expired_session() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/inbox/unreadMessagesCount"))
+            .respond_with(
+                ResponseTemplate::new(302).insert_header("Location", "/loguj/portalRodzina"),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/loguj/portalRodzina"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+            .expect(0)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/wiadomosci3"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        assert!(matches!(
+            client.unread_counts().await,
+            Err(Error::SessionExpired)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_auth_redirects_are_recorded() {
+        let server = MockServer::start().await;
+        // An interstitial before the login form; mounted before
+        // `mount_login`, so it answers first.
+        Mock::given(method("GET"))
+            .and(path("/loguj/portalRodzina"))
+            .respond_with(ResponseTemplate::new(302).insert_header("Location", "/zgody?krok=1"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/zgody"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        mount_login(
+            &server,
+            include_str!("../tests/fixtures/login_ok.json"),
+            200,
+            200,
+        )
+        .await;
+
+        let client = ClientBuilder::new()
+            .username("1234567u")
+            .password("p")
+            .trace_auth()
+            .build_with(mock_endpoints(&server))
+            .await
+            .unwrap();
+        let trace = client.last_auth_trace().unwrap();
+        let zgody = format!("{}/zgody", server.uri());
+        assert_eq!(trace.steps()[0].hops, [zgody.as_str()]);
+        assert_eq!(trace.steps()[0].redirect, Some(zgody.clone()));
+        assert!(trace.steps()[1..].iter().all(|step| step.hops.is_empty()));
+        assert!(trace.to_string().contains(&format!("via {zgody}")));
+    }
+
+    #[tokio::test]
+    async fn test_auth_redirect_off_librus_hosts() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/loguj/portalRodzina"))
+            .respond_with(ResponseTemplate::new(302).insert_header(
+                "Location",
+                "https://consent.example.com/cmp?return=%2Floguj",
+            ))
+            .mount(&server)
+            .await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        match client.log_in("user", "pass").await {
+            Err(e @ Error::UnexpectedRedirect { .. }) => {
+                assert_eq!(e.code(), ErrorCode::UnexpectedRedirect);
+                assert_eq!(
+                    e.to_string(),
+                    "unexpected redirect to https://consent.example.com/cmp"
+                );
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        let endpoints = mock_endpoints(&server);
+        for url in [
+            "https://api.librus.pl/OAuth/Authorization/Grant",
+            "https://wiadomosci.librus.pl/api/",
+            &format!("{}/elsewhere", server.uri()),
+        ] {
+            assert!(endpoints.allows_redirect_to(&url.parse().unwrap()), "{url}");
+        }
+        for url in ["https://librus.pl.example.com/", "http://127.0.0.1:1/"] {
+            assert!(
+                !endpoints.allows_redirect_to(&url.parse().unwrap()),
+                "{url}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth_redirect_loop_is_capped() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/loguj/portalRodzina"))
+            .respond_with(ResponseTemplate::new(302).insert_header("Location", "/petla"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/petla"))
+            .respond_with(ResponseTemplate::new(302).insert_header("Location", "/petla"))
+            .expect(redirects::MAX_HOPS as u64)
+            .mount(&server)
+            .await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        assert!(matches!(
+            client.log_in("user", "pass").await,
+            Err(Error::GrantFailed {
+                step: AuthStep::Init,
+                status: 302
+            })
+        ));
+    }
+
+    /// Mounts an inbox of messages "1" to "5", listed two per page.
+    async fn mount_mailbox(server: &MockServer) {
+        use wiremock::matchers::query_param;
+        let ids = ["1", "2", "3", "4", "5"];
+        for (page, chunk) in ids.chunks(2).enumerate() {
+            let data: Vec<_> = chunk
+                .iter()
+                .map(|id| {
+                    serde_json::json!({
+                        "messageId": id, "senderFirstName": "Jan", "senderLastName": "Nowak",
+                        "senderName": "Jan Nowak", "topic": format!("Temat {id}"), "content": "",
+                        "sendDate": "2025-03-10 08:00:00", "readDate": null,
+                        "isAnyFileAttached": false, "tags": [], "category": null
+                    })
+                })
+                .collect();
+            Mock::given(method("GET"))
+                .and(path("/inbox/messages"))
+                .and(query_param("page", (page + 1).to_string()))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": data })),
+                )
+                .mount(server)
+                .await;
+        }
+        for id in ids {
+            let detail = serde_json::json!({ "data": {
+                "messageId": id, "senderFirstName": "Jan", "senderLastName": "Nowak",
+                "senderName": "Jan Nowak", "topic": format!("Temat {id}"),
+                "Message": "Q3plxZvEhw==", "sendDate": "2025-03-10 08:00:00", "readDate": null,
+                "attachments": [{"id": format!("a{id}"), "name": "plan.pdf", "size": 4}]
+            }});
+            Mock::given(method("GET"))
+                .and(path(format!("/inbox/messages/{id}")))
+                .respond_with(ResponseTemplate::new(200).set_body_json(detail))
+                .mount(server)
+                .await;
+        }
+    }
+
+    async fn detail_requests(server: &MockServer) -> usize {
+        let requests = server.received_requests().await.unwrap();
+        requests
+            .iter()
+            .filter(|r| r.url.path().starts_with("/inbox/messages/"))
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_export_messages_jsonl() {
+        let server = MockServer::start().await;
+        mount_mailbox(&server).await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+
+        let mut out = Vec::new();
+        let opts = ExportOptions::new().page_size(2);
+        let summary = client
+            .export_messages_jsonl(MessageFolder::Inbox, &mut out, &opts)
+            .await
+            .unwrap();
+        assert_eq!(summary.exported, 5);
+        assert_eq!(summary.last_message_id.as_deref(), Some("5"));
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0]["folder"], "inbox");
+        assert_eq!(lines[0]["message_id"], "1");
+        assert_eq!(lines[0]["body"], "Cześć");
+        assert_eq!(lines[0]["attachments"][0]["name"], "plan.pdf");
+        assert!(lines[0]["attachments"][0].get("data").is_none());
+    }
+
+    /// An inbox of `total` messages whose listing returns at most `cap`
+    /// messages per page, whatever limit is requested.
+    struct CappedMailbox {
+        total: usize,
+        cap: usize,
+    }
+
+    impl wiremock::Respond for CappedMailbox {
+        fn respond(&self, request: &wiremock::Request) -> ResponseTemplate {
+            if let Some(id) = request.url.path().strip_prefix("/inbox/messages/") {
+                return ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": {
+                    "messageId": id, "senderFirstName": "Jan", "senderLastName": "Nowak",
+                    "senderName": "Jan Nowak", "topic": "Temat", "Message": "",
+                    "sendDate": "2025-03-10 08:00:00", "readDate": null, "attachments": []
+                }}));
+            }
+            let param = |name: &str| -> usize {
+                request
+                    .url
+                    .query_pairs()
+                    .find(|(k, _)| k == name)
+                    .and_then(|(_, v)| v.parse().ok())
+                    .unwrap()
+            };
+            let limit = param("limit").min(self.cap);
+            let start = (param("page") - 1) * limit;
+            let data: Vec<_> = (start..(start + limit).min(self.total))
+                .map(|i| {
+                    serde_json::json!({
+                        "messageId": (i + 1).to_string(), "senderFirstName": "Jan",
+                        "senderLastName": "Nowak", "senderName": "Jan Nowak", "topic": "Temat",
+                        "content": "", "sendDate": "2025-03-10 08:00:00", "readDate": null,
+                        "isAnyFileAttached": false, "tags": []
+                    })
+                })
+                .collect();
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "data": data, "total": self.total }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_adapts_to_server_page_cap() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(CappedMailbox {
+                total: 120,
+                cap: 50,
+            })
+            .mount(&server)
+            .await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        client.messages_page_size = 200;
+        assert_eq!(client.detected_message_page_cap(), None);
+
+        let mut out = Vec::new();
+        let summary = client
+            .export_messages_jsonl(MessageFolder::Inbox, &mut out, &ExportOptions::new())
+            .await
+            .unwrap();
+        assert_eq!(summary.exported, 120);
+        assert_eq!(summary.last_message_id.as_deref(), Some("120"));
+        assert_eq!(client.detected_message_page_cap(), Some(50));
+
+        let requests = server.received_requests().await.unwrap();
+        let listings: Vec<_> = requests
+            .iter()
+            .filter(|r| r.url.path() == "/inbox/messages")
+            .map(|r| r.url.query().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            listings,
+            ["page=1&limit=200", "page=2&limit=50", "page=3&limit=50"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_messages_jsonl_resumes() {
+        let server = MockServer::start().await;
+        mount_mailbox(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/attachments/a5/messages/5"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF".to_vec()))
+            .mount(&server)
+            .await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+
+        let mut out = Vec::new();
+        let opts = ExportOptions::new()
+            .page_size(2)
+            .since_message_id("4")
+            .download_attachments();
+        let summary = client
+            .export_messages_jsonl(MessageFolder::Inbox, &mut out, &opts)
+            .await
+            .unwrap();
+        assert_eq!(summary.exported, 1);
+        // Already exported messages are skipped without fetching their details.
+        assert_eq!(detail_requests(&server).await, 1);
+
+        let line: serde_json::Value =
+            serde_json::from_str(String::from_utf8(out).unwrap().trim_end()).unwrap();
+        assert_eq!(line["message_id"], "5");
+        assert_eq!(line["attachments"][0]["data"], "JVBERg==");
+
+        // A resume point that is gone is reported, not taken as the end.
+        let mut out = Vec::new();
+        let opts = ExportOptions::new().page_size(2).since_message_id("99");
+        match client
+            .export_messages_jsonl(MessageFolder::Inbox, &mut out, &opts)
+            .await
+        {
+            Err(Error::ResumePointNotFound { message_id }) => assert_eq!(message_id, "99"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+        assert!(out.is_empty());
+    }
+
+    /// Collects written lines and cancels `token` once `after` lines were
+    /// written, like a shutdown signal arriving during an export.
+    struct CancellingWriter {
+        out: Vec<u8>,
+        token: CancellationToken,
+        after: usize,
+    }
+
+    impl tokio::io::AsyncWrite for CancellingWriter {
+        fn poll_write(
+            mut self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            self.out.extend_from_slice(buf);
+            if self.out.iter().filter(|&&b| b == b'\n').count() >= self.after {
+                self.token.cancel();
+            }
+            std::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    /// Exports the mailbox of [`mount_mailbox()`], cancelling after `after`
+    /// lines. Returns the summary, the output and the page listings made.
+    async fn cancelled_export(after: usize) -> (ExportSummary, String, usize, usize) {
+        let server = MockServer::start().await;
+        mount_mailbox(&server).await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+
+        let token = CancellationToken::new();
+        if after == 0 {
+            token.cancel();
+        }
+        let mut writer = CancellingWriter {
+            out: Vec::new(),
+            token: token.clone(),
+            after,
+        };
+        let opts = ExportOptions::new().page_size(2).cancel_on(token);
+        let summary = client
+            .export_messages_jsonl(MessageFolder::Inbox, &mut writer, &opts)
+            .await
+            .unwrap();
+        let requests = server.received_requests().await.unwrap();
+        let listings = requests
+            .iter()
+            .filter(|r| r.url.path() == "/inbox/messages")
+            .count();
+        let out = String::from_utf8(writer.out).unwrap();
+        (summary, out, listings, detail_requests(&server).await)
+    }
+
+    #[tokio::test]
+    async fn test_export_cancelled_before_start() {
+        let (summary, out, listings, details) = cancelled_export(0).await;
+        assert!(summary.cancelled);
+        assert_eq!(summary.exported, 0);
+        assert!(out.is_empty());
+        assert_eq!((listings, details), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_export_cancelled_after_first_message() {
+        let (summary, out, listings, details) = cancelled_export(1).await;
+        assert!(summary.cancelled);
+        assert_eq!(summary.exported, 1);
+        assert_eq!(summary.last_message_id.as_deref(), Some("1"));
+        // The second message of the page is not fetched.
+        assert_eq!((listings, details), (1, 1));
+        // The output ends with a complete line.
+        assert!(out.ends_with('\n'));
+        let line: serde_json::Value = serde_json::from_str(out.trim_end()).unwrap();
+        assert_eq!(line["message_id"], "1");
+    }
+
+    #[tokio::test]
+    async fn test_export_cancelled_between_pages() {
+        let (summary, out, listings, details) = cancelled_export(2).await;
+        assert!(summary.cancelled);
+        assert_eq!(summary.exported, 2);
+        assert_eq!(summary.last_message_id.as_deref(), Some("2"));
+        // The second page is not listed.
+        assert_eq!((listings, details), (1, 2));
+        assert_eq!(out.lines().count(), 2);
+
+        // Without cancellation the summary says the export finished.
+        let (summary, _, listings, _) = cancelled_export(usize::MAX).await;
+        assert!(!summary.cancelled);
+        assert_eq!((summary.exported, listings), (5, 3));
+    }
+
+    #[tokio::test]
+    async fn test_purge_local_data() {
+        use storage::{MemoryStorage, Storage, StorageKind};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Me"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/me.json")),
+            )
+            .mount(&server)
+            .await;
+        let client = mock_client(ClientBuilder::new(), &server);
+
+        let store = MemoryStorage::new();
+        let mut tracker = ChangeTracker::new();
+        tracker.update_grades(&[]);
+        for (kind, name, value) in [
+            (StorageKind::Cache, "Grades", b"{}".to_vec()),
+            (StorageKind::Session, "cookies", b"DZIENNIKSID=x".to_vec()),
+            (StorageKind::Tracker, "grades", tracker.to_bytes()),
+        ] {
+            store.put(&storage::key(900, kind, name), &value).unwrap();
+        }
+        let report = client.purge_local_data(&store).await.unwrap();
+        assert_eq!(report.removed.len(), 3);
+        assert_eq!(report.count(StorageKind::Tracker), 1);
+        assert!(store.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_archived_messages_require_messages_plus() {
+        let server = MockServer::start().await;
+        let me = include_str!("../tests/fixtures/me.json");
+        Mock::given(method("GET"))
+            .and(path("/Me"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(me))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Me"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(me.replace(
+                "\"PremiumAddons\": []",
+                "\"PremiumAddons\": [\"MessagesPlus\"]",
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/archive/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/inbox_messages.json")),
+            )
+            .mount(&server)
+            .await;
+
+        // Without the add-on the folder is not requested.
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        match client.archived_messages(1, 10).await {
+            Err(Error::PremiumRequired(addon)) => assert_eq!(addon, "MessagesPlus"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests.iter().all(|r| r.url.path() == "/Me"));
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        assert_eq!(client.archived_messages(1, 10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_last_login() {
+        let server = MockServer::start().await;
+        let me = include_str!("../tests/fixtures/me.json");
+        Mock::given(method("GET"))
+            .and(path("/Me"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(me))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Me"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(me.replace(
+                "\"PremiumAddons\": []",
+                "\"PremiumAddons\": [], \"LastLogin\": \"2025-07-01 10:00:00\"",
+            )))
+            .mount(&server)
+            .await;
+        let client = mock_client(ClientBuilder::new(), &server);
+
+        assert_eq!(client.last_login().await.unwrap(), None);
+        let last_login = client.last_login().await.unwrap().unwrap();
+        // Summer time: 10:00 in Warsaw is 08:00 UTC.
+        assert_eq!(unix_seconds(last_login), 1_751_356_800);
+    }
+
+    #[tokio::test]
+    async fn test_messages_init_redirected_to_error_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/wiadomosci3"))
+            .respond_with(ResponseTemplate::new(302).insert_header("Location", "/blad"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/blad"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html>Błąd</html>"))
+            .mount(&server)
+            .await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+
+        let err = client.unread_counts().await.unwrap_err();
+        match &err {
+            Error::MessagesUnavailable { reason } => assert!(reason.contains("/blad")),
+            other => panic!("expected MessagesUnavailable, got {other:?}"),
+        }
+        assert_eq!(err.api(), Some(ApiKind::Messages));
+        assert!(!client.messages_initialized);
     }
 
-    /// Formats API-provided HTML content into readable text.
-    ///
-    /// School notices (announcements) are often HTML-formatted. This helper removes tags
-    /// and performs a minimal entity decode to make the content readable.
-    ///
-    /// # Example
-    ///
-    /// ```rust
-    /// use librus_rs::Client;
-    ///
-    /// let html = "<p>Hello&nbsp;<b>World</b> &amp; friends</p>";
-    /// let text = Client::notice_content_to_text(html);
-    /// assert_eq!(text, "Hello World & friends");
-    /// ```
-    pub fn notice_content_to_text(content: &str) -> String {
-        let mut out = String::with_capacity(content.len());
-        let mut in_tag = false;
-
-        for ch in content.chars() {
-            match ch {
-                '<' => in_tag = true,
-                '>' => in_tag = false,
-                _ if !in_tag => out.push(ch),
-                _ => {}
+    #[tokio::test]
+    async fn test_messages_init_redirect_to_app_is_accepted() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/wiadomosci3"))
+            .respond_with(ResponseTemplate::new(302).insert_header("Location", "/module"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/module"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/inbox/unreadMessagesCount"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/unread_counts.json")),
+            )
+            .mount(&server)
+            .await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+
+        assert!(client.messages_available().await);
+        assert!(client.messages_initialized);
+    }
+
+    #[tokio::test]
+    async fn test_messages_module_disabled() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/wiadomosci3"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+
+        let err = client.unread_counts().await.unwrap_err();
+        assert!(matches!(err, Error::MessagesUnavailable { .. }));
+        assert_eq!(err.code(), ErrorCode::MessagesUnavailable);
+        assert!(!client.messages_initialized);
+        // Not marked as initialized, so the next call tries again.
+        assert!(client.unread_counts().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_messages_disabled_for_account_is_remembered() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/wiadomosci3"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .set_body_string("<html><p>Moduł Wiadomości niedostępny</p></html>"),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+
+        for _ in 0..3 {
+            let err = client.unread_counts().await.unwrap_err();
+            assert!(matches!(err, Error::MessagesDisabledForAccount), "{err:?}");
+            assert_eq!(err.api(), Some(ApiKind::Messages));
+        }
+        assert!(matches!(
+            client.inbox_messages(1, 10).await,
+            Err(Error::MessagesDisabledForAccount)
+        ));
+        assert!(!client.messages_available().await);
+        assert_eq!(
+            client.messages_availability(),
+            MessagesAvailability::Disabled
+        );
+        // Only the recheck asks the server again.
+        assert_eq!(
+            client.recheck_messages_availability().await,
+            MessagesAvailability::Disabled
+        );
+    }
+
+    #[tokio::test]
+    async fn test_messages_outage_is_classified() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/wiadomosci3"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/inbox/unreadMessagesCount"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("{}"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Subjects"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/subjects.json")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Lessons"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("{}"))
+            .mount(&server)
+            .await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+
+        assert!(!client.messages_available().await);
+        assert!(client.subjects().await.is_ok());
+
+        client.messages_initialized = true;
+        let err = client.unread_counts().await.unwrap_err();
+        assert!(matches!(err, Error::ApiError { status: 503, .. }));
+        assert_eq!(err.api(), Some(ApiKind::Messages));
+
+        let err = client.lessons().await.unwrap_err();
+        assert_eq!(err.api(), Some(ApiKind::Synergia));
+    }
+
+    #[tokio::test]
+    async fn test_login_grant_redirected_to_oauth_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/OAuth/Authorization/Grant"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("Location", "/OAuth/Error?error=access_denied"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/OAuth/Error"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+            .mount(&server)
+            .await;
+        mount_login(
+            &server,
+            include_str!("../tests/fixtures/login_ok.json"),
+            200,
+            200,
+        )
+        .await;
+        assert!(matches!(
+            mock_login(&server).await,
+            Err(Error::GrantFailed {
+                step: AuthStep::Grant,
+                status: 200
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_now_and_next_rolls_over_to_next_week() {
+        use wiremock::matchers::query_param;
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/SystemData"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"Date":"2024-03-12","Time":"09:00:00"}"#),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Timetables"))
+            .and(query_param("weekStart", "2024-03-11"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/timetable_week.json")),
+            )
+            .mount(&server)
+            .await;
+        let next_week = include_str!("../tests/fixtures/timetable_week.json")
+            .replace("2024-03-11", "2024-03-18")
+            .replace("2024-03-12", "2024-03-19");
+        Mock::given(method("GET"))
+            .and(path("/Timetables"))
+            .and(query_param("weekStart", "2024-03-18"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(next_week))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(ClientBuilder::new(), &server);
+        let lessons = client.now_and_next().await.unwrap();
+        assert!(lessons.current.is_none());
+        assert_eq!(lessons.next_date.unwrap(), "2024-03-18");
+        assert_eq!(lessons.next.unwrap().subject.unwrap().name, "Matematyka");
+    }
+
+    #[tokio::test]
+    async fn test_daily_digest_keeps_going_after_failed_section() {
+        let server = MockServer::start().await;
+        let homeworks =
+            include_str!("../tests/fixtures/homeworks.json").replace("2025-03-14", "2025-10-07");
+        for (endpoint, body) in [
+            (
+                "/Grades",
+                include_str!("../tests/fixtures/grades.json").to_string(),
+            ),
+            (
+                "/Subjects",
+                include_str!("../tests/fixtures/subjects.json").to_string(),
+            ),
+            ("/HomeWorks", homeworks),
+            (
+                "/inbox/messages",
+                include_str!("../tests/fixtures/inbox_messages.json").to_string(),
+            ),
+            (
+                "/LuckyNumbers",
+                r#"{"LuckyNumber": {"LuckyNumber": 13, "LuckyNumberDay": "2025-10-06"}}"#
+                    .to_string(),
+            ),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(endpoint))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/Calendars/Substitutions"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let digest = digest::daily_digest(&mut client, "2025-10-06", &digest::DigestOptions::new())
+            .await
+            .unwrap();
+
+        let grades = digest.new_grades.unwrap();
+        assert_eq!(grades.len(), 1);
+        assert_eq!(grades[0].subject.as_deref(), Some("Matematyka"));
+        let homework = digest.homework_tomorrow.unwrap();
+        assert_eq!(homework.len(), 1);
+        assert_eq!(homework[0].content, "Sprawdzian z ułamków");
+        assert!(digest.substitutions.is_none());
+        assert_eq!(digest.errors.len(), 1);
+        assert_eq!(
+            digest.errors[0].section,
+            digest::DigestSection::Substitutions
+        );
+        assert_eq!(digest.unread_senders.unwrap(), ["Anna Nowak"]);
+        assert_eq!(digest.lucky_number, Some(Some(13)));
+
+        assert!(matches!(
+            digest::daily_digest(&mut client, "jutro", &digest::DigestOptions::new()).await,
+            Err(Error::InvalidDate(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_daily_digest_reports_missing_subjects() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Grades"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/grades.json")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Subjects"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let digest = digest::daily_digest(&mut client, "2025-10-06", &digest::DigestOptions::new())
+            .await
+            .unwrap();
+        // The grade is still listed, and the digest says why it is unnamed.
+        let grades = digest.new_grades.unwrap();
+        assert_eq!(grades.len(), 1);
+        assert_eq!(grades[0].subject, None);
+        assert!(digest
+            .errors
+            .iter()
+            .any(|e| e.section == digest::DigestSection::Subjects));
+    }
+
+    #[derive(Default)]
+    struct MemorySink {
+        /// Sections in the order they were finished.
+        sections: Vec<snapshot::SectionName>,
+        files: std::collections::HashMap<snapshot::SectionName, Vec<u8>>,
+        manifests: Vec<snapshot::SnapshotManifest>,
+        /// Cancelled once a section is finished, like a shutdown signal
+        /// arriving during a snapshot.
+        cancel: Option<CancellationToken>,
+    }
+
+    impl snapshot::SnapshotSink for MemorySink {
+        type Section = Vec<u8>;
+        type Reader = std::io::Cursor<Vec<u8>>;
+
+        async fn create_section(&mut self, _: snapshot::SectionName) -> std::io::Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn finish_section(
+            &mut self,
+            section: snapshot::SectionName,
+            writer: Vec<u8>,
+        ) -> std::io::Result<()> {
+            self.sections.push(section);
+            self.files.insert(section, writer);
+            if let Some(token) = &self.cancel {
+                token.cancel();
             }
+            Ok(())
         }
 
-        // Minimal entity decoding for common cases.
-        let out = out
-            .replace("&nbsp;", " ")
-            .replace("&amp;", "&")
-            .replace("&lt;", "<")
-            .replace("&gt;", ">")
-            .replace("&quot;", "\"")
-            .replace("&#39;", "'");
+        async fn open_section(
+            &mut self,
+            section: snapshot::SectionName,
+        ) -> std::io::Result<Option<Self::Reader>> {
+            Ok(self.files.get(&section).cloned().map(std::io::Cursor::new))
+        }
 
-        out.trim().to_string()
+        async fn write_manifest(
+            &mut self,
+            manifest: &snapshot::SnapshotManifest,
+        ) -> std::io::Result<()> {
+            self.manifests.push(manifest.clone());
+            Ok(())
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use base64::Engine;
+    #[tokio::test]
+    async fn test_snapshot_writer_resumes_after_interruption() {
+        use snapshot::{SectionName, SnapshotWriter};
+
+        let server = MockServer::start().await;
+        for (endpoint, body) in [
+            ("/Grades", include_str!("../tests/fixtures/grades.json")),
+            (
+                "/Attendances",
+                include_str!("../tests/fixtures/attendances.json"),
+            ),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(endpoint))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/HomeWorks"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let mut writer = SnapshotWriter::new(MemorySink::default());
+        assert!(writer.write(&mut client).await.is_err());
+        let manifest = writer.manifest().clone();
+        let mut sink = writer.into_sink();
+        assert_eq!(
+            sink.sections,
+            [SectionName::Grades, SectionName::Attendances]
+        );
+        // The manifest left behind lists exactly the completed sections.
+        assert_eq!(sink.manifests.last(), Some(&manifest));
+        assert!(!manifest.complete);
+        let grades = manifest.entry(SectionName::Grades).unwrap();
+        assert_eq!(grades.count, 2);
+        assert_eq!(grades.checksum.len(), 16);
+        let json: serde_json::Value =
+            serde_json::from_slice(&sink.files[&SectionName::Grades]).unwrap();
+        assert_eq!(json.as_array().map(Vec::len), Some(2));
+
+        // Grades and attendances are no longer served: resuming must skip them.
+        server.reset().await;
+        for (endpoint, body) in [
+            (
+                "/HomeWorks",
+                include_str!("../tests/fixtures/homeworks.json"),
+            ),
+            (
+                "/SchoolNotices",
+                include_str!("../tests/fixtures/school_notices.json"),
+            ),
+            (
+                "/inbox/messages",
+                include_str!("../tests/fixtures/inbox_messages.json"),
+            ),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(endpoint))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
+        }
+        sink.sections.clear();
+        let mut writer = SnapshotWriter::resume(manifest.clone(), sink);
+        let done = writer.write(&mut client).await.unwrap().clone();
+        assert!(done.complete);
+        assert_eq!(
+            done.sections.iter().map(|e| e.section).collect::<Vec<_>>(),
+            SectionName::ALL
+        );
+        assert_eq!(done.sections[..2], manifest.sections[..]);
+        assert_eq!(done.entry(SectionName::Messages).unwrap().count, 2);
+        assert_eq!(
+            writer.into_sink().sections,
+            [
+                SectionName::Homeworks,
+                SectionName::Notices,
+                SectionName::Messages
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_writer_cancelled_after_first_section() {
+        use snapshot::{SectionName, SnapshotWriter};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Grades"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/grades.json")),
+            )
+            .mount(&server)
+            .await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+
+        let token = CancellationToken::new();
+        let sink = MemorySink {
+            cancel: Some(token.clone()),
+            ..MemorySink::default()
+        };
+        let mut writer = SnapshotWriter::new(sink).cancel_on(token);
+        let manifest = writer.write(&mut client).await.unwrap().clone();
+        assert!(!manifest.complete);
+        assert_eq!(
+            manifest
+                .sections
+                .iter()
+                .map(|e| e.section)
+                .collect::<Vec<_>>(),
+            [SectionName::Grades]
+        );
+        let sink = writer.into_sink();
+        assert_eq!(sink.sections, [SectionName::Grades]);
+        assert_eq!(sink.manifests.last(), Some(&manifest));
+        // Only the first section was fetched.
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_writer_refetches_damaged_sections() {
+        use snapshot::{SectionName, SnapshotWriter};
+
+        let server = MockServer::start().await;
+        for (endpoint, body) in [
+            ("/Grades", include_str!("../tests/fixtures/grades.json")),
+            (
+                "/Attendances",
+                include_str!("../tests/fixtures/attendances.json"),
+            ),
+            (
+                "/HomeWorks",
+                include_str!("../tests/fixtures/homeworks.json"),
+            ),
+            (
+                "/SchoolNotices",
+                include_str!("../tests/fixtures/school_notices.json"),
+            ),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(endpoint))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/inbox/messages"))
+            .respond_with(CappedMailbox {
+                total: 120,
+                cap: 50,
+            })
+            .mount(&server)
+            .await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        client.messages_page_size = 200;
+
+        let mut writer = SnapshotWriter::new(MemorySink::default());
+        let manifest = writer.write(&mut client).await.unwrap().clone();
+        // Every page past the server's cap is fetched.
+        assert_eq!(manifest.entry(SectionName::Messages).unwrap().count, 120);
+        assert_eq!(client.detected_message_page_cap(), Some(50));
+
+        // A truncated section fails its checksum and is fetched again.
+        let mut sink = writer.into_sink();
+        sink.sections.clear();
+        sink.files
+            .get_mut(&SectionName::Grades)
+            .unwrap()
+            .truncate(10);
+        let mut writer = SnapshotWriter::resume(manifest.clone(), sink);
+        let done = writer.write(&mut client).await.unwrap().clone();
+        assert!(done.complete);
+        assert_eq!(
+            done.entry(SectionName::Grades),
+            manifest.entry(SectionName::Grades)
+        );
+        assert_eq!(writer.into_sink().sections, [SectionName::Grades]);
+    }
+
+    #[tokio::test]
+    async fn test_clock_skew_from_date_header() {
+        const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+        const MONTHS: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        let ahead = unix_seconds(SystemTime::now()) + 90;
+        let day = ahead.div_euclid(86_400);
+        let secs = ahead.rem_euclid(86_400);
+        let ymd = dates::format_day(day);
+        let (y, m, d) = (&ymd[..4], &ymd[5..7], &ymd[8..]);
+        let http_date = format!(
+            "{}, {d} {} {y} {:02}:{:02}:{:02} GMT",
+            DAYS[dates::weekday(day) as usize],
+            MONTHS[m.parse::<usize>().unwrap() - 1],
+            secs / 3_600,
+            secs / 60 % 60,
+            secs % 60,
+        );
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/SystemData"))
+            .respond_with(ResponseTemplate::new(404).insert_header("Date", http_date.as_str()))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(ClientBuilder::new(), &server);
+        let skew = client.clock_skew().await.unwrap();
+        assert!((89..=91).contains(&skew), "skew was {skew}");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn test_gzipped_grades_are_decompressed() {
+        use wiremock::matchers::header_regex;
+
+        let server = MockServer::start().await;
+        let body = include_str!("../tests/fixtures/grades.json");
+        Mock::given(method("GET"))
+            .and(path("/Grades"))
+            .and(header_regex("accept-encoding", "gzip"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Encoding", "gzip")
+                    .set_body_bytes(gzip(body.as_bytes())),
+            )
+            .mount(&server)
+            .await;
+
+        let client = mock_client(ClientBuilder::new(), &server);
+        let grades = client.grades().await.unwrap();
+        assert_eq!(grades.grades.len(), 2);
+        assert_eq!(grades.grades[0].grade, "5");
+    }
+
+    #[tokio::test]
+    async fn test_school_year_from_class() {
+        let server = MockServer::start().await;
+        for (endpoint, body) in [
+            ("/Me", include_str!("../tests/fixtures/me.json")),
+            ("/Classes/12", include_str!("../tests/fixtures/class.json")),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(endpoint))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
+        }
+        let client = mock_client(ClientBuilder::new(), &server);
+        assert_eq!(client.school_year().await.unwrap(), Some(SchoolYear(2024)));
+    }
+
+    #[tokio::test]
+    async fn test_grade_by_id() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Grades/1001"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/grade.json")),
+            )
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/Grades/9999"))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_string(include_str!("../tests/fixtures/not_found.json")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Grades/5000"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(ClientBuilder::new(), &server);
+        let grade = client.grade(1001).await.unwrap().grade.unwrap();
+        assert_eq!(grade.id, 1001);
+
+        let missing = client.grade(9999).await.unwrap();
+        assert!(missing.grade.is_none());
+        assert_eq!(missing.url, format!("{}/Grades/9999", server.uri()));
+        // Only the gateway's not-found envelope means the grade is missing.
+        assert!(matches!(
+            client.grade(5000).await,
+            Err(Error::ApiError { status: 404, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_follow_resource_url() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Grades/1001"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/grade.json")),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(ClientBuilder::new(), &server);
+        for url in ["https://api.librus.pl/2.0/Grades/1001", "Grades/1001"] {
+            let grade: ResponseGrade = client.follow(&url.into()).await.unwrap();
+            assert_eq!(grade.grade.unwrap().id, 1001);
+        }
+
+        for (url, kind) in [
+            ("https://example.com/2.0/Grades/1001", ApiKind::External),
+            ("https://api.librus.pl/1.0/Grades/1001", ApiKind::Legacy),
+        ] {
+            match client.follow::<ResponseGrade>(&url.into()).await {
+                Err(Error::UnsupportedUrl {
+                    url: got,
+                    kind: got_kind,
+                }) => {
+                    assert_eq!((got.as_str(), got_kind), (url, kind));
+                }
+                other => panic!("unexpected result: {other:?}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mark_assignment_done_posts_id() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/HomeWorkAssignments/MarkAsDone"))
+            .and(body_json(serde_json::json!({ "homework": 5 })))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"Status":"Ok"}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(ClientBuilder::new(), &server);
+        client.mark_assignment_done(5).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mark_assignment_done_after_deadline() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/HomeWorkAssignments/MarkAsDone"))
+            .respond_with(ResponseTemplate::new(400).set_body_string(
+                r#"{"Status":"Error","Code":"HomeWorkAssignmentDeadlinePassed","Message":"Termin minął"}"#,
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/HomeWorkAssignments/MarkAsUndone"))
+            .respond_with(
+                ResponseTemplate::new(400)
+                    .set_body_string(r#"{"Status":"Error","Code":"Other","Message":"Nope"}"#),
+            )
+            .mount(&server)
+            .await;
+
+        let client = mock_client(ClientBuilder::new(), &server);
+        assert!(matches!(
+            client.mark_assignment_done(5).await,
+            Err(Error::DeadlinePassed)
+        ));
+        assert!(matches!(
+            client.mark_assignment_undone(5).await,
+            Err(Error::ApiError { status: 400, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_trips_present_and_disabled() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Trips"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/trips.json")),
+            )
+            .mount(&server)
+            .await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        assert_eq!(client.trips().await.unwrap().trips.len(), 2);
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Trips/7"))
+            .respond_with(ResponseTemplate::new(403).set_body_string(
+                r#"{"Status":"Error","Code":"ModuleDisabled","Message":"Module is disabled"}"#,
+            ))
+            .mount(&server)
+            .await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        match client.trip(7).await {
+            Err(Error::ModuleDisabled(module)) => assert_eq!(module, "Trips"),
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reference_indexes() {
+        let server = MockServer::start().await;
+        for (endpoint, fixture) in [
+            (
+                "/Attendances/Types",
+                include_str!("../tests/fixtures/attendance_types.json"),
+            ),
+            ("/Colors", include_str!("../tests/fixtures/colors.json")),
+            ("/Subjects", include_str!("../tests/fixtures/subjects.json")),
+            (
+                "/Grades/Categories",
+                include_str!("../tests/fixtures/grades_categories.json"),
+            ),
+            ("/Users", include_str!("../tests/fixtures/users.json")),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(endpoint))
+                .respond_with(ResponseTemplate::new(200).set_body_string(fixture))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        let client = mock_client(ClientBuilder::new(), &server);
+
+        let indexes = client.reference_indexes().await.unwrap();
+        assert_eq!(indexes.attendance_types.get(100).unwrap().identifier, "ob");
+        assert_eq!(indexes.colors.get(7).unwrap().rgb, "0000FF");
+        assert_eq!(indexes.subjects.get(22).unwrap().name, "Biologia");
+        let test = indexes.categories.get(41).unwrap();
+        assert_eq!(
+            indexes.colors.resolve(&test.color).unwrap().name,
+            "niebieski"
+        );
+        assert_eq!(indexes.users.get(51).unwrap().last_name, "Nowak");
+
+        // One failing list fails the whole call.
+        let server = MockServer::start().await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        assert!(client.reference_indexes().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_teacher_office_hours_present_and_disabled() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/TeacherOfficeHours"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/teacher_office_hours.json")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Users"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/users.json")),
+            )
+            .mount(&server)
+            .await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        assert_eq!(client.teacher_office_hours().await.unwrap().len(), 3);
+        let joined = client.teacher_office_hours_with_names().await.unwrap();
+        assert_eq!(joined[0].teacher_name().as_deref(), Some("Piotr Kowalczyk"));
+        assert_eq!(joined[0].hours.time_from, "14:30:00");
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/TeacherOfficeHours"))
+            .respond_with(ResponseTemplate::new(403).set_body_string(
+                r#"{"Status":"Error","Code":"ModuleDisabled","Message":"Module is disabled"}"#,
+            ))
+            .mount(&server)
+            .await;
+        let client = mock_client(ClientBuilder::new(), &server);
+        match client.teacher_office_hours().await {
+            Err(Error::ModuleDisabled(module)) => assert_eq!(module, "TeacherOfficeHours"),
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status_error_is_classified() {
+        use std::error::Error as _;
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Grades"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+        let source = reqwest::get(format!("{}/Grades?x=1", server.uri()))
+            .await
+            .unwrap()
+            .error_for_status()
+            .unwrap_err();
+        let error = Error::from(source);
+        assert!(error.is_retryable());
+        assert_eq!(error.api(), Some(ApiKind::Synergia));
+        let Error::ApiError { status, body, .. } = &error else {
+            panic!("unexpected error: {error:?}");
+        };
+        assert_eq!((*status, body.as_str()), (503, ""));
+        let source = error.source().unwrap().downcast_ref::<reqwest::Error>();
+        assert_eq!(
+            source.and_then(|e| e.status()).map(|s| s.as_u16()),
+            Some(503)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timeout_is_classified() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Grades"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/grades.json"))
+                    .set_delay(Duration::from_secs(2)),
+            )
+            .mount(&server)
+            .await;
+        let builder = ClientBuilder::new().timeout(Duration::from_millis(100));
+        let client = mock_client(builder, &server);
+        match client.grades().await {
+            Err(e @ Error::Timeout { .. }) => {
+                assert!(e.is_retryable());
+                assert_eq!(e.code(), ErrorCode::Timeout);
+                let Error::Timeout { endpoint, .. } = e else {
+                    unreachable!()
+                };
+                assert_eq!(endpoint, format!("{}/Grades", server.uri()));
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_refused_is_classified() {
+        // Bind and drop a listener so the port is known to be closed.
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let base = format!("http://127.0.0.1:{port}");
+        let endpoints = Endpoints {
+            api: format!("{base}/"),
+            ..Endpoints::default()
+        };
+        let client = Client::from_parts(ClientBuilder::new().http_client().unwrap(), endpoints);
+        match client.grades().await {
+            Err(Error::Connect { endpoint, .. }) => {
+                assert_eq!(endpoint, format!("{base}/Grades"))
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_go_through_proxy_from_environment() {
+        // The proxy answers for a host that does not resolve, so requests can
+        // only succeed through it.
+        let proxy = MockServer::start().await;
+        mount_login(
+            &proxy,
+            include_str!("../tests/fixtures/login_ok.json"),
+            200,
+            200,
+        )
+        .await;
+        let proxy_uri = proxy.uri();
+        let env = |name: &str| match name {
+            "http_proxy" => Some(proxy_uri.clone()),
+            "NO_PROXY" => Some("localhost".to_string()),
+            _ => None,
+        };
+        let connect = |builder: ClientBuilder| {
+            let http = builder.http_client_with_env(env).unwrap();
+            Client::from_parts(http, endpoints_at("http://librus.invalid"))
+        };
+
+        let client = connect(ClientBuilder::new().proxy_auth("jan", "tajne"));
+        client.log_in("user", "pass").await.unwrap();
+        let requests = proxy.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 4);
+        for request in &requests {
+            assert_eq!(request.headers["host"], "librus.invalid");
+            // base64("jan:tajne")
+            assert_eq!(request.headers["proxy-authorization"], "Basic amFuOnRham5l");
+        }
+
+        let direct = connect(ClientBuilder::new().use_system_proxy(false));
+        assert!(direct.log_in("user", "pass").await.is_err());
+        assert_eq!(proxy.received_requests().await.unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_proxy_from_environment() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let auth = ProxyAuth {
+            username: "jan".to_string(),
+            password: "tajne".to_string(),
+        };
+        assert!(proxy::from_env(env(&[]), None).unwrap().is_empty());
+        assert!(proxy::from_env(env(&[("HTTPS_PROXY", " ")]), None)
+            .unwrap()
+            .is_empty());
+        let all = &[
+            ("HTTPS_PROXY", "http://proxy:3128"),
+            ("http_proxy", "http://proxy:3128"),
+            ("ALL_PROXY", "socks5://proxy:1080"),
+            ("NO_PROXY", "librus.pl"),
+        ];
+        assert_eq!(proxy::from_env(env(all), Some(&auth)).unwrap().len(), 3);
+        assert!(proxy::from_env(env(&[("HTTPS_PROXY", "http://[::1")]), None).is_err());
+    }
+
+    #[test]
+    fn test_proxy_from_environment_ignored_in_cgi() {
+        // `HTTP_PROXY` of a CGI process may come from a request header.
+        let env = |name: &str| match name {
+            "REQUEST_METHOD" => Some("GET".to_string()),
+            "HTTP_PROXY" | "HTTPS_PROXY" => Some("http://attacker:8080".to_string()),
+            _ => None,
+        };
+        assert!(proxy::from_env(env, None).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_attachment_is_not_decompressed() {
+        let server = MockServer::start().await;
+        let archive = gzip(b"already compressed file");
+        Mock::given(method("GET"))
+            .and(path("/attachments/a1/messages/m1"))
+            .and(header("accept-encoding", "identity"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(archive.clone()))
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let bytes = client.attachment("a1", "m1").await.unwrap();
+        assert_eq!(bytes, archive);
+    }
+
+    #[tokio::test]
+    async fn test_attachment_honors_custom_base() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/proxy/messages/attachments/a%201/messages/m%2F1"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"file".to_vec()))
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.endpoints.messages = format!("{}/proxy/messages", server.uri());
+        client.messages_initialized = true;
+        let bytes = client.attachment("a 1", "m/1").await.unwrap();
+        assert_eq!(bytes, b"file");
+    }
+
+    #[tokio::test]
+    async fn test_attachment_download_headers() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/attachments/a1/messages/m1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(
+                        "content-disposition",
+                        "attachment; filename*=UTF-8''plan%20zaj%C4%99%C4%87.pdf",
+                    )
+                    .set_body_raw(b"%PDF-1.7".to_vec(), "application/pdf"),
+            )
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let download = client.attachment_download("a1", "m1").await.unwrap();
+        assert_eq!(download.content_type(), Some("application/pdf"));
+        assert_eq!(download.filename_header(), Some("plan zajęć.pdf"));
+        assert_eq!(download.total_len(), Some(8));
+        assert_eq!(download.bytes().await.unwrap(), b"%PDF-1.7");
+    }
+
+    #[tokio::test]
+    async fn test_attachment_being_scanned() {
+        let server = MockServer::start().await;
+        let page = include_str!("../tests/fixtures/attachment_scanning.html");
+        Mock::given(method("GET"))
+            .and(path("/attachments/a1/messages/m1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(page, "text/html; charset=UTF-8"))
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        match client.attachment("a1", "m1").await {
+            Err(Error::AttachmentScanning { retry_after_hint }) => {
+                assert_eq!(retry_after_hint, Some(Duration::from_secs(120)))
+            }
+            other => panic!("unexpected result: {:?}", other.map(|b| b.len())),
+        }
+        assert!(matches!(
+            client.attachment_resume("a1", "m1", 0).await,
+            Err(Error::AttachmentScanning { .. })
+        ));
+        let dir = std::env::temp_dir().join(format!("librus-scan-{}", std::process::id()));
+        assert!(matches!(
+            client.download_attachment_to_file("a1", "m1", &dir).await,
+            Err(Error::AttachmentScanning { .. })
+        ));
+        let _ = std::fs::remove_file(dir.with_extension("part"));
+    }
+
+    #[tokio::test]
+    async fn test_attachment_resume_partial_content() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/attachments/a1/messages/m1"))
+            .and(header("range", "bytes=4-"))
+            .respond_with(
+                ResponseTemplate::new(206)
+                    .insert_header("content-range", "bytes 4-9/10")
+                    .set_body_bytes(b"456789".to_vec()),
+            )
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let mut download = client.attachment_resume("a1", "m1", 4).await.unwrap();
+        assert!(download.resumed());
+        assert_eq!(download.total_len(), Some(10));
+        let mut rest = Vec::new();
+        while let Some(chunk) = download.chunk().await.unwrap() {
+            rest.extend_from_slice(&chunk);
+        }
+        assert_eq!(rest, b"456789");
+
+        let err = client.attachment_resume("a1", "m1", 5).await.unwrap_err();
+        assert!(matches!(err, Error::ApiError { status: 404, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_attachment_resume_wrong_range() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(206)
+                    .insert_header("content-range", "bytes 0-9/10")
+                    .set_body_bytes(b"0123456789".to_vec()),
+            )
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let err = client.attachment_resume("a1", "m1", 4).await.unwrap_err();
+        assert!(matches!(err, Error::RangeNotHonored { requested: 4, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_attachment_resume_falls_back_to_full_download() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/attachments/a1/messages/m1"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"0123456789".to_vec()))
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let download = client.attachment_resume("a1", "m1", 4).await.unwrap();
+        assert!(!download.resumed());
+        assert_eq!(download.offset(), 0);
+
+        let dir = std::env::temp_dir().join(format!("librus-fallback-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("plan.pdf");
+        std::fs::write(dir.join("plan.pdf.part"), b"0123stale").unwrap();
+        let size = client
+            .download_attachment_to_file("a1", "m1", &file)
+            .await
+            .unwrap();
+        assert_eq!(size, 10);
+        assert_eq!(std::fs::read(&file).unwrap(), b"0123456789");
+        assert!(!dir.join("plan.pdf.part").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Serves an attachment and cancels `token`, like a shutdown signal
+    /// arriving during a download.
+    struct CancellingAttachment {
+        token: CancellationToken,
+    }
+
+    impl wiremock::Respond for CancellingAttachment {
+        fn respond(&self, _: &wiremock::Request) -> ResponseTemplate {
+            self.token.cancel();
+            ResponseTemplate::new(200).set_body_bytes(b"%PDF".to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_all_attachments_cancelled_after_first() {
+        let server = MockServer::start().await;
+        let token = CancellationToken::new();
+        Mock::given(method("GET"))
+            .and(path("/attachments/a1/messages/m1"))
+            .respond_with(CancellingAttachment {
+                token: token.clone(),
+            })
+            .mount(&server)
+            .await;
+        let detail: MessageDetail = serde_json::from_value(serde_json::json!({
+            "messageId": "m1", "senderFirstName": "Jan", "senderLastName": "Nowak",
+            "senderName": "Jan Nowak", "topic": "Temat", "Message": "",
+            "sendDate": "2025-03-10 08:00:00", "readDate": null,
+            "attachments": [
+                {"id": "a1", "name": "plan.pdf", "size": 4},
+                {"id": "a2", "name": "", "size": 4}
+            ]
+        }))
+        .unwrap();
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let dir = std::env::temp_dir().join(format!("librus-bulk-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let summary = client
+            .download_all_attachments(&detail, &dir, &token)
+            .await
+            .unwrap();
+        assert!(summary.cancelled);
+        assert_eq!(summary.files, [dir.join("plan.pdf")]);
+        assert_eq!(std::fs::read(dir.join("plan.pdf")).unwrap(), b"%PDF");
+        // The second attachment is not requested.
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+        assert!(!dir.join("a2").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_download_to_file_resumes_after_disconnect() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // wiremock always sends complete bodies, so serve the first response
+        // by hand and drop the connection after 4 of 10 bytes.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for reply in [
+                "HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\n0123",
+                "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 4-9/10\r\n\
+                 Content-Length: 6\r\nConnection: close\r\n\r\n456789",
+            ] {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let n = socket.read(&mut buf).await.unwrap();
+                requests.push(String::from_utf8_lossy(&buf[..n]).to_lowercase());
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+            requests
+        });
+
+        let endpoints = Endpoints {
+            messages: format!("{base}/"),
+            ..Endpoints::default()
+        };
+        let mut client = Client::from_parts(ClientBuilder::new().http_client().unwrap(), endpoints);
+        client.messages_initialized = true;
+        let dir = std::env::temp_dir().join(format!("librus-resume-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("plan.pdf");
+        let size = client
+            .download_attachment_to_file("a1", "m1", &file)
+            .await
+            .unwrap();
+        assert_eq!(size, 10);
+        assert_eq!(std::fs::read(&file).unwrap(), b"0123456789");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let requests = server.await.unwrap();
+        assert!(!requests[0].contains("range:"));
+        assert!(requests[1].contains("range: bytes=4-"));
+    }
+
+    #[tokio::test]
+    async fn test_messages_session_is_renewed_once() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/inbox/unreadMessagesCount"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<!DOCTYPE html><html>Zaloguj</html>", "text/html"),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/inbox/unreadMessagesCount"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/unread_counts.json")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/wiadomosci3"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let counts = client.unread_counts().await.unwrap();
+        assert_eq!(counts.inbox, 3);
+    }
+
+    #[tokio::test]
+    async fn test_messages_session_expired() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/inbox/unreadMessagesCount"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html>Zaloguj</html>"))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/wiadomosci3"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        assert!(matches!(
+            client.unread_counts().await,
+            Err(Error::SessionExpired)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_messages_error_envelope() {
+        let server = MockServer::start().await;
+        for (route, body) in [
+            (
+                "/inbox/messages/1",
+                include_str!("../tests/fixtures/messages_error_deleted.json"),
+            ),
+            (
+                "/inbox/messages/2",
+                include_str!("../tests/fixtures/messages_error_permission.json"),
+            ),
+            ("/inbox/messages/3", r#"{"errorCode": 99, "message": "?"}"#),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(route))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
+        }
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        assert!(matches!(
+            client.message("1").await,
+            Err(Error::MessageDeleted)
+        ));
+        match client.message("2").await {
+            Err(e @ Error::MessagesPermissionDenied { .. }) => {
+                assert_eq!(e.api(), Some(ApiKind::Messages));
+                assert!(e.to_string().contains("Brak uprawnień"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(matches!(
+            client.message("3").await,
+            Err(Error::MessagesApiError { code: 99, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_messages_me_is_cached() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/me"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/messages_me.json")),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let me = client.messages_me().await.unwrap();
+        assert_eq!(
+            me,
+            MessagesUser {
+                user_id: "1234567".to_string(),
+                name: "Jan Kowalski".to_string(),
+                roles: vec!["parent".to_string()],
+            }
+        );
+        assert_eq!(client.messages_me().await.unwrap(), me);
+    }
+
+    #[tokio::test]
+    async fn test_message_board_and_confirm() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/notices"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/message_board.json")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/notices/77/confirm"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"data": true}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let board = client.message_board().await.unwrap();
+        let pending: Vec<_> = board.iter().filter(|e| e.needs_confirmation()).collect();
+        assert_eq!(pending.len(), 1);
+        client
+            .confirm_board_entry(&pending[0].notice_id)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            client.confirm_board_entry("78").await,
+            Err(Error::ApiError { status: 404, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_confirm_message_read() {
+        let server = MockServer::start().await;
+        let fixture = include_str!("../tests/fixtures/message_read_confirmation.json");
+        for (id, body) in [
+            ("9101", fixture.to_string()),
+            (
+                "9102",
+                fixture.replace(
+                    r#""readConfirmationDate": null"#,
+                    r#""readConfirmationDate": "2025-10-06 18:01:00""#,
+                ),
+            ),
+            (
+                "9001",
+                include_str!("../tests/fixtures/outbox_message.json").to_string(),
+            ),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(format!("/inbox/messages/{id}")))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("POST"))
+            .and(path("/inbox/messages/9101/confirm"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"data": true}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        client.confirm_message_read("9101").await.unwrap();
+        assert!(matches!(
+            client.confirm_message_read("9102").await,
+            Err(Error::ReadAlreadyConfirmed)
+        ));
+        match client.confirm_message_read("9001").await {
+            Err(e @ Error::ReadConfirmationNotRequired) => {
+                assert_eq!(e.code_str(), "READ_CONFIRMATION_NOT_REQUIRED")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_justification_requests() {
+        use wiremock::matchers::query_param;
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/justifications/messages"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(include_str!(
+                "../tests/fixtures/message_justifications.json"
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/justifications/messages/502"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/message_justification.json")),
+            )
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let requests = client.justification_requests(1, 20).await.unwrap();
+        assert_eq!(requests.len(), 3);
+
+        let detail = client
+            .justification_request(&requests[1].message_id)
+            .await
+            .unwrap();
+        assert_eq!(detail.status, JustificationStatus::Accepted);
+        assert_eq!(detail.status_comment.as_deref(), Some("Usprawiedliwiono."));
+        assert_eq!(
+            Client::decode_message_content(&detail.content).as_deref(),
+            Some("Proszę o usprawiedliwienie nieobecności córki.")
+        );
+        let attendances: ResponseAttendances =
+            serde_json::from_str(include_str!("../tests/fixtures/attendances.json")).unwrap();
+        let justified = detail.attendances(&attendances.attendances);
+        assert_eq!(justified.len(), 1);
+        assert_eq!(justified[0].date, "2025-03-04");
+    }
+
+    #[tokio::test]
+    async fn test_draft_save_then_send() {
+        use wiremock::matchers::body_json;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/drafts"))
+            .and(body_json(serde_json::json!({
+                "receivers": ["1234", "5678"],
+                "topic": "Wycieczka",
+                "content": "\"Syn\" nie pojedzie na wycieczkę.\n",
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"{"data": {"draftId": 9001}}"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/drafts/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/message_drafts.json")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/drafts/9001/send"))
+            .and(body_json(serde_json::json!({})))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"data": true}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/drafts/9002"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"data": true}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let draft = client
+            .save_draft(
+                &["1234", "5678"],
+                "Wycieczka",
+                "„Syn” nie pojedzie na wycieczkę.\r\n",
+                ValidationMode::AutoFix,
+            )
+            .await
+            .unwrap();
+        assert_eq!(draft.as_str(), "9001");
+
+        let drafts = client.drafts(1, 10).await.unwrap();
+        assert_eq!(drafts[0].message_id, draft.as_str());
+        client.send_draft(draft.as_str()).await.unwrap();
+        client.delete_draft("9002").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_strict_draft_is_rejected_before_sending() {
+        let server = MockServer::start().await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        let result = client
+            .save_draft(&["1234"], "Temat", "Treść\u{7}", ValidationMode::Strict)
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::InvalidMessage(issues)) if issues.len() == 1
+        ));
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_message_board_missing_module() {
+        let server = MockServer::start().await;
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        assert!(client.message_board().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_messages_error_envelope_renews_session() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/inbox/unreadMessagesCount"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(include_str!(
+                "../tests/fixtures/messages_error_session.json"
+            )))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/inbox/unreadMessagesCount"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../tests/fixtures/unread_counts.json")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/wiadomosci3"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        client.messages_initialized = true;
+        assert_eq!(client.unread_counts().await.unwrap().inbox, 3);
+    }
+
+    #[tokio::test]
+    async fn test_notice_attachment_download() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Attachments/56"))
+            .and(header("accept-encoding", "identity"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"PK\x03\x04".to_vec()))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(ClientBuilder::new(), &server);
+        let notices: ResponseSchoolNotices =
+            serde_json::from_str(include_str!("../tests/fixtures/school_notices.json")).unwrap();
+        let file = &notices.school_notices[0].attachments.as_ref().unwrap()[1];
+        let bytes = client.notice_attachment(file).await.unwrap();
+        assert_eq!(bytes, b"PK\x03\x04");
+    }
+
+    #[tokio::test]
+    async fn test_student_card_degrades_per_section() {
+        let server = MockServer::start().await;
+        for (route, fixture) in [
+            ("/Me", include_str!("../tests/fixtures/me.json")),
+            ("/Classes/12", include_str!("../tests/fixtures/class.json")),
+            ("/Grades", include_str!("../tests/fixtures/grades.json")),
+            ("/Subjects", include_str!("../tests/fixtures/subjects.json")),
+            (
+                "/HomeWorks",
+                include_str!("../tests/fixtures/homeworks.json"),
+            ),
+            (
+                "/inbox/unreadMessagesCount",
+                include_str!("../tests/fixtures/unread_counts.json"),
+            ),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(route))
+                .respond_with(ResponseTemplate::new(200).set_body_string(fixture))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/Attendances"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/wiadomosci3"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let mut client = mock_client(ClientBuilder::new(), &server);
+        let card = client.student_card().await.unwrap();
+        assert_eq!(card.name, "Zosia Kowalska");
+        assert_eq!(card.class.as_deref(), Some("7a"));
+        assert_eq!(card.unread_messages, Some(3));
+        let averages = card.averages.unwrap();
+        assert_eq!(averages[0].subject_name.as_deref(), Some("Biologia"));
+        assert_eq!(averages[1].average, 5.0);
+        // The fixture homework is in the past.
+        assert!(card.next_homework.is_none());
+
+        assert!(card.unexcused_absences.is_none());
+        assert_eq!(card.errors.len(), 1);
+        assert_eq!(card.errors[0].section, CardSection::Attendance);
+    }
+
+    #[cfg(feature = "raw-extras")]
+    #[test]
+    fn test_unmodeled_keys_survive_round_trip() {
+        fn round_trip<T: serde::de::DeserializeOwned + serde::Serialize>(body: &str) {
+            let mut value: serde_json::Value = serde_json::from_str(body).unwrap();
+            value["FutureField"] = serde_json::json!({"Nested": [1, 2]});
+            let entity: T = serde_json::from_value(value).unwrap();
+            let back = serde_json::to_value(&entity).unwrap();
+            assert_eq!(back["FutureField"]["Nested"][1], 2, "{}", body);
+        }
+
+        let grades: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/fixtures/grades.json")).unwrap();
+        round_trip::<Grade>(&grades["Grades"][0].to_string());
+        let homeworks: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/fixtures/homeworks.json")).unwrap();
+        round_trip::<Homework>(&homeworks["HomeWorks"][0].to_string());
+        let notices: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/fixtures/school_notices.json")).unwrap();
+        round_trip::<SchoolNotice>(&notices["SchoolNotices"][0].to_string());
+        let message: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/fixtures/outbox_message.json")).unwrap();
+        round_trip::<MessageDetail>(&message["data"].to_string());
+
+        let grade: Grade = serde_json::from_str(&grades["Grades"][1].to_string().replacen(
+            '{',
+            r#"{"IsNew": true,"#,
+            1,
+        ))
+        .unwrap();
+        assert_eq!(grade.extra["IsNew"], true);
+        assert!(!grade.extra.contains_key("Grade"));
+    }
 
     #[test]
     fn test_decode_message_content() {
@@ -1167,5 +8861,11 @@ mod tests {
         let html = "<p>Hello&nbsp;<b>World</b> &amp; friends</p>";
         let text = Client::notice_content_to_text(html);
         assert_eq!(text, "Hello World & friends");
+
+        let html = "Zebranie 12.03<br>sala 5 &lt; 10<ul><li>zgody</li></ul>";
+        assert_eq!(
+            Client::notice_content_to_text(html),
+            "Zebranie 12.03\nsala 5 < 10\n\n- zgody"
+        );
     }
 }
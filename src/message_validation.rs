@@ -0,0 +1,258 @@
+//! Checks for message topics and bodies before they are sent.
+//!
+//! The Messages API answers a too long topic or a body with control
+//! characters with a bare `400`. [`MessageDraftValidator`] reports these
+//! problems up front and can fix most of them. It needs no client, so a UI
+//! can run it while the user types.
+//!
+//! # Example
+//!
+//! ```rust
+//! use librus_rs::{MessageDraftValidator, ValidationIssue};
+//!
+//! let validator = MessageDraftValidator::new();
+//! let issues = validator.validate("Zebranie", "Dzień dobry,\u{0007} pytanie…");
+//! assert_eq!(
+//!     issues,
+//!     [ValidationIssue::DisallowedCharacter { field: "body", character: '\u{0007}' }]
+//! );
+//! assert_eq!(validator.sanitize("„Tak”\r\n"), "\"Tak\"\n");
+//! ```
+
+use std::fmt;
+
+/// Longest topic the Messages API accepts, in characters.
+pub const MAX_TOPIC_CHARS: usize = 255;
+
+/// Longest body the Messages API accepts, in characters.
+pub const MAX_BODY_CHARS: usize = 50_000;
+
+/// How [`Client::save_draft()`](crate::Client::save_draft) treats a topic or
+/// body that would be rejected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Fail with [`Error::InvalidMessage`](crate::Error::InvalidMessage).
+    #[default]
+    Strict,
+    /// Sanitize the topic and body first, and fail only on issues that
+    /// cannot be fixed, such as an empty body.
+    AutoFix,
+}
+
+/// A problem with a message topic or body, see
+/// [`MessageDraftValidator::validate()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// The topic is empty or only whitespace.
+    EmptyTopic,
+    /// The topic is longer than [`MAX_TOPIC_CHARS`].
+    TopicTooLong {
+        /// Length of the topic in characters.
+        chars: usize,
+    },
+    /// The body is empty or only whitespace.
+    EmptyBody,
+    /// The body is longer than [`MAX_BODY_CHARS`].
+    BodyTooLong {
+        /// Length of the body in characters.
+        chars: usize,
+    },
+    /// A field contains a character the API rejects. Reported once per field,
+    /// for the first such character.
+    DisallowedCharacter {
+        /// `"topic"` or `"body"`.
+        field: &'static str,
+        /// The first disallowed character.
+        character: char,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyTopic => f.write_str("topic is empty"),
+            Self::TopicTooLong { chars } => write!(
+                f,
+                "topic has {} characters, at most {} are allowed",
+                chars, MAX_TOPIC_CHARS
+            ),
+            Self::EmptyBody => f.write_str("body is empty"),
+            Self::BodyTooLong { chars } => write!(
+                f,
+                "body has {} characters, at most {} are allowed",
+                chars, MAX_BODY_CHARS
+            ),
+            Self::DisallowedCharacter { field, character } => {
+                write!(f, "{} contains disallowed character {:?}", field, character)
+            }
+        }
+    }
+}
+
+/// Validates and cleans up message topics and bodies.
+#[derive(Debug, Clone, Default)]
+pub struct MessageDraftValidator {
+    _private: (),
+}
+
+impl MessageDraftValidator {
+    /// Creates a validator with the Messages API limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lists everything the API would reject about `topic` and `body`.
+    ///
+    /// An empty list means the message can be sent as is.
+    pub fn validate(&self, topic: &str, body: &str) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        if topic.trim().is_empty() {
+            issues.push(ValidationIssue::EmptyTopic);
+        }
+        let chars = topic.chars().count();
+        if chars > MAX_TOPIC_CHARS {
+            issues.push(ValidationIssue::TopicTooLong { chars });
+        }
+        // A topic is a single line.
+        if let Some(character) = topic.chars().find(|c| c.is_control()) {
+            issues.push(ValidationIssue::DisallowedCharacter {
+                field: "topic",
+                character,
+            });
+        }
+
+        if body.trim().is_empty() {
+            issues.push(ValidationIssue::EmptyBody);
+        }
+        let chars = body.chars().count();
+        if chars > MAX_BODY_CHARS {
+            issues.push(ValidationIssue::BodyTooLong { chars });
+        }
+        if let Some(character) = body.chars().find(|c| is_disallowed_in_body(*c)) {
+            issues.push(ValidationIssue::DisallowedCharacter {
+                field: "body",
+                character,
+            });
+        }
+        issues
+    }
+
+    /// Cleans up a body: drops disallowed control characters, replaces
+    /// typographic quotes with plain ones and turns `\r\n` and `\r` line
+    /// breaks into `\n`. The length is not changed otherwise.
+    pub fn sanitize(&self, body: &str) -> String {
+        let body = body.replace("\r\n", "\n").replace('\r', "\n");
+        body.chars()
+            .filter(|c| !is_disallowed_in_body(*c))
+            .map(plain_quote)
+            .collect()
+    }
+
+    /// Cleans up a topic like [`sanitize()`](Self::sanitize), additionally
+    /// replacing line breaks and tabs with spaces, trimming it and cutting it
+    /// to [`MAX_TOPIC_CHARS`].
+    pub fn sanitize_topic(&self, topic: &str) -> String {
+        let topic: String = topic
+            .chars()
+            .map(|c| if c.is_whitespace() { ' ' } else { c })
+            .filter(|c| !c.is_control())
+            .map(plain_quote)
+            .collect();
+        topic.trim().chars().take(MAX_TOPIC_CHARS).collect()
+    }
+}
+
+/// Control characters other than line breaks and tabs.
+fn is_disallowed_in_body(c: char) -> bool {
+    c.is_control() && !matches!(c, '\n' | '\t')
+}
+
+fn plain_quote(c: char) -> char {
+    match c {
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => '\'',
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => '"',
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_message() {
+        let validator = MessageDraftValidator::new();
+        assert!(validator
+            .validate("Zebranie", "Dzień dobry,\n\tproszę o kontakt.")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_limits() {
+        let validator = MessageDraftValidator::new();
+        let longest = "a".repeat(MAX_TOPIC_CHARS);
+        assert!(validator.validate(&longest, "treść").is_empty());
+        // Characters are counted, not bytes.
+        let polish = "ż".repeat(MAX_TOPIC_CHARS);
+        assert!(validator.validate(&polish, "treść").is_empty());
+
+        let too_long = "a".repeat(MAX_TOPIC_CHARS + 1);
+        assert_eq!(
+            validator.validate(&too_long, "treść"),
+            [ValidationIssue::TopicTooLong {
+                chars: MAX_TOPIC_CHARS + 1
+            }]
+        );
+        let body = "a".repeat(MAX_BODY_CHARS + 1);
+        assert_eq!(
+            validator.validate("Temat", &body),
+            [ValidationIssue::BodyTooLong {
+                chars: MAX_BODY_CHARS + 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_empty_and_control_characters() {
+        let validator = MessageDraftValidator::new();
+        assert_eq!(
+            validator.validate(" ", "a\u{0}b\u{1b}"),
+            [
+                ValidationIssue::EmptyTopic,
+                ValidationIssue::DisallowedCharacter {
+                    field: "body",
+                    character: '\u{0}'
+                }
+            ]
+        );
+        assert_eq!(
+            validator.validate("Dwie\nlinie", ""),
+            [
+                ValidationIssue::DisallowedCharacter {
+                    field: "topic",
+                    character: '\n'
+                },
+                ValidationIssue::EmptyBody
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let validator = MessageDraftValidator::new();
+        let body = "„Dzień dobry”,\r\nto ‘test’.\rKoniec\u{7}\u{0}\t!";
+        let clean = validator.sanitize(body);
+        assert_eq!(clean, "\"Dzień dobry\",\nto 'test'.\nKoniec\t!");
+        assert!(validator.validate("Temat", &clean).is_empty());
+    }
+
+    #[test]
+    fn test_sanitize_topic() {
+        let validator = MessageDraftValidator::new();
+        assert_eq!(validator.sanitize_topic("  „Pilne”\r\n\u{7}"), "\"Pilne\"");
+        let long = format!("{}ąę", "a".repeat(MAX_TOPIC_CHARS - 1));
+        let cut = validator.sanitize_topic(&long);
+        assert_eq!(cut.chars().count(), MAX_TOPIC_CHARS);
+        assert!(cut.ends_with('ą'));
+    }
+}
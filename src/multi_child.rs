@@ -0,0 +1,192 @@
+//! Running the same request for every child of a parent.
+//!
+//! Synergia issues a separate login per child, and a session only sees the
+//! data of the account it was logged in as. [`MultiChildClient`] owns one
+//! [`Client`] and switches it between the children's accounts, so code written
+//! for a single student can run for each child in turn.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use librus_rs::{Client, LinkedAccount, MultiChildClient};
+//!
+//! # async fn example() -> Result<(), librus_rs::Error> {
+//! let client = Client::new("1234567u", "password").await?;
+//! let family = MultiChildClient::new(client, LinkedAccount::new("1234567u"), "password")
+//!     .add_child(LinkedAccount::new("7654321u").name("Jan"), "password");
+//!
+//! let grades = family
+//!     .for_each_child(async |client: &mut Client| client.grades().await)
+//!     .await?;
+//! for (child, result) in grades {
+//!     match result {
+//!         Ok(grades) => println!("{}: {} grades", child.login, grades.grades.len()),
+//!         Err(e) => println!("{}: {}", child.login, e),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+
+use tokio::sync::Mutex;
+use zeroize::Zeroize;
+
+use crate::{Client, Result};
+
+/// A child account that can be selected on a [`MultiChildClient`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LinkedAccount {
+    /// Synergia login of the child's account (e.g. `"1234567u"`).
+    pub login: String,
+    /// Display name of the child, if set.
+    pub name: Option<String>,
+}
+
+impl LinkedAccount {
+    /// Creates an account reference from a Synergia login.
+    pub fn new(login: impl Into<String>) -> Self {
+        Self {
+            login: login.into(),
+            name: None,
+        }
+    }
+
+    /// Sets the display name of the child.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+}
+
+struct Child {
+    account: LinkedAccount,
+    password: String,
+}
+
+impl Drop for Child {
+    fn drop(&mut self) {
+        self.password.zeroize();
+    }
+}
+
+struct Session {
+    client: Client,
+    /// Index into `children` of the account the client is logged in as, or
+    /// `None` after a failed switch left the session in an unknown state.
+    active: Option<usize>,
+}
+
+/// A [`Client`] shared between several linked child accounts.
+///
+/// The client is logged in as one child at a time. Every switch happens under
+/// a lock held for the whole of [`for_each_child()`](Self::for_each_child), so
+/// concurrent calls run one after another and never see each other's account.
+pub struct MultiChildClient {
+    session: Mutex<Session>,
+    children: Vec<Child>,
+    /// Index of the account the client was created with.
+    selected: usize,
+}
+
+impl MultiChildClient {
+    /// Wraps a client that is logged in as `account`.
+    ///
+    /// The password is needed to switch back to `account` after other children
+    /// have been visited.
+    pub fn new(client: Client, account: LinkedAccount, password: impl Into<String>) -> Self {
+        Self {
+            session: Mutex::new(Session {
+                client,
+                active: Some(0),
+            }),
+            children: vec![Child {
+                account,
+                password: password.into(),
+            }],
+            selected: 0,
+        }
+    }
+
+    /// Adds another child account.
+    ///
+    /// Adding an account that is already present replaces its password.
+    pub fn add_child(mut self, account: LinkedAccount, password: impl Into<String>) -> Self {
+        let password = password.into();
+        match self.children.iter_mut().find(|c| c.account == account) {
+            Some(child) => {
+                child.password.zeroize();
+                child.password = password;
+            }
+            None => self.children.push(Child { account, password }),
+        }
+        self
+    }
+
+    /// Linked accounts, starting with the originally selected one.
+    pub fn accounts(&self) -> impl Iterator<Item = &LinkedAccount> {
+        self.children.iter().map(|c| &c.account)
+    }
+
+    /// Runs `f` once per child with the client logged in as that child.
+    ///
+    /// Results are keyed by account. An error returned by `f` is recorded for
+    /// that child and does not stop the others. Afterwards the client is
+    /// switched back to the account it was created with, also when `f` failed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if switching back to the originally selected account
+    /// fails. A failed switch to a child is reported as that child's result.
+    pub async fn for_each_child<F, T>(&self, mut f: F) -> Result<BTreeMap<LinkedAccount, Result<T>>>
+    where
+        F: AsyncFnMut(&mut Client) -> Result<T>,
+    {
+        let mut session = self.session.lock().await;
+        let mut results = BTreeMap::new();
+        for index in 0..self.children.len() {
+            let result = match self.switch(&mut session, index).await {
+                Ok(()) => f(&mut session.client).await,
+                Err(e) => Err(e),
+            };
+            results.insert(self.children[index].account.clone(), result);
+        }
+        // A run interrupted by a panic or cancellation leaves another child
+        // active; `session.active` records it, so the next run restores too.
+        self.switch(&mut session, self.selected).await?;
+        Ok(results)
+    }
+
+    /// Returns the client, logged in as the account it was created with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if switching back to the originally selected account
+    /// fails.
+    pub async fn into_client(self) -> Result<Client> {
+        let mut session = self.session.into_inner();
+        if session.active != Some(self.selected) {
+            let child = &self.children[self.selected];
+            session
+                .client
+                .relogin(&child.account.login, &child.password)
+                .await?;
+        }
+        Ok(session.client)
+    }
+
+    async fn switch(&self, session: &mut Session, index: usize) -> Result<()> {
+        if session.active == Some(index) {
+            return Ok(());
+        }
+        let child = &self.children[index];
+        session.active = None;
+        session
+            .client
+            .relogin(&child.account.login, &child.password)
+            .await?;
+        session.active = Some(index);
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+//! De-duplication and stable ordering of list responses.
+//!
+//! Some instances return the same entry twice and change the order between
+//! calls. [`dedup_sorted()`] keeps one entry per ID and sorts them so that
+//! consecutive fetches can be compared item by item.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Keeps the entry with the latest `add_date` for each ID and sorts the result
+/// by date, then ID.
+///
+/// Dates are compared as strings, which orders `YYYY-MM-DD[ HH:MM:SS]` values
+/// chronologically. On equal `add_date` the entry seen last wins.
+pub(crate) fn dedup_sorted<T, K>(
+    items: Vec<T>,
+    id: impl Fn(&T) -> K,
+    date: impl Fn(&T) -> &str,
+    add_date: impl Fn(&T) -> &str,
+) -> Vec<T>
+where
+    K: Eq + Hash + Ord,
+{
+    let mut latest: HashMap<K, T> = HashMap::with_capacity(items.len());
+    for item in items {
+        let key = id(&item);
+        match latest.get(&key) {
+            Some(kept) if add_date(kept) > add_date(&item) => {}
+            _ => {
+                latest.insert(key, item);
+            }
+        }
+    }
+
+    let mut items: Vec<T> = latest.into_values().collect();
+    items.sort_by(|a, b| (date(a), id(a)).cmp(&(date(b), id(b))));
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::structs::events::ResponseHomeworks;
+    use crate::structs::grades::ResponseGrades;
+    use crate::structs::lessons::{AttendanceId, ResponseAttendances};
+
+    #[test]
+    fn test_grades_keep_latest_edit() {
+        let mut resp: ResponseGrades =
+            serde_json::from_str(include_str!("../tests/fixtures/grades.json")).unwrap();
+        // Duplicate of grade 1001 edited later, and an older stale copy of 1002.
+        let mut edited: ResponseGrades =
+            serde_json::from_str(include_str!("../tests/fixtures/grades.json")).unwrap();
+        edited.grades[0].grade = "4+".to_string();
+        edited.grades[0].add_date = "2025-10-07 09:00:00".try_into().unwrap();
+        edited.grades[1].grade = "2".to_string();
+        edited.grades[1].add_date = "2025-10-08 07:00:00".try_into().unwrap();
+        resp.grades.extend(edited.grades);
+        resp.grades.reverse();
+
+        let grades = resp.normalized().grades;
+        let values: Vec<_> = grades.iter().map(|g| (g.id, g.grade.as_str())).collect();
+        assert_eq!(values, [(1001, "4+"), (1002, "3+")]);
+    }
+
+    #[test]
+    fn test_attendances_sorted_by_date_then_id() {
+        let record = |id: &str, date: &str| {
+            format!(
+                r#"{{"Id": {id}, "Lesson": {{"Id": 1, "Url": ""}}, "Student": {{"Id": 1, "Url": ""}},
+                    "Date": "{date}", "AddDate": "{date} 08:00:00", "LessonNo": 1, "Semester": 1,
+                    "Type": {{"Id": 1, "Url": ""}}, "AddedBy": {{"Id": 1, "Url": ""}}}}"#
+            )
+        };
+        let body = format!(
+            r#"{{"Attendances": [{}, {}, {}, {}], "Url": ""}}"#,
+            record("7", "2025-03-04"),
+            record("5", "2025-03-04"),
+            record("\"t9\"", "2025-03-03"),
+            record("5", "2025-03-04"),
+        );
+        let resp: ResponseAttendances = serde_json::from_str(&body).unwrap();
+        let ids: Vec<_> = resp
+            .normalized()
+            .attendances
+            .into_iter()
+            .map(|a| a.id)
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                AttendanceId::String("t9".to_string()),
+                AttendanceId::Integer(5),
+                AttendanceId::Integer(7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_homeworks_deduplicated() {
+        let mut resp: ResponseHomeworks =
+            serde_json::from_str(include_str!("../tests/fixtures/homeworks.json")).unwrap();
+        let again: ResponseHomeworks =
+            serde_json::from_str(include_str!("../tests/fixtures/homeworks.json")).unwrap();
+        resp.homeworks.extend(again.homeworks);
+        let ids: Vec<_> = resp.normalized().homeworks.iter().map(|h| h.id).collect();
+        assert_eq!(ids, [301, 302, 303]);
+    }
+}
@@ -0,0 +1,87 @@
+//! Joining teacher office hours with the teachers' names.
+
+use crate::indexes::UserIndex;
+use crate::structs::office_hours::OfficeHours;
+use crate::structs::users::User;
+
+/// Office hours together with the teacher holding them.
+///
+/// Returned by [`Client::teacher_office_hours_with_names()`](crate::Client::teacher_office_hours_with_names).
+#[derive(Debug, Clone)]
+pub struct TeacherOfficeHours {
+    /// The teacher, or `None` if they are not in the user list.
+    pub teacher: Option<User>,
+    /// The office hours slot.
+    pub hours: OfficeHours,
+}
+
+impl TeacherOfficeHours {
+    /// The teacher's full name, e.g. `"Anna Nowak"`.
+    pub fn teacher_name(&self) -> Option<String> {
+        self.teacher
+            .as_ref()
+            .map(|t| format!("{} {}", t.first_name, t.last_name))
+    }
+
+    /// Attaches teachers from `users` to office hours, sorted by teacher
+    /// last name, then day and time. Slots of unknown teachers go last.
+    ///
+    /// Same as [`Client::teacher_office_hours_with_names()`](crate::Client::teacher_office_hours_with_names)
+    /// with an index that is already at hand.
+    pub fn join(hours: Vec<OfficeHours>, users: &UserIndex) -> Vec<Self> {
+        let mut joined: Vec<TeacherOfficeHours> = hours
+            .into_iter()
+            .map(|hours| TeacherOfficeHours {
+                teacher: hours.teacher.id().and_then(|id| users.get(id).cloned()),
+                hours,
+            })
+            .collect();
+        joined.sort_by(|a, b| {
+            let key = |entry: &TeacherOfficeHours| {
+                (
+                    entry
+                        .teacher
+                        .as_ref()
+                        .map(|t| (t.last_name.clone(), t.first_name.clone(), t.id)),
+                    entry.hours.weekday,
+                    entry.hours.time_from.clone(),
+                )
+            };
+            // `None` sorts first, so compare unknown teachers explicitly.
+            let (a, b) = (key(a), key(b));
+            a.0.is_none().cmp(&b.0.is_none()).then_with(|| a.cmp(&b))
+        });
+        joined
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::office_hours::ResponseOfficeHours;
+    use crate::structs::users::ResponseUsers;
+
+    #[test]
+    fn test_join_office_hours() {
+        let hours: ResponseOfficeHours =
+            serde_json::from_str(include_str!("../tests/fixtures/teacher_office_hours.json"))
+                .unwrap();
+        let users: ResponseUsers =
+            serde_json::from_str(include_str!("../tests/fixtures/users.json")).unwrap();
+
+        let joined = TeacherOfficeHours::join(hours.teacher_office_hours, &users.into());
+        let names: Vec<(Option<String>, u8)> = joined
+            .iter()
+            .map(|entry| (entry.teacher_name(), entry.hours.weekday))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                (Some("Piotr Kowalczyk".to_string()), 2),
+                (Some("Anna Nowak".to_string()), 4),
+                // User 99 is not in the list.
+                (None, 1),
+            ]
+        );
+    }
+}
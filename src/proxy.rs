@@ -0,0 +1,76 @@
+//! Proxies configured through environment variables.
+//!
+//! reqwest reads the same variables on its own, but offers no way to add
+//! credentials to the proxies it finds. They are therefore read here, so
+//! [`ClientBuilder::proxy_auth()`](crate::ClientBuilder::proxy_auth) applies
+//! to them.
+
+use reqwest::{NoProxy, Proxy};
+use zeroize::Zeroize;
+
+/// Proxy variables in order of precedence, with the scheme they apply to.
+const VARIABLES: [(&str, &str, Scheme); 3] = [
+    ("HTTPS_PROXY", "https_proxy", Scheme::Https),
+    ("HTTP_PROXY", "http_proxy", Scheme::Http),
+    ("ALL_PROXY", "all_proxy", Scheme::All),
+];
+
+#[derive(Clone, Copy)]
+enum Scheme {
+    Http,
+    Https,
+    All,
+}
+
+/// Credentials for an authenticating proxy.
+#[derive(Clone)]
+pub(crate) struct ProxyAuth {
+    pub username: String,
+    pub password: String,
+}
+
+impl Drop for ProxyAuth {
+    fn drop(&mut self) {
+        self.password.zeroize();
+    }
+}
+
+/// Builds the proxies named by the environment, excluding the hosts listed in
+/// `NO_PROXY`. Variables are looked up through `env`, upper case first.
+///
+/// Returns an empty list if no variable is set, leaving reqwest's own
+/// detection of system settings in place. Like reqwest, ignores every
+/// variable when `REQUEST_METHOD` is set: in a CGI process `HTTP_PROXY` may
+/// come from the `Proxy:` header of a request (CVE-2016-5385, "httpoxy").
+pub(crate) fn from_env(
+    env: impl Fn(&str) -> Option<String>,
+    auth: Option<&ProxyAuth>,
+) -> reqwest::Result<Vec<Proxy>> {
+    if env("REQUEST_METHOD").is_some() {
+        return Ok(Vec::new());
+    }
+    let lookup = |upper: &str, lower: &str| {
+        env(upper)
+            .or_else(|| env(lower))
+            .filter(|value| !value.trim().is_empty())
+    };
+    let no_proxy = lookup("NO_PROXY", "no_proxy").and_then(|list| NoProxy::from_string(&list));
+
+    let mut proxies = Vec::new();
+    for (upper, lower, scheme) in VARIABLES {
+        let Some(url) = lookup(upper, lower) else {
+            continue;
+        };
+        let url = url.trim();
+        let mut proxy = match scheme {
+            Scheme::Http => Proxy::http(url)?,
+            Scheme::Https => Proxy::https(url)?,
+            Scheme::All => Proxy::all(url)?,
+        };
+        if let Some(auth) = auth {
+            proxy = proxy.basic_auth(&auth.username, &auth.password);
+        }
+        proxies.push(proxy.no_proxy(no_proxy.clone()));
+    }
+    Ok(proxies)
+}
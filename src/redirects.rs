@@ -0,0 +1,110 @@
+//! Explicit redirect handling.
+//!
+//! The HTTP client does not follow redirects on its own. A redirect from an
+//! API call means the session has ended, while the login flow, the messages
+//! session setup and attachment downloads follow them here, so that each hop
+//! can be checked and recorded.
+
+use reqwest::header::{HeaderMap, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, LOCATION};
+use reqwest::{Method, Request, Response, StatusCode, Url};
+
+use crate::budget::{Cost, Http};
+use crate::{Error, Result};
+
+/// Most redirects followed for one request. The response of the hop after
+/// that is returned as is, so the caller reports its redirect status.
+pub(crate) const MAX_HOPS: usize = 10;
+
+/// A response and the redirects followed to get it.
+pub(crate) struct Followed {
+    pub response: Response,
+    /// The URLs the redirects pointed to, in order.
+    pub hops: Vec<Url>,
+}
+
+/// Sends `request`, following redirects to URLs for which `allowed` returns
+/// `true`. Each hop spends `cost` from the request budget.
+///
+/// # Errors
+///
+/// Returns [`Error::UnexpectedRedirect`] for a redirect to any other URL, or
+/// an error if a request fails.
+pub(crate) async fn follow(
+    http: &Http,
+    request: Request,
+    cost: Cost,
+    allowed: impl Fn(&Url) -> bool,
+) -> Result<Followed> {
+    let mut request = request;
+    let mut hops = Vec::new();
+    loop {
+        // Kept to repeat the request at the next hop. Streaming bodies cannot
+        // be cloned, and such a redirect is returned as is.
+        let retry = request.try_clone();
+        let response = http.execute(request, cost).await?;
+        let Some(to) = target(&response) else {
+            return Ok(Followed { response, hops });
+        };
+        if !allowed(&to) {
+            let mut to = to;
+            to.set_query(None);
+            return Err(Error::UnexpectedRedirect { to: to.to_string() });
+        }
+        let Some(retry) = retry.filter(|_| hops.len() < MAX_HOPS) else {
+            return Ok(Followed { response, hops });
+        };
+        request = next_request(retry, response.status(), response.url(), &to);
+        hops.push(to);
+    }
+}
+
+/// Where a redirect response points, resolved against its URL.
+fn target(response: &Response) -> Option<Url> {
+    if !matches!(
+        response.status(),
+        StatusCode::MOVED_PERMANENTLY
+            | StatusCode::FOUND
+            | StatusCode::SEE_OTHER
+            | StatusCode::TEMPORARY_REDIRECT
+            | StatusCode::PERMANENT_REDIRECT
+    ) {
+        return None;
+    }
+    let location = response.headers().get(LOCATION)?.to_str().ok()?;
+    response.url().join(location).ok()
+}
+
+/// The request for the next hop. Like browsers, `303` and a `POST`
+/// answered with `301`/`302` continue as a `GET` without the body.
+fn next_request(mut request: Request, status: StatusCode, from: &Url, to: &Url) -> Request {
+    let as_get = status == StatusCode::SEE_OTHER
+        || (matches!(status, StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND)
+            && request.method() == Method::POST);
+    if as_get {
+        *request.method_mut() = Method::GET;
+        *request.body_mut() = None;
+        remove(request.headers_mut(), &[CONTENT_TYPE, CONTENT_LENGTH]);
+    }
+    if from.host_str() != to.host_str() {
+        remove(request.headers_mut(), &[AUTHORIZATION]);
+    }
+    *request.url_mut() = to.clone();
+    request
+}
+
+fn remove(headers: &mut HeaderMap, names: &[reqwest::header::HeaderName]) {
+    for name in names {
+        headers.remove(name);
+    }
+}
+
+/// Whether `url` is on `librus.pl` or one of its subdomains.
+pub(crate) fn is_librus_host(url: &Url) -> bool {
+    url.host_str().is_some_and(is_librus_domain)
+}
+
+/// Whether `host` is `librus.pl` or one of its subdomains.
+pub(crate) fn is_librus_domain(host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    host == "librus.pl" || host.ends_with(".librus.pl")
+}
@@ -0,0 +1,210 @@
+//! School-day arithmetic combining weekends, public holidays and free days.
+
+use crate::dates;
+use crate::{Error, Result};
+
+/// Calendar of school days within a date range.
+///
+/// A day is a school day unless it is a weekend, a Polish public holiday or
+/// falls into one of the free-day ranges the calendar was built with. Ranges
+/// may overlap; they are merged on construction.
+///
+/// Build it from the API with
+/// [`Client::school_calendar()`](crate::Client::school_calendar), or directly
+/// with [`SchoolCalendar::new()`].
+///
+/// # Example
+///
+/// ```rust
+/// use librus_rs::SchoolCalendar;
+///
+/// # fn example() -> Result<(), librus_rs::Error> {
+/// // Winter break on Monday and Tuesday.
+/// let calendar = SchoolCalendar::new(
+///     "2025-02-01",
+///     "2025-02-28",
+///     [("2025-02-17", "2025-02-18")],
+/// )?;
+/// // Friday -> skips the weekend and the break.
+/// assert_eq!(calendar.next_school_day("2025-02-14").as_deref(), Some("2025-02-19"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchoolCalendar {
+    from: i64,
+    to: i64,
+    /// Sorted, non-overlapping, non-adjacent inclusive day ranges.
+    free: Vec<(i64, i64)>,
+}
+
+impl SchoolCalendar {
+    /// Creates a calendar for `from..=to` with the given free-day ranges.
+    ///
+    /// Each range is an inclusive `(date_from, date_to)` pair of `YYYY-MM-DD`
+    /// dates. Malformed ranges are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDate`] if `from` or `to` is not a valid date.
+    pub fn new<'a, I>(from: &str, to: &str, free_days: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let parse = |d: &str| dates::parse_day(d).ok_or_else(|| Error::InvalidDate(d.to_string()));
+        let (from, to) = (parse(from)?, parse(to)?);
+
+        let mut ranges: Vec<(i64, i64)> = free_days
+            .into_iter()
+            .filter_map(|(a, b)| Some((dates::parse_day(a)?, dates::parse_day(b)?)))
+            .filter(|(a, b)| a <= b)
+            .collect();
+        ranges.extend(public_holidays(from, to).map(|d| (d, d)));
+
+        Ok(Self {
+            from,
+            to,
+            free: union(ranges),
+        })
+    }
+
+    /// Whether `date` is a school day.
+    ///
+    /// Returns `false` for malformed dates.
+    pub fn is_school_day(&self, date: &str) -> bool {
+        dates::parse_day(date).is_some_and(|d| self.is_school_day_number(d))
+    }
+
+    /// The first school day strictly after `after`, within the calendar range.
+    ///
+    /// Returns `None` if there is no school day left before the end of the
+    /// range, or if `after` is malformed.
+    pub fn next_school_day(&self, after: &str) -> Option<String> {
+        let start = dates::parse_day(after)? + 1;
+        (start.max(self.from)..=self.to)
+            .find(|d| self.is_school_day_number(*d))
+            .map(dates::format_day)
+    }
+
+    /// Number of school days in `a..=b`, clamped to the calendar range.
+    ///
+    /// Returns 0 if `a` is after `b` or either date is malformed.
+    pub fn school_days_between(&self, a: &str, b: &str) -> usize {
+        let (Some(a), Some(b)) = (dates::parse_day(a), dates::parse_day(b)) else {
+            return 0;
+        };
+        (a.max(self.from)..=b.min(self.to))
+            .filter(|d| self.is_school_day_number(*d))
+            .count()
+    }
+
+    fn is_school_day_number(&self, day: i64) -> bool {
+        if dates::weekday(day) >= 5 {
+            return false;
+        }
+        // Ranges are sorted and disjoint, so only the last range starting on
+        // or before `day` can contain it.
+        let idx = self.free.partition_point(|(start, _)| *start <= day);
+        idx == 0 || self.free[idx - 1].1 < day
+    }
+}
+
+/// Merges overlapping and adjacent inclusive ranges.
+fn union(mut ranges: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(i64, i64)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Polish statutory public holidays falling in `from..=to`.
+fn public_holidays(from: i64, to: i64) -> impl Iterator<Item = i64> {
+    (dates::year_of(from)..=dates::year_of(to))
+        .flat_map(|year| {
+            let easter = dates::easter_sunday(year);
+            let fixed = [
+                (1, 1),
+                (1, 6),
+                (5, 1),
+                (5, 3),
+                (8, 15),
+                (11, 1),
+                (11, 11),
+                (12, 25),
+                (12, 26),
+            ];
+            let christmas_eve = (year >= 2025).then(|| dates::days_from_civil(year, 12, 24));
+            fixed
+                .into_iter()
+                .map(move |(m, d)| dates::days_from_civil(year, m, d))
+                // Easter Monday and Corpus Christi.
+                .chain([easter + 1, easter + 60])
+                .chain(christmas_eve)
+        })
+        .filter(move |d| (from..=to).contains(d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_ranges_are_unioned() {
+        let calendar = SchoolCalendar::new(
+            "2025-01-01",
+            "2025-03-31",
+            [
+                ("2025-02-17", "2025-02-21"),
+                ("2025-02-19", "2025-02-26"),
+                ("2025-02-27", "2025-02-27"),
+                ("2025-02-20", "2025-02-20"),
+            ],
+        )
+        .unwrap();
+        let break_range = (
+            dates::parse_day("2025-02-17").unwrap(),
+            dates::parse_day("2025-02-27").unwrap(),
+        );
+        assert!(calendar.free.contains(&break_range), "{:?}", calendar.free);
+        assert!(!calendar.is_school_day("2025-02-24"));
+        assert!(!calendar.is_school_day("2025-02-27"));
+        assert!(calendar.is_school_day("2025-02-28"));
+        assert_eq!(
+            calendar.next_school_day("2025-02-14").as_deref(),
+            Some("2025-02-28")
+        );
+        // 2025-02-10..=2025-03-07: 4 weeks of weekdays minus 9 free weekdays.
+        assert_eq!(calendar.school_days_between("2025-02-10", "2025-03-07"), 11);
+    }
+
+    #[test]
+    fn test_weekend_adjacent_holiday() {
+        // Independence Day 2024 was a Monday; Friday 2024-11-08 is a free day.
+        let calendar =
+            SchoolCalendar::new("2024-11-01", "2024-11-30", [("2024-11-08", "2024-11-08")])
+                .unwrap();
+        assert!(!calendar.is_school_day("2024-11-11"));
+        assert_eq!(
+            calendar.next_school_day("2024-11-07").as_deref(),
+            Some("2024-11-12")
+        );
+        assert_eq!(calendar.school_days_between("2024-11-04", "2024-11-15"), 8);
+    }
+
+    #[test]
+    fn test_easter_monday_and_range_end() {
+        let calendar = SchoolCalendar::new("2025-04-01", "2025-04-22", []).unwrap();
+        assert!(!calendar.is_school_day("2025-04-21"));
+        assert_eq!(
+            calendar.next_school_day("2025-04-18").as_deref(),
+            Some("2025-04-22")
+        );
+        assert_eq!(calendar.next_school_day("2025-04-22"), None);
+        assert!(SchoolCalendar::new("2025-13-01", "2025-04-22", []).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! Diacritics-insensitive search over fetched data.
+//!
+//! Names are often typed without Polish characters ("Lukasz" for "Łukasz").
+//! [`normalize_pl()`] folds case and strips diacritics so both forms compare
+//! equal; the search helpers below are built on it.
+//!
+//! # Example
+//!
+//! ```rust
+//! use librus_rs::search::{matches, normalize_pl};
+//!
+//! assert_eq!(normalize_pl("Łukasz Żółć"), "lukasz zolc");
+//! assert!(matches("Język polski", "jezyk POL"));
+//! ```
+
+use crate::structs::lessons::{LessonSubject, ResponseSubjects};
+use crate::structs::messages::{InboxMessage, MessageCategory};
+use crate::structs::users::User;
+
+/// Lowercases a string and replaces Polish letters with their ASCII base
+/// letter (`ą` → `a`, `ł` → `l`, `ż` → `z`, ...).
+pub fn normalize_pl(text: &str) -> String {
+    text.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            'ą' => 'a',
+            'ć' => 'c',
+            'ę' => 'e',
+            'ł' => 'l',
+            'ń' => 'n',
+            'ó' => 'o',
+            'ś' => 's',
+            'ź' | 'ż' => 'z',
+            c => c,
+        })
+        .collect()
+}
+
+/// Whether every whitespace-separated word of `query` occurs in `text`,
+/// ignoring case and Polish diacritics.
+///
+/// An empty query matches everything.
+pub fn matches(text: &str, query: &str) -> bool {
+    let text = normalize_pl(text);
+    normalize_pl(query)
+        .split_whitespace()
+        .all(|word| text.contains(word))
+}
+
+impl ResponseSubjects {
+    /// Subjects whose name or short code matches `query`.
+    ///
+    /// See [`matches()`] for the matching rules.
+    pub fn find(&self, query: &str) -> Vec<&LessonSubject> {
+        self.subjects
+            .iter()
+            .filter(|s| matches(&format!("{} {}", s.name, s.short), query))
+            .collect()
+    }
+}
+
+/// Search helpers for lists of [`User`]s.
+pub trait UserSearch {
+    /// School employees whose full name matches `query`.
+    fn find_teacher(&self, query: &str) -> Vec<&User>;
+}
+
+impl UserSearch for [User] {
+    fn find_teacher(&self, query: &str) -> Vec<&User> {
+        self.iter()
+            .filter(|u| u.is_employee)
+            .filter(|u| matches(&format!("{} {}", u.first_name, u.last_name), query))
+            .collect()
+    }
+}
+
+/// Search helpers for lists of [`InboxMessage`]s.
+pub trait MessageSearch {
+    /// Messages whose sender name matches `query`.
+    fn filter_by_sender(&self, query: &str) -> Vec<&InboxMessage>;
+
+    /// Messages of `category`.
+    fn by_category(&self, category: &MessageCategory) -> Vec<&InboxMessage>;
+
+    /// Messages without a category.
+    fn uncategorized(&self) -> Vec<&InboxMessage>;
+}
+
+impl MessageSearch for [InboxMessage] {
+    fn filter_by_sender(&self, query: &str) -> Vec<&InboxMessage> {
+        self.iter()
+            .filter(|m| matches(&m.sender_name, query))
+            .collect()
+    }
+
+    fn by_category(&self, category: &MessageCategory) -> Vec<&InboxMessage> {
+        self.iter()
+            .filter(|m| m.category.as_ref() == Some(category))
+            .collect()
+    }
+
+    fn uncategorized(&self) -> Vec<&InboxMessage> {
+        self.iter().filter(|m| m.category.is_none()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_polish_letters() {
+        let table = [
+            ("ą", "a"),
+            ("Ą", "a"),
+            ("ć", "c"),
+            ("Ć", "c"),
+            ("ę", "e"),
+            ("Ę", "e"),
+            ("ł", "l"),
+            ("Ł", "l"),
+            ("ń", "n"),
+            ("Ń", "n"),
+            ("ó", "o"),
+            ("Ó", "o"),
+            ("ś", "s"),
+            ("Ś", "s"),
+            ("ź", "z"),
+            ("Ź", "z"),
+            ("ż", "z"),
+            ("Ż", "z"),
+            ("Matematyka", "matematyka"),
+            ("Zażółć gęślą jaźń", "zazolc gesla jazn"),
+            ("WYCHOWANIE FIZYCZNE", "wychowanie fizyczne"),
+            ("", ""),
+        ];
+        for (input, expected) in table {
+            assert_eq!(normalize_pl(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("Łukasz Wiśniewski", "lukasz"));
+        assert!(matches("Łukasz Wiśniewski", "wisniewski  Łuk"));
+        assert!(matches("Matematyka", ""));
+        assert!(!matches("Łukasz Wiśniewski", "lukasz nowak"));
+    }
+
+    #[test]
+    fn test_list_helpers() {
+        let subjects: ResponseSubjects =
+            serde_json::from_str(include_str!("../tests/fixtures/subjects.json")).unwrap();
+        assert_eq!(subjects.find("MAT")[0].id, 21);
+        assert!(subjects.find("fizyka").is_empty());
+
+        let users: Vec<User> = serde_json::from_str(
+            r#"[
+                {"Id": 1, "AccountId": "1u", "FirstName": "Łukasz", "LastName": "Żak",
+                 "IsEmployee": true, "GroupId": 3},
+                {"Id": 2, "AccountId": "2u", "FirstName": "Łukasz", "LastName": "Nowak",
+                 "IsEmployee": false, "GroupId": 8}
+            ]"#,
+        )
+        .unwrap();
+        let found = users.find_teacher("lukasz");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, 1);
+
+        let messages: Vec<InboxMessage> = serde_json::from_str(
+            r#"[{"messageId": "1", "senderFirstName": "Anna", "senderLastName": "Ślęzak",
+                 "senderName": "Anna Ślęzak", "topic": "", "content": "", "sendDate": "",
+                 "readDate": null, "isAnyFileAttached": false, "tags": [], "category": null}]"#,
+        )
+        .unwrap();
+        assert_eq!(messages.filter_by_sender("slezak").len(), 1);
+
+        let messages: Vec<InboxMessage> = serde_json::from_value(
+            serde_json::from_str::<serde_json::Value>(include_str!(
+                "../tests/fixtures/inbox_messages_categories.json"
+            ))
+            .unwrap()["data"]
+                .clone(),
+        )
+        .unwrap();
+        let ids = |found: Vec<&InboxMessage>| -> Vec<String> {
+            found.iter().map(|m| m.message_id.clone()).collect()
+        };
+        assert_eq!(
+            ids(messages.by_category(&MessageCategory::System)),
+            ["7107", "7108"]
+        );
+        assert_eq!(
+            ids(messages.by_category(&MessageCategory::Other("wychowawca".into()))),
+            ["7110"]
+        );
+        assert_eq!(ids(messages.uncategorized()), ["7101", "7109", "7111"]);
+    }
+}
@@ -1,20 +0,0 @@
-//! Serde helpers for flexible type handling.
-
-use serde::{Deserialize, Deserializer};
-
-pub fn string_or_int<'de, D>(deserializer: D) -> Result<String, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum StringOrInt {
-        String(String),
-        Int(i64),
-    }
-
-    match StringOrInt::deserialize(deserializer)? {
-        StringOrInt::String(s) => Ok(s),
-        StringOrInt::Int(i) => Ok(i.to_string()),
-    }
-}
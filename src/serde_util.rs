@@ -0,0 +1,554 @@
+//! Deserializers for the loosely typed values sent by different instances.
+//!
+//! The crate's own structs use these, and they are public so that structs
+//! defined outside the crate, e.g. for [`Client::follow()`](crate::Client::follow),
+//! can accept the same forms. Each is a function for
+//! `#[serde(deserialize_with = "...")]`:
+//!
+//! | Function | Target | Accepts |
+//! |----------|--------|---------|
+//! | [`flexible_bool()`] | `bool` | `true`, `1`, `"1"`, `"true"` and their negations |
+//! | [`flexible_i64()`] | `i64` | `42`, `"42"` |
+//! | [`comma_decimal()`] | `Option<f64>` | `4.25`, `"4,25"`, `"4.25"`; anything else is `None` |
+//! | [`empty_array_as_none()`] | `Option<T>` | a `T`; `[]` and `null` are `None` |
+//! | [`string_or_struct()`] | `T` | a `T`, or a string parsed with `T::from_str()` |
+//! | [`string_or_int()`] | `String` | `"42"`, `42` |
+//! | [`lenient_u64()`] | `Option<u64>` | `42`, `"42"`; anything else is `None` |
+//! | [`lenient_one_or_many()`] | `Option<Vec<T>>` | a list or a single `T` |
+//!
+//! With the `serde_with` feature, [`FlexibleBool`], [`FlexibleI64`],
+//! [`CommaDecimal`], [`EmptyArrayAsNone`] and [`StringOrStruct`] do the same
+//! as `serde_with` adapters, which also compose with `Option`, `Vec` and
+//! maps.
+//!
+//! # Example
+//!
+//! ```rust
+//! use librus_rs::serde_util::{comma_decimal, flexible_bool};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! #[serde(rename_all = "PascalCase")]
+//! struct Average {
+//!     #[serde(default, deserialize_with = "comma_decimal")]
+//!     value: Option<f64>,
+//!     #[serde(deserialize_with = "flexible_bool")]
+//!     is_final: bool,
+//! }
+//!
+//! let average: Average = serde_json::from_str(r#"{"Value": "4,25", "IsFinal": "1"}"#)?;
+//! assert_eq!(average.value, Some(4.25));
+//! assert!(average.is_final);
+//! # Ok::<(), serde_json::Error>(())
+//! ```
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer};
+
+/// Deserializes a string that some instances send as a number (`42`).
+pub fn string_or_int<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrInt {
+        String(String),
+        Int(i64),
+    }
+
+    match StringOrInt::deserialize(deserializer)? {
+        StringOrInt::String(s) => Ok(s),
+        StringOrInt::Int(i) => Ok(i.to_string()),
+    }
+}
+
+/// An integer as sent by different instances.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Integer {
+    Int(i64),
+    String(String),
+}
+
+impl Integer {
+    fn to_i64<E: serde::de::Error>(&self) -> Result<i64, E> {
+        match self {
+            Self::Int(i) => Ok(*i),
+            Self::String(s) => s
+                .trim()
+                .parse()
+                .map_err(|_| E::custom(format!("invalid integer {s:?}"))),
+        }
+    }
+}
+
+/// Deserializes an integer that some instances send as a string (`"42"`).
+pub fn flexible_i64<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Integer::deserialize(deserializer)?.to_i64()
+}
+
+/// Like [`flexible_i64()`], for optional integers. `null` becomes `None`.
+pub fn flexible_i64_opt<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Integer>::deserialize(deserializer)?
+        .map(|i| i.to_i64())
+        .transpose()
+}
+
+/// Deserializes an optional unsigned number that some instances send as a
+/// string (`"1735689600"`, `"0"`). Empty and non-numeric strings become `None`.
+pub fn lenient_u64<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrInt {
+        Int(u64),
+        String(String),
+    }
+
+    Ok(match Option::<StringOrInt>::deserialize(deserializer)? {
+        Some(StringOrInt::Int(i)) => Some(i),
+        Some(StringOrInt::String(s)) => s.trim().parse().ok(),
+        None => None,
+    })
+}
+
+/// Deserializes an optional decimal that the API may send as a string with a
+/// decimal comma (`"4,25"`). Empty, `"-"` and other non-numeric values become
+/// `None`.
+pub fn comma_decimal<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber {
+        Number(f64),
+        String(String),
+    }
+
+    Ok(match Option::<StringOrNumber>::deserialize(deserializer)? {
+        Some(StringOrNumber::Number(n)) => Some(n),
+        Some(StringOrNumber::String(s)) => s.trim().replace(',', ".").parse().ok(),
+        None => None,
+    }
+    .filter(|n: &f64| n.is_finite()))
+}
+
+/// A flag as sent by different instances.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Flag {
+    Bool(bool),
+    Int(i64),
+    String(String),
+}
+
+impl Flag {
+    fn to_bool<E: serde::de::Error>(&self) -> Result<bool, E> {
+        match self {
+            Self::Bool(b) => Ok(*b),
+            Self::Int(0) => Ok(false),
+            Self::Int(1) => Ok(true),
+            Self::Int(i) => Err(E::custom(format!("invalid flag {i}, expected 0 or 1"))),
+            Self::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+                "0" | "false" => Ok(false),
+                "1" | "true" => Ok(true),
+                _ => Err(E::custom(format!("invalid flag {s:?}, expected 0 or 1"))),
+            },
+        }
+    }
+}
+
+/// Deserializes a flag that instances send as `true`/`false`, `0`/`1`,
+/// `"0"`/`"1"` or `"true"`/`"false"`.
+pub fn flexible_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Flag::deserialize(deserializer)?.to_bool()
+}
+
+/// Like [`flexible_bool()`], for optional flags. `null` becomes `None`.
+pub fn flexible_bool_opt<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Flag>::deserialize(deserializer)?
+        .map(|flag| flag.to_bool())
+        .transpose()
+}
+
+/// Deserializes an optional object that PHP-backed endpoints send as an
+/// empty list (`[]`) when it has no value.
+pub fn empty_array_as_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ValueOrEmpty<T> {
+        Empty([(); 0]),
+        Value(T),
+    }
+
+    Ok(
+        match Option::<ValueOrEmpty<T>>::deserialize(deserializer)? {
+            Some(ValueOrEmpty::Value(value)) => Some(value),
+            Some(ValueOrEmpty::Empty(_)) | None => None,
+        },
+    )
+}
+
+/// Deserializes a value that some instances send as a string in place of the
+/// full object, parsing the string with the type's [`FromStr`].
+pub fn string_or_struct<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+    T::Err: Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrStruct<T> {
+        String(String),
+        Struct(T),
+    }
+
+    match StringOrStruct::<T>::deserialize(deserializer)? {
+        StringOrStruct::String(s) => s.parse().map_err(serde::de::Error::custom),
+        StringOrStruct::Struct(value) => Ok(value),
+    }
+}
+
+/// Deserializes a list that some instances send as a single object.
+///
+/// Values of any other shape are ignored, so an unexpected format of an
+/// optional field never fails the whole response.
+pub fn lenient_one_or_many<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        Many(Vec<T>),
+        One(T),
+        Other(serde::de::IgnoredAny),
+    }
+
+    Ok(match Option::<OneOrMany<T>>::deserialize(deserializer)? {
+        Some(OneOrMany::Many(items)) => Some(items),
+        Some(OneOrMany::One(item)) => Some(vec![item]),
+        Some(OneOrMany::Other(_)) | None => None,
+    })
+}
+
+/// `serde_with` adapter for [`flexible_bool()`], for `bool` fields.
+#[cfg(feature = "serde_with")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlexibleBool;
+
+/// `serde_with` adapter for [`flexible_i64()`], for `i64` fields.
+#[cfg(feature = "serde_with")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlexibleI64;
+
+/// `serde_with` adapter for [`comma_decimal()`], for `Option<f64>` fields.
+#[cfg(feature = "serde_with")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CommaDecimal;
+
+/// `serde_with` adapter for [`empty_array_as_none()`], for `Option<T>`
+/// fields.
+#[cfg(feature = "serde_with")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmptyArrayAsNone;
+
+/// `serde_with` adapter for [`string_or_struct()`], for fields of types
+/// implementing [`FromStr`].
+#[cfg(feature = "serde_with")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringOrStruct;
+
+#[cfg(feature = "serde_with")]
+impl<'de> serde_with::DeserializeAs<'de, bool> for FlexibleBool {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+        flexible_bool(deserializer)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de> serde_with::DeserializeAs<'de, i64> for FlexibleI64 {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        flexible_i64(deserializer)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de> serde_with::DeserializeAs<'de, Option<f64>> for CommaDecimal {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+        comma_decimal(deserializer)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de, T: Deserialize<'de>> serde_with::DeserializeAs<'de, Option<T>> for EmptyArrayAsNone {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+        empty_array_as_none(deserializer)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de, T> serde_with::DeserializeAs<'de, T> for StringOrStruct
+where
+    T: Deserialize<'de> + FromStr,
+    T::Err: Display,
+{
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        string_or_struct(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deserializes `json` with `f`, as a field would.
+    fn parse<T>(
+        f: fn(serde_json::Value) -> serde_json::Result<T>,
+        json: &str,
+    ) -> serde_json::Result<T> {
+        f(serde_json::from_str(json)?)
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Flags {
+        #[serde(deserialize_with = "flexible_bool")]
+        flag: bool,
+        #[serde(default, deserialize_with = "flexible_bool_opt")]
+        optional: Option<bool>,
+    }
+
+    fn flag(json: &str) -> serde_json::Result<bool> {
+        serde_json::from_str::<Flags>(&format!(r#"{{"flag": {json}}}"#)).map(|f| f.flag)
+    }
+
+    #[test]
+    fn test_flexible_bool_booleans() {
+        assert!(flag("true").unwrap());
+        assert!(!flag("false").unwrap());
+    }
+
+    #[test]
+    fn test_flexible_bool_numbers() {
+        assert!(flag("1").unwrap());
+        assert!(!flag("0").unwrap());
+        assert!(flag("2").is_err());
+    }
+
+    #[test]
+    fn test_flexible_bool_numeric_strings() {
+        assert!(flag(r#""1""#).unwrap());
+        assert!(!flag(r#""0""#).unwrap());
+    }
+
+    #[test]
+    fn test_flexible_bool_word_strings() {
+        assert!(flag(r#""true""#).unwrap());
+        assert!(!flag(r#""False""#).unwrap());
+        assert!(flag(r#""tak""#).is_err());
+        assert!(flag("null").is_err());
+    }
+
+    #[test]
+    fn test_flexible_bool_opt() {
+        let parse = |json: &str| serde_json::from_str::<Flags>(json).unwrap().optional;
+        assert_eq!(parse(r#"{"flag": 0}"#), None);
+        assert_eq!(parse(r#"{"flag": 0, "optional": null}"#), None);
+        assert_eq!(parse(r#"{"flag": 0, "optional": "1"}"#), Some(true));
+        assert_eq!(parse(r#"{"flag": 0, "optional": false}"#), Some(false));
+    }
+
+    #[test]
+    fn test_flexible_i64() {
+        assert_eq!(parse(flexible_i64, "42").unwrap(), 42);
+        assert_eq!(parse(flexible_i64, "-7").unwrap(), -7);
+        assert_eq!(parse(flexible_i64, r#""42""#).unwrap(), 42);
+        assert_eq!(parse(flexible_i64, r#"" 42 ""#).unwrap(), 42);
+        assert!(parse(flexible_i64, r#""""#).is_err());
+        assert!(parse(flexible_i64, r#""4,5""#).is_err());
+        assert!(parse(flexible_i64, "4.5").is_err());
+        assert!(parse(flexible_i64, "null").is_err());
+
+        assert_eq!(parse(flexible_i64_opt, "null").unwrap(), None);
+        assert_eq!(parse(flexible_i64_opt, r#""42""#).unwrap(), Some(42));
+        assert!(parse(flexible_i64_opt, r#""abc""#).is_err());
+    }
+
+    #[test]
+    fn test_lenient_u64() {
+        assert_eq!(parse(lenient_u64, "1735689600").unwrap(), Some(1735689600));
+        assert_eq!(parse(lenient_u64, r#""0""#).unwrap(), Some(0));
+        assert_eq!(parse(lenient_u64, r#""""#).unwrap(), None);
+        assert_eq!(parse(lenient_u64, r#""never""#).unwrap(), None);
+        assert_eq!(parse(lenient_u64, "null").unwrap(), None);
+    }
+
+    #[test]
+    fn test_comma_decimal() {
+        assert_eq!(parse(comma_decimal, "4.25").unwrap(), Some(4.25));
+        assert_eq!(parse(comma_decimal, "4").unwrap(), Some(4.0));
+        assert_eq!(parse(comma_decimal, r#""4,25""#).unwrap(), Some(4.25));
+        assert_eq!(parse(comma_decimal, r#""4.25""#).unwrap(), Some(4.25));
+        assert_eq!(parse(comma_decimal, r#"" 3,5 ""#).unwrap(), Some(3.5));
+        assert_eq!(parse(comma_decimal, r#""""#).unwrap(), None);
+        assert_eq!(parse(comma_decimal, r#""-""#).unwrap(), None);
+        assert_eq!(parse(comma_decimal, r#""NaN""#).unwrap(), None);
+        assert_eq!(parse(comma_decimal, "null").unwrap(), None);
+    }
+
+    #[test]
+    fn test_string_or_int() {
+        assert_eq!(parse(string_or_int, r#""abc""#).unwrap(), "abc");
+        assert_eq!(parse(string_or_int, "42").unwrap(), "42");
+        assert!(parse(string_or_int, "null").is_err());
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    impl FromStr for Point {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let (x, y) = s.split_once(',').ok_or("expected x,y")?;
+            Ok(Point {
+                x: x.parse().map_err(|_| "invalid x")?,
+                y: y.parse().map_err(|_| "invalid y")?,
+            })
+        }
+    }
+
+    #[test]
+    fn test_empty_array_as_none() {
+        let parse_point = |json| parse(empty_array_as_none::<_, Point>, json);
+        assert_eq!(
+            parse_point(r#"{"x": 1, "y": 2}"#).unwrap(),
+            Some(Point { x: 1, y: 2 })
+        );
+        assert_eq!(parse_point("[]").unwrap(), None);
+        assert_eq!(parse_point("null").unwrap(), None);
+        assert!(parse_point("[1]").is_err());
+        assert!(parse_point(r#""1,2""#).is_err());
+
+        // A list target still gets its items; only the empty list is None.
+        let parse_list = |json| parse(empty_array_as_none::<_, Vec<i32>>, json);
+        assert_eq!(parse_list("[1, 2]").unwrap(), Some(vec![1, 2]));
+        assert_eq!(parse_list("[]").unwrap(), None);
+    }
+
+    #[test]
+    fn test_string_or_struct() {
+        let parse_point = |json| parse(string_or_struct::<_, Point>, json);
+        assert_eq!(
+            parse_point(r#"{"x": 1, "y": 2}"#).unwrap(),
+            Point { x: 1, y: 2 }
+        );
+        assert_eq!(parse_point(r#""3,4""#).unwrap(), Point { x: 3, y: 4 });
+        let err = parse_point(r#""3;4""#).unwrap_err();
+        assert!(err.to_string().contains("expected x,y"));
+        assert!(parse_point("null").is_err());
+    }
+
+    #[test]
+    fn test_lenient_one_or_many() {
+        let parse_points = |json| parse(lenient_one_or_many::<_, Point>, json);
+        assert_eq!(
+            parse_points(r#"[{"x": 1, "y": 2}]"#).unwrap(),
+            Some(vec![Point { x: 1, y: 2 }])
+        );
+        assert_eq!(
+            parse_points(r#"{"x": 1, "y": 2}"#).unwrap(),
+            Some(vec![Point { x: 1, y: 2 }])
+        );
+        assert_eq!(parse_points("[]").unwrap(), Some(vec![]));
+        assert_eq!(parse_points(r#""none""#).unwrap(), None);
+        assert_eq!(parse_points("null").unwrap(), None);
+    }
+
+    #[cfg(feature = "serde_with")]
+    #[test]
+    fn test_serde_with_adapters() {
+        use serde_with::As;
+
+        #[derive(Debug, Deserialize)]
+        struct Record {
+            #[serde(with = "As::<FlexibleBool>")]
+            flag: bool,
+            #[serde(default, with = "As::<Option<FlexibleBool>>")]
+            optional_flag: Option<bool>,
+            #[serde(with = "As::<FlexibleI64>")]
+            id: i64,
+            #[serde(with = "As::<Vec<FlexibleI64>>")]
+            ids: Vec<i64>,
+            #[serde(default, with = "As::<CommaDecimal>")]
+            average: Option<f64>,
+            #[serde(with = "As::<EmptyArrayAsNone>")]
+            point: Option<Point>,
+            #[serde(with = "As::<StringOrStruct>")]
+            origin: Point,
+        }
+
+        let record: Record = serde_json::from_str(
+            r#"{
+                "flag": "1",
+                "optional_flag": 0,
+                "id": "42",
+                "ids": [1, "2"],
+                "average": "4,5",
+                "point": [],
+                "origin": "0,0"
+            }"#,
+        )
+        .unwrap();
+        assert!(record.flag);
+        assert_eq!(record.optional_flag, Some(false));
+        assert_eq!(record.id, 42);
+        assert_eq!(record.ids, [1, 2]);
+        assert_eq!(record.average, Some(4.5));
+        assert_eq!(record.point, None);
+        assert_eq!(record.origin, Point { x: 0, y: 0 });
+
+        let record: Record = serde_json::from_str(
+            r#"{
+                "flag": false,
+                "id": 7,
+                "ids": [],
+                "point": {"x": 1, "y": 2},
+                "origin": {"x": 3, "y": 4}
+            }"#,
+        )
+        .unwrap();
+        assert!(!record.flag);
+        assert_eq!(record.optional_flag, None);
+        assert_eq!(record.average, None);
+        assert_eq!(record.point, Some(Point { x: 1, y: 2 }));
+        assert_eq!(record.origin, Point { x: 3, y: 4 });
+    }
+}
@@ -0,0 +1,448 @@
+//! Flattened, owned copies of the main entities for use in other apps.
+//!
+//! The API types mirror the Librus responses, with references, resource maps
+//! and URLs. The types here hold only what an app usually shows or stores,
+//! with names filled in from a [`Lookup`] of the lists fetched alongside.
+//!
+//! # Stability
+//!
+//! The serialized form of these types is meant to be stored. Within a major
+//! version of the crate, fields are never renamed, removed or given another
+//! meaning; new fields are only added as optional, so JSON written by an
+//! earlier version still deserializes. Tests read the form written by the
+//! first version of this module to enforce that.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use librus_rs::simple::{Lookup, SimpleGrade};
+//! use librus_rs::Client;
+//!
+//! # async fn example() -> Result<(), librus_rs::Error> {
+//! let client = Client::from_env().await?;
+//! let lookup = Lookup::new()
+//!     .subjects(&client.subjects().await?.subjects)
+//!     .users(&client.users().await?.users);
+//! let grades: Vec<SimpleGrade> = client
+//!     .grades()
+//!     .await?
+//!     .iter()
+//!     .map(|grade| lookup.join(grade).into())
+//!     .collect();
+//! println!("{}", serde_json::to_string(&grades).unwrap());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::decode;
+use crate::structs::events::Homework;
+use crate::structs::grades::{CommentRef, Grade, GradeCategory, GradeComment};
+use crate::structs::lessons::{Attendance, AttendanceType, LessonSubject};
+use crate::structs::messages::{InboxMessage, MessageDetail};
+use crate::structs::resources::Reference;
+use crate::structs::users::User;
+
+/// A grade with its subject, teacher and comment resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimpleGrade {
+    /// Grade ID.
+    pub id: i64,
+    /// Subject name, if the subject was in the [`Lookup`].
+    pub subject_name: Option<String>,
+    /// The grade as shown in the register, e.g. `"4+"`.
+    pub value: String,
+    /// Date of the grade (`YYYY-MM-DD`).
+    pub date: String,
+    /// Weight of the grade's category, if known.
+    pub weight: Option<u32>,
+    /// Name of the teacher who added the grade, if known.
+    pub teacher_name: Option<String>,
+    /// Text of the first comment, if embedded or in the [`Lookup`].
+    pub comment: Option<String>,
+    /// Whole days between the grade's date and when it was entered, see
+    /// [`Grade::entry_delay()`]. Missing when unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_delay_days: Option<u64>,
+}
+
+/// A message, received or opened, with its body decoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimpleMessage {
+    /// Message ID.
+    pub id: String,
+    /// Sender's display name.
+    pub sender_name: String,
+    /// Topic.
+    pub topic: String,
+    /// Decoded body, if it could be decoded.
+    pub body: Option<String>,
+    /// When the message was sent (`YYYY-MM-DD HH:MM:SS`).
+    pub sent_at: String,
+    /// Whether the message was read.
+    pub read: bool,
+    /// Whether files are attached.
+    pub has_attachments: bool,
+}
+
+/// A homework entry with its subject and teacher resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimpleHomework {
+    /// Homework ID.
+    pub id: i64,
+    /// Subject name, if the entry has a subject in the [`Lookup`].
+    pub subject_name: Option<String>,
+    /// Name of the teacher who added the entry, if known.
+    pub teacher_name: Option<String>,
+    /// What is due.
+    pub content: String,
+    /// Due date (`YYYY-MM-DD`).
+    pub date: String,
+}
+
+/// The attendance records of one day, see
+/// [`SimpleAttendanceDay::from_records()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimpleAttendanceDay {
+    /// The day (`YYYY-MM-DD`).
+    pub date: String,
+    /// Records of the day, by lesson number.
+    pub entries: Vec<SimpleAttendance>,
+}
+
+/// One attendance record in a [`SimpleAttendanceDay`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimpleAttendance {
+    /// Lesson number in the day.
+    pub lesson_no: i32,
+    /// Name of the attendance type, e.g. `"Nieobecność"`, if known.
+    pub kind: Option<String>,
+    /// Short form of the type, e.g. `"nb"`, if known.
+    pub short: Option<String>,
+    /// Whether the type counts as presence, if known.
+    pub presence: Option<bool>,
+}
+
+/// Lists used to fill in names when converting to the simple types.
+///
+/// Everything is optional: what is missing is left as `None`.
+#[derive(Debug, Clone, Default)]
+pub struct Lookup {
+    subjects: HashMap<i64, String>,
+    users: HashMap<i64, String>,
+    weights: HashMap<i64, u32>,
+    comments: HashMap<i64, String>,
+    /// Name, short form and presence kind of each attendance type.
+    attendance_types: HashMap<i64, (String, String, bool)>,
+}
+
+impl Lookup {
+    /// Creates an empty lookup.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds subject names, e.g. from [`Client::subjects()`](crate::Client::subjects)
+    /// or a [`SubjectIndex`](crate::SubjectIndex).
+    pub fn subjects<'a>(mut self, subjects: impl IntoIterator<Item = &'a LessonSubject>) -> Self {
+        self.subjects.extend(
+            subjects
+                .into_iter()
+                .map(|s| (i64::from(s.id), s.name.clone())),
+        );
+        self
+    }
+
+    /// Adds teacher names, e.g. from [`Client::users()`](crate::Client::users)
+    /// or a [`UserIndex`](crate::UserIndex).
+    pub fn users<'a>(mut self, users: impl IntoIterator<Item = &'a User>) -> Self {
+        self.users.extend(
+            users
+                .into_iter()
+                .map(|u| (u.id, format!("{} {}", u.first_name, u.last_name))),
+        );
+        self
+    }
+
+    /// Adds grade category weights.
+    pub fn categories<'a>(
+        mut self,
+        categories: impl IntoIterator<Item = &'a GradeCategory>,
+    ) -> Self {
+        self.weights.extend(
+            categories
+                .into_iter()
+                .filter_map(|c| Some((c.id, c.weight?))),
+        );
+        self
+    }
+
+    /// Adds grade comment texts, for grades that only link to them.
+    pub fn comments(mut self, comments: &[GradeComment]) -> Self {
+        self.comments
+            .extend(comments.iter().map(|c| (i64::from(c.id), c.text.clone())));
+        self
+    }
+
+    /// Adds attendance types, e.g. from
+    /// [`Client::attendance_types()`](crate::Client::attendance_types).
+    pub fn attendance_types<'a>(
+        mut self,
+        types: impl IntoIterator<Item = &'a AttendanceType>,
+    ) -> Self {
+        self.attendance_types.extend(types.into_iter().map(|t| {
+            (
+                i64::from(t.id),
+                (t.name.clone(), t.short.clone(), t.is_presence_kind),
+            )
+        }));
+        self
+    }
+
+    /// Pairs an entity with this lookup, for converting with `into()`.
+    pub fn join<'a, T: ?Sized>(&'a self, item: &'a T) -> Joined<'a, T> {
+        Joined { item, lookup: self }
+    }
+
+    fn subject(&self, reference: Option<&Reference>) -> Option<String> {
+        self.subjects.get(&reference?.id()?).cloned()
+    }
+
+    fn user(&self, reference: &Reference) -> Option<String> {
+        self.users.get(&reference.id()?).cloned()
+    }
+}
+
+/// An entity paired with a [`Lookup`], returned by [`Lookup::join()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Joined<'a, T: ?Sized> {
+    /// The entity.
+    pub item: &'a T,
+    /// Where names are looked up.
+    pub lookup: &'a Lookup,
+}
+
+impl From<Joined<'_, Grade>> for SimpleGrade {
+    fn from(
+        Joined {
+            item: grade,
+            lookup,
+        }: Joined<'_, Grade>,
+    ) -> Self {
+        let comment = grade
+            .comments
+            .iter()
+            .flatten()
+            .find_map(|comment| match comment {
+                CommentRef::Embedded(comment) => Some(comment.text.clone()),
+                CommentRef::Reference(_) => lookup.comments.get(&comment.id()?).cloned(),
+            });
+        Self {
+            id: grade.id,
+            subject_name: lookup.subject(Some(&grade.subject)),
+            value: grade.grade.clone(),
+            date: grade.date.as_str().to_string(),
+            weight: grade
+                .category
+                .id()
+                .and_then(|id| lookup.weights.get(&id).copied()),
+            teacher_name: lookup.user(&grade.added_by),
+            comment,
+            entry_delay_days: grade.entry_delay().map(|delay| delay.as_secs() / 86_400),
+        }
+    }
+}
+
+impl From<&Grade> for SimpleGrade {
+    /// Converts without names; only embedded comments are filled in.
+    fn from(grade: &Grade) -> Self {
+        Lookup::new().join(grade).into()
+    }
+}
+
+impl From<Joined<'_, Homework>> for SimpleHomework {
+    fn from(Joined { item, lookup }: Joined<'_, Homework>) -> Self {
+        Self {
+            id: item.id,
+            subject_name: lookup.subject(item.subject.as_ref()),
+            teacher_name: lookup.user(&item.created_by),
+            content: item.content.clone(),
+            date: item.date.as_str().to_string(),
+        }
+    }
+}
+
+impl From<&Homework> for SimpleHomework {
+    /// Converts without names.
+    fn from(homework: &Homework) -> Self {
+        Lookup::new().join(homework).into()
+    }
+}
+
+impl From<&InboxMessage> for SimpleMessage {
+    fn from(message: &InboxMessage) -> Self {
+        Self {
+            id: message.message_id.clone(),
+            sender_name: message.sender_name.clone(),
+            topic: message.topic.clone(),
+            body: decode::decode(&message.content).map(|decoded| decoded.text),
+            sent_at: message.send_date.as_str().to_string(),
+            read: message.read_date.is_some(),
+            has_attachments: message.is_any_file_attached,
+        }
+    }
+}
+
+impl From<&MessageDetail> for SimpleMessage {
+    fn from(message: &MessageDetail) -> Self {
+        Self {
+            id: message.message_id.clone(),
+            sender_name: message.sender_name.clone(),
+            topic: message.topic.clone(),
+            body: decode::decode(&message.message).map(|decoded| decoded.text),
+            sent_at: message.send_date.as_str().to_string(),
+            read: message.read_date.is_some(),
+            has_attachments: !message.attachments.is_empty(),
+        }
+    }
+}
+
+impl SimpleAttendanceDay {
+    /// Groups attendance records by day, in date order, with the type of
+    /// each record taken from `lookup`.
+    pub fn from_records(records: &[Attendance], lookup: &Lookup) -> Vec<Self> {
+        let mut days: BTreeMap<&str, Vec<SimpleAttendance>> = BTreeMap::new();
+        for record in records {
+            let kind = record
+                .attendance_type
+                .id()
+                .and_then(|id| lookup.attendance_types.get(&id));
+            days.entry(record.date.as_str())
+                .or_default()
+                .push(SimpleAttendance {
+                    lesson_no: record.lesson_no,
+                    kind: kind.map(|(name, _, _)| name.clone()),
+                    short: kind.map(|(_, short, _)| short.clone()),
+                    presence: kind.map(|(_, _, presence)| *presence),
+                });
+        }
+        days.into_iter()
+            .map(|(date, mut entries)| {
+                entries.sort_by_key(|e| e.lesson_no);
+                Self {
+                    date: date.to_string(),
+                    entries,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::grades::{ResponseGrades, ResponseGradesCategories};
+    use crate::structs::lessons::{ResponseAttendances, ResponseSubjects};
+    use crate::structs::messages::ResponseInboxMessages;
+    use crate::structs::users::ResponseUsers;
+
+    /// The serialized form written by the first version of this module.
+    const V1: &str = include_str!("../tests/fixtures/simple_v1.json");
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Saved {
+        grade: SimpleGrade,
+        message: SimpleMessage,
+        homework: SimpleHomework,
+        attendance_day: SimpleAttendanceDay,
+    }
+
+    #[test]
+    fn test_v1_form_still_reads_and_writes() {
+        let saved: Saved = serde_json::from_str(V1).unwrap();
+        assert_eq!(saved.grade.subject_name.as_deref(), Some("Matematyka"));
+        assert_eq!(saved.grade.weight, Some(3));
+        assert_eq!(
+            saved.message.body.as_deref(),
+            Some("Zapraszam na zebranie.")
+        );
+        assert_eq!(saved.attendance_day.entries[0].short.as_deref(), Some("nb"));
+
+        // Writing it again gives the same fields and values.
+        let written = serde_json::to_value(&saved).unwrap();
+        assert_eq!(
+            written,
+            serde_json::from_str::<serde_json::Value>(V1).unwrap()
+        );
+    }
+
+    fn attendance_type(id: i32, name: &str, short: &str, presence: bool) -> AttendanceType {
+        serde_json::from_value(serde_json::json!({
+            "Id": id, "Name": name, "Short": short, "Standard": true,
+            "IsPresenceKind": presence, "Order": id, "Identifier": short,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_conversions_fill_in_names() {
+        let grades: ResponseGrades =
+            serde_json::from_str(include_str!("../tests/fixtures/grades.json")).unwrap();
+        let subjects: ResponseSubjects =
+            serde_json::from_str(include_str!("../tests/fixtures/subjects.json")).unwrap();
+        let users: ResponseUsers =
+            serde_json::from_str(include_str!("../tests/fixtures/users.json")).unwrap();
+        let category: ResponseGradesCategories =
+            serde_json::from_str(include_str!("../tests/fixtures/grades_category.json")).unwrap();
+        let lookup = Lookup::new()
+            .subjects(&subjects.subjects)
+            .users(&users.users)
+            .categories(&[category.category]);
+
+        let grade: SimpleGrade = lookup.join(&grades.grades[0]).into();
+        assert_eq!(grade.id, 1001);
+        assert_eq!(grade.value, "5");
+        assert_eq!(grade.date, "2025-10-06");
+        assert!(grade.subject_name.is_some() && grade.teacher_name.is_some());
+        assert_eq!(grade.weight, Some(3));
+        // Linked comments need `Lookup::comments()`.
+        assert_eq!(grade.comment, None);
+
+        assert_eq!(grade.entry_delay_days, Some(0));
+
+        let bare = SimpleGrade::from(&grades.grades[0]);
+        assert_eq!((bare.subject_name, bare.weight), (None, None));
+        assert_eq!(
+            SimpleGrade::from(&grades.grades[1]).entry_delay_days,
+            Some(1)
+        );
+
+        let inbox: ResponseInboxMessages =
+            serde_json::from_str(include_str!("../tests/fixtures/inbox_messages.json")).unwrap();
+        let message = SimpleMessage::from(&inbox.data[0]);
+        assert_eq!(message.id, "7002");
+        assert_eq!(message.body.as_deref(), Some("Zapraszam na zebranie."));
+    }
+
+    #[test]
+    fn test_attendance_days() {
+        let attendances: ResponseAttendances =
+            serde_json::from_str(include_str!("../tests/fixtures/attendances.json")).unwrap();
+        let lookup =
+            Lookup::new().attendance_types(&[attendance_type(1, "Nieobecność", "nb", false)]);
+        let days = SimpleAttendanceDay::from_records(&attendances.attendances, &lookup);
+        assert_eq!(days[0].date, "2025-03-04");
+        assert_eq!(days[0].entries[0].short.as_deref(), Some("nb"));
+        assert_eq!(days[0].entries[0].presence, Some(false));
+        assert!(days.windows(2).all(|w| w[0].date < w[1].date));
+        // Types missing from the lookup are left empty.
+        let unknown = days
+            .iter()
+            .flat_map(|d| &d.entries)
+            .find(|e| e.kind.is_none());
+        assert!(unknown.is_some_and(|e| e.presence.is_none()));
+    }
+}
@@ -0,0 +1,843 @@
+//! Stable snapshots of a student's data for backups.
+//!
+//! [`canonical_json()`] writes a [`StudentSnapshot`] the same way every time:
+//! object keys sorted, entities sorted by ID and whole-number floats written
+//! as integers. Consecutive daily snapshots then differ only where the data
+//! did. [`diff()`] lists what changed per section.
+//!
+//! For accounts with many messages, [`SnapshotWriter`] fetches and writes
+//! one section at a time instead, and can resume an interrupted run.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use librus_rs::snapshot::{self, StudentSnapshot};
+//! use librus_rs::Client;
+//!
+//! # async fn example() -> Result<(), librus_rs::Error> {
+//! let mut client = Client::from_env().await?;
+//! let today = StudentSnapshot {
+//!     grades: client.grades().await?.grades,
+//!     attendances: client.attendances().await?.attendances,
+//!     homeworks: client.homeworks().await?.homeworks,
+//!     notices: client.school_notices().await?.school_notices,
+//!     messages: client.inbox_messages(1, 50).await?,
+//! };
+//! let yesterday: StudentSnapshot =
+//!     serde_json::from_str(&std::fs::read_to_string("yesterday.json").unwrap()).unwrap();
+//! println!("{:?}", snapshot::diff(&yesterday, &today).grades);
+//! std::fs::write("today.json", snapshot::canonical_json(&today)).unwrap();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use tokio::fs::{self, File};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, BufWriter};
+use tokio_util::sync::CancellationToken;
+
+use crate::anonymize::{fnv1a_update, FNV1A_OFFSET};
+use crate::structs::announcements::SchoolNotice;
+use crate::structs::events::Homework;
+use crate::structs::grades::Grade;
+use crate::structs::lessons::{Attendance, AttendanceId};
+use crate::structs::messages::InboxMessage;
+use crate::{Client, Error, MessageFolder, Result};
+
+/// A student's data at one point in time.
+///
+/// Sections missing from a saved snapshot are read as empty.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StudentSnapshot {
+    /// All grades.
+    #[serde(default)]
+    pub grades: Vec<Grade>,
+    /// All attendance records.
+    #[serde(default)]
+    pub attendances: Vec<Attendance>,
+    /// All homework entries.
+    #[serde(default)]
+    pub homeworks: Vec<Homework>,
+    /// School notices.
+    #[serde(default)]
+    pub notices: Vec<SchoolNotice>,
+    /// Received messages.
+    #[serde(default)]
+    pub messages: Vec<InboxMessage>,
+}
+
+/// Changes in one section of a [`StudentSnapshot`], as entity IDs.
+///
+/// IDs are listed numerically where possible, then as text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SectionDiff {
+    /// Entities only in the new snapshot.
+    pub added: Vec<String>,
+    /// Entities only in the old snapshot.
+    pub removed: Vec<String>,
+    /// Entities in both whose fields differ.
+    pub changed: Vec<String>,
+}
+
+impl SectionDiff {
+    /// Whether the section is unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Differences between two snapshots, returned by [`diff()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    /// Changes in [`StudentSnapshot::grades`].
+    pub grades: SectionDiff,
+    /// Changes in [`StudentSnapshot::attendances`].
+    pub attendances: SectionDiff,
+    /// Changes in [`StudentSnapshot::homeworks`].
+    pub homeworks: SectionDiff,
+    /// Changes in [`StudentSnapshot::notices`].
+    pub notices: SectionDiff,
+    /// Changes in [`StudentSnapshot::messages`].
+    pub messages: SectionDiff,
+}
+
+impl SnapshotDiff {
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        [
+            &self.grades,
+            &self.attendances,
+            &self.homeworks,
+            &self.notices,
+            &self.messages,
+        ]
+        .iter()
+        .all(|section| section.is_empty())
+    }
+}
+
+/// Serializes a snapshot to pretty-printed JSON that is identical for equal
+/// data, whatever order the entities were fetched in.
+pub fn canonical_json(snapshot: &StudentSnapshot) -> String {
+    let mut root = Map::new();
+    for (name, entities) in sections(snapshot) {
+        let values = entities.into_iter().map(|(_, value)| value).collect();
+        root.insert(name.to_string(), Value::Array(values));
+    }
+    let mut json = serde_json::to_string_pretty(&canonical(Value::Object(root)))
+        .expect("snapshot values are always serializable");
+    json.push('\n');
+    json
+}
+
+/// Lists the entities added, removed and changed from `old` to `new`, per
+/// section.
+///
+/// Entities are matched by ID and compared in their canonical form, so
+/// differences in key order or number formatting do not count as changes.
+pub fn diff(old: &StudentSnapshot, new: &StudentSnapshot) -> SnapshotDiff {
+    let mut diffs = sections(old)
+        .into_iter()
+        .zip(sections(new))
+        .map(|((_, old), (_, new))| diff_section(old, new));
+    let mut next = || diffs.next().unwrap_or_default();
+    SnapshotDiff {
+        grades: next(),
+        attendances: next(),
+        homeworks: next(),
+        notices: next(),
+        messages: next(),
+    }
+}
+
+/// Each section by name, as `(ID, canonical value)` sorted by ID.
+fn sections(snapshot: &StudentSnapshot) -> [(&'static str, Vec<(String, Value)>); 5] {
+    [
+        ("grades", entities(&snapshot.grades, grade_id)),
+        (
+            "attendances",
+            entities(&snapshot.attendances, attendance_id),
+        ),
+        ("homeworks", entities(&snapshot.homeworks, homework_id)),
+        ("notices", entities(&snapshot.notices, notice_id)),
+        ("messages", entities(&snapshot.messages, message_id)),
+    ]
+}
+
+fn grade_id(grade: &Grade) -> String {
+    grade.id.to_string()
+}
+
+fn attendance_id(attendance: &Attendance) -> String {
+    match &attendance.id {
+        AttendanceId::Integer(id) => id.to_string(),
+        AttendanceId::String(id) => id.clone(),
+    }
+}
+
+fn homework_id(homework: &Homework) -> String {
+    homework.id.to_string()
+}
+
+fn notice_id(notice: &SchoolNotice) -> String {
+    notice.id.clone()
+}
+
+fn message_id(message: &InboxMessage) -> String {
+    message.message_id.clone()
+}
+
+fn entities<T: Serialize>(items: &[T], id: impl Fn(&T) -> String) -> Vec<(String, Value)> {
+    let mut entities: Vec<(String, Value)> = items
+        .iter()
+        .map(|item| {
+            let value = serde_json::to_value(item).expect("entities are always serializable");
+            (id(item), canonical(value))
+        })
+        .collect();
+    // Stable, so entities sharing an ID keep their order.
+    entities.sort_by(|(a, _), (b, _)| compare_ids(a, b));
+    entities
+}
+
+fn diff_section(old: Vec<(String, Value)>, new: Vec<(String, Value)>) -> SectionDiff {
+    let old: BTreeMap<String, Value> = old.into_iter().collect();
+    let new: BTreeMap<String, Value> = new.into_iter().collect();
+    let mut diff = SectionDiff::default();
+    for (id, value) in &new {
+        match old.get(id) {
+            None => diff.added.push(id.clone()),
+            Some(before) if before != value => diff.changed.push(id.clone()),
+            Some(_) => {}
+        }
+    }
+    diff.removed = old
+        .keys()
+        .filter(|id| !new.contains_key(*id))
+        .cloned()
+        .collect();
+    for ids in [&mut diff.added, &mut diff.removed, &mut diff.changed] {
+        ids.sort_by(|a, b| compare_ids(a, b));
+    }
+    diff
+}
+
+/// A section of a [`StudentSnapshot`], as written by [`SnapshotWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionName {
+    /// All grades.
+    Grades,
+    /// All attendance records.
+    Attendances,
+    /// All homework entries.
+    Homeworks,
+    /// School notices.
+    Notices,
+    /// Received messages, every page of the inbox.
+    Messages,
+}
+
+impl SectionName {
+    /// Every section, in the order [`SnapshotWriter`] writes them.
+    pub const ALL: [SectionName; 5] = [
+        Self::Grades,
+        Self::Attendances,
+        Self::Homeworks,
+        Self::Notices,
+        Self::Messages,
+    ];
+
+    /// Name of the section, as used in [`StudentSnapshot`] and the manifest.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Grades => "grades",
+            Self::Attendances => "attendances",
+            Self::Homeworks => "homeworks",
+            Self::Notices => "notices",
+            Self::Messages => "messages",
+        }
+    }
+}
+
+impl fmt::Display for SectionName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A section listed in a [`SnapshotManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// The section written.
+    pub section: SectionName,
+    /// Number of entities in it.
+    pub count: usize,
+    /// FNV-1a hash of the section's bytes as written, as 16 hex digits.
+    /// Detects truncated or edited files; it is not a cryptographic checksum.
+    pub checksum: String,
+}
+
+/// The sections a [`SnapshotWriter`] has written so far.
+///
+/// Written to the sink after every section, so an interrupted run leaves a
+/// manifest of the sections that are complete.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// Completed sections, in the order they were written.
+    pub sections: Vec<ManifestEntry>,
+    /// Whether every section was written.
+    pub complete: bool,
+}
+
+impl SnapshotManifest {
+    /// The entry of `section`, if it was written.
+    pub fn entry(&self, section: SectionName) -> Option<&ManifestEntry> {
+        self.sections.iter().find(|entry| entry.section == section)
+    }
+
+    /// Whether `section` was written.
+    pub fn has(&self, section: SectionName) -> bool {
+        self.entry(section).is_some()
+    }
+}
+
+/// Where a [`SnapshotWriter`] writes sections and the manifest.
+///
+/// A section is a JSON array with one canonical entity per line. It is
+/// written piece by piece as pages arrive, so a sink never sees a whole
+/// section at once. Implementations can use `async fn` for the methods.
+pub trait SnapshotSink {
+    /// Writer for one section.
+    type Section: AsyncWrite + Unpin + Send;
+    /// Reader for a section written earlier.
+    type Reader: AsyncRead + Unpin + Send;
+
+    /// Starts writing `section`. An earlier copy is replaced only by
+    /// [`finish_section()`](Self::finish_section).
+    fn create_section(
+        &mut self,
+        section: SectionName,
+    ) -> impl Future<Output = io::Result<Self::Section>> + Send;
+
+    /// Completes a section started with
+    /// [`create_section()`](Self::create_section), once all of it was
+    /// written and flushed.
+    fn finish_section(
+        &mut self,
+        section: SectionName,
+        writer: Self::Section,
+    ) -> impl Future<Output = io::Result<()>> + Send;
+
+    /// Opens a completed section, or `None` if there is none. Used to verify
+    /// checksums before a resumed run skips the section.
+    fn open_section(
+        &mut self,
+        section: SectionName,
+    ) -> impl Future<Output = io::Result<Option<Self::Reader>>> + Send;
+
+    /// Writes the manifest, replacing the previous one. Called after every
+    /// section.
+    fn write_manifest(
+        &mut self,
+        manifest: &SnapshotManifest,
+    ) -> impl Future<Output = io::Result<()>> + Send;
+}
+
+/// A [`SnapshotSink`] writing `<section>.json` files and `manifest.json` to
+/// a directory.
+///
+/// Sections and the manifest are written to temporary files and renamed
+/// over the old ones, so they stay readable if the process is killed
+/// mid-write.
+#[derive(Debug, Clone)]
+pub struct SnapshotDir {
+    path: PathBuf,
+}
+
+impl SnapshotDir {
+    /// A sink writing to `path`, which must exist.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// The directory written to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the manifest of an earlier run, or `None` if there is none.
+    pub async fn read_manifest(&self) -> io::Result<Option<SnapshotManifest>> {
+        match fs::read(self.path.join("manifest.json")).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn section_path(&self, section: SectionName) -> PathBuf {
+        self.path.join(format!("{section}.json"))
+    }
+
+    fn tmp_path(&self, section: SectionName) -> PathBuf {
+        self.path.join(format!("{section}.json.tmp"))
+    }
+}
+
+impl SnapshotSink for SnapshotDir {
+    type Section = BufWriter<File>;
+    type Reader = BufReader<File>;
+
+    async fn create_section(&mut self, section: SectionName) -> io::Result<Self::Section> {
+        File::create(self.tmp_path(section)).await.map(BufWriter::new)
+    }
+
+    async fn finish_section(
+        &mut self,
+        section: SectionName,
+        mut writer: Self::Section,
+    ) -> io::Result<()> {
+        writer.flush().await?;
+        writer.into_inner().sync_all().await?;
+        fs::rename(self.tmp_path(section), self.section_path(section)).await
+    }
+
+    async fn open_section(&mut self, section: SectionName) -> io::Result<Option<Self::Reader>> {
+        match File::open(self.section_path(section)).await {
+            Ok(file) => Ok(Some(BufReader::new(file))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn write_manifest(&mut self, manifest: &SnapshotManifest) -> io::Result<()> {
+        let json = serde_json::to_value(manifest).expect("manifests are always serializable");
+        let tmp = self.path.join("manifest.json.tmp");
+        fs::write(&tmp, pretty(&json)).await?;
+        fs::rename(tmp, self.path.join("manifest.json")).await
+    }
+}
+
+/// Fetches a snapshot section by section, streaming each to a
+/// [`SnapshotSink`] as it is fetched.
+///
+/// Messages are written a page at a time, so memory use does not grow with
+/// the size of the inbox. After each section the manifest is updated; if
+/// the run fails, [`resume()`](Self::resume) with that manifest skips the
+/// sections already written whose checksums still match.
+///
+/// Sections other than messages are sorted by ID like [`canonical_json()`];
+/// messages keep the order of the inbox.
+///
+/// With [`cancel_on()`](Self::cancel_on) the run can be stopped between
+/// sections, e.g. on shutdown.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use librus_rs::snapshot::{SnapshotDir, SnapshotWriter};
+/// use librus_rs::Client;
+///
+/// # async fn example() -> Result<(), librus_rs::Error> {
+/// let mut client = Client::from_env().await?;
+/// let dir = SnapshotDir::new("backup/2025-10-06");
+/// let mut writer = match dir.read_manifest().await.map_err(librus_rs::Error::Io)? {
+///     Some(manifest) => SnapshotWriter::resume(manifest, dir),
+///     None => SnapshotWriter::new(dir),
+/// };
+/// let manifest = writer.write(&mut client).await?;
+/// for entry in &manifest.sections {
+///     println!("{}: {} entities", entry.section, entry.count);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SnapshotWriter<S> {
+    sink: S,
+    manifest: SnapshotManifest,
+    cancel: Option<CancellationToken>,
+}
+
+impl<S: SnapshotSink> SnapshotWriter<S> {
+    /// A writer starting a new snapshot.
+    pub fn new(sink: S) -> Self {
+        Self::resume(SnapshotManifest::default(), sink)
+    }
+
+    /// A writer continuing the run that left `manifest`, skipping the
+    /// sections it lists once their checksums are verified.
+    pub fn resume(manifest: SnapshotManifest, sink: S) -> Self {
+        Self {
+            sink,
+            manifest,
+            cancel: None,
+        }
+    }
+
+    /// Stops [`write()`](Self::write) early once `token` is cancelled.
+    ///
+    /// The token is checked before each section, never while one is
+    /// written, so every section in the manifest is complete. The run then
+    /// returns normally with [`SnapshotManifest::complete`] unset, and can be
+    /// continued with [`resume()`](Self::resume).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::snapshot::{SnapshotDir, SnapshotWriter};
+    /// use librus_rs::CancellationToken;
+    ///
+    /// let shutdown = CancellationToken::new();
+    /// let writer = SnapshotWriter::new(SnapshotDir::new("backup")).cancel_on(shutdown.clone());
+    /// // On SIGTERM:
+    /// shutdown.cancel();
+    /// ```
+    pub fn cancel_on(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Sections written so far.
+    pub fn manifest(&self) -> &SnapshotManifest {
+        &self.manifest
+    }
+
+    /// The sink, e.g. to read what was written.
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+
+    /// Fetches and writes every section not yet in the manifest, then marks
+    /// the manifest complete.
+    ///
+    /// Sections in the manifest whose stored copy is missing or does not
+    /// match its checksum are fetched again. If the token given to
+    /// [`cancel_on()`](Self::cancel_on) is cancelled, the manifest of the
+    /// sections written so far is returned, not marked complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first failed request or [`Error::Io`] if the sink fails.
+    /// Sections written before the error stay in the manifest.
+    pub async fn write(&mut self, client: &mut Client) -> Result<&SnapshotManifest> {
+        for section in SectionName::ALL {
+            if self.cancel.as_ref().is_some_and(|t| t.is_cancelled()) {
+                return Ok(&self.manifest);
+            }
+            if let Some(entry) = self.manifest.entry(section).cloned() {
+                if self.is_intact(&entry).await.map_err(Error::Io)? {
+                    continue;
+                }
+                self.manifest.sections.retain(|e| e.section != section);
+                self.manifest.complete = false;
+            }
+            let writer = self.sink.create_section(section).await.map_err(Error::Io)?;
+            let mut out = SectionWriter::new(writer).await.map_err(Error::Io)?;
+            write_section(client, section, &mut out).await?;
+            let (writer, count, hash) = out.finish().await.map_err(Error::Io)?;
+            self.sink
+                .finish_section(section, writer)
+                .await
+                .map_err(Error::Io)?;
+            self.manifest.sections.push(ManifestEntry {
+                section,
+                count,
+                checksum: format!("{hash:016x}"),
+            });
+            self.sink
+                .write_manifest(&self.manifest)
+                .await
+                .map_err(Error::Io)?;
+        }
+        if !self.manifest.complete {
+            self.manifest.complete = true;
+            self.sink
+                .write_manifest(&self.manifest)
+                .await
+                .map_err(Error::Io)?;
+        }
+        Ok(&self.manifest)
+    }
+
+    /// Whether the stored copy of a section matches its manifest entry.
+    async fn is_intact(&mut self, entry: &ManifestEntry) -> io::Result<bool> {
+        let Some(mut reader) = self.sink.open_section(entry.section).await? else {
+            return Ok(false);
+        };
+        let mut hash = FNV1A_OFFSET;
+        let mut buf = vec![0; 8 * 1024];
+        loop {
+            let read = reader.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            hash = fnv1a_update(hash, &buf[..read]);
+        }
+        Ok(format!("{hash:016x}") == entry.checksum)
+    }
+}
+
+/// Writes a section as a JSON array, one entity per line, counting the
+/// entities and hashing the bytes.
+struct SectionWriter<W> {
+    inner: W,
+    count: usize,
+    hash: u64,
+}
+
+impl<W: AsyncWrite + Unpin> SectionWriter<W> {
+    async fn new(inner: W) -> io::Result<Self> {
+        let mut writer = Self {
+            inner,
+            count: 0,
+            hash: FNV1A_OFFSET,
+        };
+        writer.write(b"[\n").await?;
+        Ok(writer)
+    }
+
+    async fn entity(&mut self, value: &Value) -> io::Result<()> {
+        if self.count > 0 {
+            self.write(b",\n").await?;
+        }
+        let json = serde_json::to_vec(value).expect("JSON values are always serializable");
+        self.write(&json).await?;
+        self.count += 1;
+        Ok(())
+    }
+
+    async fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.hash = fnv1a_update(self.hash, bytes);
+        self.inner.write_all(bytes).await
+    }
+
+    async fn finish(mut self) -> io::Result<(W, usize, u64)> {
+        self.write(if self.count > 0 { b"\n]\n" } else { b"]\n" })
+            .await?;
+        self.inner.flush().await?;
+        Ok((self.inner, self.count, self.hash))
+    }
+}
+
+/// Fetches one section and writes its canonical entities to `out`, the
+/// inbox a page at a time.
+async fn write_section<W: AsyncWrite + Unpin>(
+    client: &mut Client,
+    section: SectionName,
+    out: &mut SectionWriter<W>,
+) -> Result<()> {
+    let entities = match section {
+        SectionName::Grades => entities(&client.grades().await?.grades, grade_id),
+        SectionName::Attendances => {
+            entities(&client.attendances().await?.attendances, attendance_id)
+        }
+        SectionName::Homeworks => entities(&client.homeworks().await?.homeworks, homework_id),
+        SectionName::Notices => entities(&client.school_notices().await?.school_notices, notice_id),
+        SectionName::Messages => {
+            let page_size = client.messages_page_size;
+            let mut listed = 0;
+            for page in 1.. {
+                let (messages, more) = client
+                    .walk_message_page::<InboxMessage>(
+                        MessageFolder::Inbox,
+                        page,
+                        page_size,
+                        &mut listed,
+                    )
+                    .await?;
+                for message in &messages {
+                    let value =
+                        serde_json::to_value(message).expect("entities are always serializable");
+                    out.entity(&canonical(value)).await.map_err(Error::Io)?;
+                }
+                if !more {
+                    break;
+                }
+            }
+            return Ok(());
+        }
+    };
+    for (_, value) in &entities {
+        out.entity(value).await.map_err(Error::Io)?;
+    }
+    Ok(())
+}
+
+fn pretty(value: &Value) -> String {
+    let mut json =
+        serde_json::to_string_pretty(value).expect("JSON values are always serializable");
+    json.push('\n');
+    json
+}
+
+/// Orders numeric IDs by value, before any other IDs, which order as text.
+fn compare_ids(a: &str, b: &str) -> Ordering {
+    match (a.parse::<i64>(), b.parse::<i64>()) {
+        (Ok(x), Ok(y)) => x.cmp(&y).then_with(|| a.cmp(b)),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+/// Sorts object keys and writes whole-number floats as integers.
+fn canonical(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> =
+                map.into_iter().map(|(k, v)| (k, canonical(v))).collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(entries.into_iter().collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(canonical).collect()),
+        Value::Number(n) => Value::Number(canonical_number(n)),
+        other => other,
+    }
+}
+
+fn canonical_number(n: Number) -> Number {
+    // Integers beyond 2^53 are not exact as floats, so leave those alone.
+    const MAX_EXACT: f64 = 9_007_199_254_740_992.0;
+    match n.as_f64() {
+        Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() <= MAX_EXACT => Number::from(f as i64),
+        _ => n,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::announcements::ResponseSchoolNotices;
+    use crate::structs::events::ResponseHomeworks;
+    use crate::structs::grades::ResponseGrades;
+    use crate::structs::lessons::ResponseAttendances;
+    use crate::structs::messages::ResponseInboxMessages;
+
+    fn snapshot() -> StudentSnapshot {
+        let grades: ResponseGrades =
+            serde_json::from_str(include_str!("../tests/fixtures/grades.json")).unwrap();
+        let attendances: ResponseAttendances =
+            serde_json::from_str(include_str!("../tests/fixtures/attendances.json")).unwrap();
+        let homeworks: ResponseHomeworks =
+            serde_json::from_str(include_str!("../tests/fixtures/homeworks.json")).unwrap();
+        let notices: ResponseSchoolNotices =
+            serde_json::from_str(include_str!("../tests/fixtures/school_notices.json")).unwrap();
+        let messages: ResponseInboxMessages =
+            serde_json::from_str(include_str!("../tests/fixtures/inbox_messages.json")).unwrap();
+        StudentSnapshot {
+            grades: grades.grades,
+            attendances: attendances.attendances,
+            homeworks: homeworks.homeworks,
+            notices: notices.school_notices,
+            messages: messages.data,
+        }
+    }
+
+    #[test]
+    fn test_canonical_json_is_deterministic() {
+        let json = canonical_json(&snapshot());
+        assert_eq!(json, canonical_json(&snapshot()));
+
+        // Fetch order does not matter.
+        let mut reversed = snapshot();
+        reversed.grades.reverse();
+        reversed.notices.reverse();
+        reversed.messages.reverse();
+        assert_eq!(canonical_json(&reversed), json);
+
+        // Reading the output back gives the same bytes.
+        let restored: StudentSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(canonical_json(&restored), json);
+        let grades = json.find("\"grades\"").unwrap();
+        assert!(json.find("\"attendances\"").unwrap() < grades);
+        assert!(json.find("\"Id\": 1001").unwrap() < json.find("\"Id\": 1002").unwrap());
+    }
+
+    #[test]
+    fn test_canonical_numbers() {
+        let value = canonical(serde_json::json!({"b": 5.0, "a": [2.5, -0.0, 7]}));
+        assert_eq!(value.to_string(), r#"{"a":[2.5,0,7],"b":5}"#);
+    }
+
+    #[test]
+    fn test_diff_one_changed_grade() {
+        let old = snapshot();
+        let mut new = snapshot();
+        new.grades[1].grade = "4".to_string();
+        assert!(diff(&old, &old).is_empty());
+
+        let changes = diff(&old, &new);
+        assert_eq!(
+            changes,
+            SnapshotDiff {
+                grades: SectionDiff {
+                    changed: vec!["1002".to_string()],
+                    ..SectionDiff::default()
+                },
+                ..SnapshotDiff::default()
+            }
+        );
+
+        new.notices.remove(0);
+        new.messages.truncate(1);
+        let changes = diff(&new, &old);
+        assert_eq!(changes.notices.added, ["a1b2c3"]);
+        assert_eq!(changes.messages.added, ["7001"]);
+        assert_eq!(diff(&old, &new).messages.removed, ["7001"]);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_dir_round_trip() {
+        let path = std::env::temp_dir().join(format!("librus-snapshot-{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        let mut dir = SnapshotDir::new(&path);
+        assert_eq!(dir.read_manifest().await.unwrap(), None);
+        assert!(dir
+            .open_section(SectionName::Notices)
+            .await
+            .unwrap()
+            .is_none());
+
+        let section = dir.create_section(SectionName::Notices).await.unwrap();
+        let mut out = SectionWriter::new(section).await.unwrap();
+        out.entity(&serde_json::json!({"Id": "a1"})).await.unwrap();
+        out.entity(&serde_json::json!({"Id": "b2"})).await.unwrap();
+        let (writer, count, hash) = out.finish().await.unwrap();
+        // Not visible until finished.
+        assert!(!path.join("notices.json").exists());
+        dir.finish_section(SectionName::Notices, writer)
+            .await
+            .unwrap();
+        let written = std::fs::read(path.join("notices.json")).unwrap();
+        assert_eq!(written, b"[\n{\"Id\":\"a1\"},\n{\"Id\":\"b2\"}\n]\n");
+        assert_eq!((count, hash), (2, crate::anonymize::fnv1a(&written)));
+
+        let manifest = SnapshotManifest {
+            sections: vec![ManifestEntry {
+                section: SectionName::Notices,
+                count,
+                checksum: format!("{hash:016x}"),
+            }],
+            complete: false,
+        };
+        dir.write_manifest(&manifest).await.unwrap();
+        assert_eq!(dir.read_manifest().await.unwrap(), Some(manifest.clone()));
+        assert!(!path.join("manifest.json.tmp").exists());
+        let mut writer = SnapshotWriter::resume(manifest.clone(), dir.clone());
+        assert!(writer.is_intact(&manifest.sections[0]).await.unwrap());
+        std::fs::write(path.join("notices.json"), "[\n]\n").unwrap();
+        assert!(!writer.is_intact(&manifest.sections[0]).await.unwrap());
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -0,0 +1,257 @@
+//! Local storage of account data, and deleting it.
+//!
+//! A [`Storage`] holds byte values under `/`-separated keys. Data of one
+//! account lives under `<account ID>/<kind>/`, see [`key()`], so all of it
+//! can be removed at once with [`purge_account()`] or
+//! [`Client::purge_local_data()`](crate::Client::purge_local_data), e.g.
+//! when a family leaves a service.
+//!
+//! # Example
+//!
+//! ```rust
+//! use librus_rs::storage::{self, MemoryStorage, Storage, StorageKind};
+//! use librus_rs::ChangeTracker;
+//!
+//! let storage = MemoryStorage::new();
+//! let key = storage::key(900, StorageKind::Tracker, "grades");
+//! storage.put(&key, &ChangeTracker::new().to_bytes()).unwrap();
+//!
+//! let report = storage::purge_account(&storage, 900).unwrap();
+//! assert_eq!(report.count(StorageKind::Tracker), 1);
+//! assert!(storage.is_empty());
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::sync::Mutex;
+
+/// A key-value store for data kept between runs: cached responses, session
+/// state and change tracker snapshots.
+pub trait Storage: Send + Sync {
+    /// The value stored under `key`, or `None` if there is none.
+    fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>>;
+
+    /// Stores `value` under `key`, replacing an earlier value.
+    fn put(&self, key: &str, value: &[u8]) -> io::Result<()>;
+
+    /// Removes the value under `key`. Removing a missing key is not an
+    /// error.
+    fn remove(&self, key: &str) -> io::Result<()>;
+
+    /// Every key starting with `prefix`, in any order.
+    fn list(&self, prefix: &str) -> io::Result<Vec<String>>;
+}
+
+/// What a stored value holds, the second segment of its [`key()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageKind {
+    /// Cached API responses.
+    Cache,
+    /// Exported session state, e.g. cookies.
+    Session,
+    /// [`ChangeTracker`](crate::ChangeTracker) snapshots.
+    Tracker,
+}
+
+impl StorageKind {
+    /// Name of the kind, as used in keys.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cache => "cache",
+            Self::Session => "session",
+            Self::Tracker => "tracker",
+        }
+    }
+
+    fn from_str(s: &str) -> Option<Self> {
+        [Self::Cache, Self::Session, Self::Tracker]
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+    }
+}
+
+impl fmt::Display for StorageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Key of the value `name` of `kind` for the account with ID `account_id`,
+/// the `me.account.id` of [`Client::me()`](crate::Client::me).
+///
+/// ```rust
+/// use librus_rs::storage::{key, StorageKind};
+///
+/// assert_eq!(key(900, StorageKind::Cache, "Grades"), "900/cache/Grades");
+/// ```
+pub fn key(account_id: u32, kind: StorageKind, name: &str) -> String {
+    format!("{account_id}/{kind}/{name}")
+}
+
+/// Keys removed by [`purge_account()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PurgeReport {
+    /// Every removed key, sorted.
+    pub removed: Vec<String>,
+}
+
+impl PurgeReport {
+    /// Number of removed values of `kind`.
+    pub fn count(&self, kind: StorageKind) -> usize {
+        self.removed
+            .iter()
+            .filter(|key| key.split('/').nth(1).and_then(StorageKind::from_str) == Some(kind))
+            .count()
+    }
+
+    /// Whether nothing was removed.
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty()
+    }
+}
+
+/// Removes every value stored for the account with ID `account_id`,
+/// including values of kinds this version does not know.
+///
+/// # Errors
+///
+/// Returns the first error of the storage. Values removed before it stay
+/// removed; purging again removes the rest. Fails with
+/// [`io::ErrorKind::Other`] if values of the account remain afterwards.
+pub fn purge_account(storage: &dyn Storage, account_id: u32) -> io::Result<PurgeReport> {
+    let prefix = format!("{account_id}/");
+    let mut removed = storage.list(&prefix)?;
+    removed.sort();
+    for key in &removed {
+        storage.remove(key)?;
+    }
+    let left = storage.list(&prefix)?;
+    if !left.is_empty() {
+        return Err(io::Error::other(format!(
+            "{} values of account {account_id} remain after purging",
+            left.len()
+        )));
+    }
+    Ok(PurgeReport { removed })
+}
+
+/// A [`Storage`] keeping values in memory, e.g. for tests or short-lived
+/// processes.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    values: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryStorage {
+    /// An empty storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored values.
+    pub fn len(&self) -> usize {
+        self.values().len()
+    }
+
+    /// Whether nothing is stored.
+    pub fn is_empty(&self) -> bool {
+        self.values().is_empty()
+    }
+
+    fn values(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Vec<u8>>> {
+        self.values.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.values().get(key).cloned())
+    }
+
+    fn put(&self, key: &str, value: &[u8]) -> io::Result<()> {
+        self.values().insert(key.to_string(), value.to_vec());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        self.values().remove(key);
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> io::Result<Vec<String>> {
+        Ok(self
+            .values()
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_purge_removes_only_the_account() {
+        let storage = MemoryStorage::new();
+        for (account, kind, name) in [
+            (900, StorageKind::Cache, "Grades"),
+            (900, StorageKind::Cache, "Me"),
+            (900, StorageKind::Session, "cookies"),
+            (900, StorageKind::Tracker, "grades"),
+            (9001, StorageKind::Tracker, "grades"),
+        ] {
+            storage.put(&key(account, kind, name), b"{}").unwrap();
+        }
+        storage.put("900/later-kind/x", b"{}").unwrap();
+
+        let report = purge_account(&storage, 900).unwrap();
+        assert_eq!(report.removed.len(), 5);
+        assert_eq!(
+            (
+                report.count(StorageKind::Cache),
+                report.count(StorageKind::Session),
+                report.count(StorageKind::Tracker)
+            ),
+            (2, 1, 1)
+        );
+        // An account whose ID starts the same way is kept.
+        assert_eq!(storage.list("").unwrap(), ["9001/tracker/grades"]);
+
+        assert!(purge_account(&storage, 900).unwrap().is_empty());
+        purge_account(&storage, 9001).unwrap();
+        assert!(storage.is_empty());
+    }
+
+    /// A storage whose removals do nothing.
+    struct StickyStorage(MemoryStorage);
+
+    impl Storage for StickyStorage {
+        fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+            self.0.get(key)
+        }
+
+        fn put(&self, key: &str, value: &[u8]) -> io::Result<()> {
+            self.0.put(key, value)
+        }
+
+        fn remove(&self, _: &str) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn list(&self, prefix: &str) -> io::Result<Vec<String>> {
+            self.0.list(prefix)
+        }
+    }
+
+    #[test]
+    fn test_purge_fails_if_values_remain() {
+        let storage = StickyStorage(MemoryStorage::new());
+        storage
+            .put(&key(900, StorageKind::Session, "cookies"), b"")
+            .unwrap();
+        assert!(purge_account(&storage, 900).is_err());
+    }
+}
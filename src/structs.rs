@@ -1,10 +1,22 @@
 //! Data types for Librus API responses.
 
 pub mod announcements;
+pub mod assignments;
+pub mod calendars;
+pub mod classes;
+pub mod colors;
 pub mod events;
 pub mod grades;
 pub mod lessons;
+pub mod lucky_number;
 pub mod me;
 pub mod messages;
+pub mod notes;
+pub mod office_hours;
+pub mod resources;
+pub mod semester;
+pub mod system;
 pub mod timetable;
+pub mod trips;
+pub mod units;
 pub mod users;
@@ -1,8 +1,14 @@
 //! School notices (announcements) data types.
 
-use serde::Deserialize;
+use std::fmt;
 
-use crate::serde_helpers::string_or_int;
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize};
+
+use crate::filenames;
+use crate::serde_util::{lenient_one_or_many, string_or_int};
+use crate::structs::resources::{impl_list_response, impl_resources_root, ResourceUrl};
+use crate::types::{ApiDate, ApiDateTime};
 
 /// Response containing school notices (announcements).
 #[derive(Debug, Deserialize)]
@@ -17,17 +23,31 @@ pub struct ResponseSchoolNotices {
     pub url: String,
 }
 
-/// A school notice (announcement).
+impl_list_response!(ResponseSchoolNotices => school_notices: SchoolNotice);
+
+/// Response containing a single school notice.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
+pub struct ResponseSchoolNotice {
+    /// The notice, if it exists.
+    pub school_notice: Option<SchoolNotice>,
+    /// Related API resources.
+    pub resources: Option<SchoolNoticesResources>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// A school notice (announcement).
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
 pub struct SchoolNotice {
     /// Notice ID (can be string or integer in API response).
     #[serde(deserialize_with = "string_or_int")]
     pub id: String,
     /// Start date of the notice.
-    pub start_date: String,
+    pub start_date: ApiDate,
     /// End date of the notice.
-    pub end_date: String,
+    pub end_date: ApiDate,
     /// Notice subject/title.
     pub subject: String,
     /// Notice content/body.
@@ -35,13 +55,205 @@ pub struct SchoolNotice {
     /// Author reference.
     pub added_by: SchoolNoticeAddedBy,
     /// Creation date.
-    pub creation_date: String,
+    pub creation_date: ApiDateTime,
     /// Whether the notice was read by the user.
     pub was_read: bool,
+    /// Attached files, on instances that allow them.
+    ///
+    /// Download with [`Client::notice_attachment()`](crate::Client::notice_attachment).
+    #[serde(default, alias = "File", deserialize_with = "lenient_one_or_many")]
+    pub attachments: Option<Vec<NoticeAttachment>>,
+    /// Fields returned by the API that this crate does not model yet.
+    #[cfg(feature = "raw-extras")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SchoolNotice {
+    /// Replaces inline images (`<img src="data:...">`) in the content with an
+    /// `[image]` placeholder.
+    ///
+    /// Returns the number of bytes removed from the content.
+    pub fn strip_inline_images(&mut self) -> usize {
+        let (content, stripped) = strip_inline_images(&self.content);
+        if stripped > 0 {
+            self.content = content;
+        }
+        stripped
+    }
+}
+
+/// A school notice with inline images removed.
+///
+/// Returned by [`Client::school_notices_light()`](crate::Client::school_notices_light).
+#[derive(Debug)]
+pub struct LightNotice {
+    /// The notice, with inline images in its content replaced by `[image]`.
+    pub notice: SchoolNotice,
+    /// Number of bytes of image data removed from the content.
+    pub stripped_bytes: usize,
+}
+
+/// [`ResponseSchoolNotices`] as read by
+/// [`Client::school_notices_light()`](crate::Client::school_notices_light).
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ResponseLightNotices {
+    school_notices: Vec<LightNoticeFields>,
+}
+
+impl ResponseLightNotices {
+    pub(crate) fn into_notices(self) -> Vec<LightNotice> {
+        self.school_notices
+            .into_iter()
+            .map(LightNotice::from)
+            .collect()
+    }
+}
+
+/// The fields of a [`SchoolNotice`], with the content stripped of inline
+/// images while it is read.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct LightNoticeFields {
+    #[serde(deserialize_with = "string_or_int")]
+    id: String,
+    start_date: ApiDate,
+    end_date: ApiDate,
+    subject: String,
+    content: StrippedContent,
+    added_by: SchoolNoticeAddedBy,
+    creation_date: ApiDateTime,
+    was_read: bool,
+    #[serde(default, alias = "File", deserialize_with = "lenient_one_or_many")]
+    attachments: Option<Vec<NoticeAttachment>>,
+}
+
+impl From<LightNoticeFields> for LightNotice {
+    fn from(fields: LightNoticeFields) -> Self {
+        LightNotice {
+            notice: SchoolNotice {
+                id: fields.id,
+                start_date: fields.start_date,
+                end_date: fields.end_date,
+                subject: fields.subject,
+                content: fields.content.content,
+                added_by: fields.added_by,
+                creation_date: fields.creation_date,
+                was_read: fields.was_read,
+                attachments: fields.attachments,
+                #[cfg(feature = "raw-extras")]
+                extra: serde_json::Map::new(),
+            },
+            stripped_bytes: fields.content.stripped,
+        }
+    }
+}
+
+/// Notice content with inline images replaced while it is deserialized.
+///
+/// The visitor sees the content as a `&str` borrowed from the response (or
+/// from the parser's scratch buffer), so only the stripped content is
+/// allocated.
+struct StrippedContent {
+    content: String,
+    stripped: usize,
+}
+
+impl<'de> Deserialize<'de> for StrippedContent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ContentVisitor;
+
+        impl de::Visitor<'_> for ContentVisitor {
+            type Value = StrippedContent;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("notice content")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<StrippedContent, E> {
+                let (content, stripped) = strip_inline_images(v);
+                Ok(StrippedContent { content, stripped })
+            }
+        }
+
+        deserializer.deserialize_str(ContentVisitor)
+    }
+}
+
+fn strip_inline_images(content: &str) -> (String, usize) {
+    let mut out = String::new();
+    let mut stripped = 0;
+    let mut pos = 0;
+    while let Some(start) = find_ignore_case(&content[pos..], "<img").map(|i| pos + i) {
+        // An unterminated tag runs to the end of the content.
+        let end = content[start..]
+            .find('>')
+            .map_or(content.len(), |i| start + i + 1);
+        if has_data_src(&content[start..end]) {
+            out.push_str(&content[pos..start]);
+            out.push_str("[image]");
+            stripped += end - start;
+        } else {
+            out.push_str(&content[pos..end]);
+        }
+        pos = end;
+    }
+    out.push_str(&content[pos..]);
+    (out, stripped)
+}
+
+fn has_data_src(tag: &str) -> bool {
+    let mut pos = 0;
+    while let Some(i) = find_ignore_case(&tag[pos..], "src=").map(|i| pos + i) {
+        pos = i + "src=".len();
+        let value = tag[pos..].trim_start_matches(['"', '\'']);
+        if value
+            .get(..5)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"))
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Byte offset of the first ASCII case-insensitive match of `needle`,
+/// without lowercasing a copy of `haystack`.
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+/// A file attached to a school notice or homework.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct NoticeAttachment {
+    /// Attachment ID (can be string or integer in API response).
+    #[serde(deserialize_with = "string_or_int")]
+    pub id: String,
+    /// Original filename.
+    #[serde(alias = "FileName")]
+    pub name: String,
+    /// File size in bytes, if reported.
+    pub size: Option<u64>,
+    /// Gateway URL or path of the file, if reported.
+    #[serde(alias = "Path")]
+    pub url: Option<String>,
+}
+
+impl NoticeAttachment {
+    /// The filename repaired for saving to disk, see
+    /// [`Attachment::decoded_name()`](crate::Attachment::decoded_name).
+    pub fn decoded_name(&self) -> String {
+        filenames::decode_filename(&self.name)
+    }
 }
 
 /// Reference to the author of the notice.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct SchoolNoticeAddedBy {
     /// Author ID (can be string or integer in API response).
@@ -51,14 +263,65 @@ pub struct SchoolNoticeAddedBy {
     pub url: String,
 }
 
-#[derive(Debug, Deserialize)]
+/// Related API resources of school notices.
+#[derive(Debug, Default, Deserialize)]
 pub struct SchoolNoticesResources {
+    /// Link to the parent resource.
     #[serde(rename = "..")]
-    pub empty: SchoolNoticesUrl,
+    pub root: Option<ResourceUrl>,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct SchoolNoticesUrl {
-    pub url: String,
+impl_resources_root!(SchoolNoticesResources);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notice_attachments() {
+        let resp: ResponseSchoolNotices =
+            serde_json::from_str(include_str!("../../tests/fixtures/school_notices.json")).unwrap();
+        let with_files = resp.school_notices[0].attachments.as_ref().unwrap();
+        assert_eq!(with_files.len(), 2);
+        assert_eq!(with_files[0].id, "55");
+        assert_eq!(with_files[0].size, Some(48_213));
+        assert_eq!(with_files[1].name, "zgoda.docx");
+        assert!(resp.school_notices[1].attachments.is_none());
+    }
+
+    #[test]
+    fn test_light_notices_match_full_notices() {
+        let json = include_str!("../../tests/fixtures/school_notices.json");
+        let full: ResponseSchoolNotices = serde_json::from_str(json).unwrap();
+        let light = serde_json::from_str::<ResponseLightNotices>(json)
+            .unwrap()
+            .into_notices();
+        assert_eq!(light.len(), full.school_notices.len());
+        for (light, full) in light.iter().zip(&full.school_notices) {
+            assert_eq!(light.stripped_bytes, 0);
+            assert_eq!(light.notice.id, full.id);
+            assert_eq!(light.notice.content, full.content);
+            assert_eq!(
+                light.notice.attachments.as_ref().map(Vec::len),
+                full.attachments.as_ref().map(Vec::len)
+            );
+        }
+    }
+
+    #[test]
+    fn test_strip_inline_images() {
+        let content = r#"<p>Plan:</p><IMG alt="plan" SRC="data:image/png;base64,iVBORw0K"><img src='/logo.png'>Koniec"#;
+        let (text, stripped) = strip_inline_images(content);
+        assert_eq!(text, "<p>Plan:</p>[image]<img src='/logo.png'>Koniec");
+        assert_eq!(
+            stripped,
+            r#"<IMG alt="plan" SRC="data:image/png;base64,iVBORw0K">"#.len()
+        );
+
+        let (text, stripped) =
+            strip_inline_images("Zdjęcie: <img src=data:image/jpeg;base64,/9j/4A");
+        assert_eq!(text, "Zdjęcie: [image]");
+        assert_eq!(stripped, 38);
+        assert_eq!(strip_inline_images("bez obrazków").1, 0);
+    }
 }
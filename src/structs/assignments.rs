@@ -0,0 +1,93 @@
+//! Homework assignment ("Zadania domowe") data types.
+
+use serde::Deserialize;
+
+use crate::dates;
+use crate::structs::resources::Reference;
+use crate::types::ApiDate;
+
+/// A homework assignment that students hand in through Librus.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct HomeWorkAssignment {
+    /// Unique assignment identifier.
+    pub id: i64,
+    /// Reference to the teacher who created the assignment.
+    pub teacher: Reference,
+    /// Reference to the lesson, if any.
+    pub lesson: Option<Reference>,
+    /// Assignment topic.
+    pub topic: String,
+    /// Assignment instructions.
+    pub text: String,
+    /// Date when the assignment was given (`YYYY-MM-DD`).
+    pub date: ApiDate,
+    /// Deadline (`YYYY-MM-DD`).
+    pub due_date: ApiDate,
+    /// Whether the student has to send a file as the solution.
+    #[serde(default)]
+    pub must_send_attach_file: bool,
+    /// Whether the student marked the assignment as done.
+    #[serde(default)]
+    pub marked_as_done: bool,
+}
+
+impl HomeWorkAssignment {
+    /// Number of days from `today` (`YYYY-MM-DD`) until the deadline.
+    ///
+    /// Zero means the assignment is due today, negative values mean the
+    /// deadline has passed. Returns `None` if either date is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn example(assignment: &librus_rs::HomeWorkAssignment) {
+    /// if assignment.days_until_due("2025-03-10").is_some_and(|d| (0..=2).contains(&d)) {
+    ///     println!("Due soon: {}", assignment.topic);
+    /// }
+    /// # }
+    /// ```
+    pub fn days_until_due(&self, today: &str) -> Option<i64> {
+        Some(dates::parse_day(&self.due_date)? - dates::parse_day(today)?)
+    }
+
+    /// Whether the deadline is before `today` (`YYYY-MM-DD`).
+    pub fn is_overdue(&self, today: &str) -> bool {
+        self.days_until_due(today).is_some_and(|d| d < 0)
+    }
+}
+
+/// Response containing homework assignments.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseHomeWorkAssignments {
+    /// List of assignments.
+    pub home_work_assignments: Vec<HomeWorkAssignment>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assignment_deadline_proximity() {
+        let body = r#"{"HomeWorkAssignments":[{
+            "Id": 5,
+            "Teacher": {"Id": 1, "Url": "https://api.librus.pl/2.0/Users/1"},
+            "Topic": "Essay",
+            "Text": "Write an essay",
+            "Date": "2025-02-25",
+            "DueDate": "2025-03-03",
+            "MarkedAsDone": true
+        }],"Url":"https://api.librus.pl/2.0/HomeWorkAssignments"}"#;
+        let resp: ResponseHomeWorkAssignments = serde_json::from_str(body).unwrap();
+        let assignment = &resp.home_work_assignments[0];
+        assert!(assignment.marked_as_done);
+        assert!(!assignment.must_send_attach_file);
+        assert_eq!(assignment.days_until_due("2025-02-28"), Some(3));
+        assert!(!assignment.is_overdue("2025-03-03"));
+        assert!(assignment.is_overdue("2025-03-04"));
+    }
+}
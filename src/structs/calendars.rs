@@ -0,0 +1,267 @@
+//! Calendar data types: free days, parent-teacher conferences and substitutions.
+
+use std::time::SystemTime;
+
+use serde::Deserialize;
+
+use crate::dates;
+use crate::school_calendar::SchoolCalendar;
+use crate::structs::resources::Reference;
+use crate::types::ApiDate;
+
+/// A school-wide day (or range of days) off.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SchoolFreeDay {
+    /// Unique free day identifier.
+    pub id: i64,
+    /// Name of the free day (e.g., "Ferie zimowe").
+    pub name: String,
+    /// First free day (`YYYY-MM-DD`).
+    pub date_from: ApiDate,
+    /// Last free day, inclusive (`YYYY-MM-DD`).
+    pub date_to: ApiDate,
+    /// School units the free day applies to.
+    pub units: Option<Vec<Reference>>,
+}
+
+/// Response containing school free days.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseSchoolFreeDays {
+    /// List of school free days.
+    pub school_free_days: Vec<SchoolFreeDay>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// A day (or range of days) off for the student's class only.
+///
+/// Used for class trips or exams of other grades, as opposed to school-wide
+/// [`SchoolFreeDay`]s.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ClassFreeDay {
+    /// Unique free day identifier.
+    pub id: i64,
+    /// Reference to the class.
+    pub class: Reference,
+    /// First free day (`YYYY-MM-DD`).
+    pub date_from: ApiDate,
+    /// Last free day, inclusive (`YYYY-MM-DD`).
+    pub date_to: ApiDate,
+    /// Reason for the free day, if given.
+    pub name: Option<String>,
+}
+
+/// Response containing class free days.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseClassFreeDays {
+    /// List of class free days.
+    pub class_free_days: Vec<ClassFreeDay>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// A period when a teacher is absent.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TeacherFreeDay {
+    /// Unique absence identifier.
+    pub id: i64,
+    /// Reference to the absent teacher.
+    pub teacher: Reference,
+    /// First day of absence (`YYYY-MM-DD`).
+    pub date_from: ApiDate,
+    /// Last day of absence, inclusive (`YYYY-MM-DD`).
+    pub date_to: ApiDate,
+    /// Start time, if the absence covers only part of the day.
+    pub time_from: Option<String>,
+    /// End time, if the absence covers only part of the day.
+    pub time_to: Option<String>,
+    /// Reference to the absence type.
+    #[serde(rename = "Type")]
+    pub absence_type: Option<Reference>,
+}
+
+/// Response containing teacher absences.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseTeacherFreeDays {
+    /// List of teacher absences.
+    pub teacher_free_days: Vec<TeacherFreeDay>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// A parent-teacher conference ("wywiadówka").
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ParentTeacherConference {
+    /// Unique conference identifier.
+    pub id: i64,
+    /// Conference date (`YYYY-MM-DD`).
+    pub date: ApiDate,
+    /// Conference name.
+    pub name: String,
+    /// Start time (`HH:MM:SS`).
+    pub time: Option<String>,
+    /// Conference topic.
+    pub topic: Option<String>,
+    /// Reference to the hosting teacher.
+    pub teacher: Option<Reference>,
+    /// Reference to the class.
+    pub class: Option<Reference>,
+}
+
+/// Response containing parent-teacher conferences.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseParentTeacherConferences {
+    /// List of conferences.
+    pub parent_teacher_conferences: Vec<ParentTeacherConference>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// A lesson substitution or cancellation.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Substitution {
+    /// Unique substitution identifier.
+    pub id: i64,
+    /// Whether the lesson was moved to another date or lesson number.
+    pub is_shifted: bool,
+    /// Whether the lesson was cancelled.
+    pub is_cancelled: bool,
+    /// Original lesson date (`YYYY-MM-DD`).
+    pub org_date: ApiDate,
+    /// Original lesson number in the day.
+    pub org_lesson_no: Option<String>,
+    /// Reference to the original subject.
+    pub org_subject: Option<Reference>,
+    /// Reference to the original teacher.
+    pub org_teacher: Option<Reference>,
+    /// New lesson date, if shifted.
+    pub date: Option<ApiDate>,
+    /// New lesson number, if shifted.
+    pub lesson_no: Option<String>,
+    /// Reference to the substitute subject.
+    pub subject: Option<Reference>,
+    /// Reference to the substitute teacher.
+    pub teacher: Option<Reference>,
+}
+
+/// Which school day a change falls on, see [`Substitution::affected_day()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AffectedDay {
+    /// Today.
+    Today,
+    /// The next school day, e.g. Monday when asked on a Friday.
+    Tomorrow,
+}
+
+impl Substitution {
+    /// Whether the substitution affects today or the next school day, as of
+    /// `now` in Polish local time.
+    ///
+    /// Both the original date and, for a shifted lesson, the new date count.
+    /// The next school day is looked up in `calendar`, see
+    /// [`Client::school_calendar()`](crate::Client::school_calendar). Returns
+    /// `None` for other days.
+    pub fn affected_day(&self, now: SystemTime, calendar: &SchoolCalendar) -> Option<AffectedDay> {
+        let today = dates::format_day(dates::warsaw_day(crate::unix_seconds(now)));
+        let days = [Some(&self.org_date), self.date.as_ref()];
+        let falls_on = |day: &str| days.iter().flatten().any(|date| date.as_str() == day);
+        if falls_on(&today) {
+            Some(AffectedDay::Today)
+        } else if calendar
+            .next_school_day(&today)
+            .is_some_and(|next| falls_on(&next))
+        {
+            Some(AffectedDay::Tomorrow)
+        } else {
+            None
+        }
+    }
+}
+
+/// Response containing substitutions.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseSubstitutions {
+    /// List of substitutions.
+    pub substitutions: Vec<Substitution>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_class_free_days() {
+        let resp: ResponseClassFreeDays =
+            serde_json::from_str(include_str!("../../tests/fixtures/class_free_days.json"))
+                .unwrap();
+        assert_eq!(resp.class_free_days.len(), 2);
+
+        let exams = &resp.class_free_days[0];
+        assert_eq!(exams.class.id(), Some(12));
+        assert_eq!(exams.name.as_deref(), Some("Egzamin ósmoklasisty"));
+
+        let single = &resp.class_free_days[1];
+        assert_eq!(single.date_from, single.date_to);
+        assert!(single.name.is_none());
+    }
+
+    fn substitution(org_date: &str, date: Option<&str>) -> Substitution {
+        serde_json::from_value(serde_json::json!({
+            "Id": 1,
+            "IsShifted": date.is_some(),
+            "IsCancelled": date.is_none(),
+            "OrgDate": org_date,
+            "OrgLessonNo": "3",
+            "Date": date,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_substitution_affected_day() {
+        let calendar = SchoolCalendar::new("2025-10-01", "2025-11-30", []).unwrap();
+        // Friday 2025-10-24, 06:30 local time (04:30 UTC, summer time).
+        let friday = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_761_280_200);
+
+        let today = substitution("2025-10-24", None);
+        assert_eq!(
+            today.affected_day(friday, &calendar),
+            Some(AffectedDay::Today)
+        );
+        // The next school day after a Friday is Monday.
+        let monday = substitution("2025-10-27", None);
+        assert_eq!(
+            monday.affected_day(friday, &calendar),
+            Some(AffectedDay::Tomorrow)
+        );
+        assert_eq!(
+            substitution("2025-10-25", None).affected_day(friday, &calendar),
+            None
+        );
+        // A lesson moved from next week to today affects today.
+        let shifted = substitution("2025-10-31", Some("2025-10-24"));
+        assert_eq!(
+            shifted.affected_day(friday, &calendar),
+            Some(AffectedDay::Today)
+        );
+
+        // 23:30 local time on Sunday is still Sunday, so Monday is next.
+        let sunday = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_761_517_800);
+        assert_eq!(
+            monday.affected_day(sunday, &calendar),
+            Some(AffectedDay::Tomorrow)
+        );
+    }
+}
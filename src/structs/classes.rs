@@ -0,0 +1,47 @@
+//! School class data types.
+
+use serde::Deserialize;
+
+use crate::structs::semester::SchoolYear;
+
+/// A school class (e.g. "7a").
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SchoolClass {
+    /// Unique class identifier.
+    pub id: u32,
+    /// Class year number (e.g., 7).
+    pub number: u32,
+    /// Class letter (e.g., "a").
+    pub symbol: String,
+    /// First day of the school year (`YYYY-MM-DD`).
+    pub begin_school_year: Option<String>,
+    /// Last day of the school year (`YYYY-MM-DD`).
+    pub end_school_year: Option<String>,
+}
+
+impl SchoolClass {
+    /// Display name combining the year number and letter, e.g. `"7a"`.
+    pub fn name(&self) -> String {
+        format!("{}{}", self.number, self.symbol)
+    }
+
+    /// The school year of the class, from the year its first day falls in,
+    /// or else the year before its last day.
+    pub fn school_year(&self) -> Option<SchoolYear> {
+        let year = |date: &Option<String>| date.as_deref()?.get(..4)?.parse::<i32>().ok();
+        year(&self.begin_school_year)
+            .or_else(|| Some(year(&self.end_school_year)? - 1))
+            .map(SchoolYear)
+    }
+}
+
+/// Response containing a single class.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseClass {
+    /// The class data, if found.
+    pub class: Option<SchoolClass>,
+    /// API URL for this response.
+    pub url: String,
+}
@@ -0,0 +1,55 @@
+//! Display color data types.
+
+use serde::Deserialize;
+
+/// A display color referenced by e.g. grade and homework categories.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Color {
+    /// Unique color identifier.
+    pub id: i64,
+    /// Color name (e.g., "zielony").
+    pub name: String,
+    /// RGB value (e.g., "00FF00").
+    #[serde(rename = "RGB")]
+    pub rgb: String,
+}
+
+/// Response containing all colors.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseColors {
+    /// List of colors.
+    pub colors: Vec<Color>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// Response containing a single color.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseColor {
+    /// The color, if it exists.
+    pub color: Option<Color>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colors() {
+        let resp: ResponseColors =
+            serde_json::from_str(include_str!("../../tests/fixtures/colors.json")).unwrap();
+        assert_eq!(resp.colors.len(), 3);
+        assert_eq!(resp.colors[0].rgb, "FF0000");
+
+        let resp: ResponseColor = serde_json::from_str(
+            r#"{"Color": {"Id": 3, "Name": "zielony", "RGB": "00FF00"}, "Url": ""}"#,
+        )
+        .unwrap();
+        assert_eq!(resp.color.unwrap().name, "zielony");
+    }
+}
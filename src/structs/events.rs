@@ -1,6 +1,13 @@
 //! Homework and event data types.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::content::{self, Style};
+use crate::normalize;
+use crate::serde_util::lenient_one_or_many;
+use crate::structs::announcements::NoticeAttachment;
+use crate::structs::resources::{impl_list_response, impl_resources_root, Reference, ResourceUrl};
+use crate::types::{ApiDate, ApiDateTime};
 
 /// Response containing all homeworks.
 #[derive(Debug, Deserialize)]
@@ -15,8 +22,21 @@ pub struct ResponseHomeworks {
     pub url: String,
 }
 
+impl ResponseHomeworks {
+    /// Removes duplicate homeworks and sorts them by date, then ID.
+    ///
+    /// For homeworks with the same ID, the one with the latest `AddDate` is kept.
+    pub fn normalized(mut self) -> Self {
+        self.homeworks =
+            normalize::dedup_sorted(self.homeworks, |h| h.id, |h| &h.date, |h| &h.add_date);
+        self
+    }
+}
+
+impl_list_response!(ResponseHomeworks => homeworks: Homework);
+
 /// A homework assignment.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct Homework {
     /// Unique homework identifier.
@@ -24,9 +44,9 @@ pub struct Homework {
     /// Homework content/description.
     pub content: String,
     /// Due date.
-    pub date: String,
+    pub date: ApiDate,
     /// Reference to the homework category.
-    pub category: HomeworksCategory,
+    pub category: Reference,
     /// Lesson number when assigned.
     pub lesson_no: Option<String>,
     /// Start time.
@@ -34,29 +54,53 @@ pub struct Homework {
     /// End time.
     pub time_to: String,
     /// Reference to the teacher who created this homework.
-    pub created_by: HomeworksCategory,
+    pub created_by: Reference,
     /// Reference to the class.
-    pub class: Option<HomeworksCategory>,
+    pub class: Option<Reference>,
     /// Reference to the subject.
-    pub subject: Option<HomeworksCategory>,
+    pub subject: Option<Reference>,
     /// Date when the homework was added.
-    pub add_date: String,
+    pub add_date: ApiDateTime,
     /// Classroom information.
     pub classroom: Option<HomeworksClassroom>,
+    /// Attached files, on instances that allow them.
+    ///
+    /// Download with [`Client::notice_attachment()`](crate::Client::notice_attachment).
+    #[serde(default, alias = "File", deserialize_with = "lenient_one_or_many")]
+    pub attachments: Option<Vec<NoticeAttachment>>,
+    /// Fields returned by the API that this crate does not model yet.
+    #[cfg(feature = "raw-extras")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
-/// Reference to a homework-related resource.
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct HomeworksCategory {
-    /// Resource ID.
-    pub id: i64,
-    /// API URL to fetch the resource.
-    pub url: String,
+impl Homework {
+    /// The content as plain text, with HTML tags removed and line breaks,
+    /// paragraphs and list items kept. Link targets follow their text in
+    /// parentheses.
+    pub fn content_text(&self) -> String {
+        content::render(&self.content, Style::Text)
+    }
+
+    /// The content as Markdown, keeping bold and italic text, list items and
+    /// links, e.g. for posting to Discord or Matrix. Other Markdown syntax in
+    /// the text is escaped.
+    pub fn content_markdown(&self) -> String {
+        content::render(&self.content, Style::Markdown)
+    }
+
+    /// Web links in the content, from `<a href>` tags and bare `http(s)://`
+    /// URLs, in order and without duplicates.
+    ///
+    /// Punctuation and quotation marks around a bare URL, e.g. in
+    /// `„https://example.pl/zadanie”.`, are not part of it.
+    pub fn links(&self) -> Vec<String> {
+        content::extract_links(&self.content)
+    }
 }
 
 /// Classroom information.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct HomeworksClassroom {
     /// Classroom ID.
@@ -69,16 +113,133 @@ pub struct HomeworksClassroom {
     pub size: i64,
 }
 
+/// A homework category, e.g. a test ("sprawdzian") or a quiz ("kartkówka").
 #[derive(Debug, Deserialize)]
-pub struct HomeworksResources {
-    #[serde(rename = "HomeWorks\\Categories")]
-    pub homeworks_categories: HomeworksUrl,
-    #[serde(rename = "..")]
-    pub empty: HomeworksUrl,
+#[serde(rename_all = "PascalCase")]
+pub struct HomeworkCategory {
+    /// Unique category identifier, see [`Homework::category`].
+    pub id: i64,
+    /// Category name.
+    pub name: String,
+    /// Reference to the color shown for the category.
+    pub color: Option<Reference>,
 }
 
+/// Response containing homework categories.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
-pub struct HomeworksUrl {
+pub struct ResponseHomeworkCategories {
+    /// List of homework categories.
+    pub categories: Vec<HomeworkCategory>,
+    /// API URL for this response.
     pub url: String,
 }
+
+/// Related API resources of homeworks.
+#[derive(Debug, Default, Deserialize)]
+pub struct HomeworksResources {
+    /// Link to homework categories.
+    #[serde(rename = "HomeWorks\\Categories")]
+    pub homeworks_categories: Option<ResourceUrl>,
+    /// Link to the parent resource.
+    #[serde(rename = "..")]
+    pub root: Option<ResourceUrl>,
+}
+
+impl_resources_root!(HomeworksResources);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_homework_attachments() {
+        let resp: ResponseHomeworks =
+            serde_json::from_str(include_str!("../../tests/fixtures/homeworks.json")).unwrap();
+        // A single `File` object is treated as a one-element list.
+        let files = resp.homeworks[0].attachments.as_ref().unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].name, "karta_pracy.pdf");
+        assert!(resp.homeworks[1].attachments.is_none());
+        // An unexpected shape is ignored instead of failing the response.
+        assert!(resp.homeworks[2].attachments.is_none());
+    }
+
+    fn contents() -> Vec<Homework> {
+        serde_json::from_str::<ResponseHomeworks>(include_str!(
+            "../../tests/fixtures/homework_contents.json"
+        ))
+        .unwrap()
+        .homeworks
+    }
+
+    #[test]
+    fn test_homework_plain_content() {
+        let homework = &contents()[0];
+        assert_eq!(
+            homework.content_text(),
+            "Ćwiczenia str. 34, zad. 1-3.\nPrzeczytać rozdział 2* (bez notatek)."
+        );
+        assert_eq!(
+            homework.content_markdown(),
+            "Ćwiczenia str. 34, zad. 1-3.\nPrzeczytać rozdział 2\\* (bez notatek)."
+        );
+        assert!(homework.links().is_empty());
+    }
+
+    #[test]
+    fn test_homework_html_content() {
+        let homework = &contents()[1];
+        assert_eq!(
+            homework.content_text(),
+            "Na piątek:\n\n\
+             - zad. 5 & 6 ze str. 40\n\
+             - karta pracy nr 2\n\n\
+             Materiały: strona szkoły (https://szkola.example.pl/materialy?klasa=4a&t=2)\n\
+             Pytania: x < 5"
+        );
+        assert_eq!(
+            homework.content_markdown(),
+            "Na **piątek**:\n\n\
+             - zad. 5 & 6 ze str. 40\n\
+             - karta *pracy* nr 2\n\n\
+             Materiały: [strona szkoły](https://szkola.example.pl/materialy?klasa=4a&t=2)\n\
+             Pytania: x < 5"
+        );
+        assert_eq!(
+            homework.links(),
+            ["https://szkola.example.pl/materialy?klasa=4a&t=2"]
+        );
+    }
+
+    #[test]
+    fn test_homework_links() {
+        let homework = &contents()[2];
+        assert_eq!(
+            homework.links(),
+            [
+                "https://www.youtube.com/watch?v=abc_123",
+                "https://quizizz.com/join?gc=12345",
+                "https://docs.example.pl/prezentacja",
+                "https://pl.wikipedia.org/wiki/Ułamek_(matematyka)",
+                "https://zadania.example.pl/4a",
+            ]
+        );
+        // URLs are not escaped, so chat apps still link them.
+        let markdown = homework.content_markdown();
+        assert!(markdown.starts_with("Film: https://www.youtube.com/watch?v=abc_123, quiz"));
+        assert!(markdown.contains("https://pl.wikipedia.org/wiki/Ułamek_(matematyka)."));
+    }
+
+    #[test]
+    fn test_homework_categories() {
+        let resp: ResponseHomeworkCategories = serde_json::from_str(include_str!(
+            "../../tests/fixtures/homework_categories.json"
+        ))
+        .unwrap();
+        assert_eq!(resp.categories.len(), 3);
+        assert_eq!(resp.categories[0].name, "Sprawdzian");
+        assert_eq!(resp.categories[0].color.as_ref().unwrap().id(), Some(3));
+        assert!(resp.categories[2].color.is_none());
+    }
+}
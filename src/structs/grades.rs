@@ -1,30 +1,41 @@
 //! Grade-related data types.
 
-use serde::Deserialize;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::dates;
+use crate::normalize;
+use crate::serde_util::{comma_decimal, flexible_bool};
+use crate::structs::resources::{impl_list_response, impl_resources_root, Reference, ResourceUrl};
+use crate::structs::semester::Semester;
+use crate::types::{ApiDate, ApiDateTime};
 
 /// A student's grade.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct Grade {
     /// Unique grade identifier.
     pub id: i64,
     /// Reference to the lesson this grade is from.
-    pub lesson: GradesRedirect,
+    pub lesson: Reference,
     /// Reference to the subject.
-    pub subject: GradesRedirect,
+    pub subject: Reference,
     /// Reference to the student who received this grade.
-    pub student: GradesRedirect,
+    pub student: Reference,
     /// Reference to the grade category (e.g., test, quiz).
-    pub category: GradesRedirect,
+    pub category: Reference,
     /// Reference to the teacher who added this grade.
-    pub added_by: GradesRedirect,
+    pub added_by: Reference,
     /// The grade value (e.g., "5", "4+", "A").
     pub grade: String,
     /// Date when the grade was given.
-    pub date: String,
+    pub date: ApiDate,
     /// Date when the grade was added to the system.
-    pub add_date: String,
+    pub add_date: ApiDateTime,
     /// Semester number (1 or 2).
+    #[deprecated(since = "3.0.0", note = "use `Grade::semester()` instead")]
     pub semester: i64,
     /// Whether this grade counts toward the average.
     pub is_constituent: bool,
@@ -36,51 +47,210 @@ pub struct Grade {
     pub is_final: bool,
     /// Whether this is a proposed final grade.
     pub is_final_proposition: bool,
-    /// References to comments on this grade.
-    pub comments: Option<Vec<GradesRedirect>>,
+    /// Comments on this grade, as references or with the text embedded.
+    pub comments: Option<Vec<CommentRef>>,
     /// Reference to an improvement grade.
-    pub improvement: Option<GradesRedirect>,
+    pub improvement: Option<Reference>,
     /// Reference to a resit grade.
-    pub resit: Option<GradesRedirect>,
+    pub resit: Option<Reference>,
+    /// Fields returned by the API that this crate does not model yet.
+    #[cfg(feature = "raw-extras")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
-/// A reference to another resource with ID and URL.
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct GradesRedirect {
-    /// Resource ID.
-    pub id: i32,
-    /// API URL to fetch the resource.
-    pub url: String,
+/// A comment attached to a [`Grade`].
+///
+/// Most gateway versions only link to the comment; newer ones embed it
+/// together with its text.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum CommentRef {
+    /// The comment with its text.
+    Embedded(GradeComment),
+    /// A link to the comment, fetch it with
+    /// [`Client::grade_comment()`](crate::Client::grade_comment).
+    Reference(Reference),
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct GradesUrl {
-    pub url: String,
+impl CommentRef {
+    /// Comment ID, if known.
+    pub fn id(&self) -> Option<i64> {
+        match self {
+            Self::Embedded(comment) => Some(i64::from(comment.id)),
+            Self::Reference(reference) => reference.id(),
+        }
+    }
+
+    /// The comment text, if embedded.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::Embedded(comment) => Some(&comment.text),
+            Self::Reference(_) => None,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+impl Grade {
+    /// Texts of the comments embedded in the grade.
+    ///
+    /// Comments sent only as references are skipped; fetch those with
+    /// [`Client::grade_comment()`](crate::Client::grade_comment).
+    pub fn comment_texts(&self) -> Vec<&str> {
+        self.comments
+            .iter()
+            .flatten()
+            .filter_map(CommentRef::text)
+            .collect()
+    }
+
+    /// Semester the grade belongs to.
+    #[allow(deprecated)]
+    pub fn semester(&self) -> Semester {
+        Semester::from_raw(self.semester)
+    }
+
+    /// Numeric value of the grade on the 1-6 scale.
+    ///
+    /// A trailing `+` adds 0.5 and a trailing `-` subtracts 0.25, following
+    /// the default Librus weighting. Returns `None` for non-numeric marks such
+    /// as `np`, `bz` or a bare `+`.
+    pub fn value(&self) -> Option<f64> {
+        GradeValue::parse(&self.grade).map(|value| value.value())
+    }
+
+    /// Whether the grade was added after `since`, e.g. after the previous
+    /// login as returned by [`Client::last_login()`](crate::Client::last_login).
+    ///
+    /// This is what the web interface highlights as new. Returns `false` if
+    /// `AddDate` cannot be parsed.
+    pub fn is_new_since(&self, since: SystemTime) -> bool {
+        dates::parse_warsaw_timestamp(&self.add_date)
+            .is_some_and(|added| added > crate::unix_seconds(since))
+    }
+
+    /// Whether the grade is a regular partial grade that counts toward the
+    /// running average (not a semester, final or proposed grade).
+    pub fn is_partial(&self) -> bool {
+        self.is_constituent
+            && !self.is_semester
+            && !self.is_semester_proposition
+            && !self.is_final
+            && !self.is_final_proposition
+    }
+
+    /// Time between the grade's `Date` and when it was entered (`AddDate`),
+    /// in local time from midnight of `Date`.
+    ///
+    /// An `AddDate` without a time is read as midnight. Returns `None` if
+    /// either date does not parse or is the `1970-01-01` placeholder some
+    /// instances send, and zero if the grade was entered before its date.
+    pub fn entry_delay(&self) -> Option<Duration> {
+        let (y, m, d) = self.date.components()?;
+        let (ay, am, ad, h, mi, s) = self.add_date.components()?;
+        if (y, m, d) == EPOCH || (ay, am, ad) == EPOCH {
+            return None;
+        }
+        let given = dates::days_from_civil(i64::from(y), m, d) * 86_400;
+        let added = dates::days_from_civil(i64::from(ay), am, ad) * 86_400
+            + i64::from(h * 3600 + mi * 60 + s);
+        Some(Duration::from_secs(
+            u64::try_from(added - given).unwrap_or(0),
+        ))
+    }
+}
+
+/// Date sent instead of a missing `AddDate` or `Date`.
+const EPOCH: (i32, u32, u32) = (1970, 1, 1);
+
+/// A mark on the 1-6 scale with an optional `+` or `-`, e.g. `4+`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GradeValue {
+    base: u8,
+    /// `1` for `+`, `-1` for `-`.
+    modifier: i8,
+}
+
+impl GradeValue {
+    /// Parses a mark such as `"5"`, `"4+"` or `"3-"`.
+    ///
+    /// Returns `None` for non-numeric marks such as `np`, `bz` or a bare `+`.
+    pub fn parse(mark: &str) -> Option<Self> {
+        let mark = mark.trim();
+        let (base, modifier) = match mark.strip_suffix('+') {
+            Some(base) => (base, 1),
+            None => match mark.strip_suffix('-') {
+                Some(base) => (base, -1),
+                None => (mark, 0),
+            },
+        };
+        let base: u8 = base.parse().ok()?;
+        (1..=6).contains(&base).then_some(Self { base, modifier })
+    }
+
+    /// Numeric value of the mark.
+    ///
+    /// A `+` adds 0.5 and a `-` subtracts 0.25, following the default Librus
+    /// weighting.
+    pub fn value(&self) -> f64 {
+        let modifier = match self.modifier {
+            1 => 0.5,
+            -1 => -0.25,
+            _ => 0.0,
+        };
+        f64::from(self.base) + modifier
+    }
+
+    /// The marks from `1` to `6` in ascending order of value, without `1-`
+    /// and `6+`.
+    pub(crate) fn scale() -> impl Iterator<Item = Self> {
+        (1..=6u8)
+            .flat_map(|base| [-1, 0, 1].map(|modifier| Self { base, modifier }))
+            .filter(|mark| !matches!((mark.base, mark.modifier), (1, -1) | (6, 1)))
+    }
+}
+
+impl fmt::Display for GradeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifier = match self.modifier {
+            1 => "+",
+            -1 => "-",
+            _ => "",
+        };
+        write!(f, "{}{modifier}", self.base)
+    }
+}
+
+/// Related API resources of grades.
+#[derive(Debug, Default, Deserialize)]
 pub struct GradesResources {
+    /// Link to subject averages.
     #[serde(rename = "Grades\\Averages")]
-    pub grades_averages: GradesUrl,
+    pub grades_averages: Option<ResourceUrl>,
+    /// Link to student averages.
     #[serde(rename = "Grades\\StudentsAverages")]
-    pub grades_students_averages: GradesUrl,
+    pub grades_students_averages: Option<ResourceUrl>,
+    /// Link to category averages.
     #[serde(rename = "Grades\\CategoriesAverages")]
-    pub grades_categories_averages: GradesUrl,
+    pub grades_categories_averages: Option<ResourceUrl>,
+    /// Link to grade categories.
     #[serde(rename = "Grades\\Categories")]
-    pub grades_categories: GradesUrl,
+    pub grades_categories: Option<ResourceUrl>,
+    /// Link to grade comments.
     #[serde(rename = "Grades\\Comments")]
-    pub grades_comments: GradesUrl,
+    pub grades_comments: Option<ResourceUrl>,
+    /// Link to grade scales.
     #[serde(rename = "Grades\\Scales")]
-    pub grades_scales: GradesUrl,
+    pub grades_scales: Option<ResourceUrl>,
+    /// Link to grade types.
     #[serde(rename = "Grades\\Types")]
-    pub grades_types: GradesUrl,
+    pub grades_types: Option<ResourceUrl>,
+    /// Link to unpreparedness counters.
     #[serde(rename = "Grades\\UnpreparednessPerSemesterAndSubject")]
-    pub grades_unpreparedness_per_semester_and_subject: GradesUrl,
+    pub grades_unpreparedness_per_semester_and_subject: Option<ResourceUrl>,
+    /// Link to the parent resource.
     #[serde(rename = "..")]
-    pub root: GradesUrl,
+    pub root: Option<ResourceUrl>,
 }
 
 /// Response containing all grades.
@@ -90,15 +260,173 @@ pub struct ResponseGrades {
     /// List of grades.
     pub grades: Vec<Grade>,
     /// Related API resources.
+    #[serde(default)]
     pub resources: GradesResources,
     /// API URL for this response.
     pub url: String,
 }
 
+impl ResponseGrades {
+    /// Removes duplicate grades and sorts them by date, then ID.
+    ///
+    /// For grades with the same ID, the one with the latest `AddDate` is kept.
+    pub fn normalized(mut self) -> Self {
+        self.grades = normalize::dedup_sorted(self.grades, |g| g.id, |g| &g.date, |g| &g.add_date);
+        self
+    }
+
+    /// Grades added after `since`, see [`Grade::is_new_since()`].
+    pub fn new_since(&self, since: SystemTime) -> impl Iterator<Item = &Grade> {
+        self.grades.iter().filter(move |g| g.is_new_since(since))
+    }
+
+    /// Grades entered more than `threshold` after their date, see
+    /// [`Grade::entry_delay()`]. Grades with an unknown delay are left out.
+    pub fn entered_late(&self, threshold: Duration) -> impl Iterator<Item = &Grade> {
+        self.grades
+            .iter()
+            .filter(move |g| g.entry_delay().is_some_and(|delay| delay > threshold))
+    }
+}
+
+impl_list_response!(ResponseGrades => grades: Grade);
+
+/// How many times a student may report unpreparedness ("np").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Limit {
+    /// No limit, sent by the API as `"bez limitu"`.
+    Unlimited,
+    /// At most this many times.
+    Count(u32),
+}
+
+impl Limit {
+    /// The limit, or `None` if unlimited.
+    pub fn count(&self) -> Option<u32> {
+        match self {
+            Limit::Unlimited => None,
+            Limit::Count(n) => Some(*n),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Limit {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Count(u32),
+            Text(String),
+        }
+
+        match Option::<Raw>::deserialize(deserializer)? {
+            Some(Raw::Count(n)) => Ok(Limit::Count(n)),
+            Some(Raw::Text(text)) => {
+                let text = text.trim();
+                if let Ok(n) = text.parse() {
+                    Ok(Limit::Count(n))
+                } else if text.is_empty() || text.eq_ignore_ascii_case("bez limitu") {
+                    Ok(Limit::Unlimited)
+                } else {
+                    Err(serde::de::Error::custom(format!(
+                        "invalid unpreparedness limit `{text}`"
+                    )))
+                }
+            }
+            None => Ok(Limit::Unlimited),
+        }
+    }
+}
+
+/// Unpreparedness ("np") reports used in a subject during a semester.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
-pub struct GradeColor {
-    pub id: i64,
+pub struct UnpreparednessEntry {
+    /// Reference to the subject.
+    pub subject: Reference,
+    /// Semester the counter applies to.
+    pub semester: Semester,
+    /// Number of reports used so far.
+    #[serde(alias = "Count")]
+    pub used: u32,
+    /// Number of reports allowed.
+    #[serde(alias = "Limit")]
+    pub allowed: Limit,
+}
+
+impl UnpreparednessEntry {
+    /// Reports still available, or `None` if unlimited.
+    pub fn remaining(&self) -> Option<u32> {
+        self.allowed.count().map(|n| n.saturating_sub(self.used))
+    }
+}
+
+/// Response containing unpreparedness counters.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseUnpreparedness {
+    /// Counters per subject and semester.
+    pub unpreparedness_per_semester_and_subject: Vec<UnpreparednessEntry>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// Class average of the grades in one category of a subject.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CategoryAverage {
+    /// Reference to the subject.
+    pub subject: Reference,
+    /// Reference to the grade category.
+    pub category: Reference,
+    /// Class average, or `None` if the category has no numeric grades.
+    #[serde(default, deserialize_with = "comma_decimal")]
+    pub average: Option<f64>,
+}
+
+/// Response containing class averages per grade category.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseCategoryAverages {
+    /// Averages per subject and category.
+    pub categories_averages: Vec<CategoryAverage>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// A student's average in a subject as computed by the server.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct StudentAverage {
+    /// Reference to the student.
+    pub student: Reference,
+    /// Reference to the subject.
+    pub subject: Reference,
+    /// Average of the student's grades, or `None` if there are no numeric
+    /// grades.
+    #[serde(default, deserialize_with = "comma_decimal")]
+    pub average: Option<f64>,
+}
+
+/// Response containing students' averages per subject.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseStudentAverages {
+    /// Averages per student and subject.
+    pub students_averages: Vec<StudentAverage>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// Response containing a single grade.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseGrade {
+    /// The grade, if it exists.
+    pub grade: Option<Grade>,
+    /// Related API resources.
+    pub resources: Option<GradesCategoryResources>,
+    /// API URL for this response.
     pub url: String,
 }
 
@@ -109,53 +437,68 @@ pub struct GradeCategory {
     /// Unique category identifier.
     pub id: i64,
     /// Color for display purposes.
-    pub color: GradeColor,
+    pub color: Reference,
     /// Category name (e.g., "Test", "Quiz", "Homework").
     pub name: String,
     /// Whether applicable to adult extramural students.
+    #[serde(deserialize_with = "flexible_bool")]
     pub adults_extramural: bool,
     /// Whether applicable to adult daily students.
+    #[serde(deserialize_with = "flexible_bool")]
     pub adults_daily: bool,
     /// Whether this is a standard category.
+    #[serde(deserialize_with = "flexible_bool")]
     pub standard: bool,
     /// Whether this category is read-only.
-    pub is_read_only: String,
+    ///
+    /// Sent as `"1"`/`"0"` by some instances and as a boolean by others.
+    #[serde(deserialize_with = "flexible_bool")]
+    pub is_read_only: bool,
     /// Whether grades in this category count toward average.
+    #[serde(deserialize_with = "flexible_bool")]
     pub count_to_the_average: bool,
     /// Whether this category blocks other grades.
+    #[serde(deserialize_with = "flexible_bool")]
     pub block_any_grades: bool,
     /// Whether this assessment is mandatory.
+    #[serde(deserialize_with = "flexible_bool")]
     pub obligation_to_perform: bool,
+    /// Weight of grades in this category in the weighted average, if the
+    /// school uses weights.
+    #[serde(default)]
+    pub weight: Option<u32>,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+/// Related API resources containing only the parent link.
+#[derive(Debug, Default, Deserialize)]
 pub struct GradesCategoryResources {
+    /// Link to the parent resource.
     #[serde(rename = "..")]
-    pub root: GradesUrl,
+    pub root: Option<ResourceUrl>,
 }
 
+impl_resources_root!(GradesResources, GradesCategoryResources);
+
 /// A comment attached to a grade.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct GradeComment {
     /// Unique comment identifier.
     pub id: i32,
     /// Reference to the teacher who added the comment.
-    pub added_by: GradeDetails,
+    ///
+    /// Empty if a comment embedded in a grade does not carry it.
+    #[serde(default)]
+    pub added_by: Reference,
     /// Reference to the grade this comment is attached to.
-    pub grade: GradeDetails,
+    ///
+    /// Empty if a comment embedded in a grade does not carry it.
+    #[serde(default)]
+    pub grade: Reference,
     /// The comment text.
     pub text: String,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct GradeDetails {
-    pub id: i64,
-    pub url: String,
-}
-
 /// Response containing a single grade category.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -163,9 +506,20 @@ pub struct ResponseGradesCategories {
     /// The grade category.
     pub category: GradeCategory,
     /// Related API resources.
+    #[serde(default)]
     pub resources: GradesCategoryResources,
 }
 
+/// Response containing all grade categories.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseGradeCategoryList {
+    /// List of grade categories.
+    pub categories: Vec<GradeCategory>,
+    /// API URL for this response.
+    pub url: String,
+}
+
 /// Response containing a single grade comment.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -173,7 +527,252 @@ pub struct ResponseGradesComments {
     /// The grade comment, if it exists.
     pub comment: Option<GradeComment>,
     /// Related API resources.
+    #[serde(default)]
     pub resources: GradesCategoryResources,
     /// API URL for this response.
     pub url: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_delay() {
+        let mut grades: ResponseGrades =
+            serde_json::from_str(include_str!("../../tests/fixtures/grades.json")).unwrap();
+        let hours = |h: u64| Duration::from_secs(h * 3600);
+        // Entered at 12:30 on the day, and at 08:00 the day after.
+        assert_eq!(
+            grades.grades[0].entry_delay(),
+            Some(hours(12) + hours(1) / 2)
+        );
+        assert_eq!(grades.grades[1].entry_delay(), Some(hours(32)));
+
+        let late: Vec<i64> = grades.entered_late(hours(24)).map(|g| g.id).collect();
+        assert_eq!(late, [1002]);
+
+        // No time: midnight. Entered before the date: zero.
+        grades.grades[0].add_date = ApiDateTime::from_raw("2025-10-27");
+        assert_eq!(grades.grades[0].entry_delay(), Some(hours(21 * 24)));
+        grades.grades[1].add_date = ApiDateTime::from_raw("2025-10-01 10:00:00");
+        assert_eq!(grades.grades[1].entry_delay(), Some(Duration::ZERO));
+        let late: Vec<i64> = grades.entered_late(hours(24)).map(|g| g.id).collect();
+        assert_eq!(late, [1001]);
+    }
+
+    #[test]
+    fn test_entry_delay_placeholder_is_unknown() {
+        let mut grades: ResponseGrades =
+            serde_json::from_str(include_str!("../../tests/fixtures/grades.json")).unwrap();
+        grades.grades[0].add_date = ApiDateTime::from_raw("1970-01-01 00:00:00");
+        grades.grades[1].add_date = ApiDateTime::from_raw("1970-01-01");
+        assert_eq!(grades.grades[0].entry_delay(), None);
+        assert_eq!(grades.grades[1].entry_delay(), None);
+        grades.grades[1].date = ApiDate::from_raw("1970-01-01");
+        grades.grades[1].add_date = ApiDateTime::from_raw("2025-10-09 08:00:00");
+        assert_eq!(grades.grades[1].entry_delay(), None);
+        grades.grades[1].add_date = ApiDateTime::from_raw("wczoraj");
+        assert_eq!(grades.grades[1].entry_delay(), None);
+        assert_eq!(grades.entered_late(Duration::ZERO).count(), 0);
+    }
+
+    #[test]
+    fn test_grade_category_flag_shapes() {
+        let numeric: ResponseGradesCategories =
+            serde_json::from_str(include_str!("../../tests/fixtures/grades_category.json"))
+                .unwrap();
+        assert!(numeric.category.is_read_only);
+        assert!(numeric.category.standard);
+        assert!(numeric.category.obligation_to_perform);
+        assert_eq!(numeric.category.weight, Some(3));
+
+        let boolean: ResponseGradesCategories = serde_json::from_str(include_str!(
+            "../../tests/fixtures/grades_category_bool.json"
+        ))
+        .unwrap();
+        assert!(!boolean.category.is_read_only);
+        assert!(!boolean.category.standard);
+        assert!(boolean.category.count_to_the_average);
+        assert_eq!(boolean.category.weight, None);
+    }
+
+    #[test]
+    fn test_unpreparedness_limits() {
+        let resp: ResponseUnpreparedness =
+            serde_json::from_str(include_str!("../../tests/fixtures/unpreparedness.json")).unwrap();
+        let entries = &resp.unpreparedness_per_semester_and_subject;
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].subject.id(), Some(301));
+        assert_eq!(entries[0].allowed, Limit::Count(2));
+        assert_eq!(entries[0].remaining(), Some(1));
+
+        assert_eq!(entries[1].allowed, Limit::Unlimited);
+        assert_eq!(entries[1].remaining(), None);
+
+        assert_eq!(entries[2].semester, Semester::Second);
+        assert_eq!(entries[2].allowed, Limit::Count(3));
+
+        let bad = serde_json::from_str::<Limit>(r#""dużo""#);
+        assert!(bad.is_err());
+    }
+
+    #[test]
+    fn test_averages_with_comma_decimals() {
+        let resp: ResponseCategoryAverages = serde_json::from_str(include_str!(
+            "../../tests/fixtures/grades_categories_averages.json"
+        ))
+        .unwrap();
+        let averages: Vec<_> = resp
+            .categories_averages
+            .iter()
+            .map(|a| (a.subject.id, a.category.id, a.average))
+            .collect();
+        assert_eq!(
+            averages,
+            [
+                (Some(21), Some(41), Some(4.25)),
+                (Some(22), Some(42), Some(3.5)),
+                (Some(22), Some(43), None),
+                (Some(22), Some(44), None)
+            ]
+        );
+
+        let resp: ResponseStudentAverages = serde_json::from_str(include_str!(
+            "../../tests/fixtures/grades_students_averages.json"
+        ))
+        .unwrap();
+        let averages: Vec<_> = resp
+            .students_averages
+            .iter()
+            .map(|a| (a.subject.id, a.average))
+            .collect();
+        assert_eq!(averages, [(Some(21), Some(4.8)), (Some(22), None)]);
+    }
+
+    #[test]
+    fn test_comment_references() {
+        let resp: ResponseGrades =
+            serde_json::from_str(include_str!("../../tests/fixtures/grades.json")).unwrap();
+        let comments = resp.grades[0].comments.as_ref().unwrap();
+        assert!(matches!(comments[0], CommentRef::Reference(_)));
+        assert_eq!(comments[0].id(), Some(61));
+        assert!(resp.grades[0].comment_texts().is_empty());
+    }
+
+    #[test]
+    fn test_embedded_comments() {
+        let resp: ResponseGrades = serde_json::from_str(include_str!(
+            "../../tests/fixtures/grades_embedded_comments.json"
+        ))
+        .unwrap();
+        let grade = &resp.grades[0];
+        assert_eq!(
+            grade.comment_texts(),
+            ["Poprawa w terminie", "Brak zadania 3"]
+        );
+        let comments = grade.comments.as_ref().unwrap();
+        assert_eq!(
+            comments.iter().map(CommentRef::id).collect::<Vec<_>>(),
+            [Some(61), Some(62), Some(63)]
+        );
+        // Mixed lists keep plain references.
+        assert!(matches!(comments[2], CommentRef::Reference(_)));
+        let CommentRef::Embedded(first) = &comments[0] else {
+            panic!("expected an embedded comment");
+        };
+        assert_eq!(first.added_by.id(), Some(51));
+        assert_eq!(first.grade.id(), None);
+
+        // Round-trips through serde in the same shape.
+        let json = serde_json::to_value(grade).unwrap();
+        assert_eq!(json["Comments"][1]["Text"], "Brak zadania 3");
+        let again: Grade = serde_json::from_value(json).unwrap();
+        assert_eq!(again.comment_texts().len(), 2);
+    }
+
+    #[test]
+    fn test_single_grade_response() {
+        let resp: ResponseGrade =
+            serde_json::from_str(include_str!("../../tests/fixtures/grade.json")).unwrap();
+        let grade = resp.grade.unwrap();
+        assert_eq!(grade.id, 1001);
+        assert_eq!(grade.grade, "5");
+        assert_eq!(grade.semester(), Semester::First);
+        assert!(grade.comments.is_none());
+    }
+
+    #[test]
+    fn test_grade_is_new_since() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let resp: ResponseGrades =
+            serde_json::from_str(include_str!("../../tests/fixtures/grades.json")).unwrap();
+        let mut grade = resp.grades.into_iter().next().unwrap();
+        // 2025-03-10 12:00:00 in Warsaw (UTC+1).
+        let login = UNIX_EPOCH + Duration::from_secs(1_741_604_400);
+        for (add_date, new) in [
+            ("2025-03-10 12:00:01", true),
+            ("2025-03-10T12:30:00", true),
+            ("2025-03-10 12:00:00", false),
+            ("2025-03-10 11:59:59", false),
+            ("2025-03-11", true),
+            ("2025-03-10", false),
+            ("garbage", false),
+        ] {
+            grade.add_date = serde_json::from_value(add_date.into()).unwrap();
+            assert_eq!(grade.is_new_since(login), new, "{add_date}");
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_grade_semester_accessor() {
+        let json = include_str!("../../tests/fixtures/grade.json")
+            .replace("\"Semester\": 1,", "\"Semester\": 3,");
+        let resp: ResponseGrade = serde_json::from_str(&json).unwrap();
+        let grade = resp.grade.unwrap();
+        assert_eq!(grade.semester, 3);
+        assert_eq!(grade.semester(), Semester::Other(3));
+    }
+
+    #[test]
+    fn test_single_grade_not_found() {
+        let body = r#"{"Resources":{"..":{"Url":"https://api.librus.pl/2.0/Grades"}},"Url":"https://api.librus.pl/2.0/Grades/9"}"#;
+        let resp: ResponseGrade = serde_json::from_str(body).unwrap();
+        assert!(resp.grade.is_none());
+
+        let body = r#"{"Grade":null,"Url":"https://api.librus.pl/2.0/Grades/9"}"#;
+        let resp: ResponseGrade = serde_json::from_str(body).unwrap();
+        assert!(resp.grade.is_none());
+    }
+
+    #[test]
+    fn test_grade_value() {
+        let resp: ResponseGrades =
+            serde_json::from_str(include_str!("../../tests/fixtures/grades.json")).unwrap();
+        let mut grade = resp.grades.into_iter().next().unwrap();
+        assert!(grade.is_partial());
+        for (raw, value) in [("5", Some(5.0)), ("3+", Some(3.5)), ("4-", Some(3.75))] {
+            grade.grade = raw.to_string();
+            assert_eq!(grade.value(), value, "{raw}");
+        }
+        for raw in ["np", "+", "7", ""] {
+            grade.grade = raw.to_string();
+            assert_eq!(grade.value(), None, "{raw}");
+        }
+    }
+
+    #[test]
+    fn test_grade_value_scale() {
+        let scale: Vec<GradeValue> = GradeValue::scale().collect();
+        assert_eq!(scale.len(), 16);
+        assert_eq!(scale[0].to_string(), "1");
+        assert_eq!(scale[15].to_string(), "6");
+        assert!(scale.windows(2).all(|w| w[0].value() < w[1].value()));
+        for mark in &scale {
+            assert_eq!(GradeValue::parse(&mark.to_string()), Some(*mark));
+        }
+    }
+}
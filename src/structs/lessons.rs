@@ -1,6 +1,14 @@
 //! Lesson and attendance data types.
 
-use serde::Deserialize;
+use std::fmt;
+
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::normalize;
+use crate::structs::resources::{impl_list_response, impl_resources_root, Reference, ResourceUrl};
+use crate::structs::semester::Semester;
+use crate::types::{ApiDate, ApiDateTime};
 
 /// A lesson linking a teacher, subject, and class.
 #[derive(Debug, Deserialize)]
@@ -9,33 +17,19 @@ pub struct Lesson {
     /// Unique lesson identifier.
     pub id: i32,
     /// Reference to the teacher.
-    pub teacher: LessonClass,
+    pub teacher: Reference,
     /// Reference to the subject.
-    pub subject: LessonClass,
+    pub subject: Reference,
     /// Reference to the class.
-    pub class: LessonClass,
+    pub class: Reference,
 }
 
-/// A reference to a lesson-related resource.
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct LessonClass {
-    /// Resource ID.
-    pub id: i32,
-    /// API URL to fetch the resource.
-    pub url: String,
-}
-
-#[derive(Debug, Deserialize)]
+/// Related API resources containing only the parent link.
+#[derive(Debug, Default, Deserialize)]
 pub struct LessonResources {
+    /// Link to the parent resource.
     #[serde(rename = "..")]
-    pub root: LessonUrl,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct LessonUrl {
-    pub url: String,
+    pub root: Option<ResourceUrl>,
 }
 
 /// Response containing a single lesson.
@@ -45,14 +39,27 @@ pub struct ResponseLesson {
     /// The lesson data.
     pub lesson: Lesson,
     /// Related API resources.
+    #[serde(default)]
     pub resources: LessonResources,
     /// API URL for this response.
     pub url: String,
 }
 
-/// An academic subject.
+/// Response containing all lessons of the student's classes.
+///
+/// A lesson ties a teacher to a subject taught to a class or group.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
+pub struct ResponseLessons {
+    /// List of lessons.
+    pub lessons: Vec<Lesson>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// An academic subject.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
 pub struct LessonSubject {
     /// Unique subject identifier.
     pub id: i32,
@@ -76,50 +83,90 @@ pub struct ResponseLessonSubject {
     /// The subject data, if found.
     pub subject: Option<LessonSubject>,
     /// Related API resources.
+    #[serde(default)]
     pub resources: LessonResources,
     /// API URL for this response.
     pub url: String,
 }
 
-/// A student's attendance record for a lesson.
+/// Response containing all subjects.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
+pub struct ResponseSubjects {
+    /// List of subjects.
+    pub subjects: Vec<LessonSubject>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// A student's attendance record for a lesson.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
 pub struct Attendance {
     /// Unique attendance record identifier.
     pub id: AttendanceId,
     /// Reference to the lesson.
-    pub lesson: AttendanceAddedBy,
+    pub lesson: Reference,
     /// Reference to the student.
-    pub student: AttendanceAddedBy,
+    pub student: Reference,
     /// Date of the lesson.
-    pub date: String,
+    pub date: ApiDate,
     /// Date when the record was added.
-    pub add_date: String,
+    pub add_date: ApiDateTime,
     /// Lesson number in the day (1-8+).
     pub lesson_no: i32,
     /// Semester number (1 or 2).
+    #[deprecated(since = "3.0.0", note = "use `Attendance::semester()` instead")]
     pub semester: i32,
     /// Reference to the attendance type.
     #[serde(rename = "Type")]
-    pub attendance_type: AttendanceAddedBy,
+    pub attendance_type: Reference,
     /// Reference to the teacher who recorded attendance.
-    pub added_by: AttendanceAddedBy,
+    pub added_by: Reference,
     /// Reference to a school trip, if applicable.
-    pub trip: Option<AttendanceAddedBy>,
+    pub trip: Option<Reference>,
+    /// Fields returned by the API that this crate does not model yet.
+    #[cfg(feature = "raw-extras")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Attendance {
+    /// Semester the attendance record belongs to.
+    #[allow(deprecated)]
+    pub fn semester(&self) -> Semester {
+        Semester::from_raw(i64::from(self.semester))
+    }
 }
 
-/// A reference to an attendance-related resource.
+/// A lesson whose attendance the teacher filled in, from
+/// [`Client::attendances_filled_by_teacher()`](crate::Client::attendances_filled_by_teacher).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct FilledAttendance {
+    /// Reference to the lesson, if sent.
+    #[serde(default)]
+    pub lesson: Option<Reference>,
+    /// Date of the lesson.
+    pub date: ApiDate,
+    /// Lesson number in the day.
+    pub lesson_no: i32,
+}
+
+/// Response listing the lessons whose attendance was filled in.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
-pub struct AttendanceAddedBy {
-    /// Resource ID.
-    pub id: i32,
-    /// API URL to fetch the resource.
+pub struct ResponseFilledAttendances {
+    /// The lessons, in no particular order.
+    #[serde(alias = "FilledByTeacher")]
+    pub attendances: Vec<FilledAttendance>,
+    /// API URL for this response.
+    #[serde(default)]
     pub url: String,
 }
 
 /// Attendance record ID which can be numeric or string.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum AttendanceId {
     /// Numeric ID.
@@ -128,30 +175,106 @@ pub enum AttendanceId {
     String(String),
 }
 
-#[derive(Debug, Deserialize)]
+/// Related API resources of attendances.
+#[derive(Debug, Default, Deserialize)]
 pub struct AttendanceResources {
+    /// Link to attendance types.
     #[serde(rename = "Attendances\\Types")]
-    pub attendances_types: LessonUrl,
+    pub attendances_types: Option<ResourceUrl>,
+    /// Link to per-lesson attendance statistics.
     #[serde(rename = "Attendances\\LessonsStatistics")]
-    pub attendances_lessons_statistics: LessonUrl,
+    pub attendances_lessons_statistics: Option<ResourceUrl>,
+    /// Link to attendances filled by the teacher.
     #[serde(rename = "Attendances\\FilledByTeacher")]
-    pub attendances_filled_by_teacher: LessonUrl,
+    pub attendances_filled_by_teacher: Option<ResourceUrl>,
+    /// Link to the parent resource.
     #[serde(rename = "..")]
-    pub empty: LessonUrl,
+    pub root: Option<ResourceUrl>,
 }
 
+impl_resources_root!(LessonResources, AttendanceResources);
+
 /// Response containing all attendances.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct ResponseAttendances {
     /// List of attendance records.
+    ///
+    /// Older gateways return an object mapping dates to lists of records
+    /// instead of a flat list. Such responses are flattened in date order.
+    #[serde(deserialize_with = "list_or_by_date")]
     pub attendances: Vec<Attendance>,
     /// Related API resources.
+    #[serde(default)]
     pub resources: AttendanceResources,
     /// API URL for this response.
     pub url: String,
 }
 
+impl_list_response!(ResponseAttendances => attendances: Attendance);
+
+impl ResponseAttendances {
+    /// Removes duplicate records and sorts them by date, then ID.
+    ///
+    /// For records with the same ID, the one with the latest `AddDate` is kept.
+    pub fn normalized(mut self) -> Self {
+        self.attendances = normalize::dedup_sorted(
+            self.attendances,
+            |a| a.id.clone(),
+            |a| &a.date,
+            |a| &a.add_date,
+        );
+        self
+    }
+}
+
+/// Accepts attendances as a flat list or as an object keyed by date.
+///
+/// Records in the keyed form may omit `Date`, in which case it is taken from
+/// the key.
+fn list_or_by_date<'de, D>(deserializer: D) -> Result<Vec<Attendance>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct AttendancesVisitor;
+
+    impl<'de> Visitor<'de> for AttendancesVisitor {
+        type Value = Vec<Attendance>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a list of attendances or an object keyed by date")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+            Vec::deserialize(serde::de::value::SeqAccessDeserializer::new(seq))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut days = std::collections::BTreeMap::new();
+            while let Some((date, records)) =
+                map.next_entry::<String, Vec<serde_json::Map<String, serde_json::Value>>>()?
+            {
+                days.entry(date).or_insert_with(Vec::new).extend(records);
+            }
+
+            let mut attendances = Vec::new();
+            for (date, records) in days {
+                for mut record in records {
+                    record
+                        .entry("Date")
+                        .or_insert_with(|| serde_json::Value::String(date.clone()));
+                    let attendance = Attendance::deserialize(serde_json::Value::Object(record))
+                        .map_err(serde::de::Error::custom)?;
+                    attendances.push(attendance);
+                }
+            }
+            Ok(attendances)
+        }
+    }
+
+    deserializer.deserialize_any(AttendancesVisitor)
+}
+
 /// A type of attendance (present, absent, late, etc.).
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -174,16 +297,9 @@ pub struct AttendanceType {
     /// System identifier.
     pub identifier: String,
     /// Reference to a standard type.
-    pub standard_type: Option<AttendanceColor>,
+    pub standard_type: Option<Reference>,
     /// Reference to the color.
-    pub color: Option<AttendanceColor>,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct AttendanceColor {
-    pub id: i32,
-    pub url: String,
+    pub color: Option<Reference>,
 }
 
 /// Response containing all attendance types.
@@ -193,7 +309,80 @@ pub struct ResponseAttendancesType {
     /// List of attendance types.
     pub types: Vec<AttendanceType>,
     /// Related API resources.
+    #[serde(default)]
     pub resources: LessonResources,
     /// API URL for this response.
     pub url: String,
 }
+
+/// Response containing a single attendance type.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseAttendanceType {
+    /// The attendance type, if it exists.
+    #[serde(rename = "Type")]
+    pub attendance_type: Option<AttendanceType>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(resp: &ResponseAttendances) -> Vec<AttendanceId> {
+        resp.attendances.iter().map(|a| a.id.clone()).collect()
+    }
+
+    #[test]
+    fn test_attendances_flat_list() {
+        let resp: ResponseAttendances =
+            serde_json::from_str(include_str!("../../tests/fixtures/attendances.json")).unwrap();
+        assert_eq!(
+            ids(&resp),
+            [AttendanceId::Integer(5), AttendanceId::String("t9".into())]
+        );
+    }
+
+    #[test]
+    fn test_attendances_keyed_by_date() {
+        let resp: ResponseAttendances = serde_json::from_str(include_str!(
+            "../../tests/fixtures/attendances_by_date.json"
+        ))
+        .unwrap();
+
+        // Keys are out of order in the fixture and flattened by date.
+        let days: Vec<_> = resp
+            .attendances
+            .iter()
+            .map(|a| (a.id.clone(), a.date.as_str()))
+            .collect();
+        assert_eq!(
+            days,
+            [
+                (AttendanceId::Integer(5), "2025-03-04"),
+                (AttendanceId::Integer(6), "2025-03-04"),
+                (AttendanceId::String("t9".into()), "2025-03-05"),
+            ]
+        );
+        assert!(resp.attendances[2].trip.is_some());
+        assert!(resp.resources.attendances_types.is_some());
+    }
+
+    #[test]
+    fn test_attendances_keyed_record_date_wins() {
+        let body = r#"{"Attendances": {"2025-03-04": [{"Id": 1, "Lesson": {"Id": 1, "Url": ""},
+            "Student": {"Id": 1, "Url": ""}, "Date": "2025-03-03", "AddDate": "2025-03-04 08:00:00",
+            "LessonNo": 1, "Semester": 2, "Type": {"Id": 1, "Url": ""}, "AddedBy": {"Id": 1, "Url": ""}}]},
+            "Url": ""}"#;
+        let resp: ResponseAttendances = serde_json::from_str(body).unwrap();
+        assert_eq!(resp.attendances[0].date.as_str(), "2025-03-03");
+    }
+
+    #[test]
+    fn test_attendances_keyed_invalid_record() {
+        let body = r#"{"Attendances": {"2025-03-04": [{"Id": 1}]}, "Url": ""}"#;
+        let err = serde_json::from_str::<ResponseAttendances>(body).unwrap_err();
+        assert!(err.to_string().contains("Lesson"), "{err}");
+    }
+}
@@ -0,0 +1,26 @@
+//! Lucky number data types.
+
+use serde::Deserialize;
+
+use crate::types::ApiDate;
+
+/// The school's lucky number ("szczęśliwy numerek").
+///
+/// Students with this number in the class register are not asked to answer
+/// on that day.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LuckyNumber {
+    /// The number, if the school drew one.
+    pub lucky_number: Option<u32>,
+    /// The day the number applies to (`YYYY-MM-DD`).
+    pub lucky_number_day: ApiDate,
+}
+
+/// Response containing the lucky number.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseLuckyNumber {
+    /// The lucky number data.
+    pub lucky_number: LuckyNumber,
+}
@@ -2,6 +2,10 @@
 
 use serde::Deserialize;
 
+use crate::serde_util::lenient_u64;
+use crate::structs::resources::{impl_resources_root, Reference, ResourceUrl};
+use crate::{Error, Result};
+
 /// User account information.
 #[derive(Debug, Deserialize)]
 pub struct Account {
@@ -35,12 +39,178 @@ pub struct Account {
     /// Whether this is a premium demo account.
     #[serde(rename = "IsPremiumDemo")]
     pub is_premium_demo: bool,
-    /// Premium expiration date timestamp.
-    #[serde(rename = "ExpiredPremiumDate")]
+    /// Premium expiration date as Unix seconds; `0` when not set.
+    ///
+    /// Accepts the numeric strings some instances send. With the `chrono`
+    /// feature, see [`Account::premium_expires_at()`].
+    #[serde(
+        rename = "ExpiredPremiumDate",
+        default,
+        deserialize_with = "lenient_u64"
+    )]
     pub expired_premium_date: Option<u64>,
     /// List of premium add-ons.
     #[serde(rename = "PremiumAddons")]
     pub premium_addons: Vec<String>,
+    /// Time of the previous login (`YYYY-MM-DD HH:MM:SS`, Polish local time).
+    ///
+    /// Not reported by every instance.
+    #[serde(default, rename = "LastLogin", alias = "LastLoginDate")]
+    pub last_login: Option<String>,
+}
+
+/// A premium add-on that can be enabled on a Librus account.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Addon {
+    /// Mobile add-ons ("Mobilne dodatki") unlocking extra views in the mobile app.
+    MobileAddons,
+    /// Extended messaging features (archive folders, larger attachments).
+    MessagesPlus,
+    /// An add-on this crate does not know about, with its raw name.
+    Unknown(String),
+}
+
+impl Addon {
+    /// Parses an add-on from the raw string reported in `PremiumAddons`.
+    ///
+    /// Matching ignores case, `_` and `-`, so `"MobileAddons"` and
+    /// `"mobile_addons"` map to the same variant.
+    pub fn from_raw(raw: &str) -> Self {
+        match normalize_addon(raw).as_str() {
+            "mobileaddons" => Self::MobileAddons,
+            "messagesplus" => Self::MessagesPlus,
+            _ => Self::Unknown(raw.to_string()),
+        }
+    }
+
+    /// Returns the add-on name, as reported in [`Error::PremiumRequired`] and
+    /// used for lookups.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::MobileAddons => "MobileAddons",
+            Self::MessagesPlus => "MessagesPlus",
+            Self::Unknown(raw) => raw,
+        }
+    }
+}
+
+fn normalize_addon(raw: &str) -> String {
+    raw.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Typed view of the premium status and add-ons of an [`Account`].
+///
+/// Obtain it with [`Account::capabilities()`] or
+/// [`Client::capabilities()`](crate::Client::capabilities).
+///
+/// # Example
+///
+/// ```rust
+/// use librus_rs::{Addon, Capabilities};
+///
+/// let caps = Capabilities::new(true, ["MobileAddons", "SomethingNew"]);
+/// assert!(caps.has_mobile_addons());
+/// assert!(!caps.has_messages_plus());
+/// assert!(caps.has_addon("SomethingNew"));
+/// assert!(caps.require(&Addon::MessagesPlus).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    is_premium: bool,
+    addons: Vec<Addon>,
+}
+
+impl Capabilities {
+    /// Creates a capability set from the premium flag and raw add-on names.
+    pub fn new<I, S>(is_premium: bool, addons: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            is_premium,
+            addons: addons
+                .into_iter()
+                .map(|a| Addon::from_raw(a.as_ref()))
+                .collect(),
+        }
+    }
+
+    /// Whether the account has an active premium subscription.
+    pub fn is_premium(&self) -> bool {
+        self.is_premium
+    }
+
+    /// All add-ons enabled on the account, including unknown ones.
+    pub fn addons(&self) -> &[Addon] {
+        &self.addons
+    }
+
+    /// Add-ons this crate does not recognize, as raw strings.
+    pub fn unknown_addons(&self) -> impl Iterator<Item = &str> {
+        self.addons.iter().filter_map(|a| match a {
+            Addon::Unknown(raw) => Some(raw.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Whether the given add-on is enabled.
+    ///
+    /// The name is matched the same way as [`Addon::from_raw()`], so this also
+    /// works for add-ons unknown to this crate.
+    pub fn has_addon(&self, name: &str) -> bool {
+        let name = normalize_addon(name);
+        self.addons
+            .iter()
+            .any(|a| normalize_addon(a.name()) == name)
+    }
+
+    /// Whether mobile add-ons are enabled.
+    pub fn has_mobile_addons(&self) -> bool {
+        self.addons.contains(&Addon::MobileAddons)
+    }
+
+    /// Whether the extended messaging add-on is enabled.
+    pub fn has_messages_plus(&self) -> bool {
+        self.addons.contains(&Addon::MessagesPlus)
+    }
+
+    /// Checks that an add-on is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PremiumRequired`] with the add-on name if it is missing.
+    pub fn require(&self, addon: &Addon) -> Result<()> {
+        if self.addons.contains(addon) {
+            Ok(())
+        } else {
+            Err(Error::PremiumRequired(addon.name().to_string()))
+        }
+    }
+}
+
+impl Account {
+    /// Returns the typed capability set of this account.
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities::new(self.is_premium, &self.premium_addons)
+    }
+
+    /// When the premium subscription expires, if a date is set.
+    #[cfg(feature = "chrono")]
+    pub fn premium_expires_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let secs = self.expired_premium_date.filter(|&secs| secs > 0)?;
+        chrono::DateTime::from_timestamp(i64::try_from(secs).ok()?, 0)
+    }
+
+    /// Whole days until the premium subscription expires, negative once it
+    /// has expired. `None` if no expiry date is set.
+    #[cfg(feature = "chrono")]
+    pub fn premium_days_left(&self, now: chrono::DateTime<chrono::Utc>) -> Option<i64> {
+        Some((self.premium_expires_at()? - now).num_days())
+    }
 }
 
 /// Basic user profile.
@@ -54,29 +224,16 @@ pub struct User {
     pub last_name: String,
 }
 
-/// Class reference.
-#[derive(Debug, Deserialize)]
-pub struct Class {
-    /// Class ID.
-    #[serde(rename = "Id")]
-    pub id: u32,
-    /// API URL for the class.
-    #[serde(rename = "Url")]
-    pub url: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct Resource {
-    #[serde(rename = "Url")]
-    pub url: String,
-}
-
-#[derive(Debug, Deserialize)]
+/// Related API resources containing only the parent link.
+#[derive(Debug, Default, Deserialize)]
 pub struct Resources {
+    /// Link to the parent resource.
     #[serde(rename = "..")]
-    pub url: Resource,
+    pub root: Option<ResourceUrl>,
 }
 
+impl_resources_root!(Resources);
+
 /// Current user information combining account, profile, and class.
 #[derive(Debug, Deserialize)]
 pub struct Me {
@@ -91,7 +248,7 @@ pub struct Me {
     pub user: User,
     /// Class the user belongs to.
     #[serde(rename = "Class")]
-    pub class: Class,
+    pub class: Reference,
 }
 
 /// Response containing current user information.
@@ -101,9 +258,108 @@ pub struct ResponseMe {
     #[serde(rename = "Me")]
     pub me: Me,
     /// Related API resources.
-    #[serde(rename = "Resources")]
+    #[serde(rename = "Resources", default)]
     pub resources: Resources,
     /// API URL for this response.
     #[serde(rename = "Url")]
     pub url: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_addons_are_mapped() {
+        assert_eq!(Addon::from_raw("MobileAddons"), Addon::MobileAddons);
+        assert_eq!(Addon::from_raw("mobile_addons"), Addon::MobileAddons);
+        assert_eq!(Addon::from_raw("MESSAGES-PLUS"), Addon::MessagesPlus);
+
+        let caps = Capabilities::new(true, ["mobile_addons", "MessagesPlus"]);
+        assert!(caps.is_premium());
+        assert!(caps.has_mobile_addons());
+        assert!(caps.has_messages_plus());
+        assert_eq!(caps.unknown_addons().count(), 0);
+    }
+
+    #[test]
+    fn test_unknown_addons_are_preserved() {
+        let caps = Capabilities::new(false, ["FancyCalendar"]);
+        assert!(!caps.has_mobile_addons());
+        assert!(caps.has_addon("FancyCalendar"));
+        assert!(caps.has_addon("fancy_calendar"));
+        assert_eq!(caps.unknown_addons().collect::<Vec<_>>(), ["FancyCalendar"]);
+    }
+
+    #[test]
+    fn test_require_reports_addon_name() {
+        let caps = Capabilities::default();
+        match caps.require(&Addon::MessagesPlus) {
+            Err(Error::PremiumRequired(name)) => assert_eq!(name, "MessagesPlus"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    fn account_with_expiry(value: Option<&str>) -> Account {
+        let mut me: serde_json::Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/me.json")).unwrap();
+        let account = me["Me"]["Account"].as_object_mut().unwrap();
+        match value {
+            Some(value) => {
+                account.insert(
+                    "ExpiredPremiumDate".into(),
+                    serde_json::from_str(value).unwrap(),
+                );
+            }
+            None => {
+                account.remove("ExpiredPremiumDate");
+            }
+        }
+        serde_json::from_value(me["Me"]["Account"].take()).unwrap()
+    }
+
+    #[test]
+    fn test_expired_premium_date_accepts_strings() {
+        assert_eq!(
+            account_with_expiry(Some("1767225600")).expired_premium_date,
+            Some(1767225600)
+        );
+        assert_eq!(
+            account_with_expiry(Some(r#""1767225600""#)).expired_premium_date,
+            Some(1767225600)
+        );
+        assert_eq!(
+            account_with_expiry(Some(r#""0""#)).expired_premium_date,
+            Some(0)
+        );
+        assert_eq!(account_with_expiry(Some("null")).expired_premium_date, None);
+        assert_eq!(account_with_expiry(None).expired_premium_date, None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_premium_expiry() {
+        use chrono::{TimeZone, Utc};
+
+        let now = Utc.with_ymd_and_hms(2025, 12, 20, 12, 0, 0).unwrap();
+        for value in ["1767225600", r#""1767225600""#] {
+            let account = account_with_expiry(Some(value));
+            assert_eq!(
+                account.premium_expires_at(),
+                Some(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap())
+            );
+            assert_eq!(account.premium_days_left(now), Some(11));
+        }
+        let expired = Utc.with_ymd_and_hms(2026, 1, 3, 0, 0, 0).unwrap();
+        assert_eq!(
+            account_with_expiry(Some("1767225600")).premium_days_left(expired),
+            Some(-2)
+        );
+
+        for value in [Some("0"), Some(r#""0""#), Some("null"), None] {
+            let account = account_with_expiry(value);
+            assert_eq!(account.premium_expires_at(), None);
+            assert_eq!(account.premium_days_left(now), None);
+        }
+    }
+}
@@ -1,9 +1,21 @@
 //! Message-related data types.
 
-use serde::Deserialize;
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::filenames;
+use crate::serde_util::{flexible_bool_opt, lenient_u64, string_or_int};
+use crate::structs::lessons::{Attendance, AttendanceId};
+use crate::types::ApiDateTime;
 
 /// Unread message counts across all folders.
+///
+/// Counts missing from the response, e.g. the `archive*` ones on older
+/// Messages backends, are `0`; [`UnreadCounts::present`] tells them apart
+/// from counts reported as `0`.
 #[derive(Debug, Deserialize)]
+#[serde(from = "RawUnreadCounts")]
 pub struct UnreadCounts {
     /// Unread messages in inbox.
     pub inbox: u32,
@@ -19,27 +31,139 @@ pub struct UnreadCounts {
     pub justifications: u32,
     /// Items in trash.
     pub trash: u32,
-    #[serde(rename = "archiveInbox")]
     /// Archived inbox messages.
     pub archive_inbox: u32,
-    #[serde(rename = "archiveNotes")]
     /// Archived notes.
     pub archive_notes: u32,
-    #[serde(rename = "archiveAlerts")]
     /// Archived alerts.
     pub archive_alerts: u32,
-    #[serde(rename = "archiveSubstitutions")]
     /// Archived substitution notifications.
     pub archive_substitutions: u32,
-    #[serde(rename = "archiveAbsences")]
     /// Archived absence notifications.
     pub archive_absences: u32,
-    #[serde(rename = "archiveJustifications")]
     /// Archived justification requests.
     pub archive_justifications: u32,
-    #[serde(rename = "archiveTrash")]
     /// Archived trash items.
     pub archive_trash: u32,
+    /// Which of the counts above the server reported.
+    pub present: UnreadCountsPresence,
+}
+
+/// The set of [`UnreadCounts`] fields present in a response.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use librus_rs::{Client, UnreadCountsPresence};
+///
+/// # async fn example() -> Result<(), librus_rs::Error> {
+/// let mut client = Client::from_env().await?;
+/// let counts = client.unread_counts().await?;
+/// if counts.present.contains(UnreadCountsPresence::ARCHIVE_INBOX) {
+///     println!("Archived: {}", counts.archive_inbox);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UnreadCountsPresence(u16);
+
+impl UnreadCountsPresence {
+    /// [`UnreadCounts::inbox`].
+    pub const INBOX: Self = Self(1);
+    /// [`UnreadCounts::notes`].
+    pub const NOTES: Self = Self(1 << 1);
+    /// [`UnreadCounts::alerts`].
+    pub const ALERTS: Self = Self(1 << 2);
+    /// [`UnreadCounts::substitutions`].
+    pub const SUBSTITUTIONS: Self = Self(1 << 3);
+    /// [`UnreadCounts::absences`].
+    pub const ABSENCES: Self = Self(1 << 4);
+    /// [`UnreadCounts::justifications`].
+    pub const JUSTIFICATIONS: Self = Self(1 << 5);
+    /// [`UnreadCounts::trash`].
+    pub const TRASH: Self = Self(1 << 6);
+    /// [`UnreadCounts::archive_inbox`].
+    pub const ARCHIVE_INBOX: Self = Self(1 << 7);
+    /// [`UnreadCounts::archive_notes`].
+    pub const ARCHIVE_NOTES: Self = Self(1 << 8);
+    /// [`UnreadCounts::archive_alerts`].
+    pub const ARCHIVE_ALERTS: Self = Self(1 << 9);
+    /// [`UnreadCounts::archive_substitutions`].
+    pub const ARCHIVE_SUBSTITUTIONS: Self = Self(1 << 10);
+    /// [`UnreadCounts::archive_absences`].
+    pub const ARCHIVE_ABSENCES: Self = Self(1 << 11);
+    /// [`UnreadCounts::archive_justifications`].
+    pub const ARCHIVE_JUSTIFICATIONS: Self = Self(1 << 12);
+    /// [`UnreadCounts::archive_trash`].
+    pub const ARCHIVE_TRASH: Self = Self(1 << 13);
+    /// Every field.
+    pub const ALL: Self = Self((1 << 14) - 1);
+
+    /// Whether every field in `fields` is present.
+    pub fn contains(self, fields: Self) -> bool {
+        self.0 & fields.0 == fields.0
+    }
+
+    /// Whether every field is present.
+    pub fn is_all(self) -> bool {
+        self.contains(Self::ALL)
+    }
+}
+
+/// [`UnreadCounts`] as sent, with missing counts as `None`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawUnreadCounts {
+    inbox: Option<u32>,
+    notes: Option<u32>,
+    alerts: Option<u32>,
+    substitutions: Option<u32>,
+    absences: Option<u32>,
+    justifications: Option<u32>,
+    trash: Option<u32>,
+    archive_inbox: Option<u32>,
+    archive_notes: Option<u32>,
+    archive_alerts: Option<u32>,
+    archive_substitutions: Option<u32>,
+    archive_absences: Option<u32>,
+    archive_justifications: Option<u32>,
+    archive_trash: Option<u32>,
+}
+
+impl From<RawUnreadCounts> for UnreadCounts {
+    fn from(raw: RawUnreadCounts) -> Self {
+        let mut present = UnreadCountsPresence::default();
+        let mut count = |value: Option<u32>, field: UnreadCountsPresence| {
+            if value.is_some() {
+                present.0 |= field.0;
+            }
+            value.unwrap_or(0)
+        };
+        Self {
+            inbox: count(raw.inbox, UnreadCountsPresence::INBOX),
+            notes: count(raw.notes, UnreadCountsPresence::NOTES),
+            alerts: count(raw.alerts, UnreadCountsPresence::ALERTS),
+            substitutions: count(raw.substitutions, UnreadCountsPresence::SUBSTITUTIONS),
+            absences: count(raw.absences, UnreadCountsPresence::ABSENCES),
+            justifications: count(raw.justifications, UnreadCountsPresence::JUSTIFICATIONS),
+            trash: count(raw.trash, UnreadCountsPresence::TRASH),
+            archive_inbox: count(raw.archive_inbox, UnreadCountsPresence::ARCHIVE_INBOX),
+            archive_notes: count(raw.archive_notes, UnreadCountsPresence::ARCHIVE_NOTES),
+            archive_alerts: count(raw.archive_alerts, UnreadCountsPresence::ARCHIVE_ALERTS),
+            archive_substitutions: count(
+                raw.archive_substitutions,
+                UnreadCountsPresence::ARCHIVE_SUBSTITUTIONS,
+            ),
+            archive_absences: count(raw.archive_absences, UnreadCountsPresence::ARCHIVE_ABSENCES),
+            archive_justifications: count(
+                raw.archive_justifications,
+                UnreadCountsPresence::ARCHIVE_JUSTIFICATIONS,
+            ),
+            archive_trash: count(raw.archive_trash, UnreadCountsPresence::ARCHIVE_TRASH),
+            present,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -47,8 +171,86 @@ pub(crate) struct ResponseUnreadCounts {
     pub data: UnreadCounts,
 }
 
+/// The category of a message, telling e.g. mail from a teacher apart from a
+/// system notification.
+///
+/// Deserialized from the Messages API's `category` string, ignoring case;
+/// values the crate does not know are kept in [`MessageCategory::Other`].
+/// Serialized back as [`as_str()`](Self::as_str).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MessageCategory {
+    /// Sent by a teacher (`"teacher"`).
+    Teacher,
+    /// Sent by a parent (`"parent"`).
+    Parent,
+    /// Sent by a student (`"student"`).
+    Student,
+    /// Sent by the school office (`"secretariat"`, also `"school"`).
+    Secretariat,
+    /// An automatic notification, e.g. about a new grade or a substitution
+    /// (`"system"`, also `"notification"`).
+    System,
+    /// Any other value, as received.
+    Other(String),
+}
+
+impl MessageCategory {
+    /// The category as sent by the API, e.g. `"teacher"`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Teacher => "teacher",
+            Self::Parent => "parent",
+            Self::Student => "student",
+            Self::Secretariat => "secretariat",
+            Self::System => "system",
+            Self::Other(other) => other,
+        }
+    }
+}
+
+impl From<&str> for MessageCategory {
+    fn from(category: &str) -> Self {
+        match category.trim().to_lowercase().as_str() {
+            "teacher" => Self::Teacher,
+            "parent" => Self::Parent,
+            "student" => Self::Student,
+            "secretariat" | "school" => Self::Secretariat,
+            "system" | "notification" => Self::System,
+            _ => Self::Other(category.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for MessageCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for MessageCategory {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|category| Self::from(category.as_str()))
+    }
+}
+
+impl Serialize for MessageCategory {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Deserializes an optional category. `null` and empty strings become `None`.
+fn category_opt<'de, D>(deserializer: D) -> Result<Option<MessageCategory>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?
+        .filter(|category| !category.trim().is_empty())
+        .map(|category| MessageCategory::from(category.as_str())))
+}
+
 /// A message in the inbox (received message).
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InboxMessage {
     /// Unique message identifier.
@@ -65,15 +267,20 @@ pub struct InboxMessage {
     /// Use [`Client::decode_message_content`](crate::Client::decode_message_content) to decode.
     pub content: String,
     /// Date when the message was sent.
-    pub send_date: String,
+    pub send_date: ApiDateTime,
     /// Date when the message was read, if read.
-    pub read_date: Option<String>,
+    pub read_date: Option<ApiDateTime>,
     /// Whether the message has attachments.
     pub is_any_file_attached: bool,
     /// Message tags/labels.
     pub tags: Vec<String>,
     /// Message category.
-    pub category: Option<String>,
+    #[serde(default, deserialize_with = "category_opt")]
+    pub category: Option<MessageCategory>,
+    /// Fields returned by the API that this crate does not model yet.
+    #[cfg(feature = "raw-extras")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// A message in the outbox (sent message).
@@ -92,14 +299,15 @@ pub struct OutboxMessage {
     pub topic: String,
     /// Message content (base64-encoded).
     pub content: String,
-    /// Date when the message was sent.
-    pub send_date: String,
+    /// Date when the message was sent; empty for drafts.
+    pub send_date: ApiDateTime,
     /// Whether the message has attachments.
     pub is_any_file_attached: bool,
     /// Message tags/labels.
     pub tags: Vec<String>,
     /// Message category.
-    pub category: Option<String>,
+    #[serde(default, deserialize_with = "category_opt")]
+    pub category: Option<MessageCategory>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -112,8 +320,23 @@ pub(crate) struct ResponseOutboxMessages {
     pub data: Vec<OutboxMessage>,
 }
 
-/// A file attachment in a message.
+/// A page of a folder listing, by default reduced to what pagination needs.
 #[derive(Debug, Deserialize)]
+pub(crate) struct ResponseMessagePage<T = MessageRef> {
+    pub data: Vec<T>,
+    /// Number of messages in the folder, on instances that report it.
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub total: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct MessageRef {
+    #[serde(rename = "messageId")]
+    pub message_id: String,
+}
+
+/// A file attachment in a message.
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Attachment {
     /// Unique attachment identifier.
@@ -124,8 +347,33 @@ pub struct Attachment {
     pub size: Option<u64>,
 }
 
+impl Attachment {
+    /// The filename repaired for saving to disk.
+    ///
+    /// Percent-encoding and common charset mix-ups (UTF-8 or ISO-8859-2 read
+    /// as Latin-1) are undone, directory parts and reserved characters are
+    /// removed, and the name is shortened to 255 bytes. The result is always
+    /// a single, non-empty path component.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::Attachment;
+    ///
+    /// let attachment = Attachment {
+    ///     id: "1".to_string(),
+    ///     name: "../za%C5%82%C4%85cznik.pdf".to_string(),
+    ///     size: None,
+    /// };
+    /// assert_eq!(attachment.decoded_name(), "załącznik.pdf");
+    /// ```
+    pub fn decoded_name(&self) -> String {
+        filenames::decode_filename(&self.name)
+    }
+}
+
 /// Full message details including content and attachments.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MessageDetail {
     /// Unique message identifier.
@@ -147,20 +395,529 @@ pub struct MessageDetail {
     #[serde(rename = "Message")]
     pub message: String,
     /// Date when the message was sent.
-    pub send_date: String,
+    pub send_date: ApiDateTime,
     /// Date when the message was read, if read.
-    pub read_date: Option<String>,
+    pub read_date: Option<ApiDateTime>,
     /// List of file attachments.
     pub attachments: Vec<Attachment>,
     /// Number of receivers (for group messages).
     pub receivers_count: Option<u32>,
-    /// Whether replies are disabled (1 = no reply allowed).
-    pub no_reply: Option<u8>,
-    /// Whether the message is archived (1 = archived).
-    pub archive: Option<u8>,
+    /// Receivers and their read status.
+    ///
+    /// Only filled in for sent messages, see
+    /// [`Client::outbox_message`](crate::Client::outbox_message).
+    #[serde(default)]
+    pub receivers: Vec<ReceiverReadStatus>,
+    /// Whether replies are disabled, if reported.
+    #[serde(default, deserialize_with = "flexible_bool_opt")]
+    pub no_reply: Option<bool>,
+    /// Whether the message is archived, if reported.
+    #[serde(default, deserialize_with = "flexible_bool_opt")]
+    pub archive: Option<bool>,
+    /// Message category, on instances that report it.
+    #[serde(default, deserialize_with = "category_opt")]
+    pub category: Option<MessageCategory>,
+    /// Whether the sender asks to confirm reading the message, if reported.
+    /// See [`Client::confirm_message_read()`](crate::Client::confirm_message_read).
+    #[serde(
+        default,
+        alias = "readConfirmationRequired",
+        deserialize_with = "flexible_bool_opt"
+    )]
+    pub requires_read_confirmation: Option<bool>,
+    /// Date when reading was confirmed, if confirmed.
+    #[serde(default)]
+    pub read_confirmation_date: Option<ApiDateTime>,
+    /// Fields returned by the API that this crate does not model yet.
+    #[cfg(feature = "raw-extras")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl MessageDetail {
+    /// Whether the message still waits for a read confirmation.
+    pub fn needs_read_confirmation(&self) -> bool {
+        self.requires_read_confirmation == Some(true) && !self.is_read_confirmed()
+    }
+
+    /// Whether reading was confirmed.
+    pub fn is_read_confirmed(&self) -> bool {
+        self.read_confirmation_date
+            .as_deref()
+            .is_some_and(|d| !d.is_empty())
+    }
+
+    /// Number of receivers who have read the message.
+    ///
+    /// Together with `receivers.len()` this gives the "read by 17 of 25"
+    /// summary shown in the web interface.
+    pub fn read_count(&self) -> usize {
+        self.receivers.iter().filter(|r| r.is_read()).count()
+    }
+}
+
+/// A receiver of a sent message and whether they have read it.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiverReadStatus {
+    /// Receiver's user ID.
+    pub receiver_id: Option<String>,
+    /// Receiver's full display name.
+    #[serde(alias = "receiverName")]
+    pub name: String,
+    /// Date when the receiver read the message, if read.
+    pub read_date: Option<ApiDateTime>,
+}
+
+impl ReceiverReadStatus {
+    /// Whether the receiver has read the message.
+    pub fn is_read(&self) -> bool {
+        self.read_date.as_deref().is_some_and(|d| !d.is_empty())
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct ResponseMessageDetail {
     pub data: MessageDetail,
 }
+
+/// An entry of the notice board in the Messages module.
+///
+/// Separate from the Synergia school notices returned by
+/// [`Client::school_notices()`](crate::Client::school_notices).
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardEntry {
+    /// Unique entry identifier.
+    #[serde(alias = "id", deserialize_with = "string_or_int")]
+    pub notice_id: String,
+    /// Entry subject/topic.
+    pub topic: String,
+    /// Entry content (base64-encoded, like message bodies).
+    /// Use [`Client::decode_message_content`](crate::Client::decode_message_content) to decode.
+    #[serde(default)]
+    pub content: String,
+    /// Author's full display name.
+    #[serde(default, alias = "senderName")]
+    pub author_name: String,
+    /// Date when the entry was published.
+    pub send_date: ApiDateTime,
+    /// Date when the entry was read, if read.
+    #[serde(default)]
+    pub read_date: Option<ApiDateTime>,
+    /// Whether the school requires confirming that the entry was read, see
+    /// [`Client::confirm_board_entry()`](crate::Client::confirm_board_entry).
+    #[serde(default, alias = "confirmationRequired")]
+    pub requires_confirmation: bool,
+    /// Date when reading was confirmed, if confirmed.
+    #[serde(default)]
+    pub confirmation_date: Option<ApiDateTime>,
+}
+
+impl BoardEntry {
+    /// Whether the entry still waits for a read confirmation.
+    pub fn needs_confirmation(&self) -> bool {
+        self.requires_confirmation
+            && self
+                .confirmation_date
+                .as_deref()
+                .is_none_or(|d| d.is_empty())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ResponseBoardEntries {
+    pub data: Vec<BoardEntry>,
+}
+
+/// Status of an absence justification request.
+///
+/// Sent by the Messages API as a name (`"pending"`, `"accepted"`,
+/// `"rejected"`, also in Polish) or as a number (`0`, `1`, `2`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JustificationStatus {
+    /// Waiting for the teacher's decision.
+    Pending,
+    /// The teacher accepted the justification.
+    Accepted,
+    /// The teacher rejected the justification.
+    Rejected,
+}
+
+impl<'de> Deserialize<'de> for JustificationStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StringOrInt {
+            Int(i64),
+            String(String),
+        }
+
+        let status = match Option::<StringOrInt>::deserialize(deserializer)? {
+            None => return Ok(Self::Pending),
+            Some(StringOrInt::Int(code)) => code.to_string(),
+            Some(StringOrInt::String(name)) => name.trim().to_lowercase(),
+        };
+        match status.as_str() {
+            "" | "0" | "pending" | "new" | "oczekujace" | "oczekujące" => Ok(Self::Pending),
+            "1" | "accepted" | "approved" | "zaakceptowane" | "zaakceptowano" => Ok(Self::Accepted),
+            "2" | "rejected" | "denied" | "odrzucone" | "odrzucono" => Ok(Self::Rejected),
+            other => Err(serde::de::Error::custom(format!(
+                "unknown justification status `{other}`"
+            ))),
+        }
+    }
+}
+
+/// A request in the absence justifications folder of the Messages API.
+///
+/// Parent accounts see the justifications they sent, teacher accounts the
+/// ones they have to decide on.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JustificationRequest {
+    /// Unique request identifier.
+    #[serde(alias = "id", deserialize_with = "string_or_int")]
+    pub message_id: String,
+    /// Request subject/topic.
+    pub topic: String,
+    /// Sender's full display name.
+    #[serde(default)]
+    pub sender_name: String,
+    /// Date when the request was sent.
+    pub send_date: ApiDateTime,
+    /// Date when the request was read, if read.
+    #[serde(default)]
+    pub read_date: Option<ApiDateTime>,
+    /// Whether the request was accepted, rejected or is still pending.
+    #[serde(default = "pending")]
+    pub status: JustificationStatus,
+    /// IDs of the justified attendance records, if the payload lists them.
+    #[serde(default, alias = "attendances")]
+    pub attendance_ids: Vec<AttendanceId>,
+}
+
+/// Full details of an absence justification request, returned by
+/// [`Client::justification_request()`](crate::Client::justification_request).
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JustificationRequestDetail {
+    /// Unique request identifier.
+    #[serde(alias = "id", deserialize_with = "string_or_int")]
+    pub message_id: String,
+    /// Request subject/topic.
+    pub topic: String,
+    /// Request content (base64-encoded, like message bodies).
+    /// Use [`Client::decode_message_content`](crate::Client::decode_message_content) to decode.
+    #[serde(default, alias = "Message")]
+    pub content: String,
+    /// Sender's full display name.
+    #[serde(default)]
+    pub sender_name: String,
+    /// Date when the request was sent.
+    pub send_date: ApiDateTime,
+    /// Date when the request was read, if read.
+    #[serde(default)]
+    pub read_date: Option<ApiDateTime>,
+    /// Whether the request was accepted, rejected or is still pending.
+    #[serde(default = "pending")]
+    pub status: JustificationStatus,
+    /// Date of the teacher's decision, if decided.
+    #[serde(default)]
+    pub status_date: Option<ApiDateTime>,
+    /// The teacher's comment on the decision, if any.
+    #[serde(default)]
+    pub status_comment: Option<String>,
+    /// IDs of the justified attendance records, if the payload lists them.
+    #[serde(default, alias = "attendances")]
+    pub attendance_ids: Vec<AttendanceId>,
+}
+
+fn pending() -> JustificationStatus {
+    JustificationStatus::Pending
+}
+
+impl JustificationRequest {
+    /// The records of `attendances` this request justifies, e.g. from
+    /// [`Client::attendances()`](crate::Client::attendances).
+    ///
+    /// Empty if the payload did not list attendance IDs.
+    pub fn attendances<'a>(&self, attendances: &'a [Attendance]) -> Vec<&'a Attendance> {
+        justified(&self.attendance_ids, attendances)
+    }
+}
+
+impl JustificationRequestDetail {
+    /// The records of `attendances` this request justifies, e.g. from
+    /// [`Client::attendances()`](crate::Client::attendances).
+    ///
+    /// Empty if the payload did not list attendance IDs.
+    pub fn attendances<'a>(&self, attendances: &'a [Attendance]) -> Vec<&'a Attendance> {
+        justified(&self.attendance_ids, attendances)
+    }
+}
+
+/// The attendances whose ID is in `ids`. IDs are compared as text, since the
+/// Messages API may send numeric IDs as strings.
+fn justified<'a>(ids: &[AttendanceId], attendances: &'a [Attendance]) -> Vec<&'a Attendance> {
+    let text = |id: &AttendanceId| match id {
+        AttendanceId::Integer(id) => id.to_string(),
+        AttendanceId::String(id) => id.clone(),
+    };
+    let ids: Vec<String> = ids.iter().map(text).collect();
+    attendances
+        .iter()
+        .filter(|a| ids.contains(&text(&a.id)))
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ResponseJustificationRequests {
+    pub data: Vec<JustificationRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ResponseJustificationRequestDetail {
+    pub data: JustificationRequestDetail,
+}
+
+/// The account as seen by the Messages API, returned by
+/// [`Client::messages_me()`](crate::Client::messages_me).
+///
+/// The user ID differs from the Synergia account ID.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessagesUser {
+    /// ID of the user in the Messages API.
+    #[serde(alias = "id", deserialize_with = "string_or_int")]
+    pub user_id: String,
+    /// Display name, e.g. `"Jan Kowalski"`.
+    #[serde(default)]
+    pub name: String,
+    /// Roles of the user, e.g. `["parent"]`.
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ResponseMessagesUser {
+    pub data: MessagesUser,
+}
+
+/// ID of a saved draft, returned by
+/// [`Client::save_draft()`](crate::Client::save_draft).
+///
+/// Listed drafts carry the same value in [`OutboxMessage::message_id`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub struct DraftId(#[serde(deserialize_with = "string_or_int")] String);
+
+impl DraftId {
+    /// The ID as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for DraftId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for DraftId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ResponseSavedDraft {
+    pub data: SavedDraft,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SavedDraft {
+    #[serde(alias = "id", alias = "messageId")]
+    pub draft_id: DraftId,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draft_listing_tolerates_null_send_date() {
+        let resp: ResponseOutboxMessages =
+            serde_json::from_str(include_str!("../../tests/fixtures/message_drafts.json")).unwrap();
+        let draft = &resp.data[0];
+        assert_eq!(draft.message_id, "9001");
+        assert_eq!(draft.send_date.as_str(), "");
+        assert!(draft.send_date.components().is_none());
+    }
+
+    #[test]
+    fn test_unread_counts_full_and_partial() {
+        let full: ResponseUnreadCounts =
+            serde_json::from_str(include_str!("../../tests/fixtures/unread_counts.json")).unwrap();
+        assert_eq!(full.data.inbox, 3);
+        assert!(full.data.present.is_all());
+
+        let partial: ResponseUnreadCounts = serde_json::from_str(include_str!(
+            "../../tests/fixtures/unread_counts_partial.json"
+        ))
+        .unwrap();
+        let counts = partial.data;
+        assert_eq!(
+            (counts.notes, counts.trash, counts.archive_inbox),
+            (2, 4, 0)
+        );
+        assert!(!counts.present.is_all());
+        // Reported as zero.
+        assert!(counts.present.contains(UnreadCountsPresence::INBOX));
+        assert!(!counts.present.contains(UnreadCountsPresence::ARCHIVE_INBOX));
+        assert!(!counts.present.contains(UnreadCountsPresence::ARCHIVE_TRASH));
+    }
+
+    #[test]
+    fn test_justification_requests() {
+        let resp: ResponseJustificationRequests = serde_json::from_str(include_str!(
+            "../../tests/fixtures/message_justifications.json"
+        ))
+        .unwrap();
+        let statuses: Vec<JustificationStatus> = resp.data.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                JustificationStatus::Pending,
+                JustificationStatus::Accepted,
+                JustificationStatus::Rejected,
+            ]
+        );
+        assert_eq!(resp.data[1].message_id, "502");
+        assert_eq!(
+            resp.data[0].attendance_ids,
+            [
+                AttendanceId::Integer(5),
+                AttendanceId::String("t9".to_string())
+            ]
+        );
+        assert!(resp.data[2].attendance_ids.is_empty());
+
+        let attendances: crate::ResponseAttendances =
+            serde_json::from_str(include_str!("../../tests/fixtures/attendances.json")).unwrap();
+        let justified = resp.data[0].attendances(&attendances.attendances);
+        assert_eq!(justified.len(), 2);
+        assert!(resp.data[2]
+            .attendances(&attendances.attendances)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_justification_status_forms() {
+        let parse = |json: &str| serde_json::from_str::<JustificationStatus>(json);
+        assert_eq!(parse("null").unwrap(), JustificationStatus::Pending);
+        assert_eq!(parse("1").unwrap(), JustificationStatus::Accepted);
+        assert_eq!(
+            parse(r#""Odrzucone""#).unwrap(),
+            JustificationStatus::Rejected
+        );
+        assert!(parse(r#""maybe""#).is_err());
+    }
+
+    #[test]
+    fn test_board_entries() {
+        let resp: ResponseBoardEntries =
+            serde_json::from_str(include_str!("../../tests/fixtures/message_board.json")).unwrap();
+        let entries = resp.data;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].notice_id, "77");
+        assert!(entries[0].needs_confirmation());
+        // Confirmed already.
+        assert!(entries[1].requires_confirmation);
+        assert!(!entries[1].needs_confirmation());
+        assert!(!entries[2].requires_confirmation);
+        assert!(!entries[2].needs_confirmation());
+    }
+
+    #[test]
+    fn test_outbox_message_receivers() {
+        let resp: ResponseMessageDetail =
+            serde_json::from_str(include_str!("../../tests/fixtures/outbox_message.json")).unwrap();
+        let message = resp.data;
+        assert_eq!(message.receivers.len(), 3);
+        assert_eq!(message.read_count(), 1);
+        assert_eq!(message.receivers[0].name, "Anna Nowak");
+        assert!(message.receivers[0].is_read());
+        // An empty read date means unread.
+        assert!(!message.receivers[2].is_read());
+        assert_eq!(message.no_reply, Some(false));
+        assert_eq!(message.archive, Some(false));
+        assert_eq!(message.category, Some(MessageCategory::Parent));
+    }
+
+    #[test]
+    fn test_read_confirmation_flag() {
+        let resp: ResponseMessageDetail = serde_json::from_str(include_str!(
+            "../../tests/fixtures/message_read_confirmation.json"
+        ))
+        .unwrap();
+        let message = resp.data;
+        assert_eq!(message.requires_read_confirmation, Some(true));
+        assert!(message.needs_read_confirmation());
+
+        let confirmed = include_str!("../../tests/fixtures/message_read_confirmation.json")
+            .replace(
+                r#""readConfirmationDate": null"#,
+                r#""readConfirmationDate": "2025-10-06 18:01:00""#,
+            )
+            .replace(
+                "requiresReadConfirmation\": true",
+                "readConfirmationRequired\": 1",
+            );
+        let message = serde_json::from_str::<ResponseMessageDetail>(&confirmed)
+            .unwrap()
+            .data;
+        assert_eq!(message.requires_read_confirmation, Some(true));
+        assert!(message.is_read_confirmed() && !message.needs_read_confirmation());
+
+        // Without the flag.
+        let resp: ResponseMessageDetail =
+            serde_json::from_str(include_str!("../../tests/fixtures/outbox_message.json")).unwrap();
+        assert_eq!(resp.data.requires_read_confirmation, None);
+        assert!(!resp.data.needs_read_confirmation());
+    }
+
+    #[test]
+    fn test_message_categories() {
+        let resp: ResponseInboxMessages = serde_json::from_str(include_str!(
+            "../../tests/fixtures/inbox_messages_categories.json"
+        ))
+        .unwrap();
+        let categories: Vec<Option<MessageCategory>> =
+            resp.data.iter().map(|m| m.category.clone()).collect();
+        assert_eq!(
+            categories,
+            [
+                None,
+                Some(MessageCategory::Teacher),
+                Some(MessageCategory::Parent),
+                Some(MessageCategory::Student),
+                Some(MessageCategory::Secretariat),
+                Some(MessageCategory::Secretariat),
+                Some(MessageCategory::System),
+                Some(MessageCategory::System),
+                None,
+                Some(MessageCategory::Other("wychowawca".to_string())),
+                None,
+            ]
+        );
+
+        // Written back in the API's form; unknown values as received.
+        let json = |category: MessageCategory| serde_json::to_string(&category).unwrap();
+        assert_eq!(json(MessageCategory::from("Parent")), r#""parent""#);
+        assert_eq!(json(MessageCategory::from("Wychowawca")), r#""Wychowawca""#);
+        assert_eq!(MessageCategory::System.to_string(), "system");
+    }
+}
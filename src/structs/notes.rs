@@ -0,0 +1,133 @@
+//! Note ("uwaga") data types, including behaviour points.
+
+use serde::Deserialize;
+
+use crate::serde_util::flexible_bool;
+use crate::structs::resources::Reference;
+use crate::structs::semester::Semester;
+use crate::types::{ApiDate, ApiDateTime};
+
+/// A positive or negative note about the student's behaviour.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Note {
+    /// Unique note identifier.
+    pub id: i64,
+    /// Note text.
+    pub text: String,
+    /// Date of the noted behaviour (`YYYY-MM-DD`).
+    pub date: ApiDate,
+    /// Reference to the note category.
+    pub category: Option<Reference>,
+    /// Reference to the teacher who wrote the note.
+    pub teacher: Option<Reference>,
+    /// Whether the note is positive ("pochwała").
+    #[serde(deserialize_with = "flexible_bool")]
+    pub positive: bool,
+    /// Behaviour points, on schools using a point system.
+    ///
+    /// When absent, the points of the note's category apply, see
+    /// [`BehaviourPointsSummary`](crate::BehaviourPointsSummary).
+    #[serde(default)]
+    pub points: Option<i32>,
+    /// Semester number, if sent.
+    #[serde(default)]
+    semester: Option<i64>,
+    /// When the note was added.
+    pub add_date: Option<ApiDateTime>,
+}
+
+impl Note {
+    /// Semester the note belongs to.
+    ///
+    /// Taken from the API if sent, otherwise from the date: September to
+    /// January is the first semester.
+    pub fn semester(&self) -> Semester {
+        match (self.semester, self.date.components()) {
+            (Some(raw), _) => Semester::from_raw(raw),
+            (None, Some((_, month, _))) if month >= 9 || month == 1 => Semester::First,
+            _ => Semester::Second,
+        }
+    }
+}
+
+/// Response containing the student's notes.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseNotes {
+    /// List of notes.
+    pub notes: Vec<Note>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// A note category, e.g. "Pomoc koleżeńska" or "Spóźnienie".
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct NoteCategory {
+    /// Unique category identifier.
+    pub id: i64,
+    /// Category name.
+    pub name: String,
+    /// Behaviour points of notes in this category, on schools using a point
+    /// system.
+    #[serde(default)]
+    pub points: Option<i32>,
+    /// Whether notes in this category are positive.
+    #[serde(deserialize_with = "flexible_bool")]
+    pub positive: bool,
+}
+
+/// Response containing note categories.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseNoteCategories {
+    /// List of note categories.
+    pub categories: Vec<NoteCategory>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notes_with_points() {
+        let resp: ResponseNotes =
+            serde_json::from_str(include_str!("../../tests/fixtures/notes.json")).unwrap();
+        assert_eq!(resp.notes.len(), 4);
+        let note = &resp.notes[0];
+        assert!(note.positive);
+        assert_eq!(note.points, Some(10));
+        assert_eq!(note.category.as_ref().and_then(|c| c.id()), Some(1));
+        assert_eq!(note.semester(), Semester::First);
+        // `Positive` sent as a number, points left to the category.
+        assert!(!resp.notes[1].positive);
+        assert!(resp.notes[1].points.is_none());
+        assert_eq!(resp.notes[3].semester(), Semester::Second);
+
+        let resp: ResponseNoteCategories =
+            serde_json::from_str(include_str!("../../tests/fixtures/note_categories.json"))
+                .unwrap();
+        assert_eq!(resp.categories.len(), 3);
+        assert_eq!(resp.categories[1].points, Some(-5));
+        assert!(!resp.categories[1].positive);
+    }
+
+    #[test]
+    fn test_notes_without_points() {
+        let resp: ResponseNotes =
+            serde_json::from_str(include_str!("../../tests/fixtures/notes_no_points.json"))
+                .unwrap();
+        assert_eq!(resp.notes.len(), 2);
+        assert!(resp.notes.iter().all(|note| note.points.is_none()));
+        assert_eq!(resp.notes[1].semester(), Semester::Second);
+
+        let resp: ResponseNoteCategories = serde_json::from_str(
+            r#"{"Categories": [{"Id": 1, "Name": "Uwaga ogólna", "Positive": false}], "Url": ""}"#,
+        )
+        .unwrap();
+        assert!(resp.categories[0].points.is_none());
+    }
+}
@@ -0,0 +1,53 @@
+//! Teacher office hours ("godziny dostępności") data types.
+
+use serde::Deserialize;
+
+use crate::structs::resources::Reference;
+
+/// A weekly slot when a teacher is available to parents and students.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct OfficeHours {
+    /// Unique slot identifier.
+    pub id: i64,
+    /// Reference to the teacher.
+    pub teacher: Reference,
+    /// Day of the week, `1` (Monday) to `7` (Sunday).
+    pub weekday: u8,
+    /// Start time (`HH:MM:SS`).
+    pub time_from: String,
+    /// End time (`HH:MM:SS`).
+    pub time_to: String,
+    /// Room, if given.
+    pub room: Option<String>,
+    /// Note from the teacher, e.g. "po wcześniejszym umówieniu".
+    pub note: Option<String>,
+}
+
+/// Response containing teacher office hours.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ResponseOfficeHours {
+    /// List of office hours.
+    pub teacher_office_hours: Vec<OfficeHours>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_office_hours() {
+        let resp: ResponseOfficeHours = serde_json::from_str(include_str!(
+            "../../tests/fixtures/teacher_office_hours.json"
+        ))
+        .unwrap();
+        let hours = &resp.teacher_office_hours;
+        assert_eq!(hours.len(), 3);
+        assert_eq!(hours[0].teacher.id(), Some(52));
+        assert_eq!(hours[0].weekday, 2);
+        assert_eq!(hours[0].room.as_deref(), Some("12"));
+        assert!(hours[2].room.is_none());
+        assert!(hours[2].note.is_none());
+    }
+}
@@ -0,0 +1,239 @@
+//! Shared types for the `Resources` maps attached to API responses.
+
+use serde::{Deserialize, Serialize};
+
+use crate::serde_util::flexible_i64_opt;
+use crate::types::ApiUrl;
+
+/// A reference to another API resource, e.g. the subject of a grade.
+///
+/// Some instances send `Id` as a string, or omit it and only send the `Url`;
+/// use [`id()`](Self::id), which falls back to the last segment of the URL.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Reference {
+    /// Resource ID, if sent by the API.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "flexible_i64_opt"
+    )]
+    pub id: Option<i64>,
+    /// API URL to fetch the resource, see
+    /// [`Client::follow()`](crate::Client::follow).
+    pub url: ApiUrl,
+}
+
+impl Reference {
+    /// Resource ID, taken from the URL when the API does not send it.
+    pub fn id(&self) -> Option<i64> {
+        self.id.or_else(|| id_from_url(&self.url))
+    }
+}
+
+/// Parses the last path segment of a resource URL as an ID.
+fn id_from_url(url: &str) -> Option<i64> {
+    let path = url.split(['?', '#']).next()?;
+    path.trim_end_matches('/').rsplit('/').next()?.parse().ok()
+}
+
+/// A link to a related API resource.
+#[derive(Debug, Default, Deserialize)]
+pub struct ResourceUrl {
+    /// API URL of the resource.
+    #[serde(rename = "Url")]
+    pub url: ApiUrl,
+}
+
+/// Implements `root()` for resources structs with an optional `root` field
+/// mapped from the `".."` key.
+macro_rules! impl_resources_root {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl $ty {
+                /// Returns the link to the parent resource (the `".."` key), if present.
+                pub fn root(&self) -> Option<&$crate::structs::resources::ResourceUrl> {
+                    self.root.as_ref()
+                }
+            }
+        )+
+    };
+}
+
+pub(crate) use impl_resources_root;
+
+/// Implements `len()`, `is_empty()`, `iter()` and `IntoIterator` for response
+/// wrappers holding a single list, delegating to the named `Vec` field.
+macro_rules! impl_list_response {
+    ($($ty:ty => $field:ident: $item:ty),+ $(,)?) => {
+        $(
+            impl $ty {
+                /// Number of items in the response.
+                pub fn len(&self) -> usize {
+                    self.$field.len()
+                }
+
+                /// Returns `true` if the response holds no items.
+                pub fn is_empty(&self) -> bool {
+                    self.$field.is_empty()
+                }
+
+                /// Iterates over the items without consuming the response.
+                pub fn iter(&self) -> std::slice::Iter<'_, $item> {
+                    self.$field.iter()
+                }
+            }
+
+            impl IntoIterator for $ty {
+                type Item = $item;
+                type IntoIter = std::vec::IntoIter<$item>;
+
+                fn into_iter(self) -> Self::IntoIter {
+                    self.$field.into_iter()
+                }
+            }
+
+            impl<'a> IntoIterator for &'a $ty {
+                type Item = &'a $item;
+                type IntoIter = std::slice::Iter<'a, $item>;
+
+                fn into_iter(self) -> Self::IntoIter {
+                    self.$field.iter()
+                }
+            }
+        )+
+    };
+}
+
+pub(crate) use impl_list_response;
+
+#[cfg(test)]
+mod tests {
+    use super::Reference;
+    use crate::structs::announcements::SchoolNoticesResources;
+    use crate::structs::events::HomeworksResources;
+    use crate::structs::grades::{GradesCategoryResources, GradesResources};
+    use crate::structs::lessons::{AttendanceResources, LessonResources};
+    use crate::structs::me::Resources;
+    use crate::structs::timetable::TimetableResources;
+    use crate::structs::users::UserResources;
+
+    const ROOT_ONLY: &str = r#"{"..": {"Url": "https://api.librus.pl/2.0/"}}"#;
+
+    macro_rules! assert_partial_maps_parse {
+        ($($ty:ty),+ $(,)?) => {
+            $(
+                let empty: $ty = serde_json::from_str("{}").unwrap();
+                assert!(empty.root().is_none(), "{}", stringify!($ty));
+                let root_only: $ty = serde_json::from_str(ROOT_ONLY).unwrap();
+                assert_eq!(
+                    root_only.root().map(|r| r.url.as_str()),
+                    Some("https://api.librus.pl/2.0/"),
+                    "{}",
+                    stringify!($ty)
+                );
+            )+
+        };
+    }
+
+    #[test]
+    fn test_partial_resource_maps_parse() {
+        assert_partial_maps_parse!(
+            GradesResources,
+            GradesCategoryResources,
+            AttendanceResources,
+            LessonResources,
+            HomeworksResources,
+            UserResources,
+            SchoolNoticesResources,
+            TimetableResources,
+            Resources,
+        );
+    }
+
+    #[test]
+    fn test_missing_root_with_other_keys() {
+        let body = r#"{"Grades\\Averages": {"Url": "https://api.librus.pl/2.0/Grades/Averages"}}"#;
+        let resources: GradesResources = serde_json::from_str(body).unwrap();
+        assert!(resources.root().is_none());
+        assert!(resources.grades_averages.is_some());
+        assert!(resources.grades_comments.is_none());
+
+        let body = r#"{"HomeWorks\\Categories": {"Url": "https://api.librus.pl/2.0/HomeWorks/Categories"}}"#;
+        let resources: HomeworksResources = serde_json::from_str(body).unwrap();
+        assert!(resources.homeworks_categories.is_some());
+
+        let body = r#"{"Users\\CrossedOutStudents": {"Url": "https://api.librus.pl/2.0/Users/CrossedOutStudents"}}"#;
+        let resources: UserResources = serde_json::from_str(body).unwrap();
+        assert!(resources.users_individual_education_periods.is_none());
+
+        let body =
+            r#"{"Attendances\\Types": {"Url": "https://api.librus.pl/2.0/Attendances/Types"}}"#;
+        let resources: AttendanceResources = serde_json::from_str(body).unwrap();
+        assert!(resources.attendances_lessons_statistics.is_none());
+
+        let body = r#"{"Timetables\\OtherActivitiesRegister": {"Url": "https://api.librus.pl/2.0/Timetables/OtherActivitiesRegister"}}"#;
+        let resources: TimetableResources = serde_json::from_str(body).unwrap();
+        assert!(resources.other_activities_register.is_some());
+    }
+
+    #[test]
+    fn test_reference_id_from_url() {
+        let id = |url: &str| {
+            serde_json::from_value::<Reference>(serde_json::json!({ "Url": url }))
+                .unwrap()
+                .id()
+        };
+        assert_eq!(id("https://api.librus.pl/2.0/Users/51"), Some(51));
+        assert_eq!(id("https://api.librus.pl/2.0/Users/51/"), Some(51));
+        assert_eq!(id("https://api.librus.pl/2.0/Users/51?archive=1"), Some(51));
+        assert_eq!(id("https://api.librus.pl/2.0/Users/51/?x=/2#top"), Some(51));
+        assert_eq!(id("https://api.librus.pl/2.0/Users/me"), None);
+        assert_eq!(id(""), None);
+
+        let explicit: Reference =
+            serde_json::from_str(r#"{"Id": 7, "Url": "https://api.librus.pl/2.0/Users/51"}"#)
+                .unwrap();
+        assert_eq!(explicit.id(), Some(7));
+        let string_id: Reference =
+            serde_json::from_str(r#"{"Id": "7", "Url": "https://api.librus.pl/2.0/Users/51"}"#)
+                .unwrap();
+        assert_eq!(string_id.id, Some(7));
+    }
+
+    #[test]
+    fn test_response_without_resources_parses() {
+        let body = include_str!("../../tests/fixtures/grades.json");
+        let mut value: serde_json::Value = serde_json::from_str(body).unwrap();
+        value.as_object_mut().unwrap().remove("Resources");
+        let grades: crate::ResponseGrades = serde_json::from_value(value).unwrap();
+        assert!(grades.resources.root().is_none());
+    }
+
+    macro_rules! assert_iteration_parity {
+        ($($ty:ty => $field:ident: $fixture:literal),+ $(,)?) => {
+            $(
+                let body = include_str!(concat!("../../tests/fixtures/", $fixture));
+                let resp: $ty = serde_json::from_str(body).unwrap();
+                let expected: $ty = serde_json::from_str(body).unwrap();
+                assert!(!resp.is_empty(), "{}", stringify!($ty));
+                assert_eq!(resp.len(), expected.$field.len());
+                let borrowed: Vec<_> = (&resp).into_iter().map(|item| &item.id).collect();
+                assert!(resp.iter().map(|item| &item.id).eq(borrowed));
+                let ids: Vec<_> = resp.into_iter().map(|item| item.id).collect();
+                let expected: Vec<_> = expected.$field.into_iter().map(|item| item.id).collect();
+                assert_eq!(ids, expected, "{}", stringify!($ty));
+            )+
+        };
+    }
+
+    #[test]
+    fn test_list_responses_iterate_like_their_vec() {
+        assert_iteration_parity!(
+            crate::ResponseGrades => grades: "grades.json",
+            crate::ResponseAttendances => attendances: "attendances.json",
+            crate::ResponseHomeworks => homeworks: "homeworks.json",
+            crate::ResponseSchoolNotices => school_notices: "school_notices.json",
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! School year and semester numbering.
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::dates;
+
+/// Semester of the school year, as numbered by the API.
+///
+/// Serialized as the bare number (`1`, `2`, ...), the same as in API
+/// responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Semester {
+    /// First semester (September to January).
+    First,
+    /// Second semester (February to June).
+    Second,
+    /// Any other number, e.g. from schools using trimesters.
+    Other(u8),
+}
+
+impl Semester {
+    /// The API's number for this semester.
+    pub fn number(self) -> u8 {
+        match self {
+            Semester::First => 1,
+            Semester::Second => 2,
+            Semester::Other(n) => n,
+        }
+    }
+
+    /// Converts a raw semester field, saturating numbers outside `u8`.
+    pub(crate) fn from_raw(raw: i64) -> Self {
+        Self::from(raw.clamp(0, i64::from(u8::MAX)) as u8)
+    }
+}
+
+impl From<u8> for Semester {
+    fn from(n: u8) -> Self {
+        match n {
+            1 => Semester::First,
+            2 => Semester::Second,
+            n => Semester::Other(n),
+        }
+    }
+}
+
+impl fmt::Display for Semester {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+impl Serialize for Semester {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.number())
+    }
+}
+
+impl<'de> Deserialize<'de> for Semester {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u8::deserialize(deserializer).map(Semester::from)
+    }
+}
+
+/// A school year, identified by the calendar year it starts in, e.g. `2025`
+/// for 2025/2026.
+///
+/// Serialized as the bare start year. Displayed as `2025/2026`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SchoolYear(pub i32);
+
+impl SchoolYear {
+    /// The school year a `YYYY-MM-DD` date falls in, assuming school years
+    /// start on September 1. `None` if the date is malformed.
+    pub fn containing(date: &str) -> Option<Self> {
+        dates::parse_day(date).map(Self::of_day)
+    }
+
+    /// The school year of today's date in Poland, assuming school years
+    /// start on September 1.
+    pub fn current() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        Self::of_day(dates::warsaw_day(now))
+    }
+
+    /// The school year before this one.
+    pub fn previous(self) -> Self {
+        Self(self.0 - 1)
+    }
+
+    fn of_day(day: i64) -> Self {
+        let date = dates::format_day(day);
+        let year = dates::year_of(day) as i32;
+        if date[5..] < *"09-01" {
+            Self(year - 1)
+        } else {
+            Self(year)
+        }
+    }
+}
+
+impl fmt::Display for SchoolYear {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.0, self.0 + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semester_from_json() {
+        let parse = |json| serde_json::from_str::<Semester>(json).unwrap();
+        assert_eq!(parse("1"), Semester::First);
+        assert_eq!(parse("2"), Semester::Second);
+        assert_eq!(parse("3"), Semester::Other(3));
+        assert!(serde_json::from_str::<Semester>("\"1\"").is_err());
+        assert_eq!(serde_json::to_string(&Semester::Second).unwrap(), "2");
+        assert_eq!(Semester::Other(3).to_string(), "3");
+    }
+
+    #[test]
+    fn test_school_year_starts_in_september() {
+        assert_eq!(SchoolYear::containing("2025-08-31"), Some(SchoolYear(2024)));
+        assert_eq!(SchoolYear::containing("2025-09-01"), Some(SchoolYear(2025)));
+        assert_eq!(SchoolYear::containing("2026-01-15"), Some(SchoolYear(2025)));
+        assert_eq!(SchoolYear::containing("wrzesień"), None);
+        assert_eq!(SchoolYear(2025).to_string(), "2025/2026");
+        assert_eq!(serde_json::to_string(&SchoolYear(2025)).unwrap(), "2025");
+    }
+
+    #[test]
+    fn test_semester_from_raw_field() {
+        assert_eq!(Semester::from_raw(1), Semester::First);
+        assert_eq!(Semester::from_raw(-1), Semester::Other(0));
+        assert_eq!(Semester::from_raw(1_000), Semester::Other(255));
+    }
+}
@@ -0,0 +1,14 @@
+//! Server system data types.
+
+use crate::types::ApiDate;
+use serde::Deserialize;
+
+/// Response of the `SystemData` endpoint with the server's local date and time.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseSystemData {
+    /// Server date in Polish local time (`YYYY-MM-DD`).
+    pub date: ApiDate,
+    /// Server time in Polish local time (`HH:MM:SS`).
+    pub time: String,
+}
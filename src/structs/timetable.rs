@@ -1,37 +1,38 @@
 //! Timetable data types.
-//!
-//! Note: This module is not yet fully implemented in the public API.
 
-#![allow(dead_code)]
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::collections::{BTreeMap, BTreeSet};
 
-use serde::Deserialize;
-use std::collections::HashMap;
+use crate::structs::resources::{impl_resources_root, ResourceUrl};
+use crate::structs::units::LessonRange;
+use crate::types::{ApiDate, ApiDateTime, ApiUrl};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct TimetableLesson {
     #[serde(rename = "Id")]
     pub id: String,
     #[serde(rename = "Url")]
-    pub url: String,
+    pub url: ApiUrl,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct TimetableClassroom {
     #[serde(rename = "Id")]
     pub id: i32,
     #[serde(rename = "Url")]
-    pub url: String,
+    pub url: ApiUrl,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct TimetableEntry {
     #[serde(rename = "Id")]
     pub id: String,
     #[serde(rename = "Url")]
-    pub url: String,
+    pub url: ApiUrl,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct TimetableLessonSubject {
     #[serde(rename = "Id")]
     pub id: String,
@@ -40,10 +41,10 @@ pub struct TimetableLessonSubject {
     #[serde(rename = "Short")]
     pub short: String,
     #[serde(rename = "Url")]
-    pub url: String,
+    pub url: ApiUrl,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct TimetableTeacher {
     #[serde(rename = "Id")]
     pub id: String,
@@ -52,63 +53,557 @@ pub struct TimetableTeacher {
     #[serde(rename = "LastName")]
     pub last_name: String,
     #[serde(rename = "Url")]
-    pub url: String,
+    pub url: ApiUrl,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct TimetableClass {
     #[serde(rename = "Id")]
     pub id: String,
     #[serde(rename = "Url")]
-    pub url: String,
+    pub url: ApiUrl,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct TimetableDay {
+/// One lesson in the timetable.
+///
+/// A day holds a list of lesson numbers, each with zero or more slots (more
+/// than one when the class is split into groups).
+#[derive(Deserialize, Debug, Clone)]
+pub struct TimetableSlot {
     #[serde(rename = "Lesson")]
     pub lesson: Option<TimetableLesson>,
+    /// Lesson number within the day.
+    #[serde(rename = "LessonNo", default)]
+    pub lesson_no: Option<String>,
+    /// Start time, `HH:MM`.
+    #[serde(rename = "HourFrom", default)]
+    pub hour_from: Option<String>,
+    /// End time, `HH:MM`.
+    #[serde(rename = "HourTo", default)]
+    pub hour_to: Option<String>,
+    #[serde(rename = "Subject", default)]
+    pub subject: Option<TimetableLessonSubject>,
+    #[serde(rename = "Teacher", default)]
+    pub teacher: Option<TimetableTeacher>,
+    #[serde(rename = "Classroom", default)]
+    pub classroom: Option<TimetableClassroom>,
+    #[serde(rename = "Class", default)]
+    pub class: Option<TimetableClass>,
+    #[serde(rename = "TimetableEntry", default)]
+    pub timetable_entry: Option<TimetableEntry>,
+    #[serde(rename = "IsCanceled", default)]
+    pub is_canceled: bool,
+    /// Whether another teacher or subject replaces the planned lesson.
+    #[serde(rename = "IsSubstitutionClass", default)]
+    pub is_substitution_class: bool,
+    #[serde(rename = "SubstitutionNote", default)]
+    pub substitution_note: Option<String>,
+}
+
+impl TimetableSlot {
+    /// Start and end as minutes since midnight, if both times parse.
+    fn minutes(&self) -> Option<(u32, u32)> {
+        Some((
+            minutes(self.hour_from.as_deref()?)?,
+            minutes(self.hour_to.as_deref()?)?,
+        ))
+    }
 }
 
+/// Parses `HH:MM` (seconds are ignored) into minutes since midnight.
+fn minutes(time: &str) -> Option<u32> {
+    let mut parts = time.trim().splitn(3, ':');
+    let h: u32 = parts.next()?.parse().ok()?;
+    let m: u32 = parts.next()?.parse().ok()?;
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+/// Lessons of one day: a list of lesson numbers, each with its slots.
+pub type TimetableDay = Vec<Vec<TimetableSlot>>;
+
+/// Lessons of a week keyed by date, in chronological order.
+///
+/// During holidays the API sends `false`, `null` or `[]` for a day, or leaves
+/// days out; the first three are read as a day without lessons. A week sent
+/// as `false` or `[]` has no days.
 #[derive(Debug, Deserialize)]
-#[serde(rename = "Timetable")]
+#[serde(transparent)]
 pub struct Timetable {
-    pub timetable: Option<HashMap<String, Vec<Vec<TimetableDay>>>>,
+    #[serde(deserialize_with = "timetable_days")]
+    pub timetable: Option<BTreeMap<ApiDate, TimetableDay>>,
+}
+
+fn timetable_days<'de, D>(
+    deserializer: D,
+) -> Result<Option<BTreeMap<ApiDate, TimetableDay>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Day {
+        Lessons(TimetableDay),
+        Flag(bool),
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Week {
+        Days(BTreeMap<ApiDate, Option<Day>>),
+        Empty([(); 0]),
+        Flag(bool),
+    }
+
+    let unexpected = || D::Error::custom("expected timetable days or `false`, found `true`");
+    let days = match Option::<Week>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(Week::Days(days)) => days,
+        Some(Week::Empty(_) | Week::Flag(false)) => BTreeMap::new(),
+        Some(Week::Flag(true)) => return Err(unexpected()),
+    };
+    days.into_iter()
+        .map(|(date, day)| match day {
+            Some(Day::Lessons(lessons)) => Ok((date, lessons)),
+            Some(Day::Flag(false)) | None => Ok((date, Vec::new())),
+            Some(Day::Flag(true)) => Err(unexpected()),
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
 }
 
 #[derive(Debug, Deserialize)]
 pub struct TimetablePages {
     #[serde(rename = "Next")]
-    pub next: String,
+    pub next: ApiUrl,
     #[serde(rename = "Prev")]
-    pub prev: String,
+    pub prev: ApiUrl,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct TimetablesUrl {
-    #[serde(rename = "Url")]
-    pub url: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct TimetableResources {
     #[serde(rename = "Timetables\\IndividualLearningPath")]
-    pub individual_learning_path: TimetablesUrl,
+    pub individual_learning_path: Option<ResourceUrl>,
     #[serde(rename = "Timetables\\OneToOneLearningPlan")]
-    pub onetoone_learning_plan: TimetablesUrl,
+    pub onetoone_learning_plan: Option<ResourceUrl>,
     #[serde(rename = "Timetables\\OtherActivitiesRegister")]
-    pub other_activities_register: TimetablesUrl,
+    pub other_activities_register: Option<ResourceUrl>,
     #[serde(rename = "..")]
-    pub root: TimetablesUrl,
+    pub root: Option<ResourceUrl>,
 }
 
+impl_resources_root!(TimetableResources);
+
 #[derive(Debug, Deserialize)]
 pub struct ResponseTimetable {
     #[serde(rename = "Timetable")]
     pub timetable: Timetable,
     #[serde(rename = "Pages")]
     pub pages: TimetablePages,
-    #[serde(rename = "Resources")]
+    #[serde(rename = "Resources", default)]
     pub resources: TimetableResources,
     #[serde(rename = "Url")]
-    pub url: String,
+    pub url: ApiUrl,
+}
+
+/// The current and the next lesson, returned by
+/// [`Client::now_and_next()`](crate::Client::now_and_next).
+#[derive(Debug, Clone)]
+pub struct NowAndNext {
+    /// The lesson in progress, or `None` during breaks and outside school hours.
+    pub current: Option<TimetableSlot>,
+    /// The next lesson to start, possibly on a later day.
+    pub next: Option<TimetableSlot>,
+    /// Date of [`next`](Self::next).
+    pub next_date: Option<ApiDate>,
+}
+
+/// Slots of one lesson period that are not cancelled.
+fn held(slots: &[TimetableSlot]) -> Vec<&TimetableSlot> {
+    slots.iter().filter(|slot| !slot.is_canceled).collect()
+}
+
+/// Overview of one day of a [`ResponseTimetable`], see
+/// [`ResponseTimetable::day_summary()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaySummary {
+    /// The day.
+    pub date: ApiDate,
+    /// Start of the first lesson that takes place, `HH:MM`, if known.
+    pub first_lesson_start: Option<String>,
+    /// End of the last lesson that takes place, `HH:MM`, if known.
+    pub last_lesson_end: Option<String>,
+    /// Number of lesson periods that take place.
+    pub lessons: usize,
+    /// Lesson numbers without a lesson between the first and the last one
+    /// ("okienka"), including cancelled ones.
+    pub free_periods: Vec<usize>,
+    /// Names of the subjects taught.
+    pub subjects: BTreeSet<String>,
+}
+
+impl ResponseTimetable {
+    /// Lesson counts, free periods and times of each day, in chronological
+    /// order.
+    ///
+    /// Cancelled lessons count as free, so a cancelled last lesson makes
+    /// the day end earlier. Substitutions count as lessons. A period takes
+    /// place if any of its group slots is not cancelled.
+    ///
+    /// Times are looked up by lesson number in `lessons_range`, the school's
+    /// lesson hours from [`Unit::lessons_range`](crate::Unit::lessons_range),
+    /// and taken from the slots for lesson numbers it has no hours for. Days
+    /// without lessons have none.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::Client;
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let week = client.timetable("2024-03-11").await?;
+    /// let units = client.units().await?;
+    /// let hours = units.units.first().map_or(&[][..], |unit| &unit.lessons_range);
+    /// for day in week.day_summary(hours) {
+    ///     println!("{}: ends at {:?}", day.date, day.last_lesson_end);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn day_summary(&self, lessons_range: &[LessonRange]) -> Vec<DaySummary> {
+        let hours = |no: usize| lessons_range.get(no).and_then(LessonRange::hours);
+        self.days()
+            .map(|(date, day)| {
+                let periods: Vec<usize> = (0..day.len())
+                    .filter(|&no| !held(&day[no]).is_empty())
+                    .collect();
+                let (first, last) = match (periods.first(), periods.last()) {
+                    (Some(&first), Some(&last)) => (first, last),
+                    _ => {
+                        return DaySummary {
+                            date: date.clone(),
+                            first_lesson_start: None,
+                            last_lesson_end: None,
+                            lessons: 0,
+                            free_periods: Vec::new(),
+                            subjects: BTreeSet::new(),
+                        }
+                    }
+                };
+                DaySummary {
+                    date: date.clone(),
+                    first_lesson_start: hours(first).map(|(from, _)| from.to_string()).or_else(
+                        || {
+                            held(&day[first])
+                                .iter()
+                                .filter_map(|slot| slot.hour_from.clone())
+                                .min_by_key(|time| minutes(time))
+                        },
+                    ),
+                    last_lesson_end: hours(last).map(|(_, to)| to.to_string()).or_else(|| {
+                        held(&day[last])
+                            .iter()
+                            .filter_map(|slot| slot.hour_to.clone())
+                            .max_by_key(|time| minutes(time))
+                    }),
+                    lessons: periods.len(),
+                    free_periods: (first..last).filter(|no| !periods.contains(no)).collect(),
+                    subjects: periods
+                        .iter()
+                        .flat_map(|&no| held(&day[no]))
+                        .filter_map(|slot| Some(slot.subject.as_ref()?.name.clone()))
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Days of the week in chronological order, with their lesson numbers.
+    ///
+    /// Days the API left out are not listed; holidays sent as `false` or
+    /// `[]` are listed without lessons.
+    pub fn days(&self) -> impl Iterator<Item = (&ApiDate, &[Vec<TimetableSlot>])> {
+        self.timetable
+            .timetable
+            .iter()
+            .flatten()
+            .map(|(date, day)| (date, day.as_slice()))
+    }
+
+    /// The lesson taking place at `at`, if any.
+    ///
+    /// Cancelled lessons and slots without start or end time are ignored. A
+    /// lesson covers its start minute up to, but not including, its end
+    /// minute, so a break between lessons returns `None`.
+    pub fn lesson_at(&self, at: &ApiDateTime) -> Option<&TimetableSlot> {
+        let (date, now) = date_and_minute(at)?;
+        let day = self.timetable.timetable.as_ref()?.get(&date)?;
+        slots_of(day)
+            .find(|(start, end, _)| (*start..*end).contains(&now))
+            .map(|(_, _, slot)| slot)
+    }
+
+    /// The first lesson starting after `at`, with its date.
+    ///
+    /// Looks at the rest of the day of `at` and then at the following days of
+    /// the week. Cancelled lessons are skipped. A lesson in progress at `at`
+    /// does not count.
+    pub fn next_lesson_after(&self, at: &ApiDateTime) -> Option<(&ApiDate, &TimetableSlot)> {
+        let (date, now) = date_and_minute(at)?;
+        let days = self.timetable.timetable.as_ref()?;
+        days.range(&date..).find_map(|(day, lessons)| {
+            let from = if *day == date { now + 1 } else { 0 };
+            slots_of(lessons)
+                .find(|(start, _, _)| *start >= from)
+                .map(|(_, _, slot)| (day, slot))
+        })
+    }
+}
+
+/// Lessons of a day that are not cancelled, ordered by start time.
+fn slots_of(day: &[Vec<TimetableSlot>]) -> impl Iterator<Item = (u32, u32, &TimetableSlot)> {
+    let mut slots: Vec<_> = day
+        .iter()
+        .flatten()
+        .filter(|slot| !slot.is_canceled)
+        .filter_map(|slot| slot.minutes().map(|(start, end)| (start, end, slot)))
+        .collect();
+    slots.sort_by_key(|(start, _, _)| *start);
+    slots.into_iter()
+}
+
+/// Splits a date-time into its `YYYY-MM-DD` date and minute of the day.
+fn date_and_minute(at: &ApiDateTime) -> Option<(ApiDate, u32)> {
+    let (y, mo, d, h, mi, _) = at.components()?;
+    Some((
+        ApiDate::from_raw(format!("{y:04}-{mo:02}-{d:02}")),
+        h * 60 + mi,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn week() -> ResponseTimetable {
+        serde_json::from_str(include_str!("../../tests/fixtures/timetable_week.json")).unwrap()
+    }
+
+    fn at(value: &str) -> ApiDateTime {
+        ApiDateTime::try_from(value).unwrap()
+    }
+
+    fn subject(slot: Option<&TimetableSlot>) -> Option<&str> {
+        Some(slot?.subject.as_ref()?.name.as_str())
+    }
+
+    #[test]
+    fn test_lesson_at() {
+        let week = week();
+        assert_eq!(
+            subject(week.lesson_at(&at("2024-03-11 08:00:00"))),
+            Some("Matematyka")
+        );
+        assert_eq!(
+            subject(week.lesson_at(&at("2024-03-11 08:44:59"))),
+            Some("Matematyka")
+        );
+        // Short break, then the lunch gap after the third lesson.
+        assert_eq!(subject(week.lesson_at(&at("2024-03-11 08:45:00"))), None);
+        assert_eq!(subject(week.lesson_at(&at("2024-03-11 11:00:00"))), None);
+        assert_eq!(
+            subject(week.lesson_at(&at("2024-03-11 11:30:00"))),
+            Some("Fizyka")
+        );
+        // Before and after school.
+        assert_eq!(subject(week.lesson_at(&at("2024-03-11 07:59:00"))), None);
+        assert_eq!(subject(week.lesson_at(&at("2024-03-11 13:00:00"))), None);
+    }
+
+    #[test]
+    fn test_cancelled_lesson_is_skipped() {
+        let week = week();
+        // The second lesson on Monday is cancelled.
+        assert_eq!(subject(week.lesson_at(&at("2024-03-11 09:00:00"))), None);
+        let (date, next) = week.next_lesson_after(&at("2024-03-11 08:30:00")).unwrap();
+        assert_eq!(
+            (date.as_str(), subject(Some(next))),
+            ("2024-03-11", Some("Historia"))
+        );
+    }
+
+    #[test]
+    fn test_next_lesson_after() {
+        let week = week();
+        let (_, next) = week.next_lesson_after(&at("2024-03-11 07:00:00")).unwrap();
+        assert_eq!(subject(Some(next)), Some("Matematyka"));
+
+        // A lesson in progress does not count, the lunch gap is skipped.
+        let (date, next) = week.next_lesson_after(&at("2024-03-11 10:00:00")).unwrap();
+        assert_eq!((date.as_str(), subject(Some(next))), ("2024-03-11", Some("Fizyka")));
+    }
+
+    #[test]
+    fn test_next_lesson_after_end_of_day() {
+        let week = week();
+        let (date, next) = week.next_lesson_after(&at("2024-03-11 15:00:00")).unwrap();
+        assert_eq!(
+            (date.as_str(), subject(Some(next))),
+            ("2024-03-12", Some("Biologia"))
+        );
+        // Nothing after the last lesson of the week.
+        assert!(week.next_lesson_after(&at("2024-03-12 09:00:00")).is_none());
+    }
+
+    #[test]
+    fn test_lesson_at_unknown_day() {
+        assert!(week().lesson_at(&at("2024-04-01 08:00:00")).is_none());
+    }
+
+    fn dates(week: &ResponseTimetable) -> Vec<(&str, usize)> {
+        week.days()
+            .map(|(date, day)| (date.as_str(), day.len()))
+            .collect()
+    }
+
+    #[test]
+    fn test_days_in_order() {
+        assert_eq!(
+            dates(&week()),
+            [("2024-03-11", 6), ("2024-03-12", 2), ("2024-03-13", 0)]
+        );
+    }
+
+    #[test]
+    fn test_reference_urls_are_typed() {
+        let week = week();
+        let slot = week.lesson_at(&at("2024-03-11 08:10:00")).unwrap();
+        let subject = slot.subject.as_ref().unwrap();
+        assert_eq!(subject.url.kind(), crate::ApiKind::Synergia);
+        assert_eq!(subject.url.path_relative_to_base(), Some("Subjects/21"));
+        assert_eq!(week.url.kind(), crate::ApiKind::Synergia);
+    }
+
+    #[test]
+    fn test_holiday_week() {
+        let week: ResponseTimetable = serde_json::from_str(include_str!(
+            "../../tests/fixtures/timetable_holiday_week.json"
+        ))
+        .unwrap();
+        assert_eq!(
+            dates(&week),
+            [
+                ("2024-12-23", 0),
+                ("2024-12-24", 0),
+                ("2024-12-25", 0),
+                ("2024-12-26", 0),
+                ("2024-12-27", 0)
+            ]
+        );
+        assert!(week.next_lesson_after(&at("2024-12-23 07:00:00")).is_none());
+
+        for empty in ["false", "[]"] {
+            let timetable: Timetable = serde_json::from_str(empty).unwrap();
+            assert_eq!(timetable.timetable.map(|days| days.len()), Some(0));
+        }
+        assert!(serde_json::from_str::<Timetable>("null")
+            .unwrap()
+            .timetable
+            .is_none());
+        assert!(serde_json::from_str::<Timetable>(r#"{"2024-12-23": true}"#).is_err());
+    }
+
+    #[test]
+    fn test_partial_week() {
+        let week: ResponseTimetable = serde_json::from_str(include_str!(
+            "../../tests/fixtures/timetable_partial_week.json"
+        ))
+        .unwrap();
+        assert_eq!(
+            dates(&week),
+            [("2024-03-11", 6), ("2024-03-12", 0), ("2024-03-14", 2)]
+        );
+        // Tuesday is a day off, so the next lesson is on Thursday.
+        let (date, next) = week.next_lesson_after(&at("2024-03-11 15:00:00")).unwrap();
+        assert_eq!(
+            (date.as_str(), subject(Some(next))),
+            ("2024-03-14", Some("Biologia"))
+        );
+    }
+
+    fn slot(no: usize, from: &str, to: &str, subject: &str) -> serde_json::Value {
+        serde_json::json!({
+            "Lesson": {"Id": format!("{no}"), "Url": ""},
+            "LessonNo": no.to_string(),
+            "HourFrom": from,
+            "HourTo": to,
+            "Subject": {"Id": "1", "Name": subject, "Short": "", "Url": ""},
+        })
+    }
+
+    #[test]
+    fn test_day_summary() {
+        let mut cancelled = slot(5, "11:50", "12:35", "Fizyka");
+        cancelled["IsCanceled"] = true.into();
+        let mut substitution = slot(2, "08:55", "09:40", "Chemia");
+        substitution["IsSubstitutionClass"] = true.into();
+        // Groups: English for one half of the class, German for the other.
+        let groups = [
+            slot(4, "10:45", "11:30", "Język angielski"),
+            slot(4, "10:45", "11:30", "Język niemiecki"),
+        ];
+        let week: ResponseTimetable = serde_json::from_value(serde_json::json!({
+            "Timetable": {
+                "2024-03-12": [[], [slot(1, "08:00", "08:45", "Matematyka")], [substitution],
+                    [], groups, [cancelled]],
+                "2024-03-11": [[], [], [slot(2, "08:55", "09:40", "Historia")]],
+                "2024-03-13": false,
+            },
+            "Pages": {"Next": "", "Prev": ""},
+            "Url": "",
+        }))
+        .unwrap();
+
+        let units: crate::ResponseUnits =
+            serde_json::from_str(include_str!("../../tests/fixtures/units.json")).unwrap();
+        let hours = &units.units[0].lessons_range;
+        let days = week.day_summary(hours);
+        assert_eq!(days.len(), 3);
+        assert_eq!(days[0].date, "2024-03-11");
+        assert_eq!(days[0].lessons, 1);
+        assert_eq!(days[0].first_lesson_start.as_deref(), Some("08:55"));
+
+        // The okienko is the third period; the cancelled fifth period
+        // shortens the day instead.
+        let tuesday = &days[1];
+        assert_eq!(tuesday.lessons, 3);
+        assert_eq!(tuesday.free_periods, [3]);
+        assert_eq!(tuesday.first_lesson_start.as_deref(), Some("08:00"));
+        assert_eq!(tuesday.last_lesson_end.as_deref(), Some("11:30"));
+        assert_eq!(
+            tuesday
+                .subjects
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>(),
+            ["Chemia", "Język angielski", "Język niemiecki", "Matematyka"]
+        );
+
+        let wednesday = &days[2];
+        assert_eq!(
+            (wednesday.lessons, wednesday.last_lesson_end.as_deref()),
+            (0, None)
+        );
+        assert!(wednesday.free_periods.is_empty() && wednesday.subjects.is_empty());
+
+        // The school's lesson hours win over the slots, which are used
+        // without them.
+        let mut shortened = hours.clone();
+        shortened[4].to = Some("11:25".to_string());
+        assert_eq!(
+            week.day_summary(&shortened)[1].last_lesson_end.as_deref(),
+            Some("11:25")
+        );
+        assert_eq!(week.day_summary(&[]), days);
+    }
 }
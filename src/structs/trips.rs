@@ -0,0 +1,60 @@
+//! School trip data types.
+
+use serde::Deserialize;
+
+use crate::structs::resources::Reference;
+use crate::types::ApiDate;
+
+/// A school trip referenced by [`Attendance::trip`](crate::Attendance::trip).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Trip {
+    /// Unique trip identifier.
+    pub id: i64,
+    /// Trip name.
+    pub name: String,
+    /// First day of the trip (`YYYY-MM-DD`).
+    pub date_from: ApiDate,
+    /// Last day of the trip, inclusive (`YYYY-MM-DD`).
+    pub date_to: ApiDate,
+    /// Destination, if given.
+    pub place: Option<String>,
+    /// Reference to the supervising teacher.
+    pub teacher: Option<Reference>,
+}
+
+/// Response containing all trips.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseTrips {
+    /// List of trips.
+    pub trips: Vec<Trip>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+/// Response containing a single trip.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseTrip {
+    /// The trip, if it exists.
+    pub trip: Option<Trip>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trips() {
+        let resp: ResponseTrips =
+            serde_json::from_str(include_str!("../../tests/fixtures/trips.json")).unwrap();
+        assert_eq!(resp.trips.len(), 2);
+        let trip = &resp.trips[0];
+        assert_eq!(trip.name, "Wycieczka do Krakowa");
+        assert_eq!(trip.teacher.as_ref().and_then(|t| t.id()), Some(51));
+        assert!(resp.trips[1].teacher.is_none());
+    }
+}
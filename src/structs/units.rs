@@ -0,0 +1,80 @@
+//! School unit data types.
+
+use serde::Deserialize;
+
+use crate::serde_util::flexible_i64;
+
+/// A school unit, e.g. a primary school within a school complex.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Unit {
+    /// Unit ID.
+    #[serde(deserialize_with = "flexible_i64")]
+    pub id: i64,
+    /// Name of the unit, if reported.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Start and end time of each lesson, indexed by lesson number (the
+    /// first entry is lesson 0).
+    ///
+    /// Pass it to [`ResponseTimetable::day_summary()`](crate::ResponseTimetable::day_summary).
+    #[serde(default)]
+    pub lessons_range: Vec<LessonRange>,
+}
+
+impl Unit {
+    /// Start and end time (`HH:MM`) of lesson `lesson_no`, if the school
+    /// set them.
+    pub fn lesson_hours(&self, lesson_no: usize) -> Option<(&str, &str)> {
+        self.lessons_range.get(lesson_no)?.hours()
+    }
+}
+
+/// Start and end time of one lesson number in a [`Unit`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LessonRange {
+    /// Start time, `HH:MM`. Empty or `None` for lesson numbers the school
+    /// does not use.
+    #[serde(default)]
+    pub from: Option<String>,
+    /// End time, `HH:MM`.
+    #[serde(default)]
+    pub to: Option<String>,
+}
+
+impl LessonRange {
+    /// Start and end time, if both are set.
+    pub fn hours(&self) -> Option<(&str, &str)> {
+        let from = self.from.as_deref().filter(|t| !t.is_empty())?;
+        let to = self.to.as_deref().filter(|t| !t.is_empty())?;
+        Some((from, to))
+    }
+}
+
+/// Response containing the school units.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseUnits {
+    /// The units of the school.
+    pub units: Vec<Unit>,
+    /// API URL for this response.
+    pub url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lessons_range() {
+        let resp: ResponseUnits =
+            serde_json::from_str(include_str!("../../tests/fixtures/units.json")).unwrap();
+        let unit = &resp.units[0];
+        assert_eq!(unit.id, 3);
+        assert_eq!(unit.lesson_hours(1), Some(("08:00", "08:45")));
+        // Lesson 0 is not used by this school.
+        assert_eq!(unit.lesson_hours(0), None);
+        assert_eq!(unit.lesson_hours(99), None);
+    }
+}
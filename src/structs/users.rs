@@ -1,9 +1,11 @@
 //! User data types.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::structs::resources::{impl_resources_root, Reference, ResourceUrl};
 
 /// A user in the Librus system (student, teacher, or parent).
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct User {
     /// Unique user identifier.
@@ -17,17 +19,21 @@ pub struct User {
     /// Class the user belongs to (for students).
     pub class: Option<UserClass>,
     /// School unit the user belongs to.
-    pub unit: Option<UserUnit>,
+    pub unit: Option<Reference>,
     /// Class register number (for students).
     pub class_register_number: Option<i64>,
     /// Whether this user is a school employee.
     pub is_employee: bool,
     /// User group ID.
     pub group_id: i64,
+    /// Fields returned by the API that this crate does not model yet.
+    #[cfg(feature = "raw-extras")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Reference to a user's class.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct UserClass {
     /// Class ID.
@@ -39,32 +45,22 @@ pub struct UserClass {
     pub uuid: String,
 }
 
-/// Reference to a school unit.
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct UserUnit {
-    /// Unit ID.
-    pub id: i64,
-    /// API URL for the unit.
-    pub url: String,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct UserUrl {
-    pub url: String,
-}
-
-#[derive(Debug, Deserialize)]
+/// Related API resources of users.
+#[derive(Debug, Default, Deserialize)]
 pub struct UserResources {
+    /// Link to individual education periods.
     #[serde(rename = "Users\\IndividualEducationPeriods")]
-    pub users_individual_education_periods: UserUrl,
+    pub users_individual_education_periods: Option<ResourceUrl>,
+    /// Link to crossed-out students.
     #[serde(rename = "Users\\CrossedOutStudents")]
-    pub users_crossed_out_students: UserUrl,
+    pub users_crossed_out_students: Option<ResourceUrl>,
+    /// Link to the parent resource.
     #[serde(rename = "..")]
-    pub root: UserUrl,
+    pub root: Option<ResourceUrl>,
 }
 
+impl_resources_root!(UserResources);
+
 /// Response containing a single user.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -72,7 +68,18 @@ pub struct ResponseUser {
     /// The user data, if found.
     pub user: Option<User>,
     /// Related API resources.
+    #[serde(default)]
     pub resources: UserResources,
     /// API URL for this response.
     pub url: String,
 }
+
+/// Response containing the users visible to the account.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResponseUsers {
+    /// List of users (teachers, classmates, parents).
+    pub users: Vec<User>,
+    /// API URL for this response.
+    pub url: String,
+}
@@ -0,0 +1,157 @@
+//! Composite overview of a student assembled from several endpoints.
+
+use std::collections::HashMap;
+
+use crate::structs::events::Homework;
+use crate::structs::grades::Grade;
+use crate::structs::lessons::LessonSubject;
+use crate::Error;
+
+/// A section of a [`StudentCard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardSection {
+    /// The student's class.
+    Class,
+    /// Per-subject averages.
+    Averages,
+    /// Attendance counts.
+    Attendance,
+    /// Unread messages.
+    Messages,
+    /// Upcoming homework.
+    Homework,
+}
+
+/// Why a section of a [`StudentCard`] is missing.
+#[derive(Debug, Clone)]
+pub struct SectionError {
+    /// The section that could not be filled in.
+    pub section: CardSection,
+    /// Description of the error that occurred.
+    pub message: String,
+}
+
+/// Average of a student's grades in one subject.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubjectAverage {
+    /// Subject ID.
+    pub subject_id: i64,
+    /// Subject name, if the subject list could be fetched.
+    pub subject_name: Option<String>,
+    /// Unweighted average of the numeric partial grades.
+    pub average: f64,
+    /// Number of grades included in the average.
+    pub count: usize,
+}
+
+/// A one-screen overview of a student.
+///
+/// Returned by [`Client::student_card()`](crate::Client::student_card). Every
+/// section except the name is optional: if fetching it failed, it is `None`
+/// and the reason is listed in [`errors`](Self::errors).
+#[derive(Debug, Default)]
+pub struct StudentCard {
+    /// Student's full name.
+    pub name: String,
+    /// Class name, e.g. `"7a"`.
+    pub class: Option<String>,
+    /// Current average per subject, sorted by subject name.
+    pub averages: Option<Vec<SubjectAverage>>,
+    /// Number of unexcused absences, not counting school trips.
+    pub unexcused_absences: Option<usize>,
+    /// Number of unread messages in the inbox.
+    pub unread_messages: Option<u32>,
+    /// The next homework due today or later. `None` also when there is none.
+    pub next_homework: Option<Homework>,
+    /// Sections that could not be fetched.
+    pub errors: Vec<SectionError>,
+}
+
+impl StudentCard {
+    /// Unwraps a section result, recording the error if it failed.
+    pub(crate) fn section<T>(
+        &mut self,
+        section: CardSection,
+        result: Result<T, Error>,
+    ) -> Option<T> {
+        result
+            .map_err(|e| {
+                self.errors.push(SectionError {
+                    section,
+                    message: e.to_string(),
+                })
+            })
+            .ok()
+    }
+}
+
+/// Computes per-subject averages of numeric partial grades.
+pub(crate) fn subject_averages(
+    grades: &[Grade],
+    subjects: &[LessonSubject],
+) -> Vec<SubjectAverage> {
+    let names: HashMap<i64, &str> = subjects
+        .iter()
+        .map(|s| (i64::from(s.id), s.name.as_str()))
+        .collect();
+    let mut sums: HashMap<i64, (f64, usize)> = HashMap::new();
+    for grade in grades.iter().filter(|g| g.is_partial()) {
+        if let (Some(value), Some(subject)) = (grade.value(), grade.subject.id()) {
+            let entry = sums.entry(subject).or_default();
+            entry.0 += value;
+            entry.1 += 1;
+        }
+    }
+
+    let mut averages: Vec<SubjectAverage> = sums
+        .into_iter()
+        .map(|(subject_id, (sum, count))| SubjectAverage {
+            subject_id,
+            subject_name: names.get(&subject_id).map(|n| n.to_string()),
+            average: sum / count as f64,
+            count,
+        })
+        .collect();
+    averages.sort_by(|a, b| {
+        (a.subject_name.as_deref(), a.subject_id).cmp(&(b.subject_name.as_deref(), b.subject_id))
+    });
+    averages
+}
+
+/// Picks the earliest homework due on or after `today` (`YYYY-MM-DD`).
+pub(crate) fn next_homework(homeworks: Vec<Homework>, today: &str) -> Option<Homework> {
+    homeworks
+        .into_iter()
+        .filter(|hw| hw.date.as_str() >= today)
+        .min_by(|a, b| (&a.date, &a.time_from).cmp(&(&b.date, &b.time_from)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::events::ResponseHomeworks;
+    use crate::structs::grades::ResponseGrades;
+
+    #[test]
+    fn test_subject_averages() {
+        let resp: ResponseGrades =
+            serde_json::from_str(include_str!("../tests/fixtures/grades.json")).unwrap();
+        let subjects: Vec<LessonSubject> =
+            serde_json::from_str(r#"[{"Id": 22, "Name": "Biologia", "No": 2, "Short": "bio"}]"#)
+                .unwrap();
+        let averages = subject_averages(&resp.grades, &subjects);
+        assert_eq!(averages.len(), 2);
+        // Subjects without a known name sort first.
+        assert_eq!((averages[0].subject_id, averages[0].average), (21, 5.0));
+        assert_eq!(averages[1].subject_name.as_deref(), Some("Biologia"));
+        assert_eq!(averages[1].average, 3.5);
+    }
+
+    #[test]
+    fn test_next_homework() {
+        let resp: ResponseHomeworks =
+            serde_json::from_str(include_str!("../tests/fixtures/homeworks.json")).unwrap();
+        let next = next_homework(resp.homeworks, "2025-03-13").unwrap();
+        assert_eq!(next.id, 302);
+    }
+}
@@ -0,0 +1,88 @@
+//! Joining lessons, subjects and users into "who teaches what".
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::indexes::UserIndex;
+use crate::structs::lessons::{Lesson, LessonSubject};
+use crate::structs::users::User;
+
+/// A subject together with the teachers who teach it to the student.
+///
+/// Returned by [`Client::subject_teachers()`](crate::Client::subject_teachers).
+#[derive(Debug, Clone)]
+pub struct SubjectTeacher {
+    /// The subject.
+    pub subject: LessonSubject,
+    /// Teachers of the subject, sorted by last name. A teacher who teaches
+    /// several groups of the class is listed once.
+    pub teachers: Vec<User>,
+}
+
+impl SubjectTeacher {
+    /// Builds the subject-teacher list from the lessons, keeping the order
+    /// of `subjects`. Subjects without lessons are left out, and so are
+    /// teachers missing from `users`.
+    ///
+    /// Same as [`Client::subject_teachers()`](crate::Client::subject_teachers)
+    /// with data that is already at hand.
+    pub fn join(lessons: &[Lesson], subjects: &[LessonSubject], users: &UserIndex) -> Vec<Self> {
+        let mut teacher_ids: HashMap<i64, BTreeSet<i64>> = HashMap::new();
+        for lesson in lessons {
+            if let (Some(subject), Some(teacher)) = (lesson.subject.id(), lesson.teacher.id()) {
+                teacher_ids.entry(subject).or_default().insert(teacher);
+            }
+        }
+
+        subjects
+            .iter()
+            .filter_map(|subject| {
+                let ids = teacher_ids.get(&i64::from(subject.id))?;
+                let mut teachers: Vec<User> = ids
+                    .iter()
+                    .filter_map(|&id| users.get(id).cloned())
+                    .collect();
+                teachers.sort_by(|a, b| {
+                    (&a.last_name, &a.first_name, a.id).cmp(&(&b.last_name, &b.first_name, b.id))
+                });
+                Some(SubjectTeacher {
+                    subject: subject.clone(),
+                    teachers,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::lessons::{ResponseLessons, ResponseSubjects};
+    use crate::structs::users::ResponseUsers;
+
+    #[test]
+    fn test_join_subject_teachers() {
+        let lessons: ResponseLessons =
+            serde_json::from_str(include_str!("../tests/fixtures/lessons.json")).unwrap();
+        let subjects: ResponseSubjects =
+            serde_json::from_str(include_str!("../tests/fixtures/subjects.json")).unwrap();
+        let users: ResponseUsers =
+            serde_json::from_str(include_str!("../tests/fixtures/users.json")).unwrap();
+
+        let joined = SubjectTeacher::join(&lessons.lessons, &subjects.subjects, &users.into());
+        let names: Vec<(&str, Vec<&str>)> = joined
+            .iter()
+            .map(|st| {
+                let teachers = st.teachers.iter().map(|t| t.last_name.as_str()).collect();
+                (st.subject.name.as_str(), teachers)
+            })
+            .collect();
+        assert_eq!(
+            names,
+            [
+                // Nowak teaches both maths groups but is listed once.
+                ("Matematyka", vec!["Kowalczyk", "Nowak"]),
+                ("Biologia", vec!["Nowak"]),
+            ]
+        );
+    }
+}
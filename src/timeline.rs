@@ -0,0 +1,237 @@
+//! A unified activity timeline across grades, homework, notices and messages.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use librus_rs::timeline::{self, TimelineItem};
+//! use librus_rs::Client;
+//!
+//! # async fn example() -> Result<(), librus_rs::Error> {
+//! let mut client = Client::from_env().await?;
+//! let grades = client.grades().await?;
+//! let notices = client.school_notices().await?;
+//! let messages = client.inbox_messages(1, 20).await?;
+//!
+//! let items = timeline::merge(
+//!     grades.grades.iter().map(TimelineItem::from)
+//!         .chain(notices.school_notices.iter().map(TimelineItem::from))
+//!         .chain(messages.iter().map(TimelineItem::from)),
+//! );
+//! for item in items {
+//!     println!("{:?} {:?}", item.kind(), item.timestamp());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+use crate::dates;
+use crate::structs::announcements::SchoolNotice;
+use crate::structs::events::Homework;
+use crate::structs::grades::Grade;
+use crate::structs::messages::InboxMessage;
+
+/// Kind of a [`TimelineItem`], in tiebreak order for items with the same
+/// timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimelineKind {
+    /// A grade.
+    Grade,
+    /// A homework or test entry.
+    Homework,
+    /// A school notice.
+    Notice,
+    /// A received message.
+    Message,
+}
+
+/// An entry of the activity timeline, borrowing the underlying entity.
+///
+/// Items order newest first, then by [`TimelineKind`], then by ID. Items
+/// whose date cannot be parsed sort after all others. Equality and hashing
+/// follow the same key.
+#[derive(Debug, Clone, Copy)]
+pub enum TimelineItem<'a> {
+    /// A grade, timed by when it was added.
+    Grade(&'a Grade),
+    /// A homework entry, timed by when it was added.
+    Homework(&'a Homework),
+    /// A school notice, timed by its creation date.
+    Notice(&'a SchoolNotice),
+    /// A received message, timed by when it was sent.
+    Message(&'a InboxMessage),
+}
+
+impl TimelineItem<'_> {
+    /// Kind of the item.
+    pub fn kind(&self) -> TimelineKind {
+        match self {
+            Self::Grade(_) => TimelineKind::Grade,
+            Self::Homework(_) => TimelineKind::Homework,
+            Self::Notice(_) => TimelineKind::Notice,
+            Self::Message(_) => TimelineKind::Message,
+        }
+    }
+
+    /// The item's date as reported by the API (Polish local time).
+    pub fn date(&self) -> &str {
+        match self {
+            Self::Grade(g) => &g.add_date,
+            Self::Homework(h) => &h.add_date,
+            Self::Notice(n) => &n.creation_date,
+            Self::Message(m) => &m.send_date,
+        }
+    }
+
+    /// Unix seconds of [`date()`](Self::date), or `None` if it cannot be
+    /// parsed.
+    pub fn timestamp(&self) -> Option<i64> {
+        dates::parse_warsaw_timestamp(self.date())
+    }
+
+    /// Identifies the item across all kinds.
+    pub fn id_key(&self) -> (TimelineKind, String) {
+        let id = match self {
+            Self::Grade(g) => g.id.to_string(),
+            Self::Homework(h) => h.id.to_string(),
+            Self::Notice(n) => n.id.clone(),
+            Self::Message(m) => m.message_id.clone(),
+        };
+        (self.kind(), id)
+    }
+
+    fn sort_key(&self) -> (bool, std::cmp::Reverse<i64>, TimelineKind, String) {
+        let timestamp = self.timestamp();
+        let (kind, id) = self.id_key();
+        (
+            timestamp.is_none(),
+            std::cmp::Reverse(timestamp.unwrap_or_default()),
+            kind,
+            id,
+        )
+    }
+}
+
+impl PartialEq for TimelineItem<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.sort_key() == other.sort_key()
+    }
+}
+
+impl Eq for TimelineItem<'_> {}
+
+impl PartialOrd for TimelineItem<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TimelineItem<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl Hash for TimelineItem<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sort_key().hash(state);
+    }
+}
+
+impl<'a> From<&'a Grade> for TimelineItem<'a> {
+    fn from(grade: &'a Grade) -> Self {
+        Self::Grade(grade)
+    }
+}
+
+impl<'a> From<&'a Homework> for TimelineItem<'a> {
+    fn from(homework: &'a Homework) -> Self {
+        Self::Homework(homework)
+    }
+}
+
+impl<'a> From<&'a SchoolNotice> for TimelineItem<'a> {
+    fn from(notice: &'a SchoolNotice) -> Self {
+        Self::Notice(notice)
+    }
+}
+
+impl<'a> From<&'a InboxMessage> for TimelineItem<'a> {
+    fn from(message: &'a InboxMessage) -> Self {
+        Self::Message(message)
+    }
+}
+
+/// Collects items into a sorted timeline, newest first.
+///
+/// Duplicates (same kind and ID reported twice) are kept once.
+pub fn merge<'a, I>(items: I) -> Vec<TimelineItem<'a>>
+where
+    I: IntoIterator<Item = TimelineItem<'a>>,
+{
+    let mut merged: Vec<_> = items.into_iter().collect();
+    merged.sort();
+    merged.dedup_by(|a, b| a.id_key() == b.id_key());
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::announcements::ResponseSchoolNotices;
+    use crate::structs::grades::ResponseGrades;
+    use crate::structs::messages::ResponseInboxMessages;
+
+    #[test]
+    fn test_merge_across_modules() {
+        let grades: ResponseGrades =
+            serde_json::from_str(include_str!("../tests/fixtures/grades.json")).unwrap();
+        let notices: ResponseSchoolNotices =
+            serde_json::from_str(include_str!("../tests/fixtures/school_notices.json")).unwrap();
+        let messages: ResponseInboxMessages =
+            serde_json::from_str(include_str!("../tests/fixtures/inbox_messages.json")).unwrap();
+
+        let items = merge(
+            grades
+                .grades
+                .iter()
+                .map(TimelineItem::from)
+                .chain(messages.data.iter().map(TimelineItem::from))
+                .chain(notices.school_notices.iter().map(TimelineItem::from))
+                // Fetched twice, e.g. from overlapping pages.
+                .chain(messages.data.iter().map(TimelineItem::from)),
+        );
+        let keys: Vec<_> = items.iter().map(TimelineItem::id_key).collect();
+        use TimelineKind::*;
+        assert_eq!(
+            keys,
+            [
+                (Grade, "1002".to_string()),
+                // Same timestamp: grades before messages.
+                (Grade, "1001".to_string()),
+                (Message, "7002".to_string()),
+                (Notice, "a1b2c3".to_string()),
+                (Notice, "1024".to_string()),
+                // "wczoraj" is not a date, so it goes last.
+                (Message, "7001".to_string()),
+            ]
+        );
+        assert_eq!(items.last().unwrap().timestamp(), None);
+    }
+
+    #[test]
+    fn test_ord_and_hash_agree() {
+        use std::collections::HashSet;
+
+        let messages: ResponseInboxMessages =
+            serde_json::from_str(include_str!("../tests/fixtures/inbox_messages.json")).unwrap();
+        let a = TimelineItem::from(&messages.data[0]);
+        let b = TimelineItem::from(&messages.data[1]);
+        assert!(a < b);
+        assert_eq!(a, TimelineItem::Message(&messages.data[0]));
+        let set: HashSet<_> = [a, b, a].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
+}
@@ -0,0 +1,1201 @@
+//! Change detection across consecutive fetches.
+//!
+//! A [`ChangeTracker`] remembers what was seen in the previous fetch and
+//! reports what was added, edited or removed since: grades, school notices
+//! and homework. Its state can be saved
+//! with [`ChangeTracker::to_json()`] and restored with
+//! [`ChangeTracker::from_json()`] between runs. Trackers of notifiers that
+//! poll the same account on several machines can be combined with
+//! [`ChangeTracker::merge()`].
+//!
+//! IDs restart every school year, so the state is kept per [`SchoolYear`]
+//! and kind of item, see [`ChangeTracker::set_school_year()`].
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use librus_rs::{ChangeTracker, Client, GradeChange};
+//!
+//! # async fn example() -> Result<(), librus_rs::Error> {
+//! let client = Client::from_env().await?;
+//! let mut tracker = match std::fs::read_to_string("tracker.json") {
+//!     Ok(json) => ChangeTracker::from_json(&json)?,
+//!     Err(_) => ChangeTracker::new(),
+//! };
+//!
+//! for change in tracker.update_grades(&client.grades().await?.grades) {
+//!     match change {
+//!         GradeChange::Added(g) => println!("New grade: {}", g.value),
+//!         GradeChange::Improved { original, improved } => {
+//!             println!("Grade improved: {} -> {}", original.value, improved.value)
+//!         }
+//!         GradeChange::Modified { before, after } => {
+//!             println!("Grade changed: {} -> {}", before.value, after.value)
+//!         }
+//!         GradeChange::Removed(id) => println!("Grade {} was deleted", id),
+//!     }
+//! }
+//! std::fs::write("tracker.json", tracker.to_json()).unwrap();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::anonymize::fnv1a;
+use crate::structs::announcements::SchoolNotice;
+use crate::structs::events::Homework;
+use crate::structs::grades::Grade;
+use crate::structs::semester::SchoolYear;
+use crate::types::ApiDate;
+use crate::{Error, Result};
+
+/// Current version of the [`ChangeTracker::to_json()`] format.
+///
+/// - Version 1 stores the mutable fields of each grade and their hash.
+/// - Version 2 groups the grades by school year.
+/// - Version 3 adds the revision and when each grade was last seen.
+/// - Version 4 adds school notices and homework.
+///
+/// Snapshots of later versions are read as version 4, keeping the fields
+/// this version does not know.
+const SNAPSHOT_VERSION: u32 = 4;
+
+/// The fields of a grade that teachers can change after adding it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GradeSnapshot {
+    /// Grade ID.
+    pub id: i64,
+    /// Subject ID, if known.
+    pub subject_id: Option<i64>,
+    /// Grade value, e.g. `"4+"`.
+    pub value: String,
+    /// Grade category ID, if known.
+    pub category_id: Option<i64>,
+    /// Date of the grade (`YYYY-MM-DD`).
+    pub date: ApiDate,
+    /// IDs of the comments attached to the grade.
+    pub comment_ids: Vec<i64>,
+}
+
+impl GradeSnapshot {
+    /// Stable hash of the mutable fields.
+    fn hash(&self) -> u64 {
+        let key = format!(
+            "{}\u{1f}{}\u{1f}{}\u{1f}{:?}",
+            self.value,
+            self.category_id.unwrap_or_default(),
+            self.date.as_str(),
+            self.comment_ids
+        );
+        fnv1a(key.as_bytes())
+    }
+}
+
+impl From<&Grade> for GradeSnapshot {
+    fn from(grade: &Grade) -> Self {
+        let mut comment_ids: Vec<i64> = grade
+            .comments
+            .iter()
+            .flatten()
+            .filter_map(|c| c.id())
+            .collect();
+        comment_ids.sort_unstable();
+        Self {
+            id: grade.id,
+            subject_id: grade.subject.id(),
+            value: grade.grade.clone(),
+            category_id: grade.category.id(),
+            date: grade.date.clone(),
+            comment_ids,
+        }
+    }
+}
+
+/// A change to a grade between two fetches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GradeChange {
+    /// A grade that was not seen before.
+    Added(GradeSnapshot),
+    /// A new grade that improves or resits an earlier one.
+    ///
+    /// Reported instead of [`GradeChange::Added`] for the new grade, and for
+    /// the original too when both appear in the same fetch.
+    Improved {
+        /// The first grade of the chain, e.g. the one that was failed.
+        original: GradeSnapshot,
+        /// The newest grade of the chain.
+        improved: GradeSnapshot,
+    },
+    /// A grade whose value, category, date or comments changed.
+    Modified {
+        /// The grade as previously seen.
+        before: GradeSnapshot,
+        /// The grade as currently returned.
+        after: GradeSnapshot,
+    },
+    /// A grade that is no longer returned, identified by its ID.
+    Removed(i64),
+}
+
+/// A change to a school notice or homework between two fetches, identified
+/// by its ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemChange {
+    /// An item that was not seen before.
+    Added(String),
+    /// An item whose content or dates changed.
+    Modified(String),
+    /// An item that is no longer returned.
+    Removed(String),
+}
+
+impl ItemChange {
+    /// ID of the changed item.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Added(id) | Self::Modified(id) | Self::Removed(id) => id,
+        }
+    }
+}
+
+/// Kinds of items tracked besides grades. IDs are only unique within a
+/// school year and kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum EntityKind {
+    Notice,
+    Homework,
+}
+
+/// Grades of one fetch linked by their improvement and resit references,
+/// which point from the newer grade back at the one it replaces.
+struct ImprovementChains<'a> {
+    by_id: BTreeMap<i64, &'a Grade>,
+    /// IDs of grades that another grade of the fetch improves.
+    improved: BTreeSet<i64>,
+}
+
+impl<'a> ImprovementChains<'a> {
+    fn new(grades: &'a [Grade]) -> Self {
+        let by_id: BTreeMap<i64, &Grade> = grades.iter().map(|g| (g.id, g)).collect();
+        let improved = grades
+            .iter()
+            .filter_map(replaces)
+            .filter(|id| by_id.contains_key(id))
+            .collect();
+        Self { by_id, improved }
+    }
+
+    /// Whether a later grade of the fetch improves this one.
+    fn is_improved(&self, id: i64) -> bool {
+        self.improved.contains(&id)
+    }
+
+    /// Follows the references of the newest grade of a chain back to the
+    /// first grade. `None` if the grade is improved itself, or does not
+    /// replace a grade of the fetch.
+    fn original_of(&self, id: i64) -> Option<&'a Grade> {
+        if self.is_improved(id) {
+            return None;
+        }
+        let mut seen = BTreeSet::from([id]);
+        let mut original = None;
+        let mut next = self.by_id.get(&id).and_then(|g| replaces(g));
+        while let Some(grade) = next.and_then(|id| self.by_id.get(&id)) {
+            if !seen.insert(grade.id) {
+                break;
+            }
+            original = Some(*grade);
+            next = replaces(grade);
+        }
+        original
+    }
+}
+
+/// ID of the grade that `grade` improves or resits.
+fn replaces(grade: &Grade) -> Option<i64> {
+    grade
+        .improvement
+        .as_ref()
+        .or(grade.resit.as_ref())
+        .and_then(|r| r.id())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TrackedGrade {
+    hash: u64,
+    grade: GradeSnapshot,
+    /// Unix time of the last update that returned the grade, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seen_at: Option<i64>,
+    /// Fields written by a later version of the crate.
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+impl TrackedGrade {
+    /// Orders the copies of a grade from two trackers: the one seen last
+    /// wins, and the rest only makes the choice independent of the order.
+    fn cmp_recency(&self, other: &Self) -> Ordering {
+        (self.seen_at, self.hash)
+            .cmp(&(other.seen_at, other.hash))
+            .then_with(|| {
+                let json = |g: &Self| serde_json::to_string(g).unwrap_or_default();
+                json(self).cmp(&json(other))
+            })
+    }
+}
+
+/// A seen school notice or homework.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TrackedItem {
+    id: String,
+    /// Stable hash of the fields that can be edited.
+    hash: u64,
+    /// Unix time of the last update that returned the item, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seen_at: Option<i64>,
+    /// Fields written by a later version of the crate.
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+impl TrackedItem {
+    fn new(id: String, fields: &[&str]) -> Self {
+        Self {
+            id,
+            hash: fnv1a(fields.join("\u{1f}").as_bytes()),
+            seen_at: None,
+            extra: Map::new(),
+        }
+    }
+
+    fn of_notice(notice: &SchoolNotice) -> Self {
+        Self::new(
+            notice.id.clone(),
+            &[
+                &notice.subject,
+                &notice.content,
+                notice.start_date.as_str(),
+                notice.end_date.as_str(),
+            ],
+        )
+    }
+
+    fn of_homework(homework: &Homework) -> Self {
+        let category = homework.category.id().unwrap_or_default().to_string();
+        let subject = homework
+            .subject
+            .as_ref()
+            .and_then(|s| s.id())
+            .unwrap_or_default()
+            .to_string();
+        Self::new(
+            homework.id.to_string(),
+            &[
+                &homework.content,
+                homework.date.as_str(),
+                &category,
+                &subject,
+            ],
+        )
+    }
+
+    /// See [`TrackedGrade::cmp_recency()`].
+    fn cmp_recency(&self, other: &Self) -> Ordering {
+        (self.seen_at, self.hash)
+            .cmp(&(other.seen_at, other.hash))
+            .then_with(|| {
+                let json = |i: &Self| serde_json::to_string(i).unwrap_or_default();
+                json(self).cmp(&json(other))
+            })
+    }
+}
+
+/// Remembers previously seen data and reports changes.
+#[derive(Debug, Default, Clone)]
+pub struct ChangeTracker {
+    /// School year of the following updates, if set.
+    school_year: Option<SchoolYear>,
+    /// Seen data by school year.
+    years: BTreeMap<SchoolYear, YearState>,
+    /// IDs of undated grades restored from a version 1 snapshot, whose
+    /// school year is not known. They join the year of the next update.
+    unassigned_grades: BTreeSet<i64>,
+    /// Number of updates and merges, see [`ChangeTracker::revision()`].
+    revision: u64,
+    /// Version of the snapshot this tracker was restored from, if later than
+    /// [`SNAPSHOT_VERSION`].
+    future_version: Option<u32>,
+    /// Fields of a later snapshot version.
+    extra: Map<String, Value>,
+}
+
+/// Seen data of one school year.
+#[derive(Debug, Default, Clone)]
+struct YearState {
+    /// Seen grades by ID. `None` for undated grades restored from a version
+    /// 1 snapshot, until the next update records their fields.
+    grades: BTreeMap<i64, Option<TrackedGrade>>,
+    /// Seen notices and homework by kind and ID.
+    items: BTreeMap<EntityKind, BTreeMap<String, TrackedItem>>,
+    /// Fields of a later snapshot version.
+    extra: Map<String, Value>,
+}
+
+/// Versions 2 and later. School years are keyed by their start year as a
+/// string, which `flatten` cannot parse as a number.
+#[derive(Serialize, Deserialize)]
+struct SnapshotV3 {
+    version: u32,
+    #[serde(default)]
+    revision: u64,
+    years: BTreeMap<String, YearSnapshot>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct YearSnapshot {
+    #[serde(default)]
+    grades: Vec<TrackedGrade>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    notices: Vec<TrackedItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    homeworks: Vec<TrackedItem>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotV1 {
+    version: u32,
+    grades: Vec<TrackedGrade>,
+}
+
+#[derive(Deserialize)]
+struct SnapshotVersion {
+    version: u32,
+}
+
+impl ChangeTracker {
+    /// Creates a tracker that has seen nothing yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the school year of the following updates.
+    ///
+    /// The API reuses IDs in each school year, so the tracker compares a
+    /// fetch only with earlier fetches of the same year. When the year
+    /// changes, the grades of the previous year are not reported as removed,
+    /// and grades of the new year are not mistaken for old ones with the same
+    /// ID. Without a year set, it is derived from the latest date of the
+    /// fetched grades, assuming school years start on September 1, and from
+    /// today's date only if no grade has a date.
+    ///
+    /// Only the previous school year is kept besides the current one.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{ChangeTracker, Client};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let mut tracker = ChangeTracker::new();
+    /// if let Some(year) = client.school_year().await? {
+    ///     tracker.set_school_year(year);
+    /// }
+    /// let changes = tracker.update_grades(&client.grades().await?.grades);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_school_year(&mut self, year: SchoolYear) {
+        self.school_year = Some(year);
+    }
+
+    /// Compares a fresh list of grades with the previous one of the same
+    /// school year and remembers the new state.
+    ///
+    /// On the first call of a school year every grade is reported as
+    /// [`GradeChange::Added`]. A new grade that improves or resits another is
+    /// reported once as [`GradeChange::Improved`], and a grade that another
+    /// grade in the list improves is never reported as added. Changes are
+    /// ordered by grade ID, with removals last.
+    ///
+    /// Undated grades from a version 1 snapshot that are missing from the
+    /// list are forgotten without being reported as removed, since they may
+    /// belong to another school year.
+    pub fn update_grades(&mut self, grades: &[Grade]) -> Vec<GradeChange> {
+        let year = self.year_of(grades.iter().map(|g| g.date.as_str()));
+        let YearState {
+            grades: mut seen,
+            items,
+            extra: year_extra,
+        } = self.years.remove(&year).unwrap_or_default();
+        for id in std::mem::take(&mut self.unassigned_grades) {
+            seen.entry(id).or_insert(None);
+        }
+        let mut changes = Vec::new();
+        let mut current = BTreeMap::new();
+        let chains = ImprovementChains::new(grades);
+        let now = crate::unix_seconds(SystemTime::now());
+
+        for grade in grades {
+            let grade = GradeSnapshot::from(grade);
+            let hash = grade.hash();
+            let mut extra = Map::new();
+            match seen.remove(&grade.id) {
+                // Covered by the improvement that replaces it.
+                None if chains.is_improved(grade.id) => {}
+                None => match chains.original_of(grade.id) {
+                    Some(original) => changes.push(GradeChange::Improved {
+                        original: GradeSnapshot::from(original),
+                        improved: grade.clone(),
+                    }),
+                    None => changes.push(GradeChange::Added(grade.clone())),
+                },
+                Some(Some(before)) => {
+                    if before.hash != hash {
+                        changes.push(GradeChange::Modified {
+                            before: before.grade,
+                            after: grade.clone(),
+                        });
+                    }
+                    extra = before.extra;
+                }
+                // Restored from a snapshot without fields.
+                Some(None) => {}
+            }
+            let tracked = TrackedGrade {
+                hash,
+                grade,
+                seen_at: Some(now),
+                extra,
+            };
+            current.insert(tracked.grade.id, Some(tracked));
+        }
+
+        changes.sort_by_key(|c| match c {
+            GradeChange::Added(g) => g.id,
+            GradeChange::Improved { improved, .. } => improved.id,
+            GradeChange::Modified { after, .. } => after.id,
+            GradeChange::Removed(id) => *id,
+        });
+        changes.extend(
+            seen.iter()
+                .filter(|(_, grade)| grade.is_some())
+                .map(|(&id, _)| GradeChange::Removed(id)),
+        );
+        self.years.retain(|&y, _| y >= year.previous());
+        self.years.insert(
+            year,
+            YearState {
+                grades: current,
+                items,
+                extra: year_extra,
+            },
+        );
+        self.revision += 1;
+        changes
+    }
+
+    /// Compares a fresh list of school notices with the previous one of the
+    /// same school year and remembers the new state.
+    ///
+    /// Notices are tracked like grades, see
+    /// [`ChangeTracker::update_grades()`]. Without a school year set, it is
+    /// derived from the latest start date of the notices. A notice whose
+    /// subject, content or dates changed is reported as
+    /// [`ItemChange::Modified`]. Changes are ordered by ID, with removals
+    /// last.
+    pub fn update_notices(&mut self, notices: &[SchoolNotice]) -> Vec<ItemChange> {
+        let year = self.year_of(notices.iter().map(|n| n.start_date.as_str()));
+        let items = notices.iter().map(TrackedItem::of_notice).collect();
+        self.update_items(year, EntityKind::Notice, items)
+    }
+
+    /// Compares a fresh list of homework with the previous one of the same
+    /// school year and remembers the new state.
+    ///
+    /// Homework is tracked like notices, see
+    /// [`ChangeTracker::update_notices()`], with the school year derived
+    /// from the latest due date.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use librus_rs::{ChangeTracker, Client, ItemChange};
+    ///
+    /// # async fn example() -> Result<(), librus_rs::Error> {
+    /// let client = Client::from_env().await?;
+    /// let mut tracker = ChangeTracker::new();
+    /// for change in tracker.update_homeworks(&client.homeworks().await?.homeworks) {
+    ///     if let ItemChange::Added(id) = change {
+    ///         println!("New homework {id}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update_homeworks(&mut self, homeworks: &[Homework]) -> Vec<ItemChange> {
+        let year = self.year_of(homeworks.iter().map(|h| h.date.as_str()));
+        let items = homeworks.iter().map(TrackedItem::of_homework).collect();
+        self.update_items(year, EntityKind::Homework, items)
+    }
+
+    /// The school year set with [`ChangeTracker::set_school_year()`], or
+    /// else the one of the latest of `dates`, or else the current one.
+    fn year_of<'a>(&self, dates: impl Iterator<Item = &'a str>) -> SchoolYear {
+        self.school_year.unwrap_or_else(|| {
+            dates
+                .filter_map(SchoolYear::containing)
+                .max()
+                .unwrap_or_else(SchoolYear::current)
+        })
+    }
+
+    fn update_items(
+        &mut self,
+        year: SchoolYear,
+        kind: EntityKind,
+        items: Vec<TrackedItem>,
+    ) -> Vec<ItemChange> {
+        let mut state = self.years.remove(&year).unwrap_or_default();
+        let mut seen = state.items.remove(&kind).unwrap_or_default();
+        let mut changes = Vec::new();
+        let mut current = BTreeMap::new();
+        let now = crate::unix_seconds(SystemTime::now());
+
+        for mut item in items {
+            match seen.remove(&item.id) {
+                None => changes.push(ItemChange::Added(item.id.clone())),
+                Some(before) => {
+                    if before.hash != item.hash {
+                        changes.push(ItemChange::Modified(item.id.clone()));
+                    }
+                    item.extra = before.extra;
+                }
+            }
+            item.seen_at = Some(now);
+            current.insert(item.id.clone(), item);
+        }
+
+        changes.sort_by(|a, b| a.id().cmp(b.id()));
+        changes.extend(seen.into_keys().map(ItemChange::Removed));
+        state.items.insert(kind, current);
+        self.years.retain(|&y, _| y >= year.previous());
+        self.years.insert(year, state);
+        self.revision += 1;
+        changes
+    }
+
+    /// Adds what `other` has seen, e.g. the tracker of a second machine
+    /// polling the same account.
+    ///
+    /// The result is the union of the grades, notices and homework seen by
+    /// both. For an item seen by both, the copy from the later update is
+    /// kept. The revision becomes one more than the higher of the two.
+    /// Merging is commutative: `a.merge(&b)` and `b.merge(&a)` give the same
+    /// state.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use librus_rs::ChangeTracker;
+    ///
+    /// # fn example(local: &str, remote: &str) -> Result<(), librus_rs::Error> {
+    /// let mut tracker = ChangeTracker::from_json(local)?;
+    /// tracker.merge(&ChangeTracker::from_json(remote)?);
+    /// std::fs::write("tracker.json", tracker.to_json()).unwrap();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn merge(&mut self, other: &ChangeTracker) {
+        for (&year, theirs) in &other.years {
+            let ours = self.years.entry(year).or_default();
+            for (&id, grade) in &theirs.grades {
+                let entry = ours.grades.entry(id).or_insert(None);
+                match (entry.as_ref(), grade) {
+                    (_, None) => {}
+                    (None, Some(_)) => *entry = grade.clone(),
+                    (Some(mine), Some(their)) => {
+                        if their.cmp_recency(mine) == Ordering::Greater {
+                            *entry = grade.clone();
+                        }
+                    }
+                }
+            }
+            for (&kind, items) in &theirs.items {
+                let mine = ours.items.entry(kind).or_default();
+                for (id, item) in items {
+                    match mine.get(id) {
+                        Some(m) if item.cmp_recency(m) != Ordering::Greater => {}
+                        _ => {
+                            mine.insert(id.clone(), item.clone());
+                        }
+                    }
+                }
+            }
+            merge_extra(&mut ours.extra, &theirs.extra);
+        }
+        self.unassigned_grades
+            .extend(other.unassigned_grades.iter().copied());
+        merge_extra(&mut self.extra, &other.extra);
+        self.future_version = self.future_version.max(other.future_version);
+        self.revision = self.revision.max(other.revision) + 1;
+    }
+
+    /// Number of updates and merges that led to this state.
+    ///
+    /// Restored by [`ChangeTracker::from_json()`] and only ever increases,
+    /// so of two snapshots of the same tracker the one with the higher
+    /// revision is newer.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Serializes the tracker state to JSON.
+    ///
+    /// The school year set with [`ChangeTracker::set_school_year()`] is not
+    /// part of it. Fields of a later snapshot version that the tracker was
+    /// restored from are written back unchanged, under that version.
+    pub fn to_json(&self) -> String {
+        let snapshot = SnapshotV3 {
+            version: self.future_version.unwrap_or(SNAPSHOT_VERSION),
+            revision: self.revision,
+            years: self
+                .years
+                .iter()
+                .map(|(year, state)| {
+                    let items = |kind| {
+                        state
+                            .items
+                            .get(&kind)
+                            .map(|items| items.values().cloned().collect())
+                            .unwrap_or_default()
+                    };
+                    let snapshot = YearSnapshot {
+                        grades: state.grades.values().flatten().cloned().collect(),
+                        notices: items(EntityKind::Notice),
+                        homeworks: items(EntityKind::Homework),
+                        extra: state.extra.clone(),
+                    };
+                    (year.0.to_string(), snapshot)
+                })
+                .collect(),
+            extra: self.extra.clone(),
+        };
+        serde_json::to_string(&snapshot).expect("tracker state is always serializable")
+    }
+
+    /// Serializes the tracker state for storage.
+    ///
+    /// The bytes are the UTF-8 JSON of [`ChangeTracker::to_json()`]: object
+    /// keys and items are sorted, so equal states give equal bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_json().into_bytes()
+    }
+
+    /// Restores a tracker from [`ChangeTracker::to_bytes()`] output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Parse`] if the bytes are not UTF-8 or not a valid
+    /// snapshot, see [`ChangeTracker::from_json()`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let json = std::str::from_utf8(bytes).map_err(|e| Error::Parse {
+            source: serde::de::Error::custom(e),
+            body: String::from_utf8_lossy(bytes).into_owned().into(),
+        })?;
+        Self::from_json(json)
+    }
+
+    /// Restores a tracker from [`ChangeTracker::to_json()`] output.
+    ///
+    /// Snapshots written by older versions are upgraded: grades from a
+    /// version 1 snapshot are assigned to the school year of their date.
+    /// Undated ones count as grades of the school year of the next update,
+    /// which records their fields without reporting a modification.
+    ///
+    /// Snapshots written by later versions are read as far as this version
+    /// understands them. Unknown fields are kept and written back by
+    /// [`ChangeTracker::to_json()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Parse`] if the JSON is malformed or has version 0.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let parse_err = |source| Error::Parse {
+            source,
+            body: json.into(),
+        };
+        let version: SnapshotVersion = serde_json::from_str(json).map_err(parse_err)?;
+        let mut tracker = Self::new();
+        match version.version {
+            0 => {
+                return Err(parse_err(serde::de::Error::custom(
+                    "unsupported tracker snapshot version 0",
+                )))
+            }
+            1 => {
+                let v1: SnapshotV1 = serde_json::from_str(json).map_err(parse_err)?;
+                for grade in v1.grades {
+                    match SchoolYear::containing(grade.grade.date.as_str()) {
+                        Some(year) => tracker.insert_grade(year, grade),
+                        None => {
+                            tracker.unassigned_grades.insert(grade.grade.id);
+                        }
+                    }
+                }
+            }
+            version => {
+                let v3: SnapshotV3 = serde_json::from_str(json).map_err(parse_err)?;
+                for (year, snapshot) in v3.years {
+                    let year = year.parse().map(SchoolYear).map_err(|_| {
+                        parse_err(serde::de::Error::custom(format!(
+                            "invalid school year `{year}`"
+                        )))
+                    })?;
+                    let state = tracker.years.entry(year).or_default();
+                    state.extra = snapshot.extra;
+                    for (kind, items) in [
+                        (EntityKind::Notice, snapshot.notices),
+                        (EntityKind::Homework, snapshot.homeworks),
+                    ] {
+                        let items = items.into_iter().map(|i| (i.id.clone(), i)).collect();
+                        state.items.insert(kind, items);
+                    }
+                    for grade in snapshot.grades {
+                        tracker.insert_grade(year, grade);
+                    }
+                }
+                tracker.revision = v3.revision;
+                tracker.extra = v3.extra;
+                tracker.future_version = (version > SNAPSHOT_VERSION).then_some(version);
+            }
+        }
+        Ok(tracker)
+    }
+
+    fn insert_grade(&mut self, year: SchoolYear, grade: TrackedGrade) {
+        let grades = &mut self.years.entry(year).or_default().grades;
+        grades.insert(grade.grade.id, Some(grade));
+    }
+}
+
+/// Adds the fields of `theirs` missing from `ours`. For fields in both, the
+/// greater JSON text wins, so the result does not depend on the order.
+fn merge_extra(ours: &mut Map<String, Value>, theirs: &Map<String, Value>) {
+    for (key, value) in theirs {
+        let keep = ours.get(key).is_some_and(|mine| {
+            serde_json::to_string(mine).ok() >= serde_json::to_string(value).ok()
+        });
+        if !keep {
+            ours.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::grades::ResponseGrades;
+
+    /// The fixture, in which 1002 improves 1001.
+    fn fetch_with_improvement() -> Vec<Grade> {
+        let resp: ResponseGrades =
+            serde_json::from_str(include_str!("../tests/fixtures/grades.json")).unwrap();
+        resp.grades
+    }
+
+    /// The fixture with two unrelated grades.
+    fn fetch() -> Vec<Grade> {
+        let mut grades = fetch_with_improvement();
+        grades[1].improvement = None;
+        grades
+    }
+
+    #[test]
+    fn test_add_modify_remove() {
+        let mut tracker = ChangeTracker::new();
+        let first = tracker.update_grades(&fetch());
+        assert_eq!(first.len(), 2);
+        assert!(first.iter().all(|c| matches!(c, GradeChange::Added(_))));
+        assert!(tracker.update_grades(&fetch()).is_empty());
+
+        // 1001 edited from "5" to "4+", 1002 deleted, 1003 added.
+        let mut second = fetch();
+        second[0].grade = "4+".to_string();
+        second[1].id = 1003;
+        let changes = tracker.update_grades(&second);
+        match &changes[..] {
+            [GradeChange::Modified { before, after }, GradeChange::Added(added), GradeChange::Removed(1002)] =>
+            {
+                assert_eq!((before.value.as_str(), after.value.as_str()), ("5", "4+"));
+                assert_eq!(added.id, 1003);
+            }
+            other => panic!("unexpected changes: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_comment_added_is_a_modification() {
+        let mut tracker = ChangeTracker::new();
+        tracker.update_grades(&fetch());
+        let mut second = fetch();
+        second[1].comments = second[0].comments.take();
+        let changes = tracker.update_grades(&second);
+        assert_eq!(changes.len(), 2);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut tracker = ChangeTracker::new();
+        tracker.update_grades(&fetch());
+        let mut restored = ChangeTracker::from_json(&tracker.to_json()).unwrap();
+        let mut edited = fetch();
+        edited[1].grade = "1".to_string();
+        assert_eq!(restored.update_grades(&edited).len(), 1);
+
+        for invalid in [
+            r#"{"grades":[999,1001]}"#,
+            r#"{"version":0,"grades":[]}"#,
+            r#"{"version":3,"years":{"2025/26":{}}}"#,
+        ] {
+            assert!(
+                matches!(ChangeTracker::from_json(invalid), Err(Error::Parse { .. })),
+                "{invalid}"
+            );
+        }
+    }
+
+    /// Trackers of two machines: both saw 1001, only the second saw 1002
+    /// after it was edited.
+    fn diverged() -> (ChangeTracker, ChangeTracker) {
+        let mut first = tracker_2025();
+        let mut grades = fetch();
+        grades.truncate(1);
+        first.update_grades(&grades);
+
+        let mut second = tracker_2025();
+        second.update_grades(&fetch());
+        let mut edited = fetch();
+        edited[1].grade = "1".to_string();
+        second.update_grades(&edited);
+        (first, second)
+    }
+
+    #[test]
+    fn test_merge_is_commutative() {
+        let (first, second) = diverged();
+        let mut a = first.clone();
+        a.merge(&second);
+        let mut b = second.clone();
+        b.merge(&first);
+        assert_eq!(a.to_json(), b.to_json());
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        assert_eq!(
+            (first.revision(), second.revision(), a.revision()),
+            (1, 2, 3)
+        );
+
+        // Neither machine re-notifies after the merge.
+        let mut edited = fetch();
+        edited[1].grade = "1".to_string();
+        a.set_school_year(SchoolYear(2025));
+        assert!(a.update_grades(&edited).is_empty());
+        assert_eq!(a.revision(), 4);
+
+        // Merging again changes nothing but the revision.
+        let json = b.to_json();
+        b.merge(&first);
+        assert_eq!(
+            b.to_json().replace(r#""revision":4"#, r#""revision":3"#),
+            json
+        );
+    }
+
+    #[test]
+    fn test_merge_keeps_the_latest_copy() {
+        let (mut first, second) = diverged();
+        let mut json: Value = serde_json::from_str(&second.to_json()).unwrap();
+        // The second machine saw 1002 long ago, before the first one.
+        json["years"]["2025"]["grades"][1]["seen_at"] = Value::from(1);
+        let stale = ChangeTracker::from_json(&json.to_string()).unwrap();
+        first.update_grades(&fetch());
+        first.merge(&stale);
+        first.set_school_year(SchoolYear(2025));
+        assert!(first.update_grades(&fetch()).is_empty());
+    }
+
+    #[test]
+    fn test_future_snapshot_keeps_unknown_fields() {
+        let mut tracker = tracker_2025();
+        tracker.update_grades(&fetch());
+        let mut json: Value = serde_json::from_str(&tracker.to_json()).unwrap();
+        json["version"] = Value::from(7);
+        json["devices"] = serde_json::json!(["nas", "laptop"]);
+        json["years"]["2025"]["messages"] = serde_json::json!([{"id": "a1"}]);
+        json["years"]["2025"]["grades"][0]["notified"] = Value::from(true);
+        let future = json.to_string();
+
+        let mut restored = ChangeTracker::from_bytes(future.as_bytes()).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(&restored.to_bytes()).unwrap(),
+            json
+        );
+        // Still kept after an update and a merge.
+        restored.set_school_year(SchoolYear(2025));
+        assert!(restored.update_grades(&fetch()).is_empty());
+        restored.merge(&tracker_2025());
+        let written: Value = serde_json::from_str(&restored.to_json()).unwrap();
+        assert_eq!(written["version"], 7);
+        assert_eq!(written["devices"], json["devices"]);
+        assert_eq!(
+            written["years"]["2025"]["messages"],
+            json["years"]["2025"]["messages"]
+        );
+        assert_eq!(written["years"]["2025"]["grades"][0]["notified"], true);
+
+        assert!(ChangeTracker::from_bytes(&[0xff, 0xfe]).is_err());
+    }
+
+    /// A tracker for the school year of the fixture.
+    fn tracker_2025() -> ChangeTracker {
+        let mut tracker = ChangeTracker::new();
+        tracker.set_school_year(SchoolYear(2025));
+        tracker
+    }
+
+    #[test]
+    fn test_new_school_year_reuses_ids() {
+        let mut tracker = tracker_2025();
+        assert_eq!(tracker.update_grades(&fetch()).len(), 2);
+
+        // In September the old grades are gone and IDs start over: 1001 is
+        // a different grade now.
+        let mut september = fetch();
+        september.truncate(1);
+        september[0].grade = "2".to_string();
+        september[0].date = ApiDate::from_raw("2026-09-15");
+        tracker.set_school_year(SchoolYear(2026));
+        match &tracker.update_grades(&september)[..] {
+            [GradeChange::Added(g)] => assert_eq!((g.id, g.value.as_str()), (1001, "2")),
+            other => panic!("unexpected changes: {other:?}"),
+        }
+        assert!(tracker.update_grades(&september).is_empty());
+
+        // Both years survive a round trip.
+        let json = tracker.to_json();
+        assert!(json.contains(r#""years":{"2025":"#), "{json}");
+        let mut restored = ChangeTracker::from_json(&json).unwrap();
+        restored.set_school_year(SchoolYear(2025));
+        assert!(restored.update_grades(&fetch()).is_empty());
+
+        // Older years are dropped.
+        restored.set_school_year(SchoolYear(2027));
+        restored.update_grades(&[]);
+        assert!(!restored.to_json().contains(r#""2025""#));
+    }
+
+    #[test]
+    fn test_v1_snapshot_is_assigned_by_date() {
+        let mut grades: Vec<TrackedGrade> = fetch()
+            .iter()
+            .map(|g| {
+                let grade = GradeSnapshot::from(g);
+                TrackedGrade {
+                    hash: grade.hash(),
+                    grade,
+                    seen_at: None,
+                    extra: Map::new(),
+                }
+            })
+            .collect();
+        grades[1].grade.date = ApiDate::from_raw("");
+        let v1 = serde_json::to_string(&SnapshotV1 { version: 1, grades }).unwrap();
+
+        // 1001 is dated 2025-10-06; 1002 has no date and joins the year of
+        // the update, so neither is new.
+        let mut tracker = ChangeTracker::from_json(&v1).unwrap();
+        tracker.set_school_year(SchoolYear(2025));
+        assert!(tracker.update_grades(&fetch()).is_empty());
+
+        // A tracker loaded in the next school year sees neither 1001 nor
+        // the undated grade as removed.
+        let mut tracker = ChangeTracker::from_json(&v1).unwrap();
+        tracker.set_school_year(SchoolYear(2026));
+        assert!(tracker.update_grades(&[]).is_empty());
+        assert!(tracker.update_grades(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_school_year_from_grade_dates() {
+        // The fixture is dated October 2025, so no year needs to be set.
+        let mut tracker = ChangeTracker::new();
+        assert_eq!(tracker.update_grades(&fetch()).len(), 2);
+        assert!(tracker.to_json().contains(r#""years":{"2025":"#));
+
+        // The first grades of the next year start it afresh.
+        let mut september = fetch();
+        september.truncate(1);
+        september[0].grade = "2".to_string();
+        september[0].date = ApiDate::from_raw("2026-09-15");
+        match &tracker.update_grades(&september)[..] {
+            [GradeChange::Added(g)] => assert_eq!(g.value, "2"),
+            other => panic!("unexpected changes: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_school_year_of_class() {
+        let class: crate::ResponseClass =
+            serde_json::from_str(include_str!("../tests/fixtures/class.json")).unwrap();
+        let mut class = class.class.unwrap();
+        assert_eq!(class.school_year(), Some(SchoolYear(2024)));
+        class.begin_school_year = None;
+        assert_eq!(class.school_year(), Some(SchoolYear(2024)));
+        class.end_school_year = None;
+        assert_eq!(class.school_year(), None);
+    }
+
+    fn notices() -> Vec<SchoolNotice> {
+        let resp: crate::ResponseSchoolNotices =
+            serde_json::from_str(include_str!("../tests/fixtures/school_notices.json")).unwrap();
+        resp.school_notices
+    }
+
+    fn homeworks() -> Vec<Homework> {
+        let resp: crate::ResponseHomeworks =
+            serde_json::from_str(include_str!("../tests/fixtures/homeworks.json")).unwrap();
+        resp.homeworks
+    }
+
+    #[test]
+    fn test_notices_and_homework() {
+        let mut tracker = tracker_2025();
+        let added = tracker.update_notices(&notices());
+        assert_eq!(added.len(), notices().len());
+        assert!(added.iter().all(|c| matches!(c, ItemChange::Added(_))));
+        assert!(tracker.update_notices(&notices()).is_empty());
+
+        // The same ID as a notice is a different homework.
+        let mut homework = homeworks();
+        homework.truncate(1);
+        homework[0].id = notices()[0].id.parse().unwrap_or(1);
+        assert!(matches!(
+            &tracker.update_homeworks(&homework)[..],
+            [ItemChange::Added(_)]
+        ));
+
+        let mut edited = notices();
+        edited[0].content.push_str(" (zmiana)");
+        let removed = edited.pop().unwrap().id;
+        let changes = tracker.update_notices(&edited);
+        assert_eq!(
+            changes.first(),
+            Some(&ItemChange::Modified(edited[0].id.clone()))
+        );
+        assert_eq!(changes.last(), Some(&ItemChange::Removed(removed)));
+
+        // Grades of the same year are kept, and everything survives a round
+        // trip and a merge.
+        tracker.update_grades(&fetch());
+        let mut restored = ChangeTracker::from_json(&tracker.to_json()).unwrap();
+        restored.merge(&ChangeTracker::new());
+        restored.set_school_year(SchoolYear(2025));
+        assert!(restored.update_notices(&edited).is_empty());
+        assert!(restored.update_homeworks(&homework).is_empty());
+        assert!(restored.update_grades(&fetch()).is_empty());
+    }
+
+    #[test]
+    fn test_new_school_year_reuses_notice_and_homework_ids() {
+        let mut tracker = tracker_2025();
+        tracker.update_notices(&notices());
+        tracker.update_homeworks(&homeworks());
+
+        // In September the IDs start over: the old items are not removed,
+        // and the new ones with reused IDs are new.
+        let mut notice = notices();
+        notice.truncate(1);
+        notice[0].subject = "Rozpoczęcie roku szkolnego".to_string();
+        notice[0].start_date = ApiDate::from_raw("2026-09-01");
+        let mut homework = homeworks();
+        homework.truncate(1);
+        homework[0].date = ApiDate::from_raw("2026-09-10");
+        tracker.set_school_year(SchoolYear(2026));
+        assert_eq!(
+            tracker.update_notices(&notice),
+            [ItemChange::Added(notice[0].id.clone())]
+        );
+        assert_eq!(
+            tracker.update_homeworks(&homework),
+            [ItemChange::Added(homework[0].id.to_string())]
+        );
+
+        // Later fetches of the new year, restored from a snapshot, report
+        // nothing, though the IDs were seen in the previous year too.
+        let mut restored = ChangeTracker::from_json(&tracker.to_json()).unwrap();
+        assert!(restored.update_notices(&notice).is_empty());
+        assert!(restored.update_homeworks(&homework).is_empty());
+    }
+
+    #[test]
+    fn test_improvement_in_same_fetch_is_one_change() {
+        let mut tracker = ChangeTracker::new();
+        match &tracker.update_grades(&fetch_with_improvement())[..] {
+            [GradeChange::Improved { original, improved }] => {
+                assert_eq!((original.id, improved.id), (1001, 1002));
+            }
+            other => panic!("unexpected changes: {other:?}"),
+        }
+        assert!(tracker.update_grades(&fetch_with_improvement()).is_empty());
+    }
+
+    #[test]
+    fn test_improvement_in_later_fetch() {
+        let mut tracker = ChangeTracker::new();
+        let mut first = fetch_with_improvement();
+        first.truncate(1);
+        assert!(
+            matches!(&tracker.update_grades(&first)[..], [GradeChange::Added(g)] if g.id == 1001)
+        );
+
+        match &tracker.update_grades(&fetch_with_improvement())[..] {
+            [GradeChange::Improved { original, improved }] => {
+                assert_eq!((original.id, improved.id), (1001, 1002));
+            }
+            other => panic!("unexpected changes: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_improvement_chain_resolves_to_first_grade() {
+        // 1003 resits 1002, which improves 1001.
+        let mut grades = fetch_with_improvement();
+        let mut resit = fetch_with_improvement().remove(1);
+        resit.id = 1003;
+        resit.improvement = None;
+        resit.resit = Some(crate::Reference {
+            id: Some(1002),
+            url: "https://api.librus.pl/2.0/Grades/1002".into(),
+        });
+        grades.push(resit);
+
+        let mut tracker = ChangeTracker::new();
+        match &tracker.update_grades(&grades)[..] {
+            [GradeChange::Improved { original, improved }] => {
+                assert_eq!((original.id, improved.id), (1001, 1003));
+            }
+            other => panic!("unexpected changes: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,622 @@
+//! Typed wrappers for the date and URL strings returned by the API.
+//!
+//! [`ApiDate`] and [`ApiDateTime`] keep the raw string exactly as received,
+//! so odd values such as `"wczoraj"` or an empty read date still
+//! deserialize, and parse it into components only when asked. Both sort
+//! chronologically, with unparseable values after all others, and display
+//! in a normalized `YYYY-MM-DD[ HH:MM:SS]` form.
+//!
+//! With the `chrono` feature they convert to and from
+//! [`chrono::NaiveDate`] and [`chrono::NaiveDateTime`].
+//!
+//! [`ApiUrl`] likewise keeps resource URLs as received and works out which
+//! service they point to only when asked.
+//!
+//! # Example
+//!
+//! ```rust
+//! use librus_rs::types::{ApiDate, ApiDateTime};
+//!
+//! let date = ApiDate::try_from("2024-03-12")?;
+//! assert_eq!(date.components(), Some((2024, 3, 12)));
+//!
+//! let sent = ApiDateTime::try_from("2024-03-12 08:15:33")?;
+//! assert_eq!(sent.components(), Some((2024, 3, 12, 8, 15, 33)));
+//! assert!(ApiDateTime::try_from("2024-03-12T09:00:00")? > sent);
+//! # Ok::<(), librus_rs::Error>(())
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::dates;
+use crate::error::MESSAGES_HOST;
+use crate::redirects::is_librus_domain;
+use crate::{ApiKind, Error};
+
+/// A calendar date as returned by the API, usually `YYYY-MM-DD`.
+#[derive(Clone, Default)]
+pub struct ApiDate(String);
+
+/// A local (Polish) date and time as returned by the API, usually
+/// `YYYY-MM-DD HH:MM:SS`. A bare `YYYY-MM-DD` is read as midnight.
+#[derive(Clone, Default)]
+pub struct ApiDateTime(String);
+
+impl ApiDate {
+    /// Year, month and day, or `None` if the value is not a valid date.
+    ///
+    /// A time following the date is ignored.
+    pub fn components(&self) -> Option<(i32, u32, u32)> {
+        let value = self.0.trim();
+        let date = value.split_once([' ', 'T']).map_or(value, |(date, _)| date);
+        parse_date(date)
+    }
+}
+
+impl ApiDateTime {
+    /// Year, month, day, hour, minute and second, or `None` if the value is
+    /// not a valid date-time.
+    pub fn components(&self) -> Option<(i32, u32, u32, u32, u32, u32)> {
+        let value = self.0.trim();
+        let (date, time) = value.split_once([' ', 'T']).unwrap_or((value, "00:00:00"));
+        let (y, mo, d) = parse_date(date)?;
+        let (h, mi, s) = parse_time(time)?;
+        Some((y, mo, d, h, mi, s))
+    }
+
+    /// The date part.
+    pub fn date(&self) -> ApiDate {
+        let value = self.0.trim();
+        ApiDate(
+            value
+                .split_once([' ', 'T'])
+                .map_or(value, |(d, _)| d)
+                .to_string(),
+        )
+    }
+
+    /// Unix seconds, reading the value as Polish local time.
+    pub fn unix_timestamp(&self) -> Option<i64> {
+        let (y, mo, d, h, mi, s) = self.components()?;
+        dates::parse_warsaw_datetime(
+            &format!("{y:04}-{mo:02}-{d:02}"),
+            &format!("{h:02}:{mi:02}:{s:02}"),
+        )
+    }
+}
+
+/// Parses `YYYY-MM-DD`, checking the day against the month length.
+fn parse_date(date: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = date.splitn(3, '-');
+    let year = parts.next()?;
+    let month = parts.next()?;
+    let day = parts.next()?;
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return None;
+    }
+    dates::parse_day(date)?;
+    Some((year.parse().ok()?, month.parse().ok()?, day.parse().ok()?))
+}
+
+/// Parses `HH:MM` or `HH:MM:SS`.
+fn parse_time(time: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = time.splitn(3, ':');
+    let h: u32 = parts.next()?.parse().ok()?;
+    let m: u32 = parts.next()?.parse().ok()?;
+    let s: u32 = parts.next().map_or(Some(0), |s| s.parse().ok())?;
+    (h < 24 && m < 60 && s < 61).then_some((h, m, s))
+}
+
+macro_rules! impl_api_date {
+    ($ty:ident) => {
+        impl $ty {
+            /// The value exactly as returned by the API.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Returns the raw string.
+            pub fn into_string(self) -> String {
+                self.0
+            }
+
+            /// Wraps a raw API value without validating it, like deserializing does.
+            pub(crate) fn from_raw(raw: impl Into<String>) -> Self {
+                Self(raw.into())
+            }
+
+            fn sort_key(&self) -> (bool, impl Ord + '_, &str) {
+                let components = self.components();
+                (components.is_none(), components, self.0.as_str())
+            }
+        }
+
+        impl TryFrom<&str> for $ty {
+            type Error = Error;
+
+            /// Accepts values whose [`components()`](Self::components) parse.
+            fn try_from(value: &str) -> Result<Self, Error> {
+                let parsed = Self(value.to_string());
+                match parsed.components() {
+                    Some(_) => Ok(parsed),
+                    None => Err(Error::InvalidDate(value.to_string())),
+                }
+            }
+        }
+
+        impl TryFrom<String> for $ty {
+            type Error = Error;
+
+            fn try_from(value: String) -> Result<Self, Error> {
+                Self::try_from(value.as_str())
+            }
+        }
+
+        impl From<$ty> for String {
+            fn from(value: $ty) -> Self {
+                value.0
+            }
+        }
+
+        impl Deref for $ty {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $ty {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Debug for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        impl PartialEq for $ty {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl Eq for $ty {}
+
+        impl Hash for $ty {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.hash(state);
+            }
+        }
+
+        impl PartialEq<str> for $ty {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $ty {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialOrd for $ty {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $ty {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.sort_key().cmp(&other.sort_key())
+            }
+        }
+
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            /// A `null` value is read as an empty string.
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                Option::<String>::deserialize(deserializer).map(|s| Self(s.unwrap_or_default()))
+            }
+        }
+    };
+}
+
+impl_api_date!(ApiDate);
+impl_api_date!(ApiDateTime);
+
+impl fmt::Display for ApiDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.components() {
+            Some((y, m, d)) => write!(f, "{y:04}-{m:02}-{d:02}"),
+            None => f.write_str(&self.0),
+        }
+    }
+}
+
+impl fmt::Display for ApiDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.components() {
+            Some((y, mo, d, h, mi, s)) => {
+                write!(f, "{y:04}-{mo:02}-{d:02} {h:02}:{mi:02}:{s:02}")
+            }
+            None => f.write_str(&self.0),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ApiDate {
+    /// The date as a [`chrono::NaiveDate`], if it parses.
+    pub fn to_naive_date(&self) -> Option<chrono::NaiveDate> {
+        let (y, m, d) = self.components()?;
+        chrono::NaiveDate::from_ymd_opt(y, m, d)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ApiDateTime {
+    /// The local date-time as a [`chrono::NaiveDateTime`], if it parses.
+    pub fn to_naive_date_time(&self) -> Option<chrono::NaiveDateTime> {
+        let (y, mo, d, h, mi, s) = self.components()?;
+        chrono::NaiveDate::from_ymd_opt(y, mo, d)?.and_hms_opt(h, mi, s)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for ApiDate {
+    fn from(date: chrono::NaiveDate) -> Self {
+        Self(date.format("%Y-%m-%d").to_string())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDateTime> for ApiDateTime {
+    fn from(date_time: chrono::NaiveDateTime) -> Self {
+        Self(date_time.format("%Y-%m-%d %H:%M:%S").to_string())
+    }
+}
+
+/// A resource URL as returned by the API, e.g. in a
+/// [`Reference`](crate::Reference).
+///
+/// Usually `https://api.librus.pl/2.0/...`, but some instances send relative
+/// paths or links to other Librus services. The value is kept as received;
+/// [`kind()`](Self::kind) and [`path_relative_to_base()`](Self::path_relative_to_base)
+/// parse it when called.
+///
+/// # Example
+///
+/// ```rust
+/// use librus_rs::types::ApiUrl;
+/// use librus_rs::ApiKind;
+///
+/// let url = ApiUrl::from("https://api.librus.pl/2.0/Grades/1001");
+/// assert_eq!(url.kind(), ApiKind::Synergia);
+/// assert_eq!(url.path_relative_to_base(), Some("Grades/1001"));
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct ApiUrl(String);
+
+/// Version prefix of the API the gateway serves.
+const API_VERSION: &str = "2.0";
+
+impl ApiUrl {
+    /// The value exactly as returned by the API.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the raw string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// The service the URL points to.
+    ///
+    /// Relative paths are read as Synergia API paths, unless they start
+    /// with another API version.
+    pub fn kind(&self) -> ApiKind {
+        match self.split() {
+            (Some(host), _) if host.eq_ignore_ascii_case(MESSAGES_HOST) => ApiKind::Messages,
+            (Some(host), _) if !is_librus_domain(host) => ApiKind::External,
+            (_, path) if api_path(path, self.is_relative()).is_some() => ApiKind::Synergia,
+            _ => ApiKind::Legacy,
+        }
+    }
+
+    /// The part after the Synergia API base, e.g. `Grades/1001` for
+    /// `https://api.librus.pl/2.0/Grades/1001`, with the query kept.
+    ///
+    /// Returns `None` unless [`kind()`](Self::kind) is [`ApiKind::Synergia`].
+    pub fn path_relative_to_base(&self) -> Option<&str> {
+        if self.kind() != ApiKind::Synergia {
+            return None;
+        }
+        let (_, path) = self.split();
+        api_path(path, self.is_relative())
+    }
+
+    fn is_relative(&self) -> bool {
+        self.split().0.is_none()
+    }
+
+    /// Splits into the host, if any, and the path with the query, without
+    /// the fragment.
+    fn split(&self) -> (Option<&str>, &str) {
+        let value = self.0.trim();
+        let value = value.split('#').next().unwrap_or_default();
+        let rest = match value.split_once("://") {
+            Some((_, rest)) => rest,
+            None => match value.strip_prefix("//") {
+                Some(rest) => rest,
+                None => return (None, value),
+            },
+        };
+        let (authority, path) = rest.split_at(rest.find(['/', '?']).unwrap_or(rest.len()));
+        let host = authority.rsplit('@').next().unwrap_or_default();
+        let host = host.split(':').next().unwrap_or_default();
+        (Some(host), path)
+    }
+}
+
+/// The path below the API base, for a path on a Librus host.
+///
+/// Absolute URLs must carry the `2.0` version prefix, optionally below
+/// `/gateway/api/`. Relative paths may leave it out.
+fn api_path(path: &str, relative: bool) -> Option<&str> {
+    let path = path.trim_start_matches('/');
+    let path = path.strip_prefix("gateway/api/").unwrap_or(path);
+    let first = path.split(['/', '?']).next().unwrap_or_default();
+    if first == API_VERSION {
+        return Some(path[first.len()..].trim_start_matches('/'));
+    }
+    let versioned = !first.is_empty() && first.chars().all(|c| c.is_ascii_digit() || c == '.');
+    (relative && !versioned).then_some(path)
+}
+
+impl From<String> for ApiUrl {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for ApiUrl {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl From<ApiUrl> for String {
+    fn from(value: ApiUrl) -> Self {
+        value.0
+    }
+}
+
+impl Deref for ApiUrl {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for ApiUrl {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for ApiUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for ApiUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl PartialEq<str> for ApiUrl {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for ApiUrl {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Serialize for ApiUrl {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ApiUrl {
+    /// A `null` value is read as an empty string.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<String>::deserialize(deserializer).map(|s| Self(s.unwrap_or_default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_observed_formats() {
+        for (raw, expected) in [
+            ("2024-03-12 08:15:33", Some((2024, 3, 12, 8, 15, 33))),
+            ("2024-03-12T08:15:33", Some((2024, 3, 12, 8, 15, 33))),
+            ("2024-03-12 08:15", Some((2024, 3, 12, 8, 15, 0))),
+            ("2024-03-12", Some((2024, 3, 12, 0, 0, 0))),
+            (" 2024-02-29 23:59:59 ", Some((2024, 2, 29, 23, 59, 59))),
+            ("2023-02-29 10:00:00", None),
+            ("2024-03-12 24:00:00", None),
+            ("2024-3-12", None),
+            ("wczoraj", None),
+            ("", None),
+        ] {
+            let value: ApiDateTime = serde_json::from_value(raw.into()).unwrap();
+            assert_eq!(value.components(), expected, "{raw:?}");
+            assert_eq!(value.as_str(), raw);
+            assert_eq!(ApiDateTime::try_from(raw).is_ok(), expected.is_some());
+        }
+
+        for (raw, expected) in [
+            ("2024-03-12", Some((2024, 3, 12))),
+            ("2024-03-12 08:15:33", Some((2024, 3, 12))),
+            ("2024-13-01", None),
+            ("12.03.2024", None),
+        ] {
+            let value: ApiDate = serde_json::from_value(raw.into()).unwrap();
+            assert_eq!(value.components(), expected, "{raw:?}");
+        }
+        assert!(matches!(
+            ApiDate::try_from("jutro"),
+            Err(Error::InvalidDate(v)) if v == "jutro"
+        ));
+    }
+
+    #[test]
+    fn test_order_display_and_serde() {
+        let mut values: Vec<ApiDateTime> = [
+            "wczoraj",
+            "2024-03-12T09:00:00",
+            "2024-03-12 08:15:33",
+            "2024-03-11",
+        ]
+        .into_iter()
+        .map(|raw| serde_json::from_value(raw.into()).unwrap())
+        .collect();
+        values.sort();
+        let raw: Vec<&str> = values.iter().map(ApiDateTime::as_str).collect();
+        assert_eq!(
+            raw,
+            [
+                "2024-03-11",
+                "2024-03-12 08:15:33",
+                "2024-03-12T09:00:00",
+                "wczoraj"
+            ]
+        );
+        assert_eq!(values[2].to_string(), "2024-03-12 09:00:00");
+        assert_eq!(values[3].to_string(), "wczoraj");
+        assert_eq!(values[2].date(), "2024-03-12");
+        assert_eq!(
+            serde_json::to_string(&values[2]).unwrap(),
+            r#""2024-03-12T09:00:00""#
+        );
+        // Same instant, different spelling: ordered but not equal.
+        let a = ApiDateTime::try_from("2024-03-12 09:00:00").unwrap();
+        assert_ne!(a, values[2]);
+        assert_eq!(a.cmp(&values[2]), Ordering::Less);
+    }
+
+    #[test]
+    fn test_api_url_kinds() {
+        for (raw, kind, path) in [
+            (
+                "https://api.librus.pl/2.0/Grades/1001",
+                ApiKind::Synergia,
+                Some("Grades/1001"),
+            ),
+            (
+                r"https://api.librus.pl/2.0/Timetables?weekStart=2025-10-06",
+                ApiKind::Synergia,
+                Some("Timetables?weekStart=2025-10-06"),
+            ),
+            ("https://api.librus.pl/2.0/", ApiKind::Synergia, Some("")),
+            (
+                "https://synergia.librus.pl/gateway/api/2.0/Users/51#top",
+                ApiKind::Synergia,
+                Some("Users/51"),
+            ),
+            ("/2.0/Lessons/11", ApiKind::Synergia, Some("Lessons/11")),
+            ("Subjects/21", ApiKind::Synergia, Some("Subjects/21")),
+            (
+                "https://api.librus.pl/1.0/Grades/1001",
+                ApiKind::Legacy,
+                None,
+            ),
+            ("/3.0/Grades", ApiKind::Legacy, None),
+            (
+                "https://synergia.librus.pl/przegladaj_oceny/uczen",
+                ApiKind::Legacy,
+                None,
+            ),
+            (
+                "https://wiadomosci.librus.pl/api/inbox/messages",
+                ApiKind::Messages,
+                None,
+            ),
+            (
+                "https://librus.pl.example.com/2.0/Grades",
+                ApiKind::External,
+                None,
+            ),
+            ("//cdn.example.com/2.0/logo.png", ApiKind::External, None),
+            (
+                "https://user@API.Librus.pl:443/2.0/Me",
+                ApiKind::Synergia,
+                Some("Me"),
+            ),
+        ] {
+            let url = ApiUrl::from(raw);
+            assert_eq!(url.kind(), kind, "{raw}");
+            assert_eq!(url.path_relative_to_base(), path, "{raw}");
+        }
+    }
+
+    #[test]
+    fn test_api_url_from_fixtures() {
+        let grades: crate::ResponseGrades =
+            serde_json::from_str(include_str!("../tests/fixtures/grades.json")).unwrap();
+        let grade = &grades.grades[0];
+        for reference in [&grade.subject, &grade.added_by, &grade.category] {
+            assert_eq!(reference.url.kind(), ApiKind::Synergia);
+        }
+        assert_eq!(
+            grade.category.url.path_relative_to_base(),
+            Some("Grades/Categories/41")
+        );
+        // Written back as received.
+        let json = serde_json::to_value(&grade.subject).unwrap();
+        assert_eq!(json["Url"], "https://api.librus.pl/2.0/Subjects/21");
+        let null: ApiUrl = serde_json::from_str("null").unwrap();
+        assert_eq!(null, "");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_conversions() {
+        use chrono::NaiveDate;
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 12).unwrap();
+        assert_eq!(ApiDate::from(date), "2024-03-12");
+        assert_eq!(ApiDate::from(date).to_naive_date(), Some(date));
+        let time = date.and_hms_opt(8, 15, 33).unwrap();
+        let value = ApiDateTime::try_from("2024-03-12T08:15:33").unwrap();
+        assert_eq!(value.to_naive_date_time(), Some(time));
+        assert_eq!(ApiDateTime::from(time), "2024-03-12 08:15:33");
+    }
+}
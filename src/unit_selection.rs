@@ -0,0 +1,96 @@
+//! Recognizing the unit-selection page shown to district-wide accounts.
+//!
+//! Accounts linked to several schools ("jednostki") land on a form listing
+//! them after the grant step. Until one is chosen the session is not active.
+
+use crate::content::{attribute, decode_entities, render, Style};
+
+/// A school unit offered on the unit-selection page, see
+/// [`Error::UnitSelectionRequired`](crate::Error::UnitSelectionRequired).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitChoice {
+    /// Unit ID, for [`ClientBuilder::unit()`](crate::ClientBuilder::unit).
+    pub id: String,
+    /// Name of the school as shown on the page.
+    pub name: String,
+}
+
+/// The parsed unit-selection form.
+#[derive(Debug)]
+pub(crate) struct UnitSelectionPage {
+    /// Where the form is posted, if the page says.
+    pub action: Option<String>,
+    /// The units to choose from.
+    pub units: Vec<UnitChoice>,
+}
+
+/// Parses a unit-selection page, or returns `None` for any other page.
+///
+/// The page is a form with a `unit` radio input per school, each inside a
+/// `<label>` holding the school name.
+pub(crate) fn parse(page: &str) -> Option<UnitSelectionPage> {
+    let mut units = Vec::new();
+    let mut rest = page;
+    while let Some(start) = rest.find("<input") {
+        let tag_end = rest[start..].find('>')? + start;
+        let tag = &rest[start..tag_end];
+        rest = &rest[tag_end + 1..];
+        if attribute(tag, "name") != Some("unit") {
+            continue;
+        }
+        let Some(id) = attribute(tag, "value") else {
+            continue;
+        };
+        let label = rest.find("</label>").map_or(rest, |end| &rest[..end]);
+        let name = render(label, Style::Text);
+        units.push(UnitChoice {
+            id: id.to_string(),
+            name: name.split_whitespace().collect::<Vec<_>>().join(" "),
+        });
+    }
+    if units.is_empty() {
+        return None;
+    }
+    let action = page
+        .find("<form")
+        .and_then(|start| {
+            let end = page[start..].find('>')? + start;
+            attribute(&page[start..end], "action")
+        })
+        .map(decode_entities);
+    Some(UnitSelectionPage { action, units })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_unit_selection() {
+        let page = parse(include_str!("../tests/fixtures/unit_selection.html")).unwrap();
+        assert_eq!(
+            page.action.as_deref(),
+            Some("/OAuth/Authorization/Unit?client_id=46")
+        );
+        assert_eq!(
+            page.units,
+            [
+                UnitChoice {
+                    id: "101".to_string(),
+                    name: "Szkoła Podstawowa nr 1 im. M. Kopernika".to_string(),
+                },
+                UnitChoice {
+                    id: "102".to_string(),
+                    name: "Liceum Ogólnokształcące \"Nad Wisłą\"".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_other_pages() {
+        assert!(parse("").is_none());
+        assert!(parse(r#"<form><input type="text" name="login"></form>"#).is_none());
+        assert!(parse(include_str!("../tests/fixtures/attachment_scanning.html")).is_none());
+    }
+}
@@ -0,0 +1,86 @@
+//! URL construction for API requests.
+//!
+//! Bases and endpoint paths come from different places (constants, builder
+//! overrides, URLs reported by the API), so slashes at the junction are not
+//! consistent. Some school proxies normalize paths and answer `404` for
+//! `//` or a trailing `/`, so every request URL goes through [`join()`].
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// Joins a base URL and a relative path with exactly one slash.
+///
+/// A trailing slash on the path (before any query string) is removed.
+pub(crate) fn join(base: &str, path: &str) -> String {
+    let (path, query) = match path.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (path, None),
+    };
+    let base = base.trim_end_matches('/');
+    let path = path.trim_matches('/');
+    let mut url = if path.is_empty() {
+        base.to_string()
+    } else {
+        format!("{base}/{path}")
+    };
+    if let Some(query) = query {
+        url.push('?');
+        url.push_str(query);
+    }
+    url
+}
+
+/// Percent-encodes a single path segment, such as an ID supplied by the
+/// caller. Unreserved characters (RFC 3986) are left as they are.
+pub(crate) fn encode_segment(segment: &str) -> Cow<'_, str> {
+    let unreserved = |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~');
+    if segment.bytes().all(unreserved) {
+        return Cow::Borrowed(segment);
+    }
+    let mut encoded = String::with_capacity(segment.len() * 3);
+    for b in segment.bytes() {
+        if unreserved(b) {
+            encoded.push(b as char);
+        } else {
+            let _ = write!(encoded, "%{b:02X}");
+        }
+    }
+    Cow::Owned(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_matrix() {
+        let expected = "https://h/api/2.0/Attendances";
+        for base in [
+            "https://h/api/2.0",
+            "https://h/api/2.0/",
+            "https://h/api/2.0//",
+        ] {
+            for path in [
+                "Attendances",
+                "/Attendances",
+                "Attendances/",
+                "//Attendances/",
+            ] {
+                assert_eq!(join(base, path), expected, "{base} + {path}");
+            }
+        }
+        assert_eq!(
+            join("https://h/api/", "/inbox/messages/?page=1&limit=10"),
+            "https://h/api/inbox/messages?page=1&limit=10"
+        );
+        assert_eq!(join("https://h/api/", "/"), "https://h/api");
+    }
+
+    #[test]
+    fn test_encode_segment() {
+        assert!(matches!(encode_segment("12345"), Cow::Borrowed("12345")));
+        assert_eq!(encode_segment("a/b c"), "a%2Fb%20c");
+        assert_eq!(encode_segment("ż"), "%C5%BC");
+        assert_eq!(encode_segment("x?y#z"), "x%3Fy%23z");
+    }
+}
@@ -0,0 +1,220 @@
+//! Poll intervals that change over the day.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{dates, unix_seconds};
+
+/// When to poll a source of changes, e.g. substitutions.
+///
+/// Polls every interval given to [`WatchSchedule::every()`], except in the
+/// windows added with [`WatchSchedule::between()`]. Window times are Polish
+/// local time and follow the switch to and from summer time.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::{Duration, SystemTime};
+/// use librus_rs::WatchSchedule;
+///
+/// // Substitutions every 10 minutes before school, hourly otherwise.
+/// let schedule = WatchSchedule::every(Duration::from_secs(3600))
+///     .between((6, 0), (8, 0), Duration::from_secs(600));
+/// let next = schedule.next_poll(SystemTime::now());
+/// assert!(next > SystemTime::now());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchSchedule {
+    interval: Duration,
+    windows: Vec<Window>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Window {
+    /// Seconds since local midnight.
+    start: i64,
+    end: i64,
+    interval: Duration,
+}
+
+impl Window {
+    fn contains(&self, time_of_day: i64) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&time_of_day)
+        } else {
+            time_of_day >= self.start || time_of_day < self.end
+        }
+    }
+}
+
+impl WatchSchedule {
+    /// Polls every `interval`, at least once a second.
+    pub fn every(interval: Duration) -> Self {
+        Self {
+            interval: min_interval(interval),
+            windows: Vec::new(),
+        }
+    }
+
+    /// Polls every `interval` from `start` until `end`, given as
+    /// `(hour, minute)` in Polish local time.
+    ///
+    /// A window ending before it starts runs over midnight. Where windows
+    /// overlap, the one added first applies.
+    pub fn between(mut self, start: (u8, u8), end: (u8, u8), interval: Duration) -> Self {
+        let secs = |(hour, minute): (u8, u8)| {
+            i64::from(hour.min(23)) * 3_600 + i64::from(minute.min(59)) * 60
+        };
+        self.windows.push(Window {
+            start: secs(start),
+            end: secs(end),
+            interval: min_interval(interval),
+        });
+        self
+    }
+
+    /// The interval in effect at `time`.
+    pub fn interval_at(&self, time: SystemTime) -> Duration {
+        let time_of_day = dates::warsaw_time_of_day(unix_seconds(time));
+        self.windows
+            .iter()
+            .find(|window| window.contains(time_of_day))
+            .map_or(self.interval, |window| window.interval)
+    }
+
+    /// When to poll after a poll at `last`.
+    ///
+    /// That is `last` plus the interval in effect then, or the start of a
+    /// window if one starts earlier, so the first poll of a window is on time.
+    pub fn next_poll(&self, last: SystemTime) -> SystemTime {
+        let regular = last + self.interval_at(last);
+        let last = unix_seconds(last);
+        let today = dates::warsaw_day(last);
+        let window_start = (today..=today + 1)
+            .flat_map(|day| {
+                self.windows
+                    .iter()
+                    .map(move |window| dates::warsaw_to_unix(day, window.start))
+            })
+            .filter(|&start| start > last)
+            .min();
+        match window_start {
+            Some(start) if start < unix_seconds(regular) => {
+                UNIX_EPOCH + Duration::from_secs(start.max(0) as u64)
+            }
+            _ => regular,
+        }
+    }
+}
+
+fn min_interval(interval: Duration) -> Duration {
+    interval.max(Duration::from_secs(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+    const HOUR: Duration = Duration::from_secs(3_600);
+
+    fn local(timestamp: &str) -> SystemTime {
+        let unix = dates::parse_warsaw_timestamp(timestamp).unwrap();
+        UNIX_EPOCH + Duration::from_secs(unix as u64)
+    }
+
+    fn format(time: SystemTime) -> String {
+        dates::format_warsaw_datetime(unix_seconds(time))
+    }
+
+    fn morning() -> WatchSchedule {
+        WatchSchedule::every(HOUR).between((6, 0), (8, 0), 10 * MINUTE)
+    }
+
+    #[test]
+    fn test_interval_in_local_time() {
+        let schedule = morning();
+        // The days before and of the switches to and from summer time.
+        for day in ["2025-03-29", "2025-03-30", "2025-10-25", "2025-10-26"] {
+            assert_eq!(
+                schedule.interval_at(local(&format!("{day} 05:59:59"))),
+                HOUR
+            );
+            assert_eq!(
+                schedule.interval_at(local(&format!("{day} 06:00:00"))),
+                10 * MINUTE
+            );
+            assert_eq!(
+                schedule.interval_at(local(&format!("{day} 07:59:00"))),
+                10 * MINUTE
+            );
+            assert_eq!(
+                schedule.interval_at(local(&format!("{day} 08:00:00"))),
+                HOUR
+            );
+        }
+    }
+
+    #[test]
+    fn test_window_starts_on_time_across_dst() {
+        let schedule = morning();
+        for day in ["2025-03-29", "2025-03-30", "2025-10-25", "2025-10-26"] {
+            let next = schedule.next_poll(local(&format!("{day} 05:30:00")));
+            assert_eq!(format(next), format!("{day} 06:00:00"));
+        }
+        // 06:00 is an hour earlier in UTC once summer time starts.
+        let saturday = local("2025-03-29 06:00:00");
+        let sunday = local("2025-03-30 06:00:00");
+        assert_eq!(sunday.duration_since(saturday).unwrap(), 23 * HOUR);
+        // The night of the switch has no 02:00-03:00.
+        let next = schedule.next_poll(local("2025-03-30 01:30:00"));
+        assert_eq!(format(next), "2025-03-30 03:30:00");
+    }
+
+    #[test]
+    fn test_polls_over_a_day() {
+        let schedule = morning();
+        let mut polls = Vec::new();
+        let mut time = local("2025-10-25 22:00:00");
+        while time < local("2025-10-26 22:00:00") {
+            time = schedule.next_poll(time);
+            polls.push(format(time));
+        }
+        let in_window: Vec<&String> = polls
+            .iter()
+            .filter(|p| p.as_str() >= "2025-10-26 06:00:00" && p.as_str() < "2025-10-26 08:00:00")
+            .collect();
+        assert_eq!(in_window.len(), 12);
+        assert_eq!(in_window[0], "2025-10-26 06:00:00");
+        assert_eq!(in_window[11], "2025-10-26 07:50:00");
+        // Hourly again from 08:00.
+        let after = polls
+            .iter()
+            .position(|p| p == "2025-10-26 08:00:00")
+            .unwrap();
+        assert_eq!(polls[after + 1], "2025-10-26 09:00:00");
+        // The clocks go back at 03:00, so 02:00 comes twice: 8 hourly polls
+        // before the window and 15 from 08:00 until 22:00.
+        assert_eq!(polls.iter().filter(|p| p.ends_with("02:00:00")).count(), 2);
+        assert_eq!(polls.len() - in_window.len(), 8 + 15);
+    }
+
+    #[test]
+    fn test_window_over_midnight() {
+        let schedule = WatchSchedule::every(HOUR).between((22, 0), (1, 0), 5 * MINUTE);
+        assert_eq!(
+            schedule.interval_at(local("2025-01-10 23:30:00")),
+            5 * MINUTE
+        );
+        assert_eq!(
+            schedule.interval_at(local("2025-01-11 00:59:00")),
+            5 * MINUTE
+        );
+        assert_eq!(schedule.interval_at(local("2025-01-11 01:00:00")), HOUR);
+        let next = schedule.next_poll(local("2025-01-10 21:30:00"));
+        assert_eq!(format(next), "2025-01-10 22:00:00");
+        assert_eq!(
+            WatchSchedule::every(Duration::ZERO).interval_at(local("2025-01-10 12:00:00")),
+            Duration::from_secs(1)
+        );
+    }
+}
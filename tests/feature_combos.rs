@@ -0,0 +1,45 @@
+//! Checks that the HTTP client can be constructed with each TLS and
+//! compression feature combination.
+//!
+//! `ClientBuilder::build()` creates the HTTP client before validating the
+//! credentials, so a credential error proves that the client was built with
+//! the enabled TLS backend (rustls when both are enabled), without any
+//! network access. Run with `--no-default-features --features rustls-tls` to
+//! cover the other backend.
+
+use librus_rs::{ClientBuilder, Error};
+
+async fn builds_http_client(builder: ClientBuilder) {
+    match builder.build().await {
+        Err(Error::MissingCredentials("username")) => {}
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("client built without credentials"),
+    }
+}
+
+#[tokio::test]
+async fn enabled_features_build() {
+    builds_http_client(ClientBuilder::new()).await;
+}
+
+#[tokio::test]
+async fn compression_can_be_disabled() {
+    builds_http_client(ClientBuilder::new().disable_compression()).await;
+}
+
+#[tokio::test]
+async fn malformed_credentials_fail_after_the_client_is_built() {
+    let build = ClientBuilder::new()
+        .username("jan.kowalski")
+        .password("secret")
+        .timeout(std::time::Duration::from_secs(1))
+        .build()
+        .await;
+    match build {
+        Err(Error::InvalidCredentialFormat {
+            field: "username", ..
+        }) => {}
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("client built with a malformed username"),
+    }
+}
@@ -0,0 +1,175 @@
+//! Checks that the futures returned by the public async API are `Send`, so
+//! they can be spawned on a multi-threaded runtime or used from web handlers.
+//!
+//! The checks are done by the type checker: the closures below are never
+//! called.
+
+use std::sync::Arc;
+
+use librus_rs::digest::{daily_digest, DigestOptions};
+use librus_rs::snapshot::{SnapshotDir, SnapshotWriter};
+use librus_rs::types::ApiUrl;
+use librus_rs::{
+    AttachmentDownload, Client, ClientBuilder, ClientPool, ExportOptions, MessageFolder,
+    MultiChildClient, NoticeAttachment, ResponseGrade, ValidationMode,
+};
+
+fn assert_send<T: Send>(_: T) {}
+
+fn assert_send_static<T: Send + 'static>(_: T) {}
+
+#[test]
+fn client_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+    assert_send_sync::<Client>();
+    assert_send_sync::<ClientBuilder>();
+    assert_send_sync::<ClientPool>();
+    assert_send_sync::<MultiChildClient>();
+    assert_send_sync::<AttachmentDownload>();
+}
+
+#[test]
+fn construction_futures_are_send() {
+    let _ = || {
+        assert_send_static(ClientBuilder::new().build());
+        assert_send_static(Client::from_env());
+        assert_send(Client::new("user", "pass"));
+    };
+    let _ = |pool: &ClientPool| {
+        assert_send(pool.authenticate_many(Vec::new(), 2));
+    };
+}
+
+#[test]
+fn synergia_futures_are_send() {
+    let _ = |client: &Client, attachment: &NoticeAttachment, url: &ApiUrl| {
+        assert_send(client.me());
+        assert_send(client.follow::<ResponseGrade>(url));
+        assert_send(client.last_login());
+        assert_send(client.capabilities());
+        assert_send(client.grades());
+        assert_send(client.grades_raw());
+        assert_send(client.grade(1));
+        assert_send(client.grade_categories());
+        assert_send(client.grade_category(1));
+        assert_send(client.grade_comment(1));
+        assert_send(client.unpreparedness());
+        assert_send(client.category_averages());
+        assert_send(client.students_averages());
+        assert_send(client.lesson(1));
+        assert_send(client.lessons());
+        assert_send(client.subject(1));
+        assert_send(client.subjects());
+        assert_send(client.class(1));
+        assert_send(client.school_year());
+        assert_send(client.units());
+        assert_send(client.attendances());
+        assert_send(client.attendances_raw());
+        assert_send(client.attendance_types());
+        assert_send(client.attendance_type(1));
+        assert_send(client.attendance_summary());
+        assert_send(client.attendances_filled_by_teacher());
+        assert_send(client.attendance_calendar("2025-10-06", "2025-10-10"));
+        assert_send(client.trips());
+        assert_send(client.trip(1));
+        assert_send(client.notes());
+        assert_send(client.note_categories());
+        assert_send(client.homeworks());
+        assert_send(client.homework_categories());
+        assert_send(client.home_work_assignments());
+        assert_send(client.mark_assignment_done(1));
+        assert_send(client.mark_assignment_undone(1));
+        assert_send(client.school_free_days());
+        assert_send(client.class_free_days());
+        assert_send(client.teacher_free_days());
+        assert_send(client.parent_teacher_conferences());
+        assert_send(client.substitutions());
+        assert_send(client.lucky_number());
+        assert_send(client.family_calendar("2025-10-01", "2025-10-31"));
+        assert_send(client.school_calendar("2025-10-01", "2025-10-31"));
+        assert_send(client.timetable("2025-10-06"));
+        assert_send(client.now_and_next());
+        assert_send(client.school_notices());
+        assert_send(client.school_notice("1"));
+        assert_send(client.school_notices_light());
+        assert_send(client.school_notices_page(1, 10));
+        assert_send(client.school_notices_latest(10));
+        assert_send(client.notice_attachment(attachment));
+        assert_send(client.user(1));
+        assert_send(client.users());
+        assert_send(client.subject_teachers());
+        assert_send(client.teacher_office_hours());
+        assert_send(client.teacher_office_hours_with_names());
+        assert_send(client.colors());
+        assert_send(client.color(1));
+        assert_send(client.reference_indexes());
+        assert_send(client.current_user());
+        assert_send(client.server_time());
+        assert_send(client.clock_skew());
+    };
+}
+
+#[test]
+fn messages_futures_are_send() {
+    let _ = |client: &mut Client| {
+        assert_send(client.select_unit("1"));
+    };
+    let _ = |client: &mut Client| assert_send(client.student_card());
+    let _ = |client: &mut Client| assert_send(client.messages_available());
+    let _ = |client: &mut Client| assert_send(client.recheck_messages_availability());
+    let _ = |client: &mut Client| assert_send(client.unread_counts());
+    let _ = |client: &mut Client| assert_send(client.inbox_messages(1, 10));
+    let _ = |client: &mut Client| assert_send(client.messages_me());
+    let _ = |client: &mut Client| assert_send(client.outbox_messages(1, 10));
+    let _ = |client: &mut Client| assert_send(client.message("1"));
+    let _ = |client: &mut Client| assert_send(client.outbox_message("1"));
+    let _ = |client: &mut Client| assert_send(client.message_read_receipts("1"));
+    let _ = |client: &mut Client| assert_send(client.justification_requests(1, 10));
+    let _ = |client: &mut Client| assert_send(client.justification_request("1"));
+    let _ = |client: &mut Client| assert_send(client.message_board());
+    let _ = |client: &mut Client| assert_send(client.confirm_board_entry("1"));
+    let _ = |client: &mut Client| assert_send(client.confirm_message_read("1"));
+    let _ = |client: &mut Client| {
+        assert_send(client.save_draft(&["1"], "Temat", "Treść", ValidationMode::Strict))
+    };
+    let _ = |client: &mut Client| assert_send(client.drafts(1, 10));
+    let _ = |client: &mut Client| assert_send(client.delete_draft("1"));
+    let _ = |client: &mut Client| assert_send(client.send_draft("1"));
+    let _ = |client: &mut Client, opts: &ExportOptions| {
+        assert_send(client.export_messages_jsonl(MessageFolder::Inbox, tokio::io::sink(), opts))
+    };
+    let _ = |client: &mut Client| assert_send(client.attachment("1", "1"));
+    let _ = |client: &mut Client| assert_send(client.attachment_download("1", "1"));
+    let _ = |client: &mut Client| assert_send(client.attachment_resume("1", "1", 0));
+    let _ = |client: &mut Client| {
+        assert_send(client.download_attachment_to_file("1", "1", "attachment.pdf"))
+    };
+    let _ = |download: &mut AttachmentDownload| assert_send(download.chunk());
+    let _ = |download: AttachmentDownload| assert_send_static(download.bytes());
+}
+
+#[test]
+fn helper_futures_are_send() {
+    let _ = |client: &mut Client, opts: &DigestOptions| {
+        assert_send(daily_digest(client, "2025-10-06", opts));
+    };
+    let _ = |multi: &MultiChildClient| {
+        assert_send(multi.for_each_child(async |client: &mut Client| client.grades().await));
+    };
+    let _ = |multi: MultiChildClient| assert_send_static(multi.into_client());
+    let _ = |writer: &mut SnapshotWriter<SnapshotDir>, client: &mut Client| {
+        assert_send(writer.write(client))
+    };
+    let _ = |dir: &SnapshotDir| assert_send(dir.read_manifest());
+}
+
+#[test]
+fn shared_client_can_be_spawned() {
+    // The pattern of web handlers: a shared client moved into a task.
+    let _ = |client: Arc<Client>| {
+        assert_send_static(async move { client.grades().await });
+    };
+    let _ = |client: Arc<tokio::sync::Mutex<Client>>| {
+        assert_send_static(async move { client.lock().await.inbox_messages(1, 10).await });
+    };
+}